@@ -0,0 +1,177 @@
+//! # Canvas 适配器模块
+//!
+//! 本模块提供 Obsidian Canvas（`.canvas`）白板文件的适配器实现，
+//! 使白板作为 `canvas` 类型的对象出现在知识图谱中。
+//!
+//! ## 模块依赖
+//!
+//! - `serde_json` - Canvas JSON 解析
+//! - [`crate::dcom`] - DCOM 核心数据结构
+//! - [`super`] - 适配器接口定义
+//!
+//! ## 导出的主要内容
+//!
+//! ### 结构体
+//! - [`CanvasAdapter`] - Canvas 适配器
+//!
+//! ## Canvas 格式
+//!
+//! ```json
+//! {
+//!   "nodes": [
+//!     { "id": "1", "type": "file", "file": "notes/a.md", "x": 0, "y": 0, "width": 400, "height": 400 },
+//!     { "id": "2", "type": "text", "text": "想法", "x": 500, "y": 0, "width": 200, "height": 100 }
+//!   ],
+//!   "edges": []
+//! }
+//! ```
+//!
+//! 只有 `type` 为 `file` 的卡片会产生链接：引用笔记时为 Wiki 链接，
+//! 引用其他附件（如图片）时为嵌入。
+
+use crate::adapters::{ExtractedLink, LinkKind, ObjectAdapter};
+use crate::dcom::{
+    serialization::{BinarySource, SerializationSource},
+    CognitiveObject,
+};
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Canvas 对象的节点类型
+pub const CANVAS_NODE_TYPE: &str = "canvas";
+
+/// Canvas 适配器
+///
+/// 将 `.canvas` 文件加载为 `canvas` 类型的认知对象，原始 JSON 作为对象内容保存，
+/// 白板中引用的文件作为链接提取。
+///
+/// # 支持的扩展名
+///
+/// - `.canvas`
+#[derive(Debug, Clone, Default)]
+pub struct CanvasAdapter;
+
+impl CanvasAdapter {
+    /// 创建新的 Canvas 适配器
+    pub fn new() -> Self {
+        CanvasAdapter
+    }
+
+    /// 将文件卡片引用的路径转换为链接
+    ///
+    /// Markdown 笔记去掉扩展名后作为 Wiki 链接，与 `[[notes/a]]` 的解析方式一致；
+    /// 其他文件保留完整路径作为嵌入。
+    fn file_link(file: &str) -> ExtractedLink {
+        let path = Path::new(file);
+        let is_note = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+            });
+
+        if is_note {
+            let target = path.with_extension("").to_string_lossy().replace('\\', "/");
+            ExtractedLink::new(target, LinkKind::WikiLink)
+        } else {
+            ExtractedLink::new(file, LinkKind::Embed)
+        }
+    }
+}
+
+impl ObjectAdapter for CanvasAdapter {
+    fn name(&self) -> &'static str {
+        "canvas"
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["canvas"]
+    }
+
+    fn load(&self, path: &Path, content: &[u8]) -> Result<CognitiveObject> {
+        let text = std::str::from_utf8(content).context("Canvas 文件必须是 UTF-8 编码")?;
+        serde_json::from_str::<serde_json::Value>(text).context("Canvas 文件不是合法的 JSON")?;
+
+        let mut obj = CognitiveObject::new();
+        if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+            obj.set_title(stem);
+        }
+        obj.set_type(CANVAS_NODE_TYPE);
+        obj.set_content(text);
+
+        let content_hash = blake3::hash(content).to_hex().to_string();
+        let now = chrono::Utc::now().timestamp();
+        obj.add_source(SerializationSource::Binary(BinarySource::new(
+            path.to_string_lossy().to_string(),
+            content_hash,
+            "application/json",
+            content.len() as u64,
+            now,
+        )));
+
+        Ok(obj)
+    }
+
+    fn save(&self, object: &CognitiveObject) -> Result<Vec<u8>> {
+        Ok(object.content().unwrap_or("{}").as_bytes().to_vec())
+    }
+
+    fn extract_links(&self, object: &CognitiveObject) -> Vec<ExtractedLink> {
+        let Some(canvas) = object
+            .content()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(content).ok())
+        else {
+            return Vec::new();
+        };
+
+        canvas
+            .get("nodes")
+            .and_then(|nodes| nodes.as_array())
+            .into_iter()
+            .flatten()
+            .filter(|node| node.get("type").and_then(|t| t.as_str()) == Some("file"))
+            .filter_map(|node| node.get("file").and_then(|f| f.as_str()))
+            .map(Self::file_link)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_CANVAS: &str = r#"{
+        "nodes": [
+            {"id": "1", "type": "file", "file": "notes/Alpha.md", "x": 0, "y": 0, "width": 400, "height": 400},
+            {"id": "2", "type": "file", "file": "Beta.md", "x": 500, "y": 0, "width": 400, "height": 400},
+            {"id": "3", "type": "text", "text": "Just a card", "x": 0, "y": 500, "width": 200, "height": 100}
+        ],
+        "edges": [{"id": "e1", "fromNode": "1", "toNode": "2"}]
+    }"#;
+
+    #[test]
+    fn test_load_canvas() {
+        let adapter = CanvasAdapter::new();
+        let obj = adapter
+            .load(Path::new("boards/Plan.canvas"), SAMPLE_CANVAS.as_bytes())
+            .unwrap();
+
+        assert_eq!(obj.title(), Some("Plan"));
+        assert_eq!(obj.get_type(), Some(CANVAS_NODE_TYPE));
+        assert_eq!(obj.content(), Some(SAMPLE_CANVAS));
+        assert!(adapter.load(Path::new("bad.canvas"), b"{nodes").is_err());
+    }
+
+    #[test]
+    fn test_extract_canvas_links() {
+        let adapter = CanvasAdapter::new();
+        let obj = adapter
+            .load(Path::new("Plan.canvas"), SAMPLE_CANVAS.as_bytes())
+            .unwrap();
+
+        let links = adapter.extract_links(&obj);
+        let targets: Vec<&str> = links.iter().map(|l| l.target.as_str()).collect();
+        assert_eq!(targets, vec!["notes/Alpha", "Beta"]);
+        assert!(links.iter().all(|l| l.kind == LinkKind::WikiLink));
+    }
+}
@@ -21,6 +21,7 @@
 //!
 //! ### 子模块
 //! - [`obsidian`] - Obsidian Markdown 适配器
+//! - [`canvas`] - Obsidian Canvas 白板适配器
 //!
 //! ## 使用示例
 //!
@@ -41,12 +42,14 @@
 //! pub struct PdfAdapter;
 //!
 //! impl ObjectAdapter for PdfAdapter {
+//!     fn name(&self) -> &'static str { "pdf" }
 //!     fn supported_extensions(&self) -> &[&str] { &["pdf"] }
 //!     fn load(&self, path: &Path, content: &[u8]) -> Result<CognitiveObject> { ... }
 //!     // ...
 //! }
 //! ```
 
+pub mod canvas;
 pub mod obsidian;
 
 use crate::dcom::CognitiveObject;
@@ -66,6 +69,8 @@ pub enum LinkKind {
     Embed,
     /// 外部链接
     External,
+    /// frontmatter 属性中的引用：`related: "[[target]]"`
+    FrontmatterRef,
 }
 
 /// 提取的链接
@@ -129,6 +134,10 @@ impl ExtractedLink {
 ///
 /// ```rust,ignore
 /// impl ObjectAdapter for MyAdapter {
+///     fn name(&self) -> &'static str {
+///         "my"
+///     }
+///
 ///     fn supported_extensions(&self) -> &[&str] {
 ///         &["myext"]
 ///     }
@@ -149,6 +158,15 @@ impl ExtractedLink {
 /// }
 /// ```
 pub trait ObjectAdapter: Send + Sync {
+    /// 适配器标识
+    ///
+    /// 用于诊断（如查询某个文件由哪个适配器处理）。
+    ///
+    /// # 返回值
+    ///
+    /// 固定的适配器名称，如 `"obsidian"`
+    fn name(&self) -> &'static str;
+
     /// 适配器支持的文件扩展名
     ///
     /// 返回此适配器能处理的文件扩展名列表（不含点号）。
@@ -227,20 +245,22 @@ pub trait ObjectAdapter: Send + Sync {
 /// 适配器注册表
 ///
 /// 管理多个适配器，根据文件扩展名自动选择合适的适配器。
+/// 多个适配器支持同一扩展名时，优先级高者胜出；优先级相同时按注册顺序选择。
 ///
 /// # 使用示例
 ///
 /// ```rust,ignore
 /// let mut registry = AdapterRegistry::new();
 /// registry.register(Box::new(ObsidianAdapter::new()));
+/// registry.register_with_priority(Box::new(StrictMarkdownAdapter::new()), 10);
 ///
 /// if let Some(adapter) = registry.find_adapter("md") {
 ///     let obj = adapter.load(path, content)?;
 /// }
 /// ```
 pub struct AdapterRegistry {
-    /// 已注册的适配器列表
-    adapters: Vec<Box<dyn ObjectAdapter>>,
+    /// 已注册的适配器列表（优先级, 适配器）
+    adapters: Vec<(i32, Box<dyn ObjectAdapter>)>,
 }
 
 impl AdapterRegistry {
@@ -253,15 +273,30 @@ impl AdapterRegistry {
 
     /// 注册一个适配器
     ///
+    /// 使用默认优先级 0 注册。
+    ///
     /// # 参数
     ///
     /// * `adapter` - 适配器实例
     pub fn register(&mut self, adapter: Box<dyn ObjectAdapter>) {
-        self.adapters.push(adapter);
+        self.register_with_priority(adapter, 0);
+    }
+
+    /// 以指定优先级注册一个适配器
+    ///
+    /// # 参数
+    ///
+    /// * `adapter` - 适配器实例
+    /// * `priority` - 优先级，数值越大越优先
+    pub fn register_with_priority(&mut self, adapter: Box<dyn ObjectAdapter>, priority: i32) {
+        self.adapters.push((priority, adapter));
     }
 
     /// 根据扩展名查找适配器
     ///
+    /// 在所有支持该扩展名的适配器中选择优先级最高的一个，
+    /// 优先级相同时返回最先注册的。
+    ///
     /// # 参数
     ///
     /// * `ext` - 文件扩展名（不含点号）
@@ -270,10 +305,13 @@ impl AdapterRegistry {
     ///
     /// 如果找到返回适配器引用，否则返回 None
     pub fn find_adapter(&self, ext: &str) -> Option<&dyn ObjectAdapter> {
-        self.adapters
-            .iter()
-            .find(|a| a.supports(ext))
-            .map(|a| a.as_ref())
+        let mut best: Option<&(i32, Box<dyn ObjectAdapter>)> = None;
+        for entry in self.adapters.iter().filter(|(_, a)| a.supports(ext)) {
+            if best.is_none_or(|(priority, _)| entry.0 > *priority) {
+                best = Some(entry);
+            }
+        }
+        best.map(|(_, a)| a.as_ref())
     }
 
     /// 根据文件路径查找适配器
@@ -295,8 +333,9 @@ impl AdapterRegistry {
 impl Default for AdapterRegistry {
     fn default() -> Self {
         let mut registry = Self::new();
-        // 默认注册 Obsidian 适配器
+        // 默认注册 Obsidian 与 Canvas 适配器
         registry.register(Box::new(obsidian::ObsidianAdapter::new()));
+        registry.register(Box::new(canvas::CanvasAdapter::new()));
         registry
     }
 }
@@ -324,11 +363,64 @@ mod tests {
         // 应该找到 md 扩展名的适配器
         assert!(registry.find_adapter("md").is_some());
         assert!(registry.find_adapter("markdown").is_some());
+        assert!(registry.find_adapter("canvas").is_some());
 
         // 不支持的扩展名
         assert!(registry.find_adapter("pdf").is_none());
     }
 
+    /// 测试用适配器，加载时以固定标题标识自身
+    struct TitledAdapter(&'static str);
+
+    impl ObjectAdapter for TitledAdapter {
+        fn name(&self) -> &'static str {
+            "titled"
+        }
+
+        fn supported_extensions(&self) -> &[&str] {
+            &["md"]
+        }
+
+        fn load(&self, _path: &Path, _content: &[u8]) -> Result<CognitiveObject> {
+            let mut obj = CognitiveObject::new();
+            obj.set_title(self.0);
+            Ok(obj)
+        }
+
+        fn save(&self, _object: &CognitiveObject) -> Result<Vec<u8>> {
+            Ok(vec![])
+        }
+
+        fn extract_links(&self, _object: &CognitiveObject) -> Vec<ExtractedLink> {
+            vec![]
+        }
+    }
+
+    fn loaded_title(registry: &AdapterRegistry) -> String {
+        let adapter = registry.find_adapter("md").unwrap();
+        let obj = adapter.load(Path::new("note.md"), b"").unwrap();
+        obj.title().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_adapter_registry_priority() {
+        let mut registry = AdapterRegistry::new();
+        registry.register_with_priority(Box::new(TitledAdapter("low")), -1);
+        registry.register_with_priority(Box::new(TitledAdapter("high")), 10);
+        registry.register(Box::new(TitledAdapter("default")));
+
+        assert_eq!(loaded_title(&registry), "high");
+    }
+
+    #[test]
+    fn test_adapter_registry_same_priority_keeps_order() {
+        let mut registry = AdapterRegistry::new();
+        registry.register(Box::new(TitledAdapter("first")));
+        registry.register(Box::new(TitledAdapter("second")));
+
+        assert_eq!(loaded_title(&registry), "first");
+    }
+
     #[test]
     fn test_find_adapter_for_path() {
         let registry = AdapterRegistry::default();
@@ -339,4 +431,18 @@ mod tests {
         let pdf_path = Path::new("docs/test.pdf");
         assert!(registry.find_adapter_for_path(pdf_path).is_none());
     }
+
+    #[test]
+    fn test_adapter_name_for_path() {
+        let registry = AdapterRegistry::default();
+
+        let name = registry
+            .find_adapter_for_path(Path::new("notes/test.md"))
+            .map(|a| a.name());
+        assert_eq!(name, Some("obsidian"));
+
+        assert!(registry
+            .find_adapter_for_path(Path::new("data/file.xyz"))
+            .is_none());
+    }
 }
@@ -1,12 +1,15 @@
 //! # Frontmatter 模块
 //!
-//! 本模块提供 YAML Frontmatter 的解析和转换功能。
+//! 本模块提供 Frontmatter 的解析和转换功能，支持 YAML（`---` 分隔）
+//! 和 Hugo/Zola 风格的 TOML（`+++` 分隔）两种写法。
 //!
 //! ## 模块依赖
 //!
 //! - `serde_yaml` - YAML 解析
+//! - `toml` - TOML 解析
 //! - `serde_json` - JSON 转换（用于复杂对象）
 //! - [`crate::dcom::PropertyValue`] - DCOM 属性值类型
+//! - [`crate::dcom::property`] - 时长与相对日期解析
 //!
 //! ## 导出的主要内容
 //!
@@ -15,7 +18,13 @@
 //!
 //! ### 函数
 //! - [`parse_frontmatter`] - 解析 frontmatter
+//! - [`frontmatter_end`] - 定位 frontmatter 块之后正文的起始位置
 //! - [`yaml_to_property_value`] - YAML 值转 PropertyValue
+//! - [`yaml_to_property_list`] - YAML 值转列表型 PropertyValue（标量视为单项列表）
+//! - [`detect_list_style`] - 检测列表字段的书写形式
+//!
+//! ### 常量
+//! - [`LIST_PROPERTIES`] - 既可写作标量也可写作列表的属性
 //!
 //! ## 使用示例
 //!
@@ -26,10 +35,17 @@
 //! let (fm, body) = parse_frontmatter(content);
 //! ```
 
-use crate::dcom::PropertyValue;
+use crate::dcom::property::{parse_duration, parse_relative_date};
+use crate::dcom::{PropertyValue, YamlListStyle};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// 列表型属性
+///
+/// 与 `tags`、`aliases` 一样，这些属性既可以写成单个标量（`cssclasses: wide`），
+/// 也可以写成列表，加载时统一转换为 `List`，并记录书写形式以便保存时还原。
+pub const LIST_PROPERTIES: [&str; 2] = ["cssclasses", "cssclass"];
+
 /// Frontmatter 元数据
 ///
 /// 存储从 Markdown 文件头部 YAML 区域提取的结构化元数据。
@@ -51,14 +67,17 @@ use std::collections::HashMap;
 /// * `node_type` - 节点类型（映射自 YAML 中的 `type` 字段）
 /// * `created` - 创建日期字符串
 /// * `properties` - 其他自定义属性（通过 `#[serde(flatten)]` 捕获）
+/// * `list_styles` - `tags`、`aliases` 在源文件中的书写形式（不参与序列化）
+///
+/// `tags` 和 `aliases` 既可以写成列表，也可以写成单个字符串。
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Frontmatter {
     /// 标签列表
-    #[serde(default)]
+    #[serde(default, deserialize_with = "string_or_list")]
     pub tags: Vec<String>,
 
     /// 别名列表
-    #[serde(default)]
+    #[serde(default, deserialize_with = "string_or_list")]
     pub aliases: Vec<String>,
 
     /// 节点类型
@@ -72,6 +91,30 @@ pub struct Frontmatter {
     /// 其他自定义属性
     #[serde(flatten)]
     pub properties: HashMap<String, serde_yaml::Value>,
+
+    /// 列表字段的书写形式
+    #[serde(skip)]
+    pub list_styles: HashMap<String, YamlListStyle>,
+}
+
+/// 反序列化单个字符串或字符串列表
+fn string_or_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrList {
+        One(String),
+        Many(Vec<String>),
+        None(()),
+    }
+
+    Ok(match StringOrList::deserialize(deserializer)? {
+        StringOrList::One(s) => vec![s],
+        StringOrList::Many(items) => items,
+        StringOrList::None(()) => Vec::new(),
+    })
 }
 
 impl Frontmatter {
@@ -87,7 +130,7 @@ impl Frontmatter {
 
 /// 解析 Frontmatter
 ///
-/// 从 Markdown 内容开头提取 YAML frontmatter。
+/// 从 Markdown 内容开头提取 YAML 或 TOML frontmatter。
 ///
 /// # 参数
 ///
@@ -101,9 +144,9 @@ impl Frontmatter {
 ///
 /// # 解析规则
 ///
-/// 1. 内容必须以 `---` 开头
-/// 2. frontmatter 结束标记为另一个 `---`（在新行）
-/// 3. 两个标记之间的内容作为 YAML 解析
+/// 1. 内容必须以 `---` 或 `+++` 开头（忽略开头的 UTF-8 BOM 和空白行）
+/// 2. frontmatter 结束标记为同样的分隔符（在新行）
+/// 3. 两个标记之间的内容按 `---` 作为 YAML、按 `+++` 作为 TOML 解析
 ///
 /// # 示例
 ///
@@ -113,26 +156,133 @@ impl Frontmatter {
 /// assert!(body.contains("Content"));
 /// ```
 pub fn parse_frontmatter(content: &str) -> (Option<Frontmatter>, String) {
-    // 检查是否以 --- 开头
-    if !content.starts_with("---") {
+    let Some((delimiter, block, fence_end)) = locate_block(content) else {
         return (None, content.to_string());
+    };
+    let remaining = &content[fence_end..];
+
+    let parsed = if delimiter == "+++" {
+        parse_toml_block(block)
+    } else {
+        parse_yaml_block(block)
+    };
+    match parsed {
+        Some(fm) => (Some(fm), remaining.trim_start().to_string()),
+        None => (None, content.to_string()),
     }
+}
 
-    // 查找结束的 ---
-    if let Some(end_pos) = content[3..].find("\n---") {
-        let yaml_content = &content[3..3 + end_pos];
-        let remaining = &content[3 + end_pos + 4..]; // 跳过 \n---
+/// 返回 frontmatter 块（含结束分隔符所在行）之后正文的起始字节位置
+///
+/// 与 [`parse_frontmatter`] 使用相同的分隔符规则（`---` 或 `+++`），只判断块的边界，
+/// 不解析块内容。没有 frontmatter 时返回 0。
+///
+/// # 参数
+///
+/// * `content` - Markdown 文本内容
+pub fn frontmatter_end(content: &str) -> usize {
+    match locate_block(content) {
+        Some((_, _, fence_end)) => content[fence_end..]
+            .find('\n')
+            .map_or(content.len(), |newline| fence_end + newline + 1),
+        None => 0,
+    }
+}
 
-        // 解析 YAML
-        match serde_yaml::from_str::<Frontmatter>(yaml_content.trim()) {
-            Ok(fm) => (Some(fm), remaining.trim_start().to_string()),
-            Err(_) => (None, content.to_string()),
-        }
+/// 定位 frontmatter 块
+///
+/// 忽略开头的 BOM 和空白行后检查起始分隔符，并查找新行上的同一分隔符。
+///
+/// # 返回值
+///
+/// `(分隔符, 块内容, 结束分隔符之后的字节位置)`，没有完整的块时返回 `None`
+fn locate_block(content: &str) -> Option<(&'static str, &str, usize)> {
+    let offset = content.len() - content.trim_start_matches('\u{feff}').trim_start().len();
+    let start = &content[offset..];
+    let delimiter = if start.starts_with("---") {
+        "---"
+    } else if start.starts_with("+++") {
+        "+++"
     } else {
-        (None, content.to_string())
+        return None;
+    };
+
+    let end_pos = start[3..].find(&format!("\n{}", delimiter))?;
+    let block = &start[3..3 + end_pos];
+    // 跳过换行和分隔符
+    Some((delimiter, block, offset + 3 + end_pos + 4))
+}
+
+/// 解析 YAML frontmatter 块，并记录列表字段的书写形式
+fn parse_yaml_block(yaml: &str) -> Option<Frontmatter> {
+    let mut fm = serde_yaml::from_str::<Frontmatter>(yaml.trim()).ok()?;
+    for key in ["tags", "aliases"].into_iter().chain(LIST_PROPERTIES) {
+        if let Some(style) = detect_list_style(yaml, key) {
+            fm.list_styles.insert(key.to_string(), style);
+        }
+    }
+    Some(fm)
+}
+
+/// 解析 TOML frontmatter 块
+///
+/// 先转换为 YAML 值再反序列化，使自定义属性与 YAML 写法得到相同的 [`Frontmatter`]。
+fn parse_toml_block(toml: &str) -> Option<Frontmatter> {
+    let value = toml::from_str::<toml::Value>(toml).ok()?;
+    serde_yaml::from_value(toml_to_yaml(value)).ok()
+}
+
+/// 将 TOML 值转换为 YAML 值
+///
+/// TOML 的日期时间没有对应的 YAML 类型，按原始写法转换为字符串。
+fn toml_to_yaml(value: toml::Value) -> serde_yaml::Value {
+    match value {
+        toml::Value::String(s) => serde_yaml::Value::String(s),
+        toml::Value::Integer(i) => serde_yaml::Value::Number(i.into()),
+        toml::Value::Float(f) => serde_yaml::Value::Number(f.into()),
+        toml::Value::Boolean(b) => serde_yaml::Value::Bool(b),
+        toml::Value::Datetime(dt) => serde_yaml::Value::String(dt.to_string()),
+        toml::Value::Array(items) => {
+            serde_yaml::Value::Sequence(items.into_iter().map(toml_to_yaml).collect())
+        }
+        toml::Value::Table(table) => serde_yaml::Value::Mapping(
+            table
+                .into_iter()
+                .map(|(k, v)| (serde_yaml::Value::String(k), toml_to_yaml(v)))
+                .collect(),
+        ),
     }
 }
 
+/// 检测列表字段的书写形式
+///
+/// 只检查顶层字段：值为空（后续为 `- item` 行）视为块列表，
+/// 以 `[` 开头视为行内数组，其余视为单个标量。
+///
+/// # 参数
+///
+/// * `yaml` - frontmatter 的 YAML 文本
+/// * `key` - 字段名
+///
+/// # 返回值
+///
+/// 字段的书写形式，字段不存在时返回 `None`
+pub fn detect_list_style(yaml: &str, key: &str) -> Option<YamlListStyle> {
+    let prefix = format!("{}:", key);
+    let value = yaml
+        .lines()
+        .find_map(|line| line.strip_prefix(&prefix))?
+        .trim();
+
+    Some(if value.is_empty() {
+        YamlListStyle::Block
+    } else if value.starts_with('[') {
+        YamlListStyle::Flow
+    } else {
+        YamlListStyle::Scalar
+    })
+}
+
 /// 将 YAML 值转换为 PropertyValue
 ///
 /// 将 `serde_yaml::Value` 递归转换为 DCOM 的 `PropertyValue`。
@@ -153,6 +303,8 @@ pub fn parse_frontmatter(content: &str) -> (Option<Frontmatter>, String) {
 /// | Bool | `Boolean` |
 /// | Number (整数) | `Integer` |
 /// | Number (浮点) | `Float` |
+/// | String（时长，如 `2h`、`1h30m`） | `Duration` |
+/// | String（相对日期，如 `+3d`、`-1w`） | `DateTime`（相对当前时间解析） |
 /// | String | `String` |
 /// | Sequence | `List` |
 /// | Mapping | `Json` |
@@ -170,7 +322,15 @@ pub fn yaml_to_property_value(value: &serde_yaml::Value) -> PropertyValue {
                 PropertyValue::Null
             }
         }
-        serde_yaml::Value::String(s) => PropertyValue::String(s.clone()),
+        serde_yaml::Value::String(s) => {
+            if let Some(secs) = parse_duration(s) {
+                PropertyValue::Duration(secs)
+            } else if let Some(dt) = parse_relative_date(s, chrono::Utc::now()) {
+                PropertyValue::DateTime(dt)
+            } else {
+                PropertyValue::String(s.clone())
+            }
+        }
         serde_yaml::Value::Sequence(seq) => {
             let items: Vec<PropertyValue> = seq.iter().map(yaml_to_property_value).collect();
             PropertyValue::List(items)
@@ -187,6 +347,26 @@ pub fn yaml_to_property_value(value: &serde_yaml::Value) -> PropertyValue {
     }
 }
 
+/// 将 YAML 值转换为列表型 PropertyValue
+///
+/// 用于 [`LIST_PROPERTIES`] 等语义上为列表的属性：序列按 [`yaml_to_property_value`]
+/// 转换，空值转换为空列表，其余标量转换为单项列表。
+///
+/// # 参数
+///
+/// * `value` - YAML 值
+///
+/// # 返回值
+///
+/// `PropertyValue::List`
+pub fn yaml_to_property_list(value: &serde_yaml::Value) -> PropertyValue {
+    match value {
+        serde_yaml::Value::Null => PropertyValue::List(Vec::new()),
+        serde_yaml::Value::Sequence(_) => yaml_to_property_value(value),
+        _ => PropertyValue::List(vec![yaml_to_property_value(value)]),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,6 +393,29 @@ mod tests {
         assert_eq!(fm.aliases, vec!["alias1", "alias2"]);
     }
 
+    #[test]
+    fn test_parse_frontmatter_list_styles() {
+        let content = "---\naliases: Solo\ntags:\n  - a\n  - b\ncssclasses: wide\n---\nContent";
+        let (fm, _body) = parse_frontmatter(content);
+
+        let fm = fm.unwrap();
+        assert_eq!(fm.aliases, vec!["Solo"]);
+        assert_eq!(fm.tags, vec!["a", "b"]);
+        assert_eq!(fm.list_styles["aliases"], YamlListStyle::Scalar);
+        assert_eq!(fm.list_styles["tags"], YamlListStyle::Block);
+        assert_eq!(fm.list_styles["cssclasses"], YamlListStyle::Scalar);
+        assert_eq!(
+            yaml_to_property_list(&fm.properties["cssclasses"]),
+            PropertyValue::List(vec![PropertyValue::string("wide")])
+        );
+
+        assert_eq!(
+            detect_list_style("tags: [a]", "tags"),
+            Some(YamlListStyle::Flow)
+        );
+        assert_eq!(detect_list_style("tags: [a]", "aliases"), None);
+    }
+
     #[test]
     fn test_parse_frontmatter_with_created() {
         let content = "---\ncreated: 2024-01-15\n---\nContent";
@@ -252,6 +455,58 @@ mod tests {
         assert_eq!(body, content); // 返回原始内容
     }
 
+    #[test]
+    fn test_parse_frontmatter_toml() {
+        let content = "+++\ntags = [\"rust\", \"zola\"]\ntype = \"note\"\ncreated = 2024-01-15\nweight = 3\n+++\n# Hello\n\n+++ not a delimiter";
+        let (fm, body) = parse_frontmatter(content);
+
+        let fm = fm.unwrap();
+        assert_eq!(fm.tags, vec!["rust", "zola"]);
+        assert_eq!(fm.node_type, Some("note".to_string()));
+        assert_eq!(fm.created, Some("2024-01-15".to_string()));
+        assert_eq!(
+            yaml_to_property_value(&fm.properties["weight"]),
+            PropertyValue::Integer(3)
+        );
+        assert_eq!(body, "# Hello\n\n+++ not a delimiter");
+
+        // 无效的 TOML 返回原始内容
+        let content = "+++\ntags: [a]\n+++\nContent";
+        let (fm, body) = parse_frontmatter(content);
+        assert!(fm.is_none());
+        assert_eq!(body, content);
+    }
+
+    #[test]
+    fn test_parse_frontmatter_with_bom() {
+        let content = "\u{feff}---\ntags: [rust]\n---\n# Hello";
+        let (fm, body) = parse_frontmatter(content);
+
+        assert_eq!(fm.unwrap().tags, vec!["rust"]);
+        assert_eq!(body, "# Hello");
+    }
+
+    #[test]
+    fn test_parse_frontmatter_leading_blank_line() {
+        let content = "\n---\ntype: note\n---\n# Hello\n\n  Indented";
+        let (fm, body) = parse_frontmatter(content);
+
+        assert_eq!(fm.unwrap().node_type.as_deref(), Some("note"));
+        assert_eq!(body, "# Hello\n\n  Indented");
+    }
+
+    #[test]
+    fn test_frontmatter_end() {
+        let yaml = "---\ntype: note\n---\n\nBody";
+        assert_eq!(&yaml[frontmatter_end(yaml)..], "\nBody");
+
+        let toml = "\u{feff}+++\ntitle = \"T\"\n+++\nBody";
+        assert_eq!(&toml[frontmatter_end(toml)..], "Body");
+
+        assert_eq!(frontmatter_end("# Plain\n---\n"), 0);
+        assert_eq!(frontmatter_end("---\nunclosed"), 0);
+    }
+
     #[test]
     fn test_yaml_to_property_value_primitives() {
         assert!(matches!(
@@ -277,6 +532,40 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_yaml_to_property_value_duration() {
+        let two_hours = serde_yaml::Value::String("2h".to_string());
+        assert_eq!(
+            yaml_to_property_value(&two_hours),
+            PropertyValue::Duration(7200)
+        );
+
+        let half_hour = serde_yaml::Value::String("30m".to_string());
+        assert_eq!(
+            yaml_to_property_value(&half_hour),
+            PropertyValue::Duration(1800)
+        );
+
+        // 不是时长写法的字符串保持原样
+        assert_eq!(
+            yaml_to_property_value(&serde_yaml::Value::String("rust".to_string())),
+            PropertyValue::string("rust")
+        );
+    }
+
+    #[test]
+    fn test_yaml_to_property_value_relative_date() {
+        let now = chrono::Utc::now();
+        let value = yaml_to_property_value(&serde_yaml::Value::String("+3d".to_string()));
+
+        let PropertyValue::DateTime(dt) = value else {
+            panic!("Expected DateTime");
+        };
+        let parsed = chrono::DateTime::parse_from_rfc3339(&dt).unwrap();
+        let delta = parsed.with_timezone(&chrono::Utc) - now;
+        assert!((delta.num_seconds() - 3 * 86400).abs() < 5);
+    }
+
     #[test]
     fn test_yaml_to_property_value_sequence() {
         let seq = serde_yaml::Value::Sequence(vec![
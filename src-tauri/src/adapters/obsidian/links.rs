@@ -17,7 +17,9 @@
 //! - [`extract_wikilinks`] - 提取 wikilink
 //! - [`extract_embeds`] - 提取嵌入
 //! - [`extract_external_links`] - 提取外部链接
+//! - [`is_http_url`] - 判断文本是否为单个 HTTP(S) 地址
 //! - [`extract_block_references`] - 提取块 ID
+//! - [`strip_comments`] - 移除 `%% 注释 %%`
 //!
 //! ## Obsidian 链接语法
 //!
@@ -30,6 +32,9 @@
 //! | `![[embed]]` | 嵌入 | `![[image.png]]` |
 //! | `[text](url)` | 外部链接 | `[Google](https://...)` |
 //!
+//! `%% ... %%` 注释中的内容在 Obsidian 中不渲染，提取链接和标签前应先调用
+//! [`strip_comments`] 移除。
+//!
 //! ## 使用示例
 //!
 //! ```rust,ignore
@@ -216,6 +221,27 @@ pub fn extract_external_links(content: &str) -> Vec<ExtractedLink> {
     links
 }
 
+/// 判断文本是否为单个 HTTP(S) 地址
+///
+/// 用于识别 frontmatter 中 `source: https://...` 这类值为链接的属性；
+/// 首尾空白被忽略，包含其他空白字符的文本不视为地址。
+///
+/// # 参数
+///
+/// * `text` - 待判断的文本
+pub fn is_http_url(text: &str) -> bool {
+    let text = text.trim();
+    let lower = text.to_ascii_lowercase();
+    let rest = if lower.starts_with("https://") {
+        &text[8..]
+    } else if lower.starts_with("http://") {
+        &text[7..]
+    } else {
+        return false;
+    };
+    !rest.is_empty() && !rest.contains(char::is_whitespace)
+}
+
 /// 提取块 ID
 ///
 /// 从 Markdown 内容中提取所有 `^blockid` 格式的块标识符。
@@ -241,6 +267,33 @@ pub fn extract_block_references(content: &str) -> Vec<BlockReference> {
     refs
 }
 
+/// 移除 Obsidian 注释
+///
+/// 删除 `%% ... %%` 包围的内容（含标记本身），支持行内和跨行注释。
+/// 注释中的换行符会被保留，使后续提取的行号与原文一致。
+/// 未闭合的 `%%` 视为注释到文末。
+///
+/// # 参数
+///
+/// * `content` - Markdown 文本内容
+///
+/// # 返回值
+///
+/// 移除注释后的文本
+pub fn strip_comments(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+
+    for (i, segment) in content.split("%%").enumerate() {
+        if i % 2 == 0 {
+            result.push_str(segment);
+        } else {
+            result.extend(segment.chars().filter(|c| *c == '\n'));
+        }
+    }
+
+    result
+}
+
 /// 解析链接文本
 ///
 /// 解析 `link` 或 `link|display` 格式。
@@ -328,6 +381,16 @@ mod tests {
         assert!(links.iter().any(|l| l.target.contains("google.com")));
     }
 
+    #[test]
+    fn test_is_http_url() {
+        assert!(is_http_url("https://example.com"));
+        assert!(is_http_url(" http://example.org/a?b=c "));
+        assert!(is_http_url("HTTPS://Example.com"));
+        assert!(!is_http_url("https://"));
+        assert!(!is_http_url("see https://example.com"));
+        assert!(!is_http_url("ftp://example.com"));
+    }
+
     #[test]
     fn test_extract_block_references() {
         let content = "Paragraph one ^abc123\n\nParagraph two ^def456";
@@ -362,6 +425,22 @@ mod tests {
         assert!(display.is_none());
     }
 
+    #[test]
+    fn test_strip_comments() {
+        assert_eq!(strip_comments("a %%hidden [[X]]%% b"), "a  b");
+        assert_eq!(strip_comments("no comments"), "no comments");
+
+        // 跨行注释保留换行，行号不变
+        let content = "Line 1\n%%\n[[Hidden]]\n%%\nLine 5 [[Shown]]";
+        let links = extract_wikilinks(&strip_comments(content));
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target, "Shown");
+        assert_eq!(links[0].line_number, Some(5));
+
+        // 未闭合的注释延续到文末
+        assert_eq!(strip_comments("keep %% [[Lost]]"), "keep ");
+    }
+
     #[test]
     fn test_line_numbers() {
         let content = "Line 1 [[A]]\nLine 2\nLine 3 [[B]]";
@@ -6,7 +6,7 @@
 //!
 //! Obsidian 适配器负责：
 //! - 解析 Obsidian 格式的 Markdown 文件
-//! - 提取 YAML frontmatter 元数据
+//! - 提取 YAML（`---`）或 TOML（`+++`）frontmatter 元数据
 //! - 识别 wikilink、标签、块引用等 Obsidian 特有语法
 //! - 将解析结果映射到 DCOM 认知对象
 //! - 将 DCOM 对象序列化回 Markdown
@@ -16,6 +16,7 @@
 //! - `pulldown_cmark` - Markdown 解析器
 //! - `regex` - 正则表达式匹配
 //! - `serde_yaml` - YAML Frontmatter 解析
+//! - `toml` - TOML Frontmatter 解析
 //! - [`crate::dcom`] - DCOM 核心数据结构
 //! - [`super`] - 适配器接口定义
 //!
@@ -37,6 +38,14 @@
 //! | `#tag` | 标签 |
 //! | `^blockid` | 块 ID |
 //! | `[[note#^blockid]]` | 块引用链接 |
+//! | `related: "[[link]]"` | frontmatter 引用 |
+//! | `key:: value` | Dataview 内联字段，作为属性加载 |
+//!
+//! ## 加密笔记
+//!
+//! 以 age 或 PGP 加密头（见 [`ENCRYPTED_HEADERS`]）开头的文件不会按 Markdown 解析，
+//! 而是加载为类型为 [`ENCRYPTED_NODE_TYPE`]、内容为空、没有链接和标签的对象；
+//! 这类对象也不能被 [`ObjectAdapter::save`] 写回，以免覆盖密文。
 //!
 //! ## 使用示例
 //!
@@ -54,17 +63,55 @@ mod frontmatter;
 mod links;
 mod parser;
 
-use crate::adapters::{ExtractedLink, ObjectAdapter};
+use crate::adapters::{ExtractedLink, LinkKind, ObjectAdapter};
 use crate::dcom::{
-    serialization::{MarkdownSource, SerializationSource},
+    is_computed_property,
+    serialization::{LineEnding, MarkdownSource, SerializationSource, YamlListStyle},
     CognitiveObject, PropertyValue,
 };
 use anyhow::{Context, Result};
+use pulldown_cmark::Options;
+use std::collections::HashMap;
 use std::path::Path;
 
-pub use frontmatter::Frontmatter;
+pub use frontmatter::{frontmatter_end, parse_frontmatter, yaml_to_property_value, Frontmatter};
 pub use links::BlockReference;
-pub use parser::{parse_markdown, ParsedMarkdown};
+pub use parser::{
+    extract_tags, parse_markdown, parse_markdown_with_options, Heading, ParsedMarkdown,
+    DEFAULT_MARKDOWN_OPTIONS,
+};
+
+/// 加密笔记的节点类型
+pub const ENCRYPTED_NODE_TYPE: &str = "encrypted";
+
+/// 加密文件的起始标记
+///
+/// 文件（忽略开头的 BOM 和空白）以其中任一标记开头时视为加密笔记。
+pub const ENCRYPTED_HEADERS: &[&str] = &[
+    "-----BEGIN AGE ENCRYPTED FILE-----",
+    "age-encryption.org/v1",
+    "-----BEGIN PGP MESSAGE-----",
+];
+
+/// 检查文件内容是否为加密笔记
+///
+/// # 参数
+///
+/// * `content` - 文件原始字节
+///
+/// # 返回值
+///
+/// 以 [`ENCRYPTED_HEADERS`] 中的任一标记开头时返回 `true`
+pub fn is_encrypted(content: &[u8]) -> bool {
+    let content = content.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(content);
+    let start = content
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(content.len());
+    ENCRYPTED_HEADERS
+        .iter()
+        .any(|header| content[start..].starts_with(header.as_bytes()))
+}
 
 /// Obsidian Markdown 适配器
 ///
@@ -72,56 +119,95 @@ pub use parser::{parse_markdown, ParsedMarkdown};
 ///
 /// # 特性
 ///
-/// - 无状态设计，可安全并发使用
+/// - 创建后不可变，可安全并发使用
 /// - 支持完整的 Obsidian Markdown 语法
+/// - 默认启用 GFM 表格、删除线、任务列表和脚注，可通过 [`ObsidianAdapter::with_markdown_options`] 调整
 /// - 提供双向转换（Markdown ↔ CognitiveObject）
 ///
 /// # 支持的扩展名
 ///
 /// - `.md`
 /// - `.markdown`
-#[derive(Debug, Clone, Default)]
-pub struct ObsidianAdapter;
+#[derive(Debug, Clone)]
+pub struct ObsidianAdapter {
+    /// Markdown 扩展选项
+    markdown_options: Options,
+}
 
 impl ObsidianAdapter {
     /// 创建新的 Obsidian 适配器
     pub fn new() -> Self {
-        ObsidianAdapter
+        ObsidianAdapter {
+            markdown_options: DEFAULT_MARKDOWN_OPTIONS,
+        }
+    }
+
+    /// 设置 Markdown 扩展选项
+    ///
+    /// # 参数
+    ///
+    /// * `options` - `pulldown_cmark` 扩展选项，默认为 [`DEFAULT_MARKDOWN_OPTIONS`]
+    pub fn with_markdown_options(mut self, options: Options) -> Self {
+        self.markdown_options = options;
+        self
     }
 
     /// 计算内容哈希
     ///
-    /// 使用简单的哈希算法生成内容指纹。
+    /// 使用 BLAKE3 生成跨平台稳定的内容指纹。
     fn compute_hash(content: &[u8]) -> String {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
+        blake3::hash(content).to_hex().to_string()
+    }
+}
 
-        let mut hasher = DefaultHasher::new();
-        content.hash(&mut hasher);
-        format!("{:016x}", hasher.finish())
+impl Default for ObsidianAdapter {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl ObjectAdapter for ObsidianAdapter {
+    fn name(&self) -> &'static str {
+        "obsidian"
+    }
+
     fn supported_extensions(&self) -> &[&str] {
         &["md", "markdown"]
     }
 
     fn load(&self, path: &Path, content: &[u8]) -> Result<CognitiveObject> {
+        // 加密笔记只记录类型和序列化源，不解析内容
+        if is_encrypted(content) {
+            let mut obj = CognitiveObject::new();
+            obj.set_type(ENCRYPTED_NODE_TYPE);
+            obj.set_content("");
+            let path_str = path.to_string_lossy().to_string();
+            obj.add_source(SerializationSource::Markdown(MarkdownSource::new(
+                path_str,
+                Self::compute_hash(content),
+                chrono::Utc::now().timestamp(),
+            )));
+            return Ok(obj);
+        }
+
         // 将字节转换为 UTF-8 字符串
         let text = std::str::from_utf8(content).context("Markdown 文件必须是 UTF-8 编码")?;
 
         // 解析 Markdown
-        let parsed = parse_markdown(text);
+        let parsed = parse_markdown_with_options(text, self.markdown_options);
 
         // 构建 CognitiveObject
         let mut obj = CognitiveObject::new();
 
         // 设置基本属性
-        obj.set_title(&parsed.title);
+        // 正文没有可用标题时不设置，由调用方回退到文件名；frontmatter 的 title 属性优先
+        if parsed.has_title {
+            obj.set_title(&parsed.title);
+        }
         obj.set_content(&parsed.content);
 
         // 从 frontmatter 获取类型和其他属性
+        let mut raw_values = HashMap::new();
         if let Some(ref fm) = parsed.frontmatter {
             if let Some(ref node_type) = fm.node_type {
                 obj.set_type(node_type);
@@ -138,7 +224,19 @@ impl ObjectAdapter for ObsidianAdapter {
                 if key == "tags" || key == "aliases" || key == "type" || key == "created" {
                     continue;
                 }
-                let prop_value = frontmatter::yaml_to_property_value(value);
+                let prop_value = if frontmatter::LIST_PROPERTIES.contains(&key.as_str()) {
+                    frontmatter::yaml_to_property_list(value)
+                } else {
+                    frontmatter::yaml_to_property_value(value)
+                };
+                // 时长和相对日期记录原始写法，值未改动时按原样写回
+                if let (
+                    serde_yaml::Value::String(raw),
+                    PropertyValue::Duration(_) | PropertyValue::DateTime(_),
+                ) = (value, &prop_value)
+                {
+                    raw_values.insert(key.clone(), (raw.clone(), prop_value.clone()));
+                }
                 obj.set_property(key, prop_value);
             }
 
@@ -148,6 +246,24 @@ impl ObjectAdapter for ObsidianAdapter {
             }
         }
 
+        // 内联字段按出现顺序覆盖 frontmatter 及之前的同名属性；
+        // 只出现在正文中的字段记录下来，保存时留在正文而不写入 frontmatter
+        let in_frontmatter = |key: &str| {
+            parsed.frontmatter.as_ref().is_some_and(|fm| {
+                fm.properties.contains_key(key) || (key == "created" && fm.created.is_some())
+            })
+        };
+        let mut inline_keys: Vec<String> = Vec::new();
+        for (key, value) in &parsed.inline_fields {
+            if key == "title" || key == "content" {
+                continue;
+            }
+            obj.set_property(key, Self::inline_field_value(value));
+            if !in_frontmatter(key) && !inline_keys.contains(key) {
+                inline_keys.push(key.clone());
+            }
+        }
+
         // 设置标签
         for tag in &parsed.tags {
             obj.add_tag(tag);
@@ -157,14 +273,28 @@ impl ObjectAdapter for ObsidianAdapter {
         let content_hash = Self::compute_hash(content);
         let now = chrono::Utc::now().timestamp();
         let path_str = path.to_string_lossy().to_string();
-        let source =
-            SerializationSource::Markdown(MarkdownSource::new(path_str, content_hash, now));
+        let list_styles = parsed
+            .frontmatter
+            .as_ref()
+            .map(|fm| fm.list_styles.clone())
+            .unwrap_or_default();
+        let source = SerializationSource::Markdown(
+            MarkdownSource::new(path_str, content_hash, now)
+                .with_list_styles(list_styles)
+                .with_inline_fields(inline_keys)
+                .with_raw_values(raw_values)
+                .with_line_format(text),
+        );
         obj.add_source(source);
 
         Ok(obj)
     }
 
     fn save(&self, object: &CognitiveObject) -> Result<Vec<u8>> {
+        if object.get_type() == Some(ENCRYPTED_NODE_TYPE) {
+            anyhow::bail!("加密笔记不能写回 Markdown");
+        }
+
         let mut output = String::new();
 
         // 生成 frontmatter
@@ -172,7 +302,7 @@ impl ObjectAdapter for ObsidianAdapter {
         if !fm.is_empty() {
             output.push_str("---\n");
             output.push_str(&fm);
-            output.push_str("---\n\n");
+            output.push_str("\n---\n\n");
         }
 
         // 添加标题
@@ -189,17 +319,44 @@ impl ObjectAdapter for ObsidianAdapter {
             output.push_str(&content_without_title);
         }
 
+        // 沿用源文件的换行符和结尾换行，避免整文件的换行符被改写
+        if let Some(source) = object.markdown_source() {
+            output = Self::apply_line_format(&output, source);
+        }
+
         Ok(output.into_bytes())
     }
 
     fn extract_links(&self, object: &CognitiveObject) -> Vec<ExtractedLink> {
         let mut links_result = Vec::new();
 
-        // 从内容中提取链接
+        // 从内容中提取链接（忽略 `%% 注释 %%`）
         if let Some(content) = object.content() {
-            links_result.extend(links::extract_wikilinks(content));
-            links_result.extend(links::extract_embeds(content));
-            links_result.extend(links::extract_external_links(content));
+            let visible = links::strip_comments(content);
+            links_result.extend(links::extract_wikilinks(&visible));
+            links_result.extend(links::extract_embeds(&visible));
+            links_result.extend(links::extract_external_links(&visible));
+        }
+
+        // 从 frontmatter 属性值中提取引用和 HTTP(S) 地址（按属性名排序，保证结果稳定）
+        let mut names: Vec<&String> = object.properties().keys().collect();
+        names.sort();
+        for name in names {
+            if name == "title" || name == "content" {
+                continue;
+            }
+            let mut texts = Vec::new();
+            Self::collect_strings(&object.properties()[name], &mut texts);
+            for text in texts {
+                if links::is_http_url(text) {
+                    links_result.push(ExtractedLink::new(text.trim(), LinkKind::External));
+                    continue;
+                }
+                links_result.extend(links::extract_wikilinks(text).into_iter().map(|mut link| {
+                    link.kind = LinkKind::FrontmatterRef;
+                    link
+                }));
+            }
         }
 
         links_result
@@ -207,6 +364,31 @@ impl ObjectAdapter for ObsidianAdapter {
 }
 
 impl ObsidianAdapter {
+    /// 转换内联字段的值
+    ///
+    /// 布尔值和数字按 YAML 标量解析，其余（包括 `[[link]]`）保留为原始字符串。
+    fn inline_field_value(value: &str) -> PropertyValue {
+        match serde_yaml::from_str::<serde_yaml::Value>(value) {
+            Ok(v @ (serde_yaml::Value::Bool(_) | serde_yaml::Value::Number(_))) => {
+                frontmatter::yaml_to_property_value(&v)
+            }
+            _ => PropertyValue::string(value),
+        }
+    }
+
+    /// 收集属性值中的所有字符串（递归展开列表）
+    fn collect_strings<'a>(value: &'a PropertyValue, out: &mut Vec<&'a str>) {
+        match value {
+            PropertyValue::String(s) => out.push(s),
+            PropertyValue::List(items) => {
+                for item in items {
+                    Self::collect_strings(item, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// 构建 frontmatter YAML
     fn build_frontmatter(&self, object: &CognitiveObject) -> String {
         let mut lines = Vec::new();
@@ -216,27 +398,41 @@ impl ObsidianAdapter {
             lines.push(format!("type: {}", t));
         }
 
-        // 标签
-        let tags = object.tags();
-        if !tags.is_empty() {
-            let tags_str: Vec<&str> = tags.iter().map(|s| s.as_str()).collect();
-            lines.push(format!("tags: [{}]", tags_str.join(", ")));
+        // 标签、别名及列表型属性，尽量沿用源文件中的书写形式
+        let list_style = |key: &str| {
+            object
+                .markdown_source()
+                .and_then(|source| source.list_styles.get(key).copied())
+        };
+        if let Some(yaml) = Self::list_to_yaml("tags", object.tags(), list_style("tags")) {
+            lines.push(yaml);
         }
-
-        // 别名
-        let aliases = object.aliases();
-        if !aliases.is_empty() {
-            let aliases_str: Vec<&str> = aliases.iter().map(|s| s.as_str()).collect();
-            lines.push(format!("aliases: [{}]", aliases_str.join(", ")));
+        if let Some(yaml) = Self::list_to_yaml("aliases", object.aliases(), list_style("aliases")) {
+            lines.push(yaml);
         }
 
         // 其他属性
+        let inline_fields = object
+            .markdown_source()
+            .map(|source| source.inline_fields.as_slice())
+            .unwrap_or_default();
         for (key, value) in object.properties() {
-            // 跳过内部属性
-            if key == "title" || key == "content" || key == "type" {
+            // 跳过内部属性、计算属性和由正文内联字段表达的属性
+            if key == "title"
+                || key == "content"
+                || key == "type"
+                || is_computed_property(key)
+                || inline_fields.contains(key)
+            {
                 continue;
             }
-            if let Some(yaml) = self.property_to_yaml_line(key, value) {
+            let raw = object
+                .markdown_source()
+                .and_then(|source| source.raw_values.get(key))
+                .filter(|(_, parsed)| parsed == value);
+            if let Some((raw, _)) = raw {
+                lines.push(format!("{}: {}", key, raw));
+            } else if let Some(yaml) = self.property_to_yaml_line(key, value, list_style(key)) {
                 lines.push(yaml);
             }
         }
@@ -244,8 +440,50 @@ impl ObsidianAdapter {
         lines.join("\n")
     }
 
+    /// 按序列化源记录的格式调整换行符和结尾换行
+    ///
+    /// 先统一为 `\n`，再按 `trailing_newline` 补上或去掉结尾换行，最后转换为源文件的换行符。
+    fn apply_line_format(text: &str, source: &MarkdownSource) -> String {
+        let mut text = text.replace("\r\n", "\n");
+        match source.trailing_newline {
+            Some(true) if !text.ends_with('\n') => text.push('\n'),
+            Some(false) => text.truncate(text.trim_end_matches('\n').len()),
+            _ => {}
+        }
+        match source.line_ending {
+            LineEnding::Lf => text,
+            LineEnding::Crlf => text.replace('\n', LineEnding::Crlf.as_str()),
+        }
+    }
+
+    /// 将字符串列表转换为 YAML
+    ///
+    /// 单个标量只在恰好一项时使用；没有记录书写形式或无法沿用时使用块列表。
+    fn list_to_yaml(key: &str, items: &[String], style: Option<YamlListStyle>) -> Option<String> {
+        match (items, style) {
+            ([], _) => None,
+            ([item], Some(YamlListStyle::Scalar)) => Some(format!("{}: {}", key, item)),
+            (_, Some(YamlListStyle::Flow)) => Some(format!("{}: [{}]", key, items.join(", "))),
+            _ => {
+                let mut yaml = format!("{}:", key);
+                for item in items {
+                    yaml.push_str("\n  - ");
+                    yaml.push_str(item);
+                }
+                Some(yaml)
+            }
+        }
+    }
+
     /// 将属性转换为 YAML 行
-    fn property_to_yaml_line(&self, key: &str, value: &PropertyValue) -> Option<String> {
+    ///
+    /// 列表默认写成行内数组，记录了书写形式（`style`）时沿用原写法。
+    fn property_to_yaml_line(
+        &self,
+        key: &str,
+        value: &PropertyValue,
+        style: Option<YamlListStyle>,
+    ) -> Option<String> {
         match value {
             PropertyValue::Null => None,
             PropertyValue::String(s) => Some(format!("{}: \"{}\"", key, s)),
@@ -253,13 +491,21 @@ impl ObsidianAdapter {
             PropertyValue::Float(f) => Some(format!("{}: {}", key, f)),
             PropertyValue::Boolean(b) => Some(format!("{}: {}", key, b)),
             PropertyValue::DateTime(dt) => Some(format!("{}: \"{}\"", key, dt)),
+            PropertyValue::Duration(secs) => Some(format!(
+                "{}: {}",
+                key,
+                crate::dcom::property::format_duration(*secs)
+            )),
             PropertyValue::Reference(r) => Some(format!("{}: \"[[{}]]\"", key, r)),
             PropertyValue::List(items) => {
                 let items_str: Vec<String> = items
                     .iter()
                     .filter_map(|v| self.property_to_yaml_value(v))
                     .collect();
-                Some(format!("{}: [{}]", key, items_str.join(", ")))
+                if items_str.is_empty() {
+                    return Some(format!("{}: []", key));
+                }
+                Self::list_to_yaml(key, &items_str, style.or(Some(YamlListStyle::Flow)))
             }
             PropertyValue::Json(j) => Some(format!("{}: {}", key, j)),
         }
@@ -274,6 +520,7 @@ impl ObsidianAdapter {
             PropertyValue::Float(f) => Some(f.to_string()),
             PropertyValue::Boolean(b) => Some(b.to_string()),
             PropertyValue::DateTime(dt) => Some(format!("\"{}\"", dt)),
+            PropertyValue::Duration(secs) => Some(crate::dcom::property::format_duration(*secs)),
             PropertyValue::Reference(r) => Some(format!("\"[[{}]]\"", r)),
             _ => None,
         }
@@ -296,6 +543,7 @@ impl ObsidianAdapter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dcom::ObjectRef;
 
     #[test]
     fn test_obsidian_adapter_supported_extensions() {
@@ -332,6 +580,86 @@ mod tests {
         assert!(obj.tags().contains(&"test".to_string()));
     }
 
+    #[test]
+    fn test_obsidian_adapter_load_inline_fields() {
+        let adapter = ObsidianAdapter::new();
+        let content =
+            b"---\nstatus: draft\n---\n# Task\n\nstatus:: active\npriority:: 3\nsee:: [[Other]]";
+
+        let obj = adapter.load(Path::new("task.md"), content).unwrap();
+
+        assert_eq!(
+            obj.get_property("status"),
+            Some(&PropertyValue::string("active"))
+        );
+        assert_eq!(
+            obj.get_property("priority"),
+            Some(&PropertyValue::integer(3))
+        );
+        assert_eq!(
+            obj.get_property("see"),
+            Some(&PropertyValue::string("[[Other]]"))
+        );
+    }
+
+    #[test]
+    fn test_obsidian_adapter_duration_and_relative_date_round_trip() {
+        let adapter = ObsidianAdapter::new();
+        let content = "---\nestimate: 2h\ndue: +3d\nspent: 30m\n---\n# Task\n";
+
+        let mut obj = adapter
+            .load(Path::new("task.md"), content.as_bytes())
+            .unwrap();
+        assert_eq!(
+            obj.get_property("estimate"),
+            Some(&PropertyValue::Duration(7200))
+        );
+        assert!(matches!(
+            obj.get_property("due"),
+            Some(PropertyValue::DateTime(_))
+        ));
+
+        // 未改动的值按原始写法写回，改动过的值按新值格式化
+        obj.set_property("spent", PropertyValue::Duration(5400));
+        let saved = String::from_utf8(adapter.save(&obj).unwrap()).unwrap();
+        assert!(saved.contains("estimate: 2h\n"));
+        assert!(saved.contains("due: +3d\n"));
+        assert!(saved.contains("spent: 1h30m\n"));
+        assert_eq!(
+            adapter
+                .load(Path::new("task.md"), saved.as_bytes())
+                .unwrap()
+                .get_property("spent"),
+            Some(&PropertyValue::Duration(5400))
+        );
+    }
+
+    #[test]
+    fn test_obsidian_adapter_save_keeps_inline_fields_in_body() {
+        let adapter = ObsidianAdapter::new();
+        let content = "---\nstatus: draft\n---\n# Task\n\nstatus:: active\npriority:: 3\n";
+
+        let mut obj = adapter
+            .load(Path::new("task.md"), content.as_bytes())
+            .unwrap();
+        obj.set_property("owner", PropertyValue::string("Alice"));
+        let saved = String::from_utf8(adapter.save(&obj).unwrap()).unwrap();
+
+        let (fm, body) = parse_frontmatter(&saved);
+        let fm = fm.unwrap();
+        assert!(fm.properties.contains_key("status"));
+        assert!(fm.properties.contains_key("owner"));
+        assert!(!fm.properties.contains_key("priority"));
+        assert!(body.contains("priority:: 3"));
+        assert_eq!(
+            adapter
+                .load(Path::new("task.md"), saved.as_bytes())
+                .unwrap()
+                .get_property("priority"),
+            Some(&PropertyValue::integer(3))
+        );
+    }
+
     #[test]
     fn test_obsidian_adapter_save() {
         let adapter = ObsidianAdapter::new();
@@ -347,10 +675,132 @@ mod tests {
 
         assert!(saved_str.contains("---"));
         assert!(saved_str.contains("type: note"));
-        assert!(saved_str.contains("tags: [rust]"));
+        assert!(saved_str.contains("tags:\n  - rust"));
         assert!(saved_str.contains("# Test Note"));
     }
 
+    #[test]
+    fn test_obsidian_adapter_save_reference_round_trip() {
+        let adapter = ObsidianAdapter::new();
+
+        let reference = ObjectRef::new("Home")
+            .with_anchor("Section")
+            .with_display("Go Home");
+        let mut obj = CognitiveObject::new();
+        obj.set_title("Test Note");
+        obj.set_property("parent", PropertyValue::Reference(reference.clone()));
+
+        let saved = adapter.save(&obj).unwrap();
+        assert!(String::from_utf8_lossy(&saved).contains("parent: \"[[Home#Section|Go Home]]\""));
+
+        let loaded = adapter.load(Path::new("test.md"), &saved).unwrap();
+        let parent = loaded.get_property("parent").and_then(|v| v.as_string());
+        assert_eq!(parent.map(ObjectRef::parse), Some(reference));
+    }
+
+    #[test]
+    fn test_obsidian_adapter_save_skips_computed_properties() {
+        let adapter = ObsidianAdapter::new();
+
+        let mut obj = CognitiveObject::new();
+        obj.set_title("Test Note");
+        obj.set_property("author", "Alice");
+        obj.set_property("_word_count", 42i64);
+        obj.set_property("_private", true);
+
+        let saved = String::from_utf8(adapter.save(&obj).unwrap()).unwrap();
+
+        assert!(saved.contains("author: \"Alice\""));
+        assert!(!saved.contains("_word_count"));
+        // 用户写的下划线属性不是计算属性，照常保存
+        assert!(saved.contains("_private: true"));
+    }
+
+    #[test]
+    fn test_obsidian_adapter_save_preserves_list_styles() {
+        let adapter = ObsidianAdapter::new();
+
+        let content = "---\naliases: Solo\ntags:\n  - rust\n  - wasm\n---\n# Note\n\nBody";
+        let obj = adapter
+            .load(Path::new("note.md"), content.as_bytes())
+            .unwrap();
+        let saved = String::from_utf8(adapter.save(&obj).unwrap()).unwrap();
+
+        assert!(saved.contains("aliases: Solo\n"));
+        assert!(saved.contains("tags:\n  - rust\n  - wasm"));
+
+        // 行内数组保持行内；标量变为多项时改用块列表
+        let content = "---\naliases: Solo\ntags: [rust]\n---\n# Note\n\nBody";
+        let mut obj = adapter
+            .load(Path::new("note.md"), content.as_bytes())
+            .unwrap();
+        obj.add_alias("Other");
+        let saved = String::from_utf8(adapter.save(&obj).unwrap()).unwrap();
+
+        assert!(saved.contains("tags: [rust]"));
+        assert!(saved.contains("aliases:\n  - Solo\n  - Other"));
+
+        // 重新加载后内容一致
+        let reloaded = adapter
+            .load(Path::new("note.md"), saved.as_bytes())
+            .unwrap();
+        assert_eq!(reloaded.aliases(), ["Solo", "Other"]);
+        assert_eq!(reloaded.tags(), ["rust"]);
+    }
+
+    #[test]
+    fn test_obsidian_adapter_save_preserves_line_endings() {
+        let adapter = ObsidianAdapter::new();
+
+        let content = "---\r\ntags: [rust]\r\n---\r\n# Note\r\n\r\nFirst\r\nSecond\r\n";
+        let obj = adapter
+            .load(Path::new("note.md"), content.as_bytes())
+            .unwrap();
+        let saved = String::from_utf8(adapter.save(&obj).unwrap()).unwrap();
+
+        assert_eq!(saved.matches('\n').count(), saved.matches("\r\n").count());
+        assert!(saved.contains("tags: [rust]\r\n"));
+        assert!(saved.ends_with("First\r\nSecond\r\n"));
+
+        // LF 笔记保持 LF，且不补结尾换行
+        let content = "---\ntags: [rust]\n---\n# Note\n\nBody";
+        let obj = adapter
+            .load(Path::new("note.md"), content.as_bytes())
+            .unwrap();
+        let saved = String::from_utf8(adapter.save(&obj).unwrap()).unwrap();
+
+        assert!(!saved.contains('\r'));
+        assert!(saved.ends_with("\n\nBody"));
+    }
+
+    #[test]
+    fn test_obsidian_adapter_list_properties() {
+        let adapter = ObsidianAdapter::new();
+        let list = |items: &[&str]| {
+            PropertyValue::List(items.iter().map(|s| PropertyValue::string(*s)).collect())
+        };
+
+        let content = "---\ncssclasses: foo\n---\n# Note\n\nBody";
+        let obj = adapter
+            .load(Path::new("note.md"), content.as_bytes())
+            .unwrap();
+        assert_eq!(obj.get_property("cssclasses"), Some(&list(&["foo"])));
+
+        // 标量写法在保存后保持不变
+        let saved = String::from_utf8(adapter.save(&obj).unwrap()).unwrap();
+        assert!(saved.contains("cssclasses: \"foo\"\n"));
+        let reloaded = adapter
+            .load(Path::new("note.md"), saved.as_bytes())
+            .unwrap();
+        assert_eq!(reloaded.get_property("cssclasses"), Some(&list(&["foo"])));
+
+        let content = "---\ncssclasses: [a, b]\n---\n# Note\n\nBody";
+        let obj = adapter
+            .load(Path::new("note.md"), content.as_bytes())
+            .unwrap();
+        assert_eq!(obj.get_property("cssclasses"), Some(&list(&["a", "b"])));
+    }
+
     #[test]
     fn test_obsidian_adapter_extract_links() {
         let adapter = ObsidianAdapter::new();
@@ -365,6 +815,58 @@ mod tests {
         assert!(links.iter().any(|l| l.target == "Page B"));
     }
 
+    #[test]
+    fn test_obsidian_adapter_extract_links_skips_comments() {
+        let adapter = ObsidianAdapter::new();
+
+        let mut obj = CognitiveObject::new();
+        obj.set_content("See [[Visible]].\n%%\nTODO: [[Hidden]] ![[hidden.png]]\n%%");
+
+        let links = adapter.extract_links(&obj);
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target, "Visible");
+        assert!(obj.content().unwrap().contains("[[Hidden]]"));
+    }
+
+    #[test]
+    fn test_obsidian_adapter_extract_frontmatter_refs() {
+        let adapter = ObsidianAdapter::new();
+        let content = b"---\nrelated: \"[[Page A]]\"\nsee:\n  - \"[[Page B]]\"\n  - plain\n---\n# Note\n\nBody [[Page C]].";
+        let obj = adapter.load(Path::new("note.md"), content).unwrap();
+
+        let links = adapter.extract_links(&obj);
+        let kind_of = |target: &str| {
+            links
+                .iter()
+                .find(|l| l.target == target)
+                .map(|l| l.kind.clone())
+        };
+
+        assert_eq!(kind_of("Page A"), Some(LinkKind::FrontmatterRef));
+        assert_eq!(kind_of("Page B"), Some(LinkKind::FrontmatterRef));
+        assert_eq!(kind_of("Page C"), Some(LinkKind::WikiLink));
+        assert_eq!(links.len(), 3);
+    }
+
+    #[test]
+    fn test_obsidian_adapter_load_encrypted() {
+        let adapter = ObsidianAdapter::new();
+        let content = b"-----BEGIN AGE ENCRYPTED FILE-----\nYWdlLWVuY3J5cHRpb24ub3JnL3YxCi0+IFgyNTUxOSBbW0xpbmtdXQ==\n[[Secret]] #tag\n-----END AGE ENCRYPTED FILE-----\n";
+
+        let obj = adapter.load(Path::new("secret.md"), content).unwrap();
+
+        assert_eq!(obj.get_type(), Some(ENCRYPTED_NODE_TYPE));
+        assert_eq!(obj.title(), None);
+        assert_eq!(obj.content(), Some(""));
+        assert!(obj.tags().is_empty());
+        assert!(adapter.extract_links(&obj).is_empty());
+        assert!(adapter.save(&obj).is_err());
+
+        assert!(is_encrypted(b"\n  -----BEGIN PGP MESSAGE-----\n"));
+        assert!(!is_encrypted(b"# Note\n\n-----BEGIN PGP MESSAGE-----\n"));
+    }
+
     #[test]
     fn test_compute_hash() {
         let hash1 = ObsidianAdapter::compute_hash(b"hello");
@@ -373,5 +875,9 @@ mod tests {
 
         assert_eq!(hash1, hash2);
         assert_ne!(hash1, hash3);
+        assert_eq!(
+            hash1,
+            "ea8f163db38682925e4491c5e58d4bb3506ef8c14eb78a86e908c5624a67200f"
+        );
     }
 }
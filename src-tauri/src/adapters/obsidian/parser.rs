@@ -13,9 +13,15 @@
 //!
 //! ### 结构体
 //! - [`ParsedMarkdown`] - 解析后的 Markdown 数据
+//! - [`Heading`] - 标题（大纲条目）
 //!
 //! ### 函数
-//! - [`parse_markdown`] - 解析 Markdown 内容
+//! - [`parse_markdown`] - 解析 Markdown 内容（启用默认扩展）
+//! - [`parse_markdown_with_options`] - 使用指定扩展解析 Markdown 内容
+//! - [`extract_tags`] - 提取内联 `#tag` 标签（支持 Unicode 和 emoji）
+//!
+//! ### 常量
+//! - [`DEFAULT_MARKDOWN_OPTIONS`] - 默认启用的 Markdown 扩展
 //!
 //! ## 使用示例
 //!
@@ -28,8 +34,8 @@
 //! ```
 
 use super::frontmatter::{parse_frontmatter, Frontmatter};
-use super::links::{extract_block_references, BlockReference};
-use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use super::links::{extract_block_references, strip_comments, BlockReference};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -42,16 +48,22 @@ use std::sync::LazyLock;
 /// # 字段说明
 ///
 /// * `title` - 文档标题，从第一个 heading 提取
+/// * `has_title` - 标题是否来自正文（heading 或首行），为 `false` 时 `title` 为 `"Untitled"` 占位
 /// * `content` - 去除 frontmatter 后的 Markdown 内容
 /// * `raw_content` - 原始完整内容
 /// * `frontmatter` - 解析后的 Frontmatter 数据
 /// * `wikilinks` - 提取的 wikilinks 列表（去重）
-/// * `tags` - 提取的标签列表（去重，合并 frontmatter 和正文）
+/// * `tags` - 提取的标签列表（去重，合并 frontmatter 和正文，按首次出现顺序排列）
 /// * `block_ids` - Block ID 列表
+/// * `inline_fields` - Dataview 风格的内联字段 `key:: value`，按出现顺序排列
+/// * `headings` - 所有标题，按出现顺序排列
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedMarkdown {
     /// 文档标题
     pub title: String,
+    /// 标题是否来自正文
+    #[serde(default)]
+    pub has_title: bool,
     /// 去除 frontmatter 后的内容
     pub content: String,
     /// 原始完整内容
@@ -65,19 +77,70 @@ pub struct ParsedMarkdown {
     /// Block ID 列表
     #[serde(default)]
     pub block_ids: Vec<BlockReference>,
+    /// 内联字段 `(key, value)` 列表
+    #[serde(default)]
+    pub inline_fields: Vec<(String, String)>,
+    /// 标题列表
+    #[serde(default)]
+    pub headings: Vec<Heading>,
+}
+
+/// 标题
+///
+/// # 字段说明
+///
+/// * `level` - 标题级别（1-6）
+/// * `text` - 标题文本（不含 `#` 标记和行内格式符号）
+/// * `line_number` - 标题在原始内容（含 frontmatter）中的行号，从 1 开始
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Heading {
+    /// 标题级别
+    pub level: u8,
+    /// 标题文本
+    pub text: String,
+    /// 行号（从 1 开始）
+    pub line_number: usize,
 }
 
 // 预编译正则表达式
 static WIKILINK_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[\[([^\]]+)\]\]").unwrap());
-static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?:^|[^\w#])#([\w\-_/]+)").unwrap());
+// 标签字符：Unicode 字母、数字、`_`、`-`、`/` 及 emoji（含肤色修饰符和变体选择符）
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^\w#])#([\w\-/\p{Extended_Pictographic}\p{Emoji_Modifier}\x{FE0F}]+)")
+        .unwrap()
+});
+static INLINE_FIELD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:[-*+]\s+)?(\w[\w \-]*?)::\s*(.*?)\s*$").unwrap());
+static BRACKET_FIELD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\[(](\w[\w \-]*?)::\s*([^\])]*?)\s*[\])]").unwrap());
+
+/// 默认启用的 Markdown 扩展
+///
+/// GFM 表格、删除线、任务列表和脚注，使 `~~删除~~` 等语法不会混入标题文本。
+pub const DEFAULT_MARKDOWN_OPTIONS: Options = Options::ENABLE_TABLES
+    .union(Options::ENABLE_STRIKETHROUGH)
+    .union(Options::ENABLE_TASKLISTS)
+    .union(Options::ENABLE_FOOTNOTES);
 
 /// 解析 Markdown 内容
 ///
+/// 使用 [`DEFAULT_MARKDOWN_OPTIONS`] 解析，详见 [`parse_markdown_with_options`]。
+///
+/// # 参数
+///
+/// * `content` - Markdown 文本内容
+pub fn parse_markdown(content: &str) -> ParsedMarkdown {
+    parse_markdown_with_options(content, DEFAULT_MARKDOWN_OPTIONS)
+}
+
+/// 使用指定扩展解析 Markdown 内容
+///
 /// 从 Obsidian Markdown 文本中提取 frontmatter、标题、wikilinks 和标签。
 ///
 /// # 参数
 ///
 /// * `content` - Markdown 文本内容
+/// * `options` - `pulldown_cmark` 扩展选项，影响标题和大纲的提取
 ///
 /// # 返回值
 ///
@@ -86,53 +149,74 @@ static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?:^|[^\w#])#([\w
 /// # 解析规则
 ///
 /// - **Frontmatter**：提取 `---` 包围的 YAML 元数据
-/// - **标题**：提取第一个 heading 的文本，如果没有则使用第一行
+/// - **标题**：提取第一个 heading 的文本，如果没有则使用第一个正文行（跳过空行、内联字段和 HTML 注释）
 /// - **Wikilinks**：匹配 `[[link]]` 或 `[[link|alias]]` 格式
 /// - **标签**：匹配 `#tag` 格式 + frontmatter 中的 tags
 /// - **Block IDs**：匹配 `^blockid` 格式
+/// - **内联字段**：匹配整行 `key:: value` 以及行内 `[key:: value]`、`(key:: value)`，跳过代码块
+/// - **注释**：`%% ... %%` 中的 wikilinks、标签、Block IDs 和内联字段不提取，`content` 中保留原文
 ///
 /// # 副作用
 ///
 /// 无副作用，纯函数
-pub fn parse_markdown(content: &str) -> ParsedMarkdown {
+pub fn parse_markdown_with_options(content: &str, options: Options) -> ParsedMarkdown {
     // 解析 frontmatter
     let (frontmatter, body_content) = parse_frontmatter(content);
 
     let mut title = String::new();
     let mut wikilinks = HashSet::new();
-    let mut tags = HashSet::new();
+    // 标签保持首次出现顺序，保存时 frontmatter 中的顺序不会被打乱
+    let mut tags: Vec<String> = Vec::new();
+    let mut seen_tags = HashSet::new();
 
     // 添加 frontmatter 中的标签
     if let Some(ref fm) = frontmatter {
         for tag in &fm.tags {
-            tags.insert(tag.clone());
+            if seen_tags.insert(tag.clone()) {
+                tags.push(tag.clone());
+            }
         }
     }
 
-    // 提取标题（第一个 heading）
-    let parser = Parser::new(&body_content);
-    let mut in_heading = false;
+    // 提取所有 heading，第一个非空 heading 作为标题
+    // 正文是原始内容的后缀，据此换算行号
+    let body_offset = content.len() - body_content.len();
+    let mut headings: Vec<Heading> = Vec::new();
+    let mut current: Option<Heading> = None;
 
-    for event in parser {
+    for (event, range) in Parser::new_ext(&body_content, options).into_offset_iter() {
         match event {
-            Event::Start(Tag::Heading { level: _, .. }) => {
-                in_heading = true;
+            Event::Start(Tag::Heading { level, .. }) => {
+                let line_number = content[..body_offset + range.start].matches('\n').count() + 1;
+                current = Some(Heading {
+                    level: level as u8,
+                    text: String::new(),
+                    line_number,
+                });
             }
             Event::End(TagEnd::Heading(_)) => {
-                in_heading = false;
-                if !title.is_empty() {
-                    break;
+                if let Some(mut heading) = current.take() {
+                    heading.text = heading.text.trim().to_string();
+                    if title.is_empty() {
+                        title = heading.text.clone();
+                    }
+                    headings.push(heading);
                 }
             }
-            Event::Text(text) if in_heading => {
-                title.push_str(&text);
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = current.as_mut() {
+                    heading.text.push_str(&text);
+                }
             }
             _ => {}
         }
     }
 
+    // 注释内容不参与链接和标签提取
+    let visible_content = strip_comments(&body_content);
+
     // 提取 wikilinks [[link]]
-    for cap in WIKILINK_RE.captures_iter(&body_content) {
+    for cap in WIKILINK_RE.captures_iter(&visible_content) {
         if let Some(link) = cap.get(1) {
             let link_text = link.as_str();
             // 处理 [[link|alias]] 格式，提取实际链接
@@ -143,42 +227,142 @@ pub fn parse_markdown(content: &str) -> ParsedMarkdown {
         }
     }
 
-    // 提取标签 #tag（但不在代码块中）
-    for cap in TAG_RE.captures_iter(&body_content) {
-        if let Some(tag) = cap.get(1) {
-            tags.insert(tag.as_str().to_string());
+    // 提取标签 #tag（但不在代码块中），纯数字不视为标签
+    for tag in extract_tags(&visible_content, false) {
+        if seen_tags.insert(tag.clone()) {
+            tags.push(tag);
         }
     }
 
-    // 如果没有找到标题，尝试从第一行提取
+    // 如果没有找到标题，尝试从第一个正文行提取
     if title.is_empty() {
-        title = body_content
-            .lines()
-            .next()
-            .unwrap_or("Untitled")
-            .trim()
-            .trim_start_matches('#')
-            .trim()
-            .to_string();
+        if let Some(line) = first_prose_line(&body_content) {
+            title = line.trim_start_matches('#').trim().to_string();
+        }
     }
 
-    // 如果标题还是空，使用 "Untitled"
-    if title.is_empty() {
+    // 如果标题还是空，使用 "Untitled" 占位
+    let has_title = !title.is_empty();
+    if !has_title {
         title = "Untitled".to_string();
     }
 
     // 提取 block IDs
-    let block_ids = extract_block_references(&body_content);
+    let block_ids = extract_block_references(&visible_content);
+
+    let inline_fields = extract_inline_fields(&visible_content);
 
     ParsedMarkdown {
         title,
+        has_title,
         content: body_content,
         raw_content: content.to_string(),
         frontmatter,
         wikilinks: wikilinks.into_iter().collect(),
-        tags: tags.into_iter().collect(),
+        tags,
         block_ids,
+        inline_fields,
+        headings,
+    }
+}
+
+/// 提取 Dataview 风格的内联字段
+///
+/// 支持整行的 `key:: value`（可带列表标记）和行内的 `[key:: value]`、`(key:: value)`。
+/// 围栏代码块中的内容会被跳过。
+///
+/// # 参数
+///
+/// * `content` - 去除 frontmatter 和注释后的正文
+///
+/// # 返回值
+///
+/// 按出现顺序排列的 `(key, value)` 列表，同名字段保留全部出现
+fn extract_inline_fields(content: &str) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    let mut in_code_block = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        let before = fields.len();
+        for cap in BRACKET_FIELD_RE.captures_iter(line) {
+            fields.push((cap[1].trim().to_string(), cap[2].to_string()));
+        }
+
+        if fields.len() == before {
+            if let Some(cap) = INLINE_FIELD_RE.captures(line) {
+                fields.push((cap[1].trim().to_string(), cap[2].to_string()));
+            }
+        }
     }
+
+    fields
+}
+
+/// 提取内联标签
+///
+/// 标签以 `#` 开头，前面必须是行首或非单词字符（如 `a#b` 不是标签），
+/// 由 Unicode 字母、数字、`_`、`-`、`/` 和 emoji 组成，遇到空白或其他标点结束。
+/// 与 Obsidian 一致，默认不接受纯数字标签（如 `#1`、`#2024`）。
+///
+/// # 参数
+///
+/// * `content` - 去除 frontmatter 和注释后的正文
+/// * `allow_numeric` - 是否接受纯数字标签
+///
+/// # 返回值
+///
+/// 按出现顺序排列的标签名（不含 `#`），未去重
+pub fn extract_tags(content: &str, allow_numeric: bool) -> Vec<String> {
+    TAG_RE
+        .captures_iter(content)
+        .map(|cap| cap[1].to_string())
+        .filter(|tag| allow_numeric || !tag.chars().all(|c| c.is_ascii_digit()))
+        .collect()
+}
+
+/// 查找可作为标题的第一个正文行
+///
+/// 跳过空行、`key:: value` 内联字段和 HTML 注释（含跨行注释）。
+/// 遇到分隔线（如未闭合 frontmatter 的 `---`）时返回 `None`，不再向后查找。
+fn first_prose_line(body: &str) -> Option<&str> {
+    let mut in_comment = false;
+    for line in body.lines().map(str::trim) {
+        if in_comment {
+            in_comment = !line.contains("-->");
+            continue;
+        }
+        if line.is_empty() || INLINE_FIELD_RE.is_match(line) {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("<!--") {
+            in_comment = !rest.contains("-->");
+            continue;
+        }
+        if is_separator_line(line) {
+            return None;
+        }
+        return Some(line);
+    }
+    None
+}
+
+/// 检查是否为分隔线
+///
+/// 由三个及以上相同的 `-`、`*`、`_` 或 `=` 组成的行（Markdown 分隔线或 Setext 下划线）。
+fn is_separator_line(line: &str) -> bool {
+    let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    chars.len() >= 3
+        && matches!(chars[0], '-' | '*' | '_' | '=')
+        && chars.iter().all(|c| *c == chars[0])
 }
 
 #[cfg(test)]
@@ -244,6 +428,48 @@ mod tests {
         assert_eq!(parsed.block_ids[0].id, "abc123");
     }
 
+    #[test]
+    fn test_parse_markdown_headings() {
+        let content = "---\ntags: [a]\n---\n# Top\n\nIntro\n\n## Child `code`\n\n### Grandchild\n\n```\n# not a heading\n```\n\n## Second\n";
+        let parsed = parse_markdown(content);
+
+        let outline: Vec<(u8, &str, usize)> = parsed
+            .headings
+            .iter()
+            .map(|h| (h.level, h.text.as_str(), h.line_number))
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                (1, "Top", 4),
+                (2, "Child code", 8),
+                (3, "Grandchild", 10),
+                (2, "Second", 16),
+            ]
+        );
+        assert_eq!(parsed.title, "Top");
+    }
+
+    #[test]
+    fn test_parse_markdown_gfm() {
+        let content = "# ~~Old~~ New\n\n| Col | Val |\n|-----|-----|\n| a | b |\n\n- [ ] task #todo\n- [x] done[^1]\n\n[^1]: Footnote.\n\n## Next\n";
+        let parsed = parse_markdown(content);
+
+        assert_eq!(parsed.title, "Old New");
+        assert_eq!(parsed.headings.len(), 2);
+        assert_eq!(parsed.headings[1].text, "Next");
+        assert_eq!(parsed.headings[1].line_number, 12);
+        assert!(parsed.tags.contains(&"todo".to_string()));
+
+        // 表格开头的笔记仍以第一个 heading 作为标题
+        let parsed = parse_markdown("| A | B |\n|---|---|\n| 1 | 2 |\n\n## Real Title\n");
+        assert_eq!(parsed.title, "Real Title");
+
+        // 关闭扩展时删除线按原文保留
+        let parsed = parse_markdown_with_options(content, Options::empty());
+        assert_eq!(parsed.title, "~~Old~~ New");
+    }
+
     #[test]
     fn test_parse_markdown_no_title() {
         let content = "Just some content without heading.";
@@ -259,6 +485,41 @@ mod tests {
         let parsed = parse_markdown(content);
 
         assert_eq!(parsed.title, "Untitled");
+        assert!(!parsed.has_title);
+    }
+
+    #[test]
+    fn test_parse_markdown_without_usable_title() {
+        // 仅包含 frontmatter
+        let parsed = parse_markdown("---\ntags: [a]\n---\n");
+        assert!(!parsed.has_title);
+
+        // 未闭合的 frontmatter，首行为 `---`
+        let parsed = parse_markdown("---\nkey: value\n");
+        assert!(!parsed.has_title);
+        assert_eq!(parsed.title, "Untitled");
+
+        // 首行为空时使用第一个非空行
+        let parsed = parse_markdown("\n\nFirst line");
+        assert!(parsed.has_title);
+        assert_eq!(parsed.title, "First line");
+    }
+
+    #[test]
+    fn test_parse_markdown_title_skips_fields_and_comments() {
+        let parsed = parse_markdown("status:: active\n- due:: 2024-01-01\n\nReal prose here");
+        assert_eq!(parsed.title, "Real prose here");
+
+        let parsed = parse_markdown("<!-- comment -->\nProse after comment");
+        assert_eq!(parsed.title, "Prose after comment");
+
+        let parsed = parse_markdown("<!--\nmulti-line\ncomment\n-->\nProse");
+        assert_eq!(parsed.title, "Prose");
+
+        // 没有正文行时回退到文件名
+        let parsed = parse_markdown("status:: active\n<!-- only metadata -->\n");
+        assert!(!parsed.has_title);
+        assert_eq!(parsed.title, "Untitled");
     }
 
     #[test]
@@ -271,6 +532,55 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn test_parse_markdown_ignores_comments() {
+        let content = "# Test\n\n[[Outside]] #shown %%[[Inline]] #hidden%%\n%%\n[[Block]]\n%%";
+        let parsed = parse_markdown(content);
+
+        assert_eq!(parsed.wikilinks, vec!["Outside"]);
+        assert_eq!(parsed.tags, vec!["shown"]);
+        // 原始内容保留注释
+        assert!(parsed.content.contains("%%[[Inline]] #hidden%%"));
+    }
+
+    #[test]
+    fn test_parse_markdown_inline_fields() {
+        let content = "# Task\n\nstatus:: active\n- priority:: 3\nDue [due:: 2024-01-01] and (owner:: Bob).\n```\nignored:: yes\n```\nstatus:: done";
+        let parsed = parse_markdown(content);
+
+        let field = |key: &str| -> Vec<&str> {
+            parsed
+                .inline_fields
+                .iter()
+                .filter(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+                .collect()
+        };
+
+        assert_eq!(field("status"), vec!["active", "done"]);
+        assert_eq!(field("priority"), vec!["3"]);
+        assert_eq!(field("due"), vec!["2024-01-01"]);
+        assert_eq!(field("owner"), vec!["Bob"]);
+        assert!(field("ignored").is_empty());
+    }
+
+    #[test]
+    fn test_parse_markdown_unicode_tags() {
+        let content =
+            "# Test\n\n#日本語 と #projet-français, lire #📚.\n# alone, #1 #2024 but #y2024";
+        let parsed = parse_markdown(content);
+
+        assert_eq!(
+            parsed.tags,
+            vec!["日本語", "projet-français", "📚", "y2024"]
+        );
+
+        // 纯数字标签可按需接受
+        assert_eq!(extract_tags("Issue #1 and #a1", true), vec!["1", "a1"]);
+        assert!(extract_tags("#", true).is_empty());
+        assert!(extract_tags("a#tag", false).is_empty());
+    }
+
     #[test]
     fn test_parse_markdown_dedup_tags() {
         let content = "---\ntags: [dup]\n---\n# Test\n\n#dup here and #dup again";
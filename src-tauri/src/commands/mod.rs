@@ -6,22 +6,84 @@
 //!
 //! - [`crate::db`] - 数据库操作
 //! - [`crate::sync`] - 文件同步和监听
+//! - [`crate::linkcheck`] - 外部链接检查
+//! - [`crate::config`] - 知识库配置
 //!
 //! ## 导出的主要内容
 //!
 //! ### 结构体
 //! - [`AppState`] - 应用程序全局状态
 //! - [`FileNode`] - 文件树节点
+//! - [`SyncProgress`] - 同步进度事件载荷
+//! - [`SyncError`] - 同步错误事件载荷
+//! - [`OutlineItem`] - 笔记大纲条目
+//! - [`NoteBundle`] - 打开笔记所需的全部数据
 //!
 //! ### 命令
 //! - [`open_vault`] - 打开知识库
-//! - [`get_graph_data`] - 获取图数据
+//! - [`reindex_vault`] - 重新索引知识库并创建快照
+//! - [`get_config`] / [`save_config`] - 读取和保存知识库配置
+//! - [`rebuild_graph_edges`] - 仅根据已索引内容重建链接图
+//! - [`diff_last_sync`] - 比较最近两次索引快照
+//! - [`get_graph_data`] - 获取图数据（超过节点上限时按连接度裁剪）
+//! - [`get_full_graph`] - 获取不裁剪的完整图数据
+//! - [`get_graph_metadata`] - 获取不含节点正文的轻量图数据
+//! - [`get_force_graph`] - 获取力导向布局格式的图数据
+//! - [`get_cytoscape_graph`] - 获取 Cytoscape.js 格式的图数据
+//! - [`export_note_subgraph`] - 导出笔记邻域子图（含属性和标签）
+//! - [`export_vault_ndjson`] / [`import_vault_ndjson`] - 以 NDJSON 文件导出和导入整个索引
+//! - [`save_node_position`] / [`get_node_positions`] - 保存和读取图谱节点坐标
+//! - [`get_backlink_index`] - 获取（缓存的）反向链接索引
 //! - [`get_file_tree`] - 获取文件树
+//! - [`get_file_tree_level`] - 按需获取单层目录内容
 //! - [`get_file_content`] - 获取文件内容
-//! - [`save_file`] - 保存文件
+//! - [`open_note`] - 一次获取笔记内容、元数据、出链和反向链接
+//! - [`resolve_wikilink`] - 将 wikilink 目标解析为文件路径
+//! - [`get_embedders`] - 获取嵌入指定文件的笔记
+//! - [`get_related_notes`] - 获取经常与指定笔记被一起链接的笔记
+//! - [`get_file_contents`] - 批量获取多个文件的内容
+//! - [`get_content_range`] - 按行获取文件内容片段
+//! - [`get_block_content`] - 获取块引用 `^block` 对应的内容
+//! - [`save_file`] - 原子保存文件，可选备份原内容
+//! - [`append_to_note`] - 向笔记末尾追加一行并重新同步该笔记
 //! - [`search_nodes`] - 搜索节点
+//! - [`advanced_search`] - 按文本、类型、时间和标签组合搜索
+//! - [`get_random_note`] - 随机获取一篇笔记
+//! - [`get_changes_since`] - 获取指定时间之后修改过的笔记
+//! - [`validate_query`] - 校验 CozoScript 的语法及是否只读，不执行写入
 //! - [`get_vault_statistics`] - 获取 Vault 统计信息
+//! - [`get_link_statistics`] - 获取链接总数、断链数和入链最多的笔记
+//! - [`get_all_degrees`] - 获取所有节点的入度和出度
+//! - [`find_exact_duplicates`] - 查找内容完全相同的笔记
+//! - [`get_referenced_titles`] - 获取所有被引用的链接目标及引用数（含未解析的）
+//! - [`find_link_cycles`] - 查找笔记之间的链接环
+//! - [`get_activity`] - 按天、周或月统计创建/更新的笔记数量
+//! - [`get_note_stats`] - 获取笔记字数、字符数和阅读时间
+//! - [`get_note_outline`] - 获取笔记的标题大纲
+//! - [`check_integrity`] - 检查索引完整性
+//! - [`repair_integrity`] - 修复悬空边和孤立属性
 //! - [`get_dcom_info`] - 获取文件的 DCOM 信息
+//! - [`which_adapter`] - 查询负责处理文件的适配器
+//! - [`get_nodes_by_source`] - 按序列化源类型（Markdown、Binary）列出节点
+//! - [`get_note_properties`] - 获取笔记的属性（含内联字段）
+//! - [`query_property_cmp`] - 按属性值比较（数值、日期感知）查找笔记
+//! - [`set_note_property`] - 设置笔记的单个属性并写回 frontmatter
+//! - [`delete_note_property`] - 删除笔记的单个属性
+//! - [`remove_note_tag`] - 移除笔记的单个标签
+//! - [`sync_frontmatter_to_disk`] - 以数据库中的属性、标签和别名重写笔记 frontmatter
+//! - [`get_tag_conflicts`] - 获取仅大小写不同的标签冲突
+//! - [`get_all_aliases`] - 获取所有别名及其所属笔记
+//! - [`get_alias_conflicts`] - 获取被多篇笔记声明的别名
+//! - [`get_tag_usages`] - 获取携带或链接到某标签的笔记
+//! - [`get_tag_cooccurrence`] - 获取经常同时出现的标签对
+//! - [`get_communities`] - 按链接连通性对笔记分组
+//! - [`explain_connection`] - 以带关系的步骤描述两篇笔记之间的最短路径
+//! - [`check_external_links`] - 检查外部链接是否有效
+//! - [`rename_file`] - 重命名文件并迁移其链接
+//! - [`merge_notes`] - 将一篇笔记合并到另一篇并重定向其入链
+//! - [`replace_all`] - 在整个知识库中查找替换文本
+//! - [`list_templates`] - 列出可用的笔记模板
+//! - [`get_template`] - 获取模板原始内容
 //!
 //! ## 使用示例
 //!
@@ -34,15 +96,39 @@
 //! const dcomInfo = await invoke('get_dcom_info', { path: 'notes/example.md' });
 //! ```
 
-use crate::adapters::AdapterRegistry;
-use crate::db::{Database, GraphData, Node};
-use crate::sync::{sync_vault, FileWatcher};
+use crate::adapters::obsidian::{frontmatter_end, is_encrypted, parse_markdown, Heading};
+use crate::adapters::{AdapterRegistry, ObjectAdapter};
+use crate::config::{load_config, VaultConfig};
+use crate::db::{
+    AliasConflict, Capabilities, ConnectionStep, Database, ForceGraph, GraphData, GraphMetadata,
+    IntegrityReport, LinkStats, Node, NoteLink, QueryValidation, SearchHit, SearchQuery, SyncPlan,
+    TagCaseConflict,
+};
+use crate::dcom::{CognitiveObject, PropertyValue};
+use crate::linkcheck::{LinkCheckResult, LinkChecker};
+use crate::sync::{
+    compute_note_stats, load_type_rules, uuid_for_path, FileWatcher, GitignoreFilter, NoteStats,
+    VaultSyncer, ID_PROPERTY,
+};
+use regex::{NoExpand, Regex};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::io::{BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
-use tauri::State;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// 笔记模板目录（相对于知识库根目录）
+const TEMPLATES_DIR: &str = ".cognistruct/templates";
+
+/// 保存文件时的备份目录（相对于知识库根目录）
+const BACKUPS_DIR: &str = ".cognistruct/backups";
+
+/// 合并文件变化事件的时间窗口
+const WATCH_COALESCE_WINDOW: Duration = Duration::from_millis(300);
 
 /// 应用程序全局状态
 ///
@@ -54,6 +140,8 @@ use tauri::State;
 /// * `db` - 数据库实例，用于存储和查询知识图谱数据
 /// * `vault_path` - 当前打开的知识库路径
 /// * `watcher` - 文件监听器，用于监控知识库文件变化
+/// * `backlinks` - 反向链接索引缓存，首次查询时构建，链接图变化后失效
+/// * `watch_generation` - 监听同步线程的代数，每次打开知识库时递增，旧线程据此退出
 #[derive(Default)]
 pub struct AppState {
     /// 数据库实例，封装在 Option 中表示可能未初始化
@@ -62,6 +150,19 @@ pub struct AppState {
     pub vault_path: Mutex<Option<PathBuf>>,
     /// 文件变化监听器
     pub watcher: Mutex<Option<FileWatcher>>,
+    /// 反向链接索引缓存（目标 UUID 到源 UUID 列表）
+    pub backlinks: Mutex<Option<HashMap<String, Vec<String>>>>,
+    /// 监听同步线程代数
+    pub watch_generation: AtomicU64,
+}
+
+impl AppState {
+    /// 使反向链接索引缓存失效
+    ///
+    /// 任何可能修改链接边的操作完成后都应调用。
+    pub fn invalidate_backlinks(&self) {
+        *self.backlinks.lock().unwrap() = None;
+    }
 }
 
 /// 文件树节点
@@ -86,13 +187,177 @@ pub struct FileNode {
     pub children: Option<Vec<FileNode>>,
 }
 
+/// 同步进度
+///
+/// 随 `sync-progress` 事件发送给前端。
+///
+/// # 字段说明
+///
+/// * `processed` - 已处理的步骤数
+/// * `total` - 总步骤数
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SyncProgress {
+    /// 已处理的步骤数
+    pub processed: usize,
+    /// 总步骤数
+    pub total: usize,
+}
+
+/// 同步错误
+///
+/// 文件读取或解析失败时随 `sync-error` 事件发送给前端，每个失败的文件一个事件。
+///
+/// # 字段说明
+///
+/// * `path` - 文件相对路径
+/// * `error` - 错误信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncError {
+    /// 文件相对路径
+    pub path: String,
+    /// 错误信息
+    pub error: String,
+}
+
+/// 创建同步器，应用知识库配置（`.cognistruct/config.toml`）和类型推断规则（`.cognistruct/rules.yaml`）
+fn vault_syncer(vault_path: &Path) -> Result<VaultSyncer, String> {
+    let config = load_config(vault_path).map_err(|e| format!("{:#}", e))?;
+    let rules = load_type_rules(vault_path).map_err(|e| format!("{:#}", e))?;
+    Ok(VaultSyncer::with_defaults()
+        .with_config(&config)
+        .with_type_rules(rules))
+}
+
+/// 发送同步错误事件
+fn emit_sync_errors(app: &AppHandle, errors: Vec<(String, String)>) {
+    for (path, error) in errors {
+        // 事件发送失败不影响同步
+        let _ = app.emit("sync-error", SyncError { path, error });
+    }
+}
+
+/// 启动监听同步线程
+///
+/// 循环从 [`AppState::watcher`] 接收一个时间窗口内合并去重后的文件变化，
+/// 逐个重新同步后发送一次 `graph-updated` 事件（载荷为本批同步的相对路径），
+/// 失败的文件各发送一个 `sync-error` 事件。重新打开知识库后 `watch_generation` 变化，旧线程随即退出。
+fn spawn_watch_sync(app: AppHandle, generation: u64) {
+    std::thread::spawn(move || {
+        let state = app.state::<AppState>();
+        while state.watch_generation.load(Ordering::SeqCst) == generation {
+            // 接收期间持有监听器的锁，最多两个合并窗口
+            let paths = match state.watcher.lock().unwrap().as_ref() {
+                Some(watcher) => match watcher.next_batch(WATCH_COALESCE_WINDOW) {
+                    Some(paths) => paths,
+                    None => break,
+                },
+                None => break,
+            };
+            if paths.is_empty() {
+                continue;
+            }
+
+            let Some(vault_path) = state.vault_path.lock().unwrap().clone() else {
+                break;
+            };
+            let (synced, errors) = {
+                let mut db_guard = state.db.lock().unwrap();
+                let Some(db) = db_guard.as_mut() else {
+                    break;
+                };
+                sync_changed_paths(&vault_path, db, &paths)
+            };
+            state.invalidate_backlinks();
+
+            emit_sync_errors(&app, errors);
+            if !synced.is_empty() {
+                let _ = app.emit("graph-updated", synced);
+            }
+        }
+    });
+}
+
+/// 重新同步发生变化的文件
+///
+/// 整批路径一起按子树同步（见 [`VaultSyncer::sync_subtrees`]），链接索引只构建一次、基于整个知识库解析，
+/// 已删除的文件从索引中移除。不在知识库中的路径（如切换知识库前残留的事件）被忽略。
+///
+/// # 返回值
+///
+/// 同步成功的相对路径，以及失败的 `(相对路径, 错误信息)`；
+/// 配置文件或类型规则无效、或构建链接索引失败时不同步任何文件，错误的路径为空字符串
+fn sync_changed_paths(
+    vault_path: &Path,
+    db: &mut Database,
+    paths: &[PathBuf],
+) -> (Vec<String>, Vec<(String, String)>) {
+    let syncer = match vault_syncer(vault_path) {
+        Ok(syncer) => syncer,
+        Err(error) => return (Vec::new(), vec![(String::new(), error)]),
+    };
+    let relatives: Vec<&Path> = paths
+        .iter()
+        .filter_map(|path| path.strip_prefix(vault_path).ok())
+        .collect();
+    let results = match syncer.sync_subtrees(vault_path, &relatives, db) {
+        Ok(results) => results,
+        Err(e) => return (Vec::new(), vec![(String::new(), format!("{:#}", e))]),
+    };
+
+    let mut synced = Vec::new();
+    let mut errors = Vec::new();
+    for (relative, result) in relatives.iter().zip(results) {
+        let relative_str = relative.to_string_lossy().to_string();
+        match result {
+            Ok(_) => synced.push(relative_str),
+            Err(e) => errors.push((relative_str, format!("{:#}", e))),
+        }
+    }
+
+    (synced, errors)
+}
+
+/// 笔记大纲条目
+///
+/// # 字段说明
+///
+/// * `level` - 标题级别（1-6）
+/// * `text` - 标题文本
+/// * `line_number` - 标题所在行号（从 1 开始，含 frontmatter）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutlineItem {
+    /// 标题级别
+    pub level: u8,
+    /// 标题文本
+    pub text: String,
+    /// 行号
+    pub line_number: usize,
+}
+
+impl From<Heading> for OutlineItem {
+    fn from(heading: Heading) -> Self {
+        OutlineItem {
+            level: heading.level,
+            text: heading.text,
+            line_number: heading.line_number,
+        }
+    }
+}
+
 /// 打开知识库
 ///
 /// 初始化并打开指定路径的知识库，创建数据库、同步文件并启动文件监听。
+/// 之后磁盘上的文件变化会被自动同步，每批变化完成后发送 `graph-updated` 事件。
+/// 同步选项和监听防抖时间读取自 `.cognistruct/config.toml`（见 [`VaultConfig`]）。
+/// 同步过程中向前端发送 `sync-progress` 事件（载荷为 [`SyncProgress`]），
+/// 无法加载的文件各发送一个 `sync-error` 事件（载荷为 [`SyncError`]），其余文件照常索引。
 ///
 /// # 参数
 ///
+/// * `app` - Tauri 应用句柄，用于发送进度事件
 /// * `path` - 知识库目录的绝对路径
+/// * `create_folder_nodes` - 是否为目录创建文件夹节点，未指定时使用配置文件中的设置
+/// * `respect_gitignore` - 是否跳过被 `.gitignore` 忽略的文件，未指定时使用配置文件中的设置
 /// * `state` - 应用程序状态
 ///
 /// # 返回值
@@ -107,7 +372,13 @@ pub struct FileNode {
 /// * 文件同步失败
 /// * 文件监听器创建失败
 #[tauri::command]
-pub async fn open_vault(path: String, state: State<'_, AppState>) -> Result<String, String> {
+pub async fn open_vault(
+    app: AppHandle,
+    path: String,
+    create_folder_nodes: Option<bool>,
+    respect_gitignore: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
     let vault_path = PathBuf::from(&path);
 
     if !vault_path.exists() || !vault_path.is_dir() {
@@ -122,49 +393,84 @@ pub async fn open_vault(path: String, state: State<'_, AppState>) -> Result<Stri
 
     let mut db = Database::new(db_path).map_err(|e| e.to_string())?;
 
-    // Sync vault
-    sync_vault(&vault_path, &mut db).map_err(|e| e.to_string())?;
+    let config = load_config(&vault_path).map_err(|e| format!("{:#}", e))?;
+    db.set_expand_tag_parents(config.expand_tag_parents);
+
+    // Sync vault（命令参数优先于配置文件）
+    let result = vault_syncer(&vault_path)?
+        .with_folder_nodes(create_folder_nodes.unwrap_or(config.create_folder_nodes))
+        .with_respect_gitignore(respect_gitignore.unwrap_or(config.respect_gitignore))
+        .sync_full_with_progress(&vault_path, &mut db, |processed, total| {
+            // 进度事件发送失败不影响同步
+            let _ = app.emit("sync-progress", SyncProgress { processed, total });
+        })
+        .map_err(|e| e.to_string())?;
+    emit_sync_errors(&app, result.errors);
+    db.create_snapshot().map_err(|e| e.to_string())?;
 
     // Set up file watcher
-    let watcher = FileWatcher::new(&vault_path).map_err(|e| e.to_string())?;
+    let watcher =
+        FileWatcher::with_debounce(&vault_path, config.debounce()).map_err(|e| e.to_string())?;
 
     // Store state
     *state.db.lock().unwrap() = Some(db);
     *state.vault_path.lock().unwrap() = Some(vault_path);
     *state.watcher.lock().unwrap() = Some(watcher);
+    state.invalidate_backlinks();
+
+    let generation = state.watch_generation.fetch_add(1, Ordering::SeqCst) + 1;
+    spawn_watch_sync(app, generation);
 
     Ok("Vault opened successfully".to_string())
 }
 
-/// 获取知识图谱数据
+/// 重新索引知识库
 ///
-/// 从数据库中获取所有节点和边，用于前端图形可视化。
+/// 对当前打开的知识库执行全量同步，并创建新的索引快照。
+/// 无法加载的文件各发送一个 `sync-error` 事件（载荷为 [`SyncError`]）。
 ///
 /// # 参数
 ///
+/// * `app` - Tauri 应用句柄，用于发送错误事件
+/// * `create_folder_nodes` - 是否为目录创建文件夹节点，未指定时使用配置文件中的设置
+/// * `respect_gitignore` - 是否跳过被 `.gitignore` 忽略的文件，未指定时使用配置文件中的设置
 /// * `state` - 应用程序状态
 ///
 /// # 返回值
 ///
-/// * `Ok(GraphData)` - 包含所有节点和边的图数据
-/// * `Err(String)` - 获取失败，返回错误信息
+/// * `Ok(i64)` - 新快照 ID
+/// * `Err(String)` - 重新索引失败，返回错误信息
 ///
 /// # 错误情况
 ///
 /// * 未打开知识库
-/// * 数据库查询失败
+/// * 文件同步失败
+/// * 数据库操作失败
 #[tauri::command]
-pub async fn get_graph_data(state: State<'_, AppState>) -> Result<GraphData, String> {
-    let db_guard = state.db.lock().unwrap();
-    let db = db_guard.as_ref().ok_or("No vault opened")?;
+pub async fn reindex_vault(
+    app: AppHandle,
+    create_folder_nodes: Option<bool>,
+    respect_gitignore: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<i64, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
 
-    db.get_graph_data().map_err(|e| e.to_string())
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+
+    let config = load_config(vault_path).map_err(|e| format!("{:#}", e))?;
+    let result = vault_syncer(vault_path)?
+        .with_folder_nodes(create_folder_nodes.unwrap_or(config.create_folder_nodes))
+        .with_respect_gitignore(respect_gitignore.unwrap_or(config.respect_gitignore))
+        .sync_full(vault_path, db)
+        .map_err(|e| e.to_string())?;
+    state.invalidate_backlinks();
+    emit_sync_errors(&app, result.errors);
+    db.create_snapshot().map_err(|e| e.to_string())
 }
 
-/// 获取文件树结构
-///
-/// 递归构建知识库的文件树结构，用于前端文件浏览器显示。
-/// 自动过滤隐藏文件和 `.cognistruct` 目录。
+/// 获取知识库配置
 ///
 /// # 参数
 ///
@@ -172,361 +478,2969 @@ pub async fn get_graph_data(state: State<'_, AppState>) -> Result<GraphData, Str
 ///
 /// # 返回值
 ///
-/// * `Ok(Vec<FileNode>)` - 文件树根节点的子节点列表
-/// * `Err(String)` - 获取失败，返回错误信息
+/// * `Ok(VaultConfig)` - 当前配置，配置文件不存在时为默认配置
+/// * `Err(String)` - 读取失败，返回错误信息
 ///
 /// # 错误情况
 ///
 /// * 未打开知识库
-/// * 文件系统读取失败
+/// * 配置文件读取失败或格式无效
 #[tauri::command]
-pub async fn get_file_tree(state: State<'_, AppState>) -> Result<Vec<FileNode>, String> {
+pub async fn get_config(state: State<'_, AppState>) -> Result<VaultConfig, String> {
     let vault_path_guard = state.vault_path.lock().unwrap();
     let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
 
-    /// 递归构建文件树
-    ///
-    /// 内部辅助函数，递归遍历目录并构建 FileNode 树结构。
-    fn build_tree(path: &Path, base_path: &Path) -> Result<FileNode, String> {
-        let name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
-
-        let relative_path = path
-            .strip_prefix(base_path)
-            .unwrap_or(path)
-            .to_string_lossy()
-            .to_string();
-
-        let is_dir = path.is_dir();
-
-        let children = if is_dir {
-            let mut child_nodes = Vec::new();
-            if let Ok(entries) = fs::read_dir(path) {
-                for entry in entries.filter_map(|e| e.ok()) {
-                    let entry_path = entry.path();
-                    // Skip hidden files and .cognistruct directory
-                    if let Some(name) = entry_path.file_name().and_then(|n| n.to_str()) {
-                        if name.starts_with('.') {
-                            continue;
-                        }
-                    }
-                    if let Ok(child) = build_tree(&entry_path, base_path) {
-                        child_nodes.push(child);
-                    }
-                }
-            }
-            // Sort: directories first, then files
-            child_nodes.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-                (true, false) => std::cmp::Ordering::Less,
-                (false, true) => std::cmp::Ordering::Greater,
-                _ => a.name.cmp(&b.name),
-            });
-            Some(child_nodes)
-        } else {
-            None
-        };
-
-        Ok(FileNode {
-            name,
-            path: relative_path,
-            is_dir,
-            children,
-        })
-    }
-
-    let tree = build_tree(vault_path, vault_path)?;
-    Ok(tree.children.unwrap_or_default())
+    load_config(vault_path).map_err(|e| format!("{:#}", e))
 }
 
-/// 获取文件内容
+/// 保存知识库配置
 ///
-/// 读取指定路径文件的完整内容。
+/// 写入 `.cognistruct/config.toml`。同步选项在下次重新索引时生效，
+/// 防抖时间在下次打开知识库时生效。
 ///
 /// # 参数
 ///
-/// * `path` - 相对于知识库根目录的文件路径
+/// * `config` - 新配置
 /// * `state` - 应用程序状态
 ///
-/// # 返回值
-///
-/// * `Ok(String)` - 文件内容
-/// * `Err(String)` - 读取失败，返回错误信息
-///
 /// # 错误情况
 ///
 /// * 未打开知识库
-/// * 文件不存在或无法读取
+/// * 配置文件写入失败
 #[tauri::command]
-pub async fn get_file_content(path: String, state: State<'_, AppState>) -> Result<String, String> {
+pub async fn save_config(config: VaultConfig, state: State<'_, AppState>) -> Result<(), String> {
     let vault_path_guard = state.vault_path.lock().unwrap();
     let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
 
-    let file_path = vault_path.join(&path);
-    fs::read_to_string(file_path).map_err(|e| e.to_string())
+    crate::config::save_config(vault_path, &config).map_err(|e| format!("{:#}", e))
 }
 
-/// 保存文件内容
+/// 重建链接图
 ///
-/// 将内容写入指定路径的文件，如果父目录不存在则自动创建。
+/// 根据已索引的节点内容重新解析链接并重建所有边，不重新读取文件。
+/// 使用知识库配置中的链接解析设置，适用于调整设置后快速刷新图谱。
 ///
 /// # 参数
 ///
-/// * `path` - 相对于知识库根目录的文件路径
-/// * `content` - 要写入的文件内容
 /// * `state` - 应用程序状态
 ///
 /// # 返回值
 ///
-/// * `Ok(String)` - 保存成功，返回成功消息
-/// * `Err(String)` - 保存失败，返回错误信息
+/// * `Ok(usize)` - 创建的边数量
+/// * `Err(String)` - 重建失败，返回错误信息
 ///
 /// # 错误情况
 ///
 /// * 未打开知识库
-/// * 无法创建父目录
-/// * 无法写入文件
+/// * 知识库配置读取失败
+/// * 数据库读写失败
 #[tauri::command]
-pub async fn save_file(
-    path: String,
-    content: String,
-    state: State<'_, AppState>,
-) -> Result<String, String> {
+pub async fn rebuild_graph_edges(state: State<'_, AppState>) -> Result<usize, String> {
     let vault_path_guard = state.vault_path.lock().unwrap();
     let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
 
-    let file_path = vault_path.join(&path);
-
-    // Ensure parent directory exists
-    if let Some(parent) = file_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-
-    fs::write(&file_path, content).map_err(|e| e.to_string())?;
-
-    Ok("File saved successfully".to_string())
+    let created = vault_syncer(vault_path)?
+        .rebuild_edges(db)
+        .map_err(|e| e.to_string())?;
+    state.invalidate_backlinks();
+    Ok(created)
 }
 
-/// 搜索节点
+/// 比较最近两次索引快照
 ///
-/// 根据查询字符串搜索匹配的知识节点，支持标题和内容搜索。
+/// 返回最近两次索引（打开知识库或重新索引）之间新增、更新和删除的文件。
 ///
 /// # 参数
 ///
-/// * `query` - 搜索关键词
 /// * `state` - 应用程序状态
 ///
 /// # 返回值
 ///
-/// * `Ok(Vec<Node>)` - 匹配的节点列表
-/// * `Err(String)` - 搜索失败，返回错误信息
+/// * `Ok(SyncPlan)` - 文件变化
+/// * `Err(String)` - 比较失败，返回错误信息
 ///
 /// # 错误情况
 ///
 /// * 未打开知识库
+/// * 快照少于两个
 /// * 数据库查询失败
 #[tauri::command]
-pub async fn search_nodes(query: String, state: State<'_, AppState>) -> Result<Vec<Node>, String> {
+pub async fn diff_last_sync(state: State<'_, AppState>) -> Result<SyncPlan, String> {
     let db_guard = state.db.lock().unwrap();
     let db = db_guard.as_ref().ok_or("No vault opened")?;
 
-    db.search_nodes(&query).map_err(|e| e.to_string())
+    let snapshots = db.list_snapshots().map_err(|e| e.to_string())?;
+    match snapshots.as_slice() {
+        [.., from, to] => db.diff_snapshots(*from, *to).map_err(|e| e.to_string()),
+        _ => Err("At least two snapshots are required".to_string()),
+    }
 }
 
-/// 获取 Vault 统计信息
+/// 获取知识图谱数据
 ///
-/// 返回知识库的基本统计数据，包括节点数、边数和标签数。
+/// 从数据库中获取节点和边，用于前端图形可视化。
+/// 以 `create_folder_nodes` 打开知识库时，结果包含 `folder` 类型节点及 `contains` 边。
+///
+/// 节点数超过上限时只返回连接度最高的节点及其之间的边，并将 `truncated` 置为 `true`，
+/// 避免一次渲染数万个节点导致界面卡死。需要全部数据时使用 [`get_full_graph`]。
 ///
 /// # 参数
 ///
+/// * `max_nodes` - 节点数上限，`0` 表示不限制；未指定时使用配置中的 `max_graph_nodes`
 /// * `state` - 应用程序状态
 ///
 /// # 返回值
 ///
-/// * `Ok(VaultStatistics)` - 统计信息
+/// * `Ok(GraphData)` - 图数据，`total_nodes` 为裁剪前的节点总数
 /// * `Err(String)` - 获取失败，返回错误信息
 ///
 /// # 错误情况
 ///
 /// * 未打开知识库
+/// * 配置文件读取失败或格式无效
 /// * 数据库查询失败
 #[tauri::command]
-pub async fn get_vault_statistics(
+pub async fn get_graph_data(
+    max_nodes: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<crate::db::VaultStatistics, String> {
+) -> Result<GraphData, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+    let max_nodes = match max_nodes {
+        Some(max_nodes) => max_nodes,
+        None => {
+            load_config(vault_path)
+                .map_err(|e| format!("{:#}", e))?
+                .max_graph_nodes
+        }
+    };
+
     let db_guard = state.db.lock().unwrap();
     let db = db_guard.as_ref().ok_or("No vault opened")?;
 
-    db.get_statistics().map_err(|e| e.to_string())
-}
-
-/// DCOM 序列化源信息
-///
-/// 描述认知对象的物理存储位置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DCOMSourceInfo {
-    /// 源类型: "markdown", "binary", "virtual"
-    pub source_type: String,
-    /// 文件路径（如果是物理文件）
-    pub path: Option<String>,
-    /// 最后同步时间戳
-    pub last_sync: Option<i64>,
-}
-
-/// DCOM 链接信息
-///
-/// 描述从当前对象提取的链接
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DCOMLinkInfo {
-    /// 链接目标
-    pub target: String,
-    /// 链接类型: "WikiLink", "BlockReference", "Embed", "External"
-    pub kind: String,
-    /// 显示文本
-    pub display_text: Option<String>,
-}
-
-/// DCOM 对象信息
-///
-/// 前端展示用的认知对象详细信息
-///
-/// # 字段说明
-///
-/// * `id` - 对象唯一标识
-/// * `title` - 标题
-/// * `object_type` - 对象类型（如 "note", "person"）
-/// * `tags` - 标签列表
-/// * `aliases` - 别名列表
-/// * `links` - 提取的链接信息
-/// * `properties` - 自定义属性
-/// * `sources` - 序列化源信息
-/// * `created_at` - 创建时间戳
-/// * `updated_at` - 更新时间戳
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DCOMInfo {
-    /// 对象唯一标识
-    pub id: String,
-    /// 标题
-    pub title: String,
-    /// 对象类型
-    pub object_type: Option<String>,
-    /// 标签列表
-    pub tags: Vec<String>,
-    /// 别名列表
-    pub aliases: Vec<String>,
-    /// 链接信息
-    pub links: Vec<DCOMLinkInfo>,
-    /// 自定义属性
-    pub properties: HashMap<String, serde_json::Value>,
-    /// 序列化源信息
-    pub sources: Vec<DCOMSourceInfo>,
-    /// 创建时间戳
-    pub created_at: i64,
-    /// 更新时间戳
-    pub updated_at: i64,
+    db.get_graph_data_limited(max_nodes)
+        .map_err(|e| e.to_string())
 }
 
-/// 获取文件的 DCOM 信息
+/// 获取完整的知识图谱数据
 ///
-/// 使用适配器解析指定文件，返回完整的 DCOM 认知对象信息。
+/// 与 [`get_graph_data`] 相同，但不受节点数上限限制。
 ///
 /// # 参数
 ///
-/// * `path` - 文件相对路径（相对于知识库根目录）
 /// * `state` - 应用程序状态
 ///
 /// # 返回值
 ///
-/// * `Ok(DCOMInfo)` - DCOM 对象信息
+/// * `Ok(GraphData)` - 包含所有节点和边的图数据
 /// * `Err(String)` - 获取失败，返回错误信息
 ///
 /// # 错误情况
 ///
 /// * 未打开知识库
-/// * 文件不存在
-/// * 文件格式不支持
-/// * 解析失败
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_full_graph(state: State<'_, AppState>) -> Result<GraphData, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_graph_data().map_err(|e| e.to_string())
+}
+
+/// 获取不含节点正文的图数据
+///
+/// 与 [`get_full_graph`] 返回相同的节点和边，但节点省略 `content`，
+/// 供只需要标题和类型的图谱视图使用。需要正文时使用 [`get_full_graph`]。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(GraphMetadata)` - 轻量图数据
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_graph_metadata(state: State<'_, AppState>) -> Result<GraphMetadata, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_graph_data()
+        .map(GraphData::into_metadata)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取力导向布局格式的图数据
+///
+/// 返回 `{ nodes: [{id, label, group}], links: [{source, target, value}] }`，
+/// 前端无需在每次渲染时转换 [`GraphData`]。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(ForceGraph)` - 力导向布局数据
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_force_graph(state: State<'_, AppState>) -> Result<ForceGraph, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_graph_data()
+        .map(|graph| graph.to_force_layout())
+        .map_err(|e| e.to_string())
+}
+
+/// 获取 Cytoscape.js 格式的图数据
+///
+/// 返回 `{ elements: { nodes: [{data, classes}], edges: [{data}] } }`，
+/// 格式见 [`GraphData::to_cytoscape`]。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(serde_json::Value)` - Cytoscape.js elements 数据
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_cytoscape_graph(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_graph_data()
+        .map(|graph| graph.to_cytoscape())
+        .map_err(|e| e.to_string())
+}
+
+/// 导出笔记的邻域子图
+///
+/// 用于分享或调试，返回笔记及其 `depth` 跳内的邻居、它们之间的边以及属性和标签。
+///
+/// # 参数
+///
+/// * `path` - 笔记相对路径
+/// * `depth` - 遍历深度，0 表示仅包含该笔记
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(GraphData)` - 子图数据
+/// * `Err(String)` - 导出失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 笔记未被索引
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn export_note_subgraph(
+    path: String,
+    depth: usize,
+    state: State<'_, AppState>,
+) -> Result<GraphData, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    let node = db
+        .get_node_by_path(&path)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Note not found: {}", path))?;
+
+    db.export_subgraph(&node.uuid, depth)
+        .map_err(|e| e.to_string())
+}
+
+/// 以 NDJSON 格式导出整个索引
+///
+/// 将节点、边、属性、标签和别名逐行写入磁盘文件，格式见 [`crate::db::ExportRecord`]。
+/// 节点分批读取后逐行写出，笔记正文不会一次性全部载入内存。
+///
+/// # 参数
+///
+/// * `path` - 输出文件路径（由用户选择，不限于知识库内）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(usize)` - 写出的记录数
+/// * `Err(String)` - 导出失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 文件创建或写入失败
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn export_vault_ndjson(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    let file = fs::File::create(&path).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    let count = db
+        .export_vault_to_writer(&mut writer)
+        .map_err(|e| e.to_string())?;
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    Ok(count)
+}
+
+/// 从 NDJSON 文件导入索引
+///
+/// 读取 [`export_vault_ndjson`] 写出的文件，将记录写入当前知识库的数据库。
+///
+/// # 参数
+///
+/// * `path` - 输入文件路径
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(usize)` - 导入的记录数
+/// * `Err(String)` - 导入失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 文件读取失败或某行格式无效
+/// * 数据库操作失败
+#[tauri::command]
+pub async fn import_vault_ndjson(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+
+    let file = fs::File::open(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let count = db
+        .import_vault_from_reader(BufReader::new(file))
+        .map_err(|e| format!("{:#}", e))?;
+    state.invalidate_backlinks();
+
+    Ok(count)
+}
+
+/// 保存笔记在图谱视图中的坐标
+///
+/// 坐标保存在知识库数据库中，重新打开后仍然有效。
+///
+/// # 参数
+///
+/// * `path` - 笔记相对路径
+/// * `x` - 横坐标
+/// * `y` - 纵坐标
+/// * `state` - 应用程序状态
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 笔记未被索引
+/// * 数据库操作失败
+#[tauri::command]
+pub async fn save_node_position(
+    path: String,
+    x: f64,
+    y: f64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+
+    let node = db
+        .get_node_by_path(&path)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Note not found: {}", path))?;
+
+    db.save_node_position(&node.uuid, x, y)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取图谱视图中已保存的节点坐标
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(HashMap<String, (f64, f64)>)` - 节点 UUID 到 `(x, y)` 的映射，未保存坐标的节点不包含在内
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_node_positions(
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, (f64, f64)>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_node_positions().map_err(|e| e.to_string())
+}
+
+/// 获取反向链接索引
+///
+/// 优先返回缓存的索引；缓存不存在时从数据库构建并缓存，
+/// 直到下一次同步或修改链接的操作使其失效。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(HashMap<String, Vec<String>>)` - 链接目标 UUID 到引用它的笔记 UUID 列表的映射
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_backlink_index(
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    let mut cache = state.backlinks.lock().unwrap();
+    if let Some(index) = cache.as_ref() {
+        return Ok(index.clone());
+    }
+
+    let index = db.build_backlink_index().map_err(|e| e.to_string())?;
+    *cache = Some(index.clone());
+    Ok(index)
+}
+
+/// 获取文件树结构
+///
+/// 递归构建知识库的文件树结构，用于前端文件浏览器显示。
+/// 自动过滤隐藏文件和 `.cognistruct` 目录。
+///
+/// # 参数
+///
+/// * `respect_gitignore` - 是否隐藏被 `.gitignore` 忽略的文件和目录，默认关闭
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<FileNode>)` - 文件树根节点的子节点列表
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 文件系统读取失败
+#[tauri::command]
+pub async fn get_file_tree(
+    respect_gitignore: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FileNode>, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    /// 递归构建文件树
+    ///
+    /// 内部辅助函数，递归遍历目录并构建 FileNode 树结构。
+    fn build_tree(
+        path: &Path,
+        base_path: &Path,
+        gitignore: &mut Option<GitignoreFilter>,
+    ) -> Result<FileNode, String> {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        let relative_path = path
+            .strip_prefix(base_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+
+        let is_dir = path.is_dir();
+
+        let children = if is_dir {
+            let mut child_nodes = Vec::new();
+            if let Ok(entries) = fs::read_dir(path) {
+                for entry in entries.filter_map(|e| e.ok()) {
+                    let entry_path = entry.path();
+                    // Skip hidden files and .cognistruct directory
+                    if let Some(name) = entry_path.file_name().and_then(|n| n.to_str()) {
+                        if name.starts_with('.') {
+                            continue;
+                        }
+                    }
+                    if let Some(filter) = gitignore.as_mut() {
+                        if filter.is_ignored(&entry_path, entry_path.is_dir()) {
+                            continue;
+                        }
+                    }
+                    if let Ok(child) = build_tree(&entry_path, base_path, gitignore) {
+                        child_nodes.push(child);
+                    }
+                }
+            }
+            // Sort: directories first, then files
+            child_nodes.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+                (true, false) => std::cmp::Ordering::Less,
+                (false, true) => std::cmp::Ordering::Greater,
+                _ => a.name.cmp(&b.name),
+            });
+            Some(child_nodes)
+        } else {
+            None
+        };
+
+        Ok(FileNode {
+            name,
+            path: relative_path,
+            is_dir,
+            children,
+        })
+    }
+
+    let mut gitignore = respect_gitignore
+        .unwrap_or(false)
+        .then(|| GitignoreFilter::new(vault_path));
+    let tree = build_tree(vault_path, vault_path, &mut gitignore)?;
+    Ok(tree.children.unwrap_or_default())
+}
+
+/// 获取单层目录内容
+///
+/// 仅返回指定目录的直接子节点，目录节点的 `children` 为 `None`，
+/// 由前端在展开时再次调用以实现懒加载。自动过滤隐藏文件和 `.cognistruct` 目录。
+///
+/// # 参数
+///
+/// * `path` - 相对于知识库根目录的目录路径，`None` 表示根目录
+/// * `filter` - 文件过滤条件：扩展名（如 `md`、`.md`）或通配符（如 `*.md`、`daily-*`），
+///   仅作用于文件，目录始终返回
+/// * `respect_gitignore` - 是否隐藏被 `.gitignore` 忽略的文件和目录，默认关闭
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<FileNode>)` - 直接子节点列表，目录在前，按名称排序
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 目录不存在
+/// * 文件系统读取失败
+#[tauri::command]
+pub async fn get_file_tree_level(
+    path: Option<String>,
+    filter: Option<String>,
+    respect_gitignore: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FileNode>, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    list_directory(
+        vault_path,
+        path.as_deref(),
+        filter.as_deref(),
+        respect_gitignore.unwrap_or(false),
+    )
+}
+
+/// 列出目录的直接子节点
+///
+/// `get_file_tree_level` 的实现，便于脱离 Tauri 状态测试。
+fn list_directory(
+    vault_path: &Path,
+    path: Option<&str>,
+    filter: Option<&str>,
+    respect_gitignore: bool,
+) -> Result<Vec<FileNode>, String> {
+    let dir = match path {
+        Some(p) if !p.is_empty() => vault_file_path(vault_path, p)?,
+        _ => vault_path.to_path_buf(),
+    };
+
+    if !dir.is_dir() {
+        return Err(format!("Directory not found: {}", path.unwrap_or("")));
+    }
+
+    let mut gitignore = respect_gitignore.then(|| GitignoreFilter::new(vault_path));
+    let mut nodes = Vec::new();
+    for entry in fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+    {
+        let entry_path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        // Skip hidden files and .cognistruct directory
+        if name.starts_with('.') {
+            continue;
+        }
+
+        let is_dir = entry_path.is_dir();
+        if let Some(gitignore) = gitignore.as_mut() {
+            if gitignore.is_ignored(&entry_path, is_dir) {
+                continue;
+            }
+        }
+        if !is_dir {
+            if let Some(filter) = filter {
+                if !matches_file_filter(&name, filter) {
+                    continue;
+                }
+            }
+        }
+
+        nodes.push(FileNode {
+            path: entry_path
+                .strip_prefix(vault_path)
+                .unwrap_or(&entry_path)
+                .to_string_lossy()
+                .to_string(),
+            name,
+            is_dir,
+            children: None,
+        });
+    }
+
+    // Sort: directories first, then files
+    nodes.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.cmp(&b.name),
+    });
+
+    Ok(nodes)
+}
+
+/// 检查文件名是否匹配过滤条件
+///
+/// 包含 `*` 或 `?` 的过滤条件按通配符匹配整个文件名，
+/// 否则视为扩展名（忽略前导点号和大小写）。
+fn matches_file_filter(name: &str, filter: &str) -> bool {
+    if filter.contains(['*', '?']) {
+        let pattern: String = filter
+            .chars()
+            .map(|c| match c {
+                '*' => ".*".to_string(),
+                '?' => ".".to_string(),
+                c => regex::escape(&c.to_string()),
+            })
+            .collect();
+        return regex::Regex::new(&format!("^{}$", pattern))
+            .map(|re| re.is_match(name))
+            .unwrap_or(false);
+    }
+
+    let extension = filter.trim_start_matches('.');
+    Path::new(name)
+        .extension()
+        .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case(extension))
+}
+
+/// 获取文件内容
+///
+/// 读取指定路径文件的完整内容。
+///
+/// # 参数
+///
+/// * `path` - 相对于知识库根目录的文件路径
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 文件内容
+/// * `Err(String)` - 读取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 路径不在知识库内
+/// * 文件不存在或无法读取
+#[tauri::command]
+pub async fn get_file_content(path: String, state: State<'_, AppState>) -> Result<String, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    let file_path = vault_file_path(vault_path, &path)?;
+    fs::read_to_string(file_path).map_err(|e| e.to_string())
+}
+
+/// 打开笔记时返回的数据
+///
+/// 由 [`open_note`] 生成，合并了原本需要分别请求的内容、属性、标签、别名和链接。
+///
+/// # 字段说明
+///
+/// * `content` - 磁盘上的文件内容
+/// * `properties` - 属性名到 JSON 值的映射（同 [`get_note_properties`]）
+/// * `tags` - 标签列表
+/// * `aliases` - 别名列表
+/// * `outgoing_links` - 出链，目标已解析为标题和路径
+/// * `backlinks` - 反向链接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteBundle {
+    /// 文件内容
+    pub content: String,
+    /// 属性
+    pub properties: HashMap<String, serde_json::Value>,
+    /// 标签
+    pub tags: Vec<String>,
+    /// 别名
+    pub aliases: Vec<String>,
+    /// 出链
+    pub outgoing_links: Vec<NoteLink>,
+    /// 反向链接
+    pub backlinks: Vec<NoteLink>,
+}
+
+/// 打开笔记
+///
+/// 一次调用返回编辑器打开笔记所需的全部数据，减少 IPC 往返。
+///
+/// # 参数
+///
+/// * `path` - 相对于知识库根目录的笔记路径
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(NoteBundle)` - 笔记内容、元数据和链接
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 路径不在知识库内
+/// * 笔记未被索引
+/// * 文件无法读取
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn open_note(path: String, state: State<'_, AppState>) -> Result<NoteBundle, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    load_note_bundle(vault_path, db, &path)
+}
+
+/// 读取笔记文件并从数据库收集其元数据和链接
+fn load_note_bundle(vault_path: &Path, db: &Database, path: &str) -> Result<NoteBundle, String> {
+    let file_path = vault_file_path(vault_path, path)?;
+    let node = db
+        .get_node_by_path(path)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Note not found: {}", path))?;
+    let content = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
+
+    let properties = db
+        .get_properties(&node.uuid)
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|(name, value)| (name.clone(), property_to_json(value)))
+        .collect();
+
+    Ok(NoteBundle {
+        content,
+        properties,
+        tags: db.get_tags(&node.uuid).map_err(|e| e.to_string())?,
+        aliases: db.get_aliases(&node.uuid).map_err(|e| e.to_string())?,
+        outgoing_links: db
+            .get_outgoing_links(&node.uuid)
+            .map_err(|e| e.to_string())?,
+        backlinks: db.get_backlinks(&node.uuid).map_err(|e| e.to_string())?,
+    })
+}
+
+/// 批量获取文件内容
+///
+/// 一次调用读取多个文件，减少加载搜索结果预览时的 IPC 往返。
+/// 不存在、无法读取或不在知识库内的路径会被跳过，不影响其他文件。
+///
+/// # 参数
+///
+/// * `paths` - 相对于知识库根目录的文件路径列表
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(HashMap<String, String>)` - 成功读取的路径到内容的映射
+/// * `Err(String)` - 未打开知识库
+#[tauri::command]
+pub async fn get_file_contents(
+    paths: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, String>, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    Ok(read_vault_files(vault_path, &paths))
+}
+
+/// 读取多个知识库文件，跳过读取失败的路径
+fn read_vault_files(vault_path: &Path, paths: &[String]) -> HashMap<String, String> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let file_path = vault_file_path(vault_path, path).ok()?;
+            let content = fs::read_to_string(file_path).ok()?;
+            Some((path.clone(), content))
+        })
+        .collect()
+}
+
+/// 将相对路径解析为知识库内的绝对路径
+///
+/// 拒绝绝对路径和包含 `..` 的路径，防止读取知识库以外的文件。
+fn vault_file_path(vault_path: &Path, path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(path);
+    let escapes = relative.components().any(|c| {
+        !matches!(
+            c,
+            std::path::Component::Normal(_) | std::path::Component::CurDir
+        )
+    });
+    if escapes {
+        return Err(format!("Path outside vault: {}", path));
+    }
+    Ok(vault_path.join(relative))
+}
+
+/// 按行获取文件内容片段
+///
+/// 供编辑器只加载大文件的可见部分，行号超出范围时自动截断。
+///
+/// # 参数
+///
+/// * `path` - 相对于知识库根目录的文件路径
+/// * `start` - 起始行号（从 1 开始，包含）
+/// * `end` - 结束行号（包含）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 指定行范围的内容
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 文件未被索引
+/// * 文件无法读取
+#[tauri::command]
+pub async fn get_content_range(
+    path: String,
+    start: usize,
+    end: usize,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    let uuid = uuid_for_path(db, &path).map_err(|e| e.to_string())?;
+    db.get_content_range(vault_path, &uuid, start, end)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取块引用对应的内容
+///
+/// 供前端渲染 `![[Note#^block]]` 嵌入时获取块文本。
+///
+/// # 参数
+///
+/// * `path` - 笔记相对路径
+/// * `block_id` - 块 ID（可带或不带 `^` 前缀）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Some(String))` - 块的文本内容
+/// * `Ok(None)` - 笔记未被索引或不存在该块
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 文件无法读取
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_block_content(
+    path: String,
+    block_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_block_content(vault_path, &path, &block_id)
+        .map_err(|e| e.to_string())
+}
+
+/// 解析 wikilink 目标
+///
+/// 使用与同步相同的规则（文件名、别名、就近原则）将链接目标解析为文件路径。
+///
+/// # 参数
+///
+/// * `source_path` - 链接所在笔记的相对路径
+/// * `target` - 链接目标（`[[` 与 `]]` 之间的内容）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Some(String))` - 目标文件的相对路径
+/// * `Ok(None)` - 无法解析（悬空链接）
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn resolve_wikilink(
+    source_path: String,
+    target: String,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    crate::sync::resolve_wikilink(db, &source_path, &target).map_err(|e| e.to_string())
+}
+
+/// 获取嵌入指定文件的笔记
+///
+/// 用于资源管理，例如查询哪些笔记使用了 `logo.png`。
+///
+/// # 参数
+///
+/// * `target` - 被嵌入的文件名或相对路径，扩展名可省略
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<Node>)` - 嵌入了该文件的笔记列表
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_embedders(
+    target: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Node>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_embedding_notes(&target).map_err(|e| e.to_string())
+}
+
+/// 获取相关笔记
+///
+/// 返回与指定笔记被相同笔记链接的其他笔记（“经常一起被链接”），
+/// 按共同来源数量从高到低排列。
+///
+/// # 参数
+///
+/// * `path` - 笔记相对路径
+/// * `limit` - 最多返回的笔记数量
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<(Node, usize)>)` - `(笔记, 共同来源数)` 列表
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_related_notes(
+    path: String,
+    limit: usize,
+    state: State<'_, AppState>,
+) -> Result<Vec<(Node, usize)>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    let uuid = uuid_for_path(db, &path).map_err(|e| e.to_string())?;
+    db.get_co_linked(&uuid, limit).map_err(|e| e.to_string())
+}
+
+/// 保存文件内容
+///
+/// 将内容原子写入指定路径的文件（先写临时文件再重命名覆盖），如果父目录不存在则自动创建。
+/// 开启备份时，原文件内容会先复制到 `.cognistruct/backups/<path>.bak`（覆盖上一次备份）。
+///
+/// # 参数
+///
+/// * `path` - 相对于知识库根目录的文件路径
+/// * `content` - 要写入的文件内容
+/// * `backup` - 是否备份原内容，默认关闭
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Some(String))` - 保存成功，返回备份文件的相对路径
+/// * `Ok(None)` - 保存成功，未创建备份（未开启或原文件不存在）
+/// * `Err(String)` - 保存失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 无法创建父目录或备份目录
+/// * 无法写入文件
+#[tauri::command]
+pub async fn save_file(
+    path: String,
+    content: String,
+    backup: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    save_with_backup(vault_path, &path, &content, backup.unwrap_or(false))
+}
+
+/// 向笔记末尾追加文本
+///
+/// 用于快速记录（如追加到日记），无需前端读取并保存整个文件。
+/// 文件不存在时自动创建；写入是原子的，完成后只重新同步该笔记。
+///
+/// # 参数
+///
+/// * `path` - 相对于知识库根目录的文件路径
+/// * `text` - 要追加的文本，自动补齐换行
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 追加成功，返回成功消息
+/// * `Err(String)` - 追加失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 路径位于知识库以外
+/// * 笔记已加密
+/// * 文件读写失败
+/// * 同步失败
+#[tauri::command]
+pub async fn append_to_note(
+    path: String,
+    text: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+
+    append_and_sync(vault_path, db, &path, &text)?;
+    state.invalidate_backlinks();
+
+    Ok("Text appended successfully".to_string())
+}
+
+/// 搜索节点
+///
+/// 根据查询字符串搜索匹配的知识节点，支持标题和内容搜索。
+/// 每个结果附带第一处匹配的行、字符偏移和所在行文本，用于高亮显示。
+///
+/// # 参数
+///
+/// * `query` - 搜索关键词
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<SearchHit>)` - 匹配的节点及匹配位置列表
+/// * `Err(String)` - 搜索失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn search_nodes(
+    query: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<SearchHit>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    let nodes = db.search_nodes(&query).map_err(|e| e.to_string())?;
+    Ok(nodes
+        .into_iter()
+        .map(|node| SearchHit::new(node, &query))
+        .collect())
+}
+
+/// 高级搜索
+///
+/// 组合文本、节点类型、更新时间和标签条件搜索节点。
+/// 设置了文本条件时，每个结果附带第一处匹配的位置。
+///
+/// # 参数
+///
+/// * `query` - 搜索条件
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<SearchHit>)` - 满足全部条件的节点及匹配位置列表
+/// * `Err(String)` - 搜索失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn advanced_search(
+    query: SearchQuery,
+    state: State<'_, AppState>,
+) -> Result<Vec<SearchHit>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    let nodes = db.advanced_search(&query).map_err(|e| e.to_string())?;
+    let text = query.text.as_deref().unwrap_or("");
+    Ok(nodes
+        .into_iter()
+        .map(|node| SearchHit::new(node, text))
+        .collect())
+}
+
+/// 随机获取一篇笔记
+///
+/// # 参数
+///
+/// * `node_type` - 节点类型过滤，如 `"note"`；未指定时从所有节点中选择
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Some(Node))` - 随机选中的节点
+/// * `Ok(None)` - 知识库中没有满足条件的节点
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_random_note(
+    node_type: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<Node>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_random_node(node_type.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// 获取指定时间之后修改过的笔记
+///
+/// 供同步客户端和插件增量拉取变化。目前不记录删除，已删除的笔记不会出现在结果中。
+///
+/// # 参数
+///
+/// * `ts` - 更新时间下限（含），Unix 时间戳
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<Node>)` - 修改过的节点，按更新时间升序排列
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_changes_since(ts: i64, state: State<'_, AppState>) -> Result<Vec<Node>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_nodes_modified_since(ts).map_err(|e| e.to_string())
+}
+
+/// 校验 CozoScript
+///
+/// 供即席查询编辑器在执行前检查脚本，校验过程不会修改数据。
+///
+/// # 参数
+///
+/// * `script` - 要校验的 CozoScript
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(QueryValidation)` - 校验结果，包含错误位置及脚本是否只读
+/// * `Err(String)` - 未打开知识库
+#[tauri::command]
+pub async fn validate_query(
+    script: String,
+    state: State<'_, AppState>,
+) -> Result<QueryValidation, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    Ok(db.validate_query(&script))
+}
+
+/// 获取 Vault 统计信息
+///
+/// 返回知识库的基本统计数据，包括节点数、边数和标签数。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(VaultStatistics)` - 统计信息
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_vault_statistics(
+    state: State<'_, AppState>,
+) -> Result<crate::db::VaultStatistics, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_statistics().map_err(|e| e.to_string())
+}
+
+/// 获取所有节点的入度和出度
+///
+/// 一次返回全部节点的连接度，前端可据此设置节点大小。
+///
+/// # 参数
+///
+/// * `include_tags` - 是否将标签边计入出度，默认不计入
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(HashMap<String, (usize, usize)>)` - 节点 UUID 到 `(入度, 出度)` 的映射
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_all_degrees(
+    include_tags: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, (usize, usize)>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_all_degrees(include_tags.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// 查找内容完全相同的笔记
+///
+/// 利用已存储的内容哈希对笔记分组，返回重复笔记的路径分组。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<Vec<String>>)` - 重复笔记的路径分组
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn find_exact_duplicates(state: State<'_, AppState>) -> Result<Vec<Vec<String>>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.find_exact_duplicates().map_err(|e| e.to_string())
+}
+
+/// 获取所有被引用的链接目标
+///
+/// 返回每个 wikilink 目标及引用它的笔记数，包括尚未创建的笔记，用于构建“未解析链接”列表。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<(String, usize)>)` - `(目标, 引用笔记数)`，按引用数降序排列
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_referenced_titles(
+    state: State<'_, AppState>,
+) -> Result<Vec<(String, usize)>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_all_link_targets().map_err(|e| e.to_string())
+}
+
+/// 查找链接环
+///
+/// 返回笔记之间长度不超过 `max_len` 的引用环（如 A→B→C→A），每个环为依次经过的笔记路径。
+///
+/// # 参数
+///
+/// * `max_len` - 环中笔记数的上限，超过 10 时按 10 处理
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<Vec<String>>)` - 链接环列表
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn find_link_cycles(
+    max_len: usize,
+    state: State<'_, AppState>,
+) -> Result<Vec<Vec<String>>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.find_cycles(max_len).map_err(|e| e.to_string())
+}
+
+/// 获取链接统计
+///
+/// 统计笔记之间的链接总数、已解析与断链数量，以及入链最多的笔记，供仪表盘展示。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(LinkStats)` - 链接统计
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_link_statistics(state: State<'_, AppState>) -> Result<LinkStats, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_link_statistics().map_err(|e| e.to_string())
+}
+
+/// 获取活动直方图
+///
+/// 按时间分桶统计创建或更新的笔记数量，供时间线视图使用。
+///
+/// # 参数
+///
+/// * `field` - 时间字段，`created_at` 或 `updated_at`
+/// * `bucket` - 分桶粒度，`day`、`week` 或 `month`
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<(i64, usize)>)` - `(桶起始时间戳（秒）, 笔记数)`，按时间升序排列
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 字段或粒度无效
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_activity(
+    field: String,
+    bucket: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<(i64, usize)>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_activity_histogram(&field, &bucket)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取笔记统计信息
+///
+/// 计算笔记正文的词数、字符数和预计阅读时间。
+///
+/// # 参数
+///
+/// * `path` - 相对于知识库根目录的文件路径
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(NoteStats)` - 统计信息
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 笔记未被索引
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_note_stats(path: String, state: State<'_, AppState>) -> Result<NoteStats, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    let node = db
+        .get_node_by_path(&path)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Note not found: {}", path))?;
+
+    Ok(compute_note_stats(&node.content))
+}
+
+/// 获取笔记大纲
+///
+/// 从磁盘上的源文件解析所有标题，供大纲面板渲染跳转列表。
+///
+/// # 参数
+///
+/// * `path` - 相对于知识库根目录的文件路径
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<OutlineItem>)` - 按出现顺序排列的标题列表
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 路径位于知识库以外
+/// * 文件不存在或无法读取
+#[tauri::command]
+pub async fn get_note_outline(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<OutlineItem>, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    let file_path = vault_file_path(vault_path, &path)?;
+    let content = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
+    Ok(build_outline(&content))
+}
+
+/// 从 Markdown 内容构建大纲
+fn build_outline(content: &str) -> Vec<OutlineItem> {
+    parse_markdown(content)
+        .headings
+        .into_iter()
+        .map(OutlineItem::from)
+        .collect()
+}
+
+/// 检查索引完整性
+///
+/// 检测悬空边、孤立属性和重复路径，用于崩溃后确认索引是否损坏。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(IntegrityReport)` - 检查报告
+/// * `Err(String)` - 检查失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn check_integrity(state: State<'_, AppState>) -> Result<IntegrityReport, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.check_integrity().map_err(|e| e.to_string())
+}
+
+/// 修复索引完整性
+///
+/// 删除悬空边和孤立属性，返回修复前的检查报告。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(IntegrityReport)` - 修复前的检查报告
+/// * `Err(String)` - 修复失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库操作失败
+#[tauri::command]
+pub async fn repair_integrity(state: State<'_, AppState>) -> Result<IntegrityReport, String> {
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+
+    let report = db.repair_integrity().map_err(|e| e.to_string())?;
+    state.invalidate_backlinks();
+    Ok(report)
+}
+
+/// DCOM 序列化源信息
+///
+/// 描述认知对象的物理存储位置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DCOMSourceInfo {
+    /// 源类型: "markdown", "binary", "virtual"
+    pub source_type: String,
+    /// 文件路径（如果是物理文件）
+    pub path: Option<String>,
+    /// 最后同步时间戳
+    pub last_sync: Option<i64>,
+}
+
+/// DCOM 链接信息
+///
+/// 描述从当前对象提取的链接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DCOMLinkInfo {
+    /// 链接目标
+    pub target: String,
+    /// 链接类型: "WikiLink", "BlockReference", "Embed", "External"
+    pub kind: String,
+    /// 显示文本
+    pub display_text: Option<String>,
+}
+
+/// DCOM 对象信息
+///
+/// 前端展示用的认知对象详细信息
+///
+/// # 字段说明
+///
+/// * `id` - 对象唯一标识
+/// * `title` - 标题
+/// * `object_type` - 对象类型（如 "note", "person"）
+/// * `tags` - 标签列表
+/// * `aliases` - 别名列表
+/// * `links` - 提取的链接信息
+/// * `properties` - 自定义属性
+/// * `sources` - 序列化源信息
+/// * `created_at` - 创建时间戳
+/// * `updated_at` - 更新时间戳
+/// * `capabilities` - 当前数据库支持的可选功能（全文索引、图算法）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DCOMInfo {
+    /// 对象唯一标识
+    pub id: String,
+    /// 标题
+    pub title: String,
+    /// 对象类型
+    pub object_type: Option<String>,
+    /// 标签列表
+    pub tags: Vec<String>,
+    /// 别名列表
+    pub aliases: Vec<String>,
+    /// 链接信息
+    pub links: Vec<DCOMLinkInfo>,
+    /// 自定义属性
+    pub properties: HashMap<String, serde_json::Value>,
+    /// 序列化源信息
+    pub sources: Vec<DCOMSourceInfo>,
+    /// 创建时间戳
+    pub created_at: i64,
+    /// 更新时间戳
+    pub updated_at: i64,
+    /// 数据库可选功能
+    pub capabilities: Capabilities,
+}
+
+/// 获取文件的 DCOM 信息
+///
+/// 使用适配器解析指定文件，返回完整的 DCOM 认知对象信息。
+///
+/// # 参数
+///
+/// * `path` - 文件相对路径（相对于知识库根目录）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(DCOMInfo)` - DCOM 对象信息
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 文件不存在
+/// * 文件格式不支持
+/// * 解析失败
 #[tauri::command]
 pub async fn get_dcom_info(path: String, state: State<'_, AppState>) -> Result<DCOMInfo, String> {
     let vault_path_guard = state.vault_path.lock().unwrap();
     let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+    let capabilities = state
+        .db
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|db| db.capabilities())
+        .unwrap_or_default();
+
+    // 构建完整文件路径
+    let file_path = vault_path.join(&path);
+    if !file_path.exists() {
+        return Err(format!("File not found: {}", path));
+    }
+
+    // 获取适配器
+    let registry = AdapterRegistry::default();
+    let adapter = registry
+        .find_adapter_for_path(&file_path)
+        .ok_or_else(|| format!("Unsupported file type: {}", path))?;
+
+    // 读取文件内容
+    let content = fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+    // 使用适配器解析
+    let obj = adapter
+        .load(Path::new(&path), &content)
+        .map_err(|e| format!("Failed to parse file: {}", e))?;
+
+    // 提取链接
+    let extracted_links = adapter.extract_links(&obj);
+    let links: Vec<DCOMLinkInfo> = extracted_links
+        .into_iter()
+        .map(|l| DCOMLinkInfo {
+            target: l.target,
+            kind: format!("{:?}", l.kind),
+            display_text: l.display_text,
+        })
+        .collect();
+
+    // 转换属性为 JSON
+    let properties: HashMap<String, serde_json::Value> = obj
+        .properties()
+        .iter()
+        .filter(|(k, _)| *k != "title" && *k != "content" && *k != "type")
+        .map(|(k, v)| (k.clone(), property_to_json(v)))
+        .collect();
+
+    // 构建源信息
+    let sources: Vec<DCOMSourceInfo> = obj
+        .sources()
+        .iter()
+        .map(|s| match s {
+            crate::dcom::SerializationSource::Markdown(m) => DCOMSourceInfo {
+                source_type: "markdown".to_string(),
+                path: Some(m.path.clone()),
+                last_sync: Some(m.last_modified),
+            },
+            crate::dcom::SerializationSource::Binary(b) => DCOMSourceInfo {
+                source_type: "binary".to_string(),
+                path: Some(b.path.clone()),
+                last_sync: Some(b.last_modified),
+            },
+            crate::dcom::SerializationSource::Virtual(v) => DCOMSourceInfo {
+                source_type: "virtual".to_string(),
+                path: None,
+                last_sync: Some(v.computed_at),
+            },
+        })
+        .collect();
+
+    Ok(DCOMInfo {
+        id: obj.id.to_string(),
+        title: obj.title().unwrap_or("Untitled").to_string(),
+        object_type: obj.get_type().map(|s| s.to_string()),
+        tags: obj.tags().to_vec(),
+        aliases: obj.aliases().to_vec(),
+        links,
+        properties,
+        sources,
+        created_at: obj.created_at,
+        updated_at: obj.updated_at,
+        capabilities,
+    })
+}
+
+/// 查询负责处理文件的适配器
+///
+/// 用于排查文件为何没有被索引：只有存在适配器的文件才会参与同步。
+///
+/// # 参数
+///
+/// * `path` - 文件相对路径（相对于 vault 根目录）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Some(String))` - 适配器名称，如 `"obsidian"`
+/// * `Ok(None)` - 没有适配器支持该文件
+/// * `Err(String)` - 未打开知识库
+#[tauri::command]
+pub async fn which_adapter(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    if state.vault_path.lock().unwrap().is_none() {
+        return Err("No vault opened".to_string());
+    }
+
+    Ok(AdapterRegistry::default()
+        .find_adapter_for_path(Path::new(&path))
+        .map(|adapter| adapter.name().to_string()))
+}
+
+/// 按序列化源类型列出节点
+///
+/// 用于按物理表示筛选，例如只显示以二进制文件（如 Canvas 白板）存储的对象。
+///
+/// # 参数
+///
+/// * `source_type` - 序列化源类型，`"Markdown"` 或 `"Binary"`（不区分大小写）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<Node>)` - 匹配的节点列表
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_nodes_by_source(
+    source_type: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Node>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_nodes_by_source_type(&source_type)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取笔记的属性
+///
+/// 返回数据库中记录的笔记属性，包括 frontmatter 属性、Dataview 风格的
+/// `key:: value` 内联字段以及同步时计算的 `_word_count`。
+///
+/// # 参数
+///
+/// * `path` - 文件相对路径（相对于知识库根目录）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(HashMap<String, serde_json::Value>)` - 属性名到 JSON 值的映射
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_note_properties(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, serde_json::Value>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    let uuid = uuid_for_path(db, &path).map_err(|e| e.to_string())?;
+    let properties = db.get_properties(&uuid).map_err(|e| e.to_string())?;
+
+    Ok(properties
+        .iter()
+        .map(|(name, value)| (name.clone(), property_to_json(value)))
+        .collect())
+}
+
+/// 按属性值比较查找笔记
+///
+/// 根据属性的存储类型进行比较，例如 `priority gt 3` 按数值比较、`due lt "2024-06-01"` 按日期比较。
+///
+/// # 参数
+///
+/// * `name` - 属性名
+/// * `op` - 比较运算符：`eq`、`ne`、`lt`、`gt`、`le`、`ge`
+/// * `value_json` - JSON 格式的目标值，无法解析为 JSON 时按纯字符串处理
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<Node>)` - 满足条件的笔记
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 运算符无效
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn query_property_cmp(
+    name: String,
+    op: String,
+    value_json: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Node>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.find_nodes_by_property_cmp(&name, &op, &parse_property_value(&value_json))
+        .map_err(|e| e.to_string())
+}
+
+/// 设置笔记的单个属性
+///
+/// 将属性写入磁盘文件的 frontmatter，并同步更新数据库中的属性记录和节点。
+///
+/// # 参数
+///
+/// * `path` - 文件相对路径（相对于知识库根目录）
+/// * `name` - 属性名
+/// * `value_json` - JSON 格式的属性值，无法解析为 JSON 时按纯字符串处理
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 设置成功，返回成功消息
+/// * `Err(String)` - 设置失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 文件不存在或格式不支持
+/// * 文件读写失败
+/// * 数据库操作失败
+#[tauri::command]
+pub async fn set_note_property(
+    path: String,
+    name: String,
+    value_json: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+
+    let value = parse_property_value(&value_json);
+    write_note_property(vault_path, db, &path, &name, value)?;
+
+    Ok("Property set successfully".to_string())
+}
+
+/// 删除笔记的单个属性
+///
+/// 从磁盘文件的 frontmatter 中移除指定属性，并同步删除数据库中的对应记录。
+///
+/// # 参数
+///
+/// * `path` - 文件相对路径（相对于知识库根目录）
+/// * `name` - 属性名
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 删除成功，返回成功消息
+/// * `Err(String)` - 删除失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 文件不存在或格式不支持
+/// * 文件读写失败
+/// * 数据库操作失败
+#[tauri::command]
+pub async fn delete_note_property(
+    path: String,
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    rewrite_note(vault_path, &path, |obj| {
+        obj.remove_property(&name).is_some()
+    })?;
+
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+    let uuid = uuid_for_path(db, &path).map_err(|e| e.to_string())?;
+    db.delete_property(&uuid, &name)
+        .map_err(|e| e.to_string())?;
+
+    Ok("Property deleted successfully".to_string())
+}
+
+/// 移除笔记的单个标签
+///
+/// 从磁盘文件的 frontmatter 中移除指定标签，并同步删除数据库中的对应记录。
+/// 正文中的内联 `#tag` 不会被改写。
+///
+/// # 参数
+///
+/// * `path` - 文件相对路径（相对于知识库根目录）
+/// * `tag` - 标签名
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 移除成功，返回成功消息
+/// * `Err(String)` - 移除失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 文件不存在或格式不支持
+/// * 文件读写失败
+/// * 数据库操作失败
+#[tauri::command]
+pub async fn remove_note_tag(
+    path: String,
+    tag: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    rewrite_note(vault_path, &path, |obj| obj.remove_tag(&tag))?;
+
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+    let uuid = uuid_for_path(db, &path).map_err(|e| e.to_string())?;
+    db.remove_tag(&uuid, &tag).map_err(|e| e.to_string())?;
+
+    Ok("Tag removed successfully".to_string())
+}
+
+/// 将数据库中的元数据写回笔记 frontmatter
+///
+/// 以数据库中的属性、标签和别名为准重新生成 frontmatter（如批量重命名标签后），
+/// 正文保持不变。
+///
+/// # 参数
+///
+/// * `path` - 文件相对路径（相对于知识库根目录）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 写入成功，返回成功消息
+/// * `Err(String)` - 写入失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 笔记未被索引、文件不存在或格式不支持
+/// * 文件读写失败
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn sync_frontmatter_to_disk(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    write_frontmatter_from_db(vault_path, db, &path)?;
+
+    Ok("Frontmatter written successfully".to_string())
+}
+
+/// 以数据库元数据重写笔记的 frontmatter 块
+///
+/// 读取数据库中的属性、标签和别名，经 [`replace_frontmatter`] 生成新文本后原子写回。
+///
+/// # 参数
+///
+/// * `vault_path` - 知识库根目录
+/// * `db` - 数据库实例
+/// * `path` - 文件相对路径
+///
+/// # 返回值
+///
+/// * `Ok(())` - 写入成功
+/// * `Err(String)` - 路径位于知识库以外，或数据库、解析、文件操作失败
+fn write_frontmatter_from_db(vault_path: &Path, db: &Database, path: &str) -> Result<(), String> {
+    let uuid = uuid_for_path(db, path).map_err(|e| e.to_string())?;
+    let stored = db.load_object(&uuid).map_err(|e| e.to_string())?;
+
+    let file_path = vault_file_path(vault_path, path)?;
+    let text = fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let output = replace_frontmatter(path, &text, stored)?;
+
+    write_atomic(&file_path, &output).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// 用给定对象的元数据替换笔记文本的 frontmatter 块
+///
+/// 从文本加载对象后，用 `stored` 的属性、标签和别名替换对象上的对应数据，
+/// 经适配器的 `save` 生成新的 frontmatter，再与原文本正文拼接。
+/// 正文中的 `#标签` 和 `key:: value` 内联字段仍由正文表达，不会复制到 frontmatter。
+///
+/// # 参数
+///
+/// * `path` - 文件相对路径，用于选择适配器
+/// * `text` - 笔记原文本
+/// * `stored` - 提供属性、标签和别名的对象
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 替换 frontmatter 后的文本
+/// * `Err(String)` - 格式不支持或解析失败
+fn replace_frontmatter(path: &str, text: &str, stored: CognitiveObject) -> Result<String, String> {
+    let file_path = Path::new(path);
+    let body = &text[frontmatter_end(text)..];
+
+    let registry = AdapterRegistry::default();
+    let adapter = registry
+        .find_adapter_for_path(file_path)
+        .ok_or_else(|| format!("Unsupported file type: {}", path))?;
+    let mut obj = adapter
+        .load(file_path, text.as_bytes())
+        .map_err(|e| format!("Failed to parse file: {}", e))?;
+
+    // 正文贡献的标签和内联字段不属于 frontmatter
+    let parsed_body = parse_markdown(body);
+    let inline_keys: Vec<&str> = parsed_body
+        .inline_fields
+        .iter()
+        .map(|(key, _)| key.as_str())
+        .collect();
+
+    // 标题、内容和类型沿用磁盘上的写法
+    let is_node_column = |key: &str| key == "title" || key == "content" || key == "type";
+    obj.properties.retain(|key, _| is_node_column(key));
+    for (key, value) in stored.properties {
+        if !is_node_column(&key) && !inline_keys.contains(&key.as_str()) {
+            obj.properties.insert(key, value);
+        }
+    }
+    obj.tags = stored
+        .tags
+        .into_iter()
+        .filter(|tag| !parsed_body.tags.contains(tag))
+        .collect();
+    obj.aliases = stored.aliases;
+
+    splice_frontmatter(adapter, text, &obj)
+}
+
+/// 用对象生成的 frontmatter 替换笔记文本的 frontmatter 块，正文逐字节保留
+///
+/// 适配器的 `save` 会重新生成正文（如补写 `# 标题`），这里只取其 frontmatter 部分。
+///
+/// # 参数
+///
+/// * `adapter` - 用于序列化对象的适配器
+/// * `text` - 笔记原文本
+/// * `obj` - 提供 frontmatter 内容的对象
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 替换 frontmatter 后的文本
+/// * `Err(String)` - 序列化失败
+fn splice_frontmatter(
+    adapter: &dyn ObjectAdapter,
+    text: &str,
+    obj: &CognitiveObject,
+) -> Result<String, String> {
+    let body = &text[frontmatter_end(text)..];
+
+    let saved = adapter
+        .save(obj)
+        .map_err(|e| format!("Failed to serialize file: {}", e))?;
+    let saved = String::from_utf8_lossy(&saved);
+    let frontmatter = &saved[..frontmatter_end(&saved)];
+
+    let mut output = frontmatter.trim_end().to_string();
+    if !output.is_empty() {
+        output.push('\n');
+        if body.len() == text.len() {
+            // 原文件没有 frontmatter 时补一个空行分隔
+            output.push('\n');
+        }
+    }
+    output.push_str(body);
+
+    Ok(output)
+}
+
+/// 获取标签大小写冲突
+///
+/// 返回仅大小写不同的标签分组（如 `#Rust` 与 `#rust`），便于用户合并。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<TagCaseConflict>)` - 冲突列表
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_tag_conflicts(state: State<'_, AppState>) -> Result<Vec<TagCaseConflict>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_tag_case_conflicts().map_err(|e| e.to_string())
+}
+
+/// 获取所有别名
+///
+/// 返回知识库中所有别名及声明它的笔记，供链接补全使用。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<(String, String)>)` - `(别名, 节点 UUID)` 列表
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_all_aliases(state: State<'_, AppState>) -> Result<Vec<(String, String)>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_all_aliases().map_err(|e| e.to_string())
+}
+
+/// 获取别名冲突
+///
+/// 返回被多篇笔记同时声明的别名，这些别名作为链接目标时无法唯一解析。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<AliasConflict>)` - 冲突列表
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_alias_conflicts(state: State<'_, AppState>) -> Result<Vec<AliasConflict>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_alias_conflicts().map_err(|e| e.to_string())
+}
+
+/// 获取标签的使用情况
+///
+/// 返回携带该标签的笔记，以及链接到该标签页（`tag:` 哨兵）的笔记。
+///
+/// # 参数
+///
+/// * `tag` - 标签名（可带前导 `#`）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<Node>)` - 节点列表
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_tag_usages(tag: String, state: State<'_, AppState>) -> Result<Vec<Node>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_tag_usages(&tag).map_err(|e| e.to_string())
+}
+
+/// 获取标签共现统计
+///
+/// 返回经常出现在同一笔记上的标签对，用于基于标签的发现。
+///
+/// # 参数
+///
+/// * `min_count` - 最少共现次数
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<(String, String, usize)>)` - `(标签 a, 标签 b, 共现次数)` 列表
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_tag_cooccurrence(
+    min_count: usize,
+    state: State<'_, AppState>,
+) -> Result<Vec<(String, String, usize)>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.get_tag_cooccurrence(min_count)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取图社区
+///
+/// 按笔记之间的链接连通性对节点分组，便于在大型知识库中按簇浏览。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(HashMap<String, usize>)` - 节点 UUID 到社区 ID 的映射
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn get_communities(state: State<'_, AppState>) -> Result<HashMap<String, usize>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    db.detect_communities().map_err(|e| e.to_string())
+}
+
+/// 解释两篇笔记之间的连接
+///
+/// 查找沿链接方向的最短路径，并为每一跳标注关系类型，
+/// 前端可据此渲染 `A —link→ B —tagged→ #x`。
+///
+/// # 参数
+///
+/// * `from_path` - 起点笔记相对路径
+/// * `to_path` - 终点笔记相对路径；以 `#` 开头时视为标签
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Some(Vec<ConnectionStep>))` - 路径上的每一跳
+/// * `Ok(None)` - 两者之间没有路径
+/// * `Err(String)` - 查询失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn explain_connection(
+    from_path: String,
+    to_path: String,
+    state: State<'_, AppState>,
+) -> Result<Option<Vec<ConnectionStep>>, String> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or("No vault opened")?;
+
+    let from = uuid_for_path(db, &from_path).map_err(|e| e.to_string())?;
+    let to = match to_path.strip_prefix('#') {
+        Some(tag) => format!("tag:{}", tag),
+        None => uuid_for_path(db, &to_path).map_err(|e| e.to_string())?,
+    };
+
+    db.explain_connection(&from, &to).map_err(|e| e.to_string())
+}
+
+/// 检查外部链接
+///
+/// 对知识库中所有外部 HTTP(S) 链接发起 HEAD 请求，返回每个链接的状态。
+/// 非 HTTP 协议的链接会被跳过。
+///
+/// # 参数
+///
+/// * `concurrency` - 并发请求上限，默认 8
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<LinkCheckResult>)` - 每个链接的检查结果（ok / broken / timeout）
+/// * `Err(String)` - 检查失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 数据库查询失败
+#[tauri::command]
+pub async fn check_external_links(
+    concurrency: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<LinkCheckResult>, String> {
+    // 请求期间不持有数据库锁
+    let urls = {
+        let db_guard = state.db.lock().unwrap();
+        let db = db_guard.as_ref().ok_or("No vault opened")?;
+        db.get_external_links().map_err(|e| e.to_string())?
+    };
+
+    let mut checker = LinkChecker::new();
+    if let Some(concurrency) = concurrency {
+        checker = checker.with_concurrency(concurrency);
+    }
+
+    Ok(checker.check(urls).await)
+}
+
+/// 重命名文件
+///
+/// 在磁盘上重命名/移动文件，并增量更新数据库：
+/// 其他笔记指向该文件的入链会被迁移到新节点，无需全量同步；
+/// 文件名改变时按 [`VaultSyncer::rename_file`] 重新解析入链。
+///
+/// # 参数
+///
+/// * `old_path` - 旧的相对路径
+/// * `new_path` - 新的相对路径
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 重命名成功，返回成功消息
+/// * `Err(String)` - 重命名失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 路径位于知识库以外
+/// * 源文件不存在或目标文件已存在
+/// * 文件系统操作失败
+/// * 数据库操作失败
+#[tauri::command]
+pub async fn rename_file(
+    old_path: String,
+    new_path: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    let old_file = vault_file_path(vault_path, &old_path)?;
+    let new_file = vault_file_path(vault_path, &new_path)?;
+    if !old_file.exists() {
+        return Err(format!("File not found: {}", old_path));
+    }
+    if new_file.exists() {
+        return Err(format!("File already exists: {}", new_path));
+    }
+
+    if let Some(parent) = new_file.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::rename(&old_file, &new_file).map_err(|e| e.to_string())?;
+
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+    vault_syncer(vault_path)?
+        .rename_file(&old_file, &new_file, vault_path, db)
+        .map_err(|e| e.to_string())?;
+    state.invalidate_backlinks();
+
+    Ok("File renamed successfully".to_string())
+}
+
+/// 合并两篇笔记
+///
+/// 将源笔记的正文追加到目标笔记末尾，并把源笔记独有的标签、别名和属性并入目标笔记的
+/// frontmatter（同名属性保留目标笔记的值）。随后删除源文件及其节点，
+/// 其他笔记指向源笔记的入链被重定向到目标笔记。
+///
+/// # 参数
+///
+/// * `source_path` - 被合并的笔记相对路径，合并后删除
+/// * `target_path` - 保留的笔记相对路径
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 合并成功，返回成功消息
+/// * `Err(String)` - 合并失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 路径位于知识库之外、文件不存在或两个路径相同
+/// * 任一笔记已加密
+/// * 文件读写失败
+/// * 数据库操作失败
+#[tauri::command]
+pub async fn merge_notes(
+    source_path: String,
+    target_path: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+
+    merge_note_files(vault_path, db, &source_path, &target_path)?;
+    state.invalidate_backlinks();
+
+    Ok("Notes merged successfully".to_string())
+}
+
+/// 全库查找替换
+///
+/// 在所有正文包含匹配内容的笔记中替换文本，逐个文件原子写回磁盘，并重新同步被修改的笔记。
+/// 只替换正文，frontmatter 保持不变（与查找时使用的索引内容一致）。
+///
+/// # 参数
+///
+/// * `pattern` - 查找内容
+/// * `replacement` - 替换内容；正则模式下支持 `$1`、`${name}` 等捕获组引用
+/// * `is_regex` - `pattern` 是否为正则表达式
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(usize)` - 被修改的文件数量
+/// * `Err(String)` - 替换失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 正则表达式无效（此时不会修改任何文件）
+/// * 文件读写失败
+/// * 数据库操作失败
+#[tauri::command]
+pub async fn replace_all(
+    pattern: String,
+    replacement: String,
+    is_regex: bool,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    let mut db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_mut().ok_or("No vault opened")?;
+
+    let changed = replace_in_vault(vault_path, db, &pattern, &replacement, is_regex)?;
+    state.invalidate_backlinks();
+    Ok(changed)
+}
+
+/// 在知识库中查找替换并重新同步被修改的笔记
+fn replace_in_vault(
+    vault_path: &Path,
+    db: &mut Database,
+    pattern: &str,
+    replacement: &str,
+    is_regex: bool,
+) -> Result<usize, String> {
+    let regex = if is_regex {
+        Regex::new(pattern)
+    } else {
+        Regex::new(&regex::escape(pattern))
+    }
+    .map_err(|e| format!("Invalid pattern: {}", e))?;
+
+    let mut paths: Vec<String> = db
+        .find_occurrences(pattern, is_regex)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(path, _, _)| path)
+        .collect();
+    paths.dedup();
+
+    let syncer = vault_syncer(vault_path)?;
+    let mut changed = 0;
+    for path in paths {
+        let file_path = vault_file_path(vault_path, &path)?;
+        let content = fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
+
+        let (frontmatter, body) = content.split_at(frontmatter_end(&content));
+        let replaced = if is_regex {
+            regex.replace_all(body, replacement)
+        } else {
+            regex.replace_all(body, NoExpand(replacement))
+        };
+        if replaced == body {
+            continue;
+        }
+
+        write_atomic(&file_path, &format!("{}{}", frontmatter, replaced))
+            .map_err(|e| e.to_string())?;
+        // 按单个文件的子树同步，重新解析该笔记的出链，入链保持不变
+        syncer
+            .sync_subtree(vault_path, Path::new(&path), db)
+            .map_err(|e| e.to_string())?;
+        changed += 1;
+    }
+
+    Ok(changed)
+}
+
+/// 将源笔记合并到目标笔记并更新索引
+///
+/// 源笔记的 `id` 属性不会并入，以免改变目标笔记的 UUID。
+/// 源笔记的文件名（不含扩展名）作为别名写入目标笔记，
+/// 使其他笔记中的 `[[源笔记]]` 在之后的全量同步中仍解析到目标笔记。
+/// 目标笔记原有的、指向源笔记的链接合并后不再保留为自环。
+fn merge_note_files(
+    vault_path: &Path,
+    db: &mut Database,
+    source: &str,
+    target: &str,
+) -> Result<(), String> {
+    if source == target {
+        return Err(format!("Cannot merge a note into itself: {}", source));
+    }
+    let source_file = vault_file_path(vault_path, source)?;
+    let target_file = vault_file_path(vault_path, target)?;
+    for (path, file) in [(source, &source_file), (target, &target_file)] {
+        if !file.is_file() {
+            return Err(format!("File not found: {}", path));
+        }
+        let content = fs::read(file).map_err(|e| format!("Failed to read file: {}", e))?;
+        reject_encrypted(path, &content)?;
+    }
+
+    let source_uuid = uuid_for_path(db, source).map_err(|e| e.to_string())?;
+    let target_uuid = uuid_for_path(db, target).map_err(|e| e.to_string())?;
+    let mut merged = db.load_object(&target_uuid).map_err(|e| e.to_string())?;
+    let extra = db.load_object(&source_uuid).map_err(|e| e.to_string())?;
+    for tag in extra.tags {
+        merged.add_tag(tag);
+    }
+    for alias in extra.aliases {
+        merged.add_alias(alias);
+    }
+    let stem = |path: &str| {
+        Path::new(path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+    };
+    if let Some(source_stem) = stem(source).filter(|s| Some(s) != stem(target).as_ref()) {
+        merged.add_alias(source_stem);
+    }
+    for (key, value) in extra.properties {
+        if key != ID_PROPERTY {
+            merged.properties.entry(key).or_insert(value);
+        }
+    }
+
+    let read =
+        |file: &Path| fs::read_to_string(file).map_err(|e| format!("Failed to read file: {}", e));
+    let source_text = read(&source_file)?;
+    let mut text = read(&target_file)?.trim_end().to_string();
+    let source_body = source_text[frontmatter_end(&source_text)..].trim();
+    if !source_body.is_empty() {
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str(source_body);
+    }
+    text.push('\n');
+
+    let output = replace_frontmatter(target, &text, merged)?;
+    write_atomic(&target_file, &output).map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::remove_file(&source_file).map_err(|e| e.to_string())?;
+
+    let inbound: Vec<_> = db
+        .get_edges_by_node(&source_uuid)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|e| {
+            e.dst_uuid == source_uuid && e.src_uuid != source_uuid && e.src_uuid != target_uuid
+        })
+        .collect();
+    db.delete_object(&source_uuid).map_err(|e| e.to_string())?;
+    vault_syncer(vault_path)?
+        .sync_subtree(vault_path, Path::new(target), db)
+        .map_err(|e| e.to_string())?;
+    for mut edge in inbound {
+        edge.dst_uuid = target_uuid.clone();
+        db.upsert_edge(&edge).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// 原子保存文件，可选备份原内容
+///
+/// 返回备份文件相对于知识库根目录的路径。
+fn save_with_backup(
+    vault_path: &Path,
+    path: &str,
+    content: &str,
+    backup: bool,
+) -> Result<Option<String>, String> {
+    let file_path = vault_file_path(vault_path, path)?;
+
+    // Ensure parent directory exists
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let mut backup_path = None;
+    if backup && file_path.is_file() {
+        let relative = format!("{}/{}.bak", BACKUPS_DIR, path);
+        let target = vault_file_path(vault_path, &relative)?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::copy(&file_path, &target).map_err(|e| e.to_string())?;
+        backup_path = Some(relative);
+    }
+
+    write_atomic(&file_path, content).map_err(|e| e.to_string())?;
+
+    Ok(backup_path)
+}
+
+/// 向文件末尾追加一行并重新同步该文件
+///
+/// 原内容不以换行结尾时先补一个换行，使追加的文本总是从新行开始。
+fn append_and_sync(
+    vault_path: &Path,
+    db: &mut Database,
+    path: &str,
+    text: &str,
+) -> Result<(), String> {
+    let file_path = vault_file_path(vault_path, path)?;
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let mut content = match fs::read_to_string(&file_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.to_string()),
+    };
+    reject_encrypted(path, content.as_bytes())?;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(text);
+    if !text.ends_with('\n') {
+        content.push('\n');
+    }
+
+    write_atomic(&file_path, &content).map_err(|e| e.to_string())?;
+
+    // 按单个文件的子树同步，追加的链接基于整个知识库的索引解析，入链保持不变
+    vault_syncer(vault_path)?
+        .sync_subtree(vault_path, Path::new(path), db)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 拒绝修改加密笔记
+///
+/// 加密笔记的内容是密文，追加或合并会破坏加密格式。
+fn reject_encrypted(path: &str, content: &[u8]) -> Result<(), String> {
+    if is_encrypted(content) {
+        return Err(format!("Cannot modify encrypted note: {}", path));
+    }
+    Ok(())
+}
+
+/// 原子写入文件
+///
+/// 先写入同目录下的临时文件再重命名覆盖，避免写入中断时留下半截内容。
+fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".cognistruct-tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+/// 列出可用的笔记模板
+///
+/// 返回 `.cognistruct/templates/` 下所有 `.md` 文件的名称（不含扩展名），按名称排序。
+///
+/// # 参数
+///
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(Vec<String>)` - 模板名称列表，模板目录不存在时为空
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 模板目录读取失败
+#[tauri::command]
+pub async fn list_templates(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
 
-    // 构建完整文件路径
-    let file_path = vault_path.join(&path);
+    list_template_names(vault_path)
+}
+
+/// 获取模板原始内容
+///
+/// 读取 `.cognistruct/templates/{name}.md` 的内容，用于预览。
+///
+/// # 参数
+///
+/// * `name` - 模板名称（不含扩展名）
+/// * `state` - 应用程序状态
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 模板内容
+/// * `Err(String)` - 获取失败，返回错误信息
+///
+/// # 错误情况
+///
+/// * 未打开知识库
+/// * 模板名称非法（包含路径分隔符）
+/// * 模板不存在或无法读取
+#[tauri::command]
+pub async fn get_template(name: String, state: State<'_, AppState>) -> Result<String, String> {
+    let vault_path_guard = state.vault_path.lock().unwrap();
+    let vault_path = vault_path_guard.as_ref().ok_or("No vault opened")?;
+
+    read_template(vault_path, &name)
+}
+
+/// 列出模板目录中的模板名称
+fn list_template_names(vault_path: &Path) -> Result<Vec<String>, String> {
+    let templates_dir = vault_path.join(TEMPLATES_DIR);
+    if !templates_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut names: Vec<String> = fs::read_dir(&templates_dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+
+    Ok(names)
+}
+
+/// 读取指定模板的内容
+fn read_template(vault_path: &Path, name: &str) -> Result<String, String> {
+    let mut components = Path::new(name).components();
+    let single_file_name = matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    );
+    if !single_file_name || name.contains(['/', '\\']) {
+        return Err(format!("Invalid template name: {}", name));
+    }
+
+    let template_path = vault_path.join(TEMPLATES_DIR).join(format!("{}.md", name));
+    if !template_path.is_file() {
+        return Err(format!("Template not found: {}", name));
+    }
+
+    fs::read_to_string(template_path).map_err(|e| e.to_string())
+}
+
+/// 通过适配器改写笔记的 frontmatter
+///
+/// 读取文件并加载为 `CognitiveObject`，交由 `edit` 修改后经 [`splice_frontmatter`]
+/// 重新生成 frontmatter 并原子写回磁盘，正文保持原样。
+/// 正文中的 `#标签` 仍由正文表达，不会复制到 frontmatter。
+///
+/// # 参数
+///
+/// * `vault_path` - 知识库根目录
+/// * `path` - 文件相对路径
+/// * `edit` - 修改对象的闭包，返回是否产生了改动
+///
+/// # 返回值
+///
+/// * `Ok(true)` - 对象已修改并写回
+/// * `Ok(false)` - 对象未改动，文件保持不变
+/// * `Err(String)` - 读取、解析或写入失败
+fn rewrite_note(
+    vault_path: &Path,
+    path: &str,
+    edit: impl FnOnce(&mut CognitiveObject) -> bool,
+) -> Result<bool, String> {
+    let file_path = vault_file_path(vault_path, path)?;
     if !file_path.exists() {
         return Err(format!("File not found: {}", path));
     }
 
-    // 获取适配器
     let registry = AdapterRegistry::default();
     let adapter = registry
         .find_adapter_for_path(&file_path)
         .ok_or_else(|| format!("Unsupported file type: {}", path))?;
 
-    // 读取文件内容
-    let content = fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
-
-    // 使用适配器解析
-    let obj = adapter
-        .load(Path::new(&path), &content)
+    let text = fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let mut obj = adapter
+        .load(Path::new(path), text.as_bytes())
         .map_err(|e| format!("Failed to parse file: {}", e))?;
 
-    // 提取链接
-    let extracted_links = adapter.extract_links(&obj);
-    let links: Vec<DCOMLinkInfo> = extracted_links
-        .into_iter()
-        .map(|l| DCOMLinkInfo {
-            target: l.target,
-            kind: format!("{:?}", l.kind),
-            display_text: l.display_text,
-        })
-        .collect();
+    if !edit(&mut obj) {
+        return Ok(false);
+    }
 
-    // 转换属性为 JSON
-    let properties: HashMap<String, serde_json::Value> = obj
-        .properties()
-        .iter()
-        .filter(|(k, _)| *k != "title" && *k != "content" && *k != "type")
-        .map(|(k, v)| (k.clone(), property_to_json(v)))
-        .collect();
+    let body_tags = parse_markdown(&text[frontmatter_end(&text)..]).tags;
+    obj.tags.retain(|tag| !body_tags.contains(tag));
+    let output = splice_frontmatter(adapter, &text, &obj)?;
+    write_atomic(&file_path, &output).map_err(|e| format!("Failed to write file: {}", e))?;
 
-    // 构建源信息
-    let sources: Vec<DCOMSourceInfo> = obj
-        .sources()
-        .iter()
-        .map(|s| match s {
-            crate::dcom::SerializationSource::Markdown(m) => DCOMSourceInfo {
-                source_type: "markdown".to_string(),
-                path: Some(m.path.clone()),
-                last_sync: Some(m.last_modified),
-            },
-            crate::dcom::SerializationSource::Binary(b) => DCOMSourceInfo {
-                source_type: "binary".to_string(),
-                path: Some(b.path.clone()),
-                last_sync: Some(b.last_modified),
-            },
-            crate::dcom::SerializationSource::Virtual(v) => DCOMSourceInfo {
-                source_type: "virtual".to_string(),
-                path: None,
-                last_sync: Some(v.computed_at),
-            },
-        })
-        .collect();
+    Ok(true)
+}
 
-    Ok(DCOMInfo {
-        id: obj.id.to_string(),
-        title: obj.title().unwrap_or("Untitled").to_string(),
-        object_type: obj.get_type().map(|s| s.to_string()),
-        tags: obj.tags().to_vec(),
-        aliases: obj.aliases().to_vec(),
-        links,
-        properties,
-        sources,
-        created_at: obj.created_at,
-        updated_at: obj.updated_at,
-    })
+/// 将前端传入的 JSON 字符串转换为属性值
+///
+/// 数字、布尔、数组、对象按 JSON 类型映射为对应的 [`PropertyValue`]；
+/// 无法解析为 JSON 的输入（如未加引号的 `done`）按纯字符串处理。
+fn parse_property_value(value_json: &str) -> PropertyValue {
+    serde_json::from_str::<serde_json::Value>(value_json)
+        .map(PropertyValue::from)
+        .unwrap_or_else(|_| PropertyValue::String(value_json.to_string()))
+}
+
+/// 写入笔记属性并更新索引
+///
+/// 通过 [`rewrite_note`] 写回 frontmatter，随后保存 properties 表记录，
+/// 并以改写后的对象刷新节点的内容和哈希。
+///
+/// # 参数
+///
+/// * `vault_path` - 知识库根目录
+/// * `db` - 数据库实例
+/// * `path` - 文件相对路径
+/// * `name` - 属性名
+/// * `value` - 属性值
+///
+/// # 返回值
+///
+/// * `Ok(())` - 写入成功
+/// * `Err(String)` - 文件或数据库操作失败
+fn write_note_property(
+    vault_path: &Path,
+    db: &mut Database,
+    path: &str,
+    name: &str,
+    value: PropertyValue,
+) -> Result<(), String> {
+    let mut updated = None;
+    rewrite_note(vault_path, path, |obj| {
+        obj.set_property(name, value.clone());
+        updated = Some(obj.clone());
+        true
+    })?;
+
+    let uuid = uuid_for_path(db, path).map_err(|e| e.to_string())?;
+    db.save_property(&uuid, name, &value)
+        .map_err(|e| e.to_string())?;
+
+    if let Some(obj) = updated {
+        let mut node = Node::from(&obj);
+        node.uuid = uuid;
+        node.path = path.to_string();
+        db.upsert_node(&node).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
 }
 
 /// 将 PropertyValue 转换为 JSON
@@ -540,7 +3454,8 @@ fn property_to_json(value: &crate::dcom::PropertyValue) -> serde_json::Value {
         PropertyValue::Float(f) => serde_json::json!(*f),
         PropertyValue::Boolean(b) => serde_json::Value::Bool(*b),
         PropertyValue::DateTime(dt) => serde_json::Value::String(dt.clone()),
-        PropertyValue::Reference(r) => serde_json::json!({ "ref": r }),
+        PropertyValue::Duration(secs) => serde_json::json!(*secs),
+        PropertyValue::Reference(r) => serde_json::json!({ "ref": r.to_string() }),
         PropertyValue::List(items) => {
             serde_json::Value::Array(items.iter().map(property_to_json).collect())
         }
@@ -551,9 +3466,116 @@ fn property_to_json(value: &crate::dcom::PropertyValue) -> serde_json::Value {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::adapters::obsidian::parse_frontmatter;
     use std::fs;
     use tempfile::TempDir;
 
+    /// 测试单层懒加载目录列表
+    #[test]
+    fn test_list_directory_single_level() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::create_dir_all(vault_path.join("sub/deep")).unwrap();
+        fs::write(vault_path.join("root.md"), "# Root").unwrap();
+        fs::write(vault_path.join("sub/child.md"), "# Child").unwrap();
+        fs::write(vault_path.join("sub/deep/leaf.md"), "# Leaf").unwrap();
+        fs::create_dir(vault_path.join(".cognistruct")).unwrap();
+
+        let root = list_directory(vault_path, None, None, false).unwrap();
+        let names: Vec<&str> = root.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["sub", "root.md"]);
+        assert!(root[0].is_dir);
+        assert!(root[0].children.is_none());
+
+        let sub = list_directory(vault_path, Some("sub"), None, false).unwrap();
+        let paths: Vec<PathBuf> = sub.iter().map(|n| PathBuf::from(&n.path)).collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("sub/deep"), PathBuf::from("sub/child.md")]
+        );
+
+        assert!(list_directory(vault_path, Some("missing"), None, false).is_err());
+        assert!(list_directory(vault_path, Some(".."), None, false).is_err());
+        assert!(list_directory(vault_path, Some("/"), None, false).is_err());
+    }
+
+    /// 测试按扩展名和通配符过滤文件
+    #[test]
+    fn test_list_directory_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::create_dir(vault_path.join("folder")).unwrap();
+        fs::write(vault_path.join("a.md"), "").unwrap();
+        fs::write(vault_path.join("B.MD"), "").unwrap();
+        fs::write(vault_path.join("daily-01.md"), "").unwrap();
+        fs::write(vault_path.join("image.png"), "").unwrap();
+
+        let names = |filter: &str| -> Vec<String> {
+            list_directory(vault_path, None, Some(filter), false)
+                .unwrap()
+                .into_iter()
+                .map(|n| n.name)
+                .collect()
+        };
+
+        assert_eq!(names("md"), vec!["folder", "B.MD", "a.md", "daily-01.md"]);
+        assert_eq!(names(".png"), vec!["folder", "image.png"]);
+        assert_eq!(names("daily-*.md"), vec!["folder", "daily-01.md"]);
+        assert_eq!(names("?.md"), vec!["folder", "a.md"]);
+    }
+
+    /// 测试列目录时遵循 .gitignore
+    #[test]
+    fn test_list_directory_respect_gitignore() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::create_dir_all(vault_path.join("code/target")).unwrap();
+        fs::create_dir(vault_path.join("code/src")).unwrap();
+        fs::write(vault_path.join("code/.gitignore"), "target/\n").unwrap();
+
+        let names = |respect: bool| -> Vec<String> {
+            list_directory(vault_path, Some("code"), None, respect)
+                .unwrap()
+                .into_iter()
+                .map(|n| n.name)
+                .collect()
+        };
+
+        assert_eq!(names(false), vec!["src", "target"]);
+        assert_eq!(names(true), vec!["src"]);
+    }
+
+    /// 测试列出和读取模板
+    #[test]
+    fn test_list_and_read_templates() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        // 模板目录不存在时返回空列表
+        assert!(list_template_names(vault_path).unwrap().is_empty());
+
+        let templates_dir = vault_path.join(TEMPLATES_DIR);
+        fs::create_dir_all(&templates_dir).unwrap();
+        fs::write(templates_dir.join("meeting.md"), "# Meeting\n\n## Agenda").unwrap();
+        fs::write(templates_dir.join("daily.md"), "# {{date}}").unwrap();
+        fs::write(templates_dir.join("notes.txt"), "ignored").unwrap();
+
+        assert_eq!(
+            list_template_names(vault_path).unwrap(),
+            vec!["daily", "meeting"]
+        );
+        assert_eq!(
+            read_template(vault_path, "meeting").unwrap(),
+            "# Meeting\n\n## Agenda"
+        );
+        assert!(read_template(vault_path, "missing").is_err());
+        assert!(read_template(vault_path, "../db").is_err());
+        assert!(read_template(vault_path, "..").is_err());
+        assert!(read_template(vault_path, "").is_err());
+        assert!(read_template(vault_path, "/etc/passwd").is_err());
+        assert!(read_template(vault_path, "sub\\meeting").is_err());
+    }
+
     /// 测试 FileNode 结构体的序列化
     #[test]
     fn test_file_node_serialization() {
@@ -601,6 +3623,677 @@ mod tests {
         assert!(state.db.lock().unwrap().is_none());
         assert!(state.vault_path.lock().unwrap().is_none());
         assert!(state.watcher.lock().unwrap().is_none());
+        assert!(state.backlinks.lock().unwrap().is_none());
+    }
+
+    /// 测试改写笔记时只删除指定属性
+    #[test]
+    fn test_rewrite_note_remove_property() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+        fs::write(
+            vault_path.join("note.md"),
+            "---\nauthor: Alice\nstatus: draft\n---\n# Note\n\nBody text.",
+        )
+        .unwrap();
+
+        let changed = rewrite_note(vault_path, "note.md", |obj| {
+            obj.remove_property("status").is_some()
+        })
+        .unwrap();
+        assert!(changed);
+
+        let saved = fs::read_to_string(vault_path.join("note.md")).unwrap();
+        let (fm, body) = parse_frontmatter(&saved);
+        let fm = fm.expect("rewritten frontmatter should parse");
+        assert_eq!(fm.properties.len(), 1);
+        assert_eq!(fm.properties["author"], serde_yaml::Value::from("Alice"));
+        assert_eq!(body, "# Note\n\nBody text.");
+        assert_eq!(&saved[frontmatter_end(&saved)..], "# Note\n\nBody text.");
+    }
+
+    /// 测试改写笔记时只移除指定标签
+    #[test]
+    fn test_rewrite_note_remove_tag() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+        fs::write(
+            vault_path.join("note.md"),
+            "---\ntags: [rust, wasm]\n---\n# Note\n\nBody text.",
+        )
+        .unwrap();
+
+        let changed = rewrite_note(vault_path, "note.md", |obj| obj.remove_tag("wasm")).unwrap();
+        assert!(changed);
+
+        let saved = fs::read_to_string(vault_path.join("note.md")).unwrap();
+        let (fm, body) = parse_frontmatter(&saved);
+        let fm = fm.expect("rewritten frontmatter should parse");
+        assert_eq!(fm.tags, vec!["rust"]);
+        assert!(fm.properties.is_empty());
+        assert_eq!(body, "# Note\n\nBody text.");
+        assert_eq!(&saved[frontmatter_end(&saved)..], "# Note\n\nBody text.");
+
+        // 未改动时不写入
+        let changed = rewrite_note(vault_path, "note.md", |obj| obj.remove_tag("missing")).unwrap();
+        assert!(!changed);
+
+        // 拒绝知识库以外的路径
+        let root_dir = TempDir::new().unwrap();
+        let inner_vault = root_dir.path().join("vault");
+        fs::create_dir(&inner_vault).unwrap();
+        let outside = "---\ntags: [rust]\n---\nOutside";
+        fs::write(root_dir.path().join("outside.md"), outside).unwrap();
+        assert!(rewrite_note(&inner_vault, "../outside.md", |obj| obj.remove_tag("rust")).is_err());
+        assert_eq!(
+            fs::read_to_string(root_dir.path().join("outside.md")).unwrap(),
+            outside
+        );
+    }
+
+    /// 测试属性值的类型转换
+    #[test]
+    fn test_build_outline() {
+        let content = "---\ntitle: Doc\n---\n# Guide\n\n## Install\n\n### Linux\n\n## Usage\n";
+        let outline = build_outline(content);
+
+        assert_eq!(
+            outline,
+            vec![
+                OutlineItem {
+                    level: 1,
+                    text: "Guide".to_string(),
+                    line_number: 4
+                },
+                OutlineItem {
+                    level: 2,
+                    text: "Install".to_string(),
+                    line_number: 6
+                },
+                OutlineItem {
+                    level: 3,
+                    text: "Linux".to_string(),
+                    line_number: 8
+                },
+                OutlineItem {
+                    level: 2,
+                    text: "Usage".to_string(),
+                    line_number: 10
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_save_with_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::create_dir_all(vault_path.join("notes")).unwrap();
+        fs::write(vault_path.join("notes/a.md"), "old content").unwrap();
+
+        let backup = save_with_backup(vault_path, "notes/a.md", "new content", true).unwrap();
+        assert_eq!(
+            backup.as_deref(),
+            Some(".cognistruct/backups/notes/a.md.bak")
+        );
+        assert_eq!(
+            fs::read_to_string(vault_path.join("notes/a.md")).unwrap(),
+            "new content"
+        );
+        assert_eq!(
+            fs::read_to_string(vault_path.join(backup.unwrap())).unwrap(),
+            "old content"
+        );
+        assert!(!vault_path.join("notes/a.md.cognistruct-tmp").exists());
+
+        // 新文件或未开启备份时不创建备份
+        assert_eq!(
+            save_with_backup(vault_path, "new/b.md", "fresh", true).unwrap(),
+            None
+        );
+        assert_eq!(
+            fs::read_to_string(vault_path.join("new/b.md")).unwrap(),
+            "fresh"
+        );
+        assert_eq!(
+            save_with_backup(vault_path, "notes/a.md", "newer", false).unwrap(),
+            None
+        );
+
+        // 拒绝知识库以外的路径
+        assert!(save_with_backup(vault_path, "../outside.md", "x", true).is_err());
+        assert!(save_with_backup(vault_path, "/tmp/outside.md", "x", true).is_err());
+        assert!(!temp_dir
+            .path()
+            .parent()
+            .unwrap()
+            .join("outside.md")
+            .exists());
+    }
+
+    #[test]
+    fn test_read_vault_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::create_dir(vault_path.join("notes")).unwrap();
+        fs::write(vault_path.join("a.md"), "# A").unwrap();
+        fs::write(vault_path.join("notes/b.md"), "# B").unwrap();
+
+        let paths = vec![
+            "a.md".to_string(),
+            "notes/b.md".to_string(),
+            "missing.md".to_string(),
+            "../a.md".to_string(),
+        ];
+        let contents = read_vault_files(vault_path, &paths);
+
+        assert_eq!(contents.len(), 2);
+        assert_eq!(contents["a.md"], "# A");
+        assert_eq!(contents["notes/b.md"], "# B");
+        assert!(vault_file_path(vault_path, "/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_replace_in_vault_keeps_links_and_frontmatter() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::write(
+            vault_path.join("a.md"),
+            "---\nstatus: OldTerm\n---\n# A\n\nOldTerm, see [[b]].",
+        )
+        .unwrap();
+        fs::write(vault_path.join("b.md"), "# B\n\nBack to [[a]].").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        let a = uuid_for_path(&db, "a.md").unwrap();
+        let b = uuid_for_path(&db, "b.md").unwrap();
+
+        let changed = replace_in_vault(vault_path, &mut db, "OldTerm", "NewTerm", false).unwrap();
+        assert_eq!(changed, 1);
+        assert_eq!(
+            fs::read_to_string(vault_path.join("a.md")).unwrap(),
+            "---\nstatus: OldTerm\n---\n# A\n\nNewTerm, see [[b]]."
+        );
+
+        // 被修改笔记的出链和指向它的入链都保留
+        let links: Vec<(String, String)> = db
+            .get_edges_by_node(&a)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.relation == "link")
+            .map(|e| (e.src_uuid, e.dst_uuid))
+            .collect();
+        assert!(links.contains(&(a.clone(), b.clone())));
+        assert!(links.contains(&(b.clone(), a.clone())));
+    }
+
+    #[test]
+    fn test_replace_in_vault() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::write(
+            vault_path.join("a.md"),
+            "# A\n\nUses OldTerm and OldTerm v1.2.",
+        )
+        .unwrap();
+        fs::write(vault_path.join("b.md"), "# B\n\nOldTerm v3.4 here.").unwrap();
+        fs::write(vault_path.join("c.md"), "# C\n\nUnrelated.").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        // 字面量替换，`$` 不被解释为捕获组
+        let changed = replace_in_vault(vault_path, &mut db, "OldTerm", "New$Term", false).unwrap();
+        assert_eq!(changed, 2);
+        assert_eq!(
+            fs::read_to_string(vault_path.join("a.md")).unwrap(),
+            "# A\n\nUses New$Term and New$Term v1.2."
+        );
+
+        // 正则捕获组
+        let changed =
+            replace_in_vault(vault_path, &mut db, r"v(\d+)\.(\d+)", "v$1-$2", true).unwrap();
+        assert_eq!(changed, 2);
+        assert_eq!(
+            fs::read_to_string(vault_path.join("b.md")).unwrap(),
+            "# B\n\nNew$Term v3-4 here."
+        );
+        assert!(!vault_path.join("b.md.cognistruct-tmp").exists());
+
+        // 数据库内容已重新同步
+        assert!(db.find_occurrences("OldTerm", false).unwrap().is_empty());
+        assert_eq!(db.find_occurrences("v1-2", false).unwrap().len(), 1);
+
+        // 无效正则不修改文件
+        assert!(replace_in_vault(vault_path, &mut db, "(", "x", true).is_err());
+        assert_eq!(
+            fs::read_to_string(vault_path.join("c.md")).unwrap(),
+            "# C\n\nUnrelated."
+        );
+    }
+
+    #[test]
+    fn test_append_and_sync() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::write(vault_path.join("a.md"), "# A\n\nFirst line").unwrap();
+        fs::write(vault_path.join("b.md"), "# B").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        let a = db.get_node_by_path("a.md").unwrap().unwrap();
+        let b = db.get_node_by_path("b.md").unwrap().unwrap();
+        assert!(db.get_edges_by_node(&a.uuid).unwrap().is_empty());
+
+        // 原内容没有结尾换行
+        append_and_sync(vault_path, &mut db, "a.md", "- see [[b]]").unwrap();
+        assert_eq!(
+            fs::read_to_string(vault_path.join("a.md")).unwrap(),
+            "# A\n\nFirst line\n- see [[b]]\n"
+        );
+        assert!(db
+            .get_edges_by_node(&a.uuid)
+            .unwrap()
+            .iter()
+            .any(|e| e.src_uuid == a.uuid && e.dst_uuid == b.uuid));
+
+        append_and_sync(vault_path, &mut db, "a.md", "second\n").unwrap();
+        assert!(fs::read_to_string(vault_path.join("a.md"))
+            .unwrap()
+            .ends_with("[[b]]\nsecond\n"));
+
+        // 文件不存在时创建
+        append_and_sync(vault_path, &mut db, "daily/today.md", "[[a]]").unwrap();
+        assert_eq!(
+            fs::read_to_string(vault_path.join("daily/today.md")).unwrap(),
+            "[[a]]\n"
+        );
+        assert!(db.get_node_by_path("daily/today.md").unwrap().is_some());
+
+        // 不向加密笔记追加明文
+        let secret = "-----BEGIN PGP MESSAGE-----\nhQEMA\n-----END PGP MESSAGE-----\n";
+        fs::write(vault_path.join("secret.md"), secret).unwrap();
+        assert!(append_and_sync(vault_path, &mut db, "secret.md", "leak").is_err());
+        assert_eq!(
+            fs::read_to_string(vault_path.join("secret.md")).unwrap(),
+            secret
+        );
+
+        // 拒绝知识库以外的路径
+        assert!(append_and_sync(vault_path, &mut db, "../outside.md", "x").is_err());
+        assert!(!temp_dir
+            .path()
+            .parent()
+            .unwrap()
+            .join("outside.md")
+            .exists());
+    }
+
+    #[test]
+    fn test_sync_changed_paths_coalesced() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::write(vault_path.join("a.md"), "# A").unwrap();
+        fs::write(vault_path.join("b.md"), "# B").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        fs::write(vault_path.join("a.md"), "# A\n\nSee [[b]]").unwrap();
+        fs::write(vault_path.join("c.md"), "# C").unwrap();
+
+        // 一次保存触发多个事件
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (a, c) = (vault_path.join("a.md"), vault_path.join("c.md"));
+        tx.send(vec![a.clone(), a.clone()]).unwrap();
+        tx.send(vec![c.clone(), a.clone()]).unwrap();
+        tx.send(vec![a, PathBuf::from("/elsewhere/x.md")]).unwrap();
+
+        let paths = crate::sync::watcher::coalesce_events(&rx, Duration::from_millis(50)).unwrap();
+        let (synced, errors) = sync_changed_paths(vault_path, &mut db, &paths);
+        assert_eq!(synced, vec!["a.md", "c.md"]);
+        assert!(errors.is_empty());
+
+        let a = db.get_node_by_path("a.md").unwrap().unwrap();
+        let b = db.get_node_by_path("b.md").unwrap().unwrap();
+        assert!(db
+            .get_edges_by_node(&b.uuid)
+            .unwrap()
+            .iter()
+            .any(|e| e.src_uuid == a.uuid));
+        assert!(db.get_node_by_path("c.md").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_parse_property_value() {
+        assert_eq!(
+            parse_property_value("\"done\""),
+            PropertyValue::string("done")
+        );
+        assert_eq!(parse_property_value("done"), PropertyValue::string("done"));
+        assert_eq!(parse_property_value("3"), PropertyValue::integer(3));
+        assert_eq!(parse_property_value("true"), PropertyValue::boolean(true));
+        assert_eq!(parse_property_value("null"), PropertyValue::Null);
+        assert_eq!(
+            parse_property_value("[\"a\", 1]"),
+            PropertyValue::List(vec![PropertyValue::string("a"), PropertyValue::integer(1)])
+        );
+    }
+
+    /// 测试将数据库元数据写回 frontmatter 且正文不变
+    #[test]
+    fn test_write_frontmatter_from_db() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        let body = "\n# Note\n\nBody with #inline tag.\n\nstatus:: open\n";
+        fs::write(
+            vault_path.join("note.md"),
+            format!("---\ntags: [a]\nauthor: Bob\n---\n{}", body),
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let uuid = uuid_for_path(&db, "note.md").unwrap();
+        let mut tags = db.get_tags(&uuid).unwrap();
+        tags.push("added".to_string());
+        db.save_tags(&uuid, &tags).unwrap();
+
+        write_frontmatter_from_db(vault_path, &db, "note.md").unwrap();
+
+        let saved = fs::read_to_string(vault_path.join("note.md")).unwrap();
+        let (frontmatter, rest) = saved.split_at(frontmatter_end(&saved));
+        assert_eq!(rest, body);
+        assert!(frontmatter.contains("author: \"Bob\""));
+        assert!(frontmatter.contains("tags: [a, added]"));
+        assert!(!frontmatter.contains("inline"));
+        assert!(!frontmatter.contains("status"));
+        assert!(!frontmatter.contains("word_count"));
+
+        // 没有 frontmatter 的笔记
+        fs::write(vault_path.join("plain.md"), "# Plain\n").unwrap();
+        VaultSyncer::with_defaults()
+            .sync_file(&vault_path.join("plain.md"), vault_path, &mut db)
+            .unwrap();
+        let uuid = uuid_for_path(&db, "plain.md").unwrap();
+        db.save_tags(&uuid, &["new".to_string()]).unwrap();
+
+        write_frontmatter_from_db(vault_path, &db, "plain.md").unwrap();
+        assert_eq!(
+            fs::read_to_string(vault_path.join("plain.md")).unwrap(),
+            "---\ntags:\n  - new\n---\n\n# Plain\n"
+        );
+
+        // TOML frontmatter 被替换而不是留在正文中
+        let toml_body = "\n# Toml\n\nBody\n";
+        fs::write(
+            vault_path.join("toml.md"),
+            format!("+++\nauthor = \"Bob\"\n+++\n{}", toml_body),
+        )
+        .unwrap();
+        VaultSyncer::with_defaults()
+            .sync_file(&vault_path.join("toml.md"), vault_path, &mut db)
+            .unwrap();
+        write_frontmatter_from_db(vault_path, &db, "toml.md").unwrap();
+        let saved = fs::read_to_string(vault_path.join("toml.md")).unwrap();
+        assert!(!saved.contains("+++"));
+        assert_eq!(&saved[frontmatter_end(&saved)..], toml_body);
+        let (fm, _) = parse_frontmatter(&saved);
+        assert_eq!(
+            fm.unwrap().properties["author"],
+            serde_yaml::Value::from("Bob")
+        );
+
+        // 拒绝知识库以外的路径
+        assert!(write_frontmatter_from_db(vault_path, &db, "../toml.md").is_err());
+        assert!(write_frontmatter_from_db(vault_path, &db, "/etc/hosts").is_err());
+    }
+
+    /// 测试合并笔记后入链指向目标笔记、源笔记被移除
+    #[test]
+    fn test_load_note_bundle() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        let note = "---\ntags: [rust]\naliases: [Middle]\nstatus: draft\n---\n# B\n\nSee [[c]].\n";
+        fs::write(vault_path.join("a.md"), "# A\n\nLinks to [[b]].").unwrap();
+        fs::write(vault_path.join("b.md"), note).unwrap();
+        fs::write(vault_path.join("c.md"), "# C").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let bundle = load_note_bundle(vault_path, &db, "b.md").unwrap();
+        assert_eq!(bundle.content, note);
+        assert_eq!(bundle.properties["status"], serde_json::json!("draft"));
+        assert_eq!(bundle.tags, vec!["rust"]);
+        assert_eq!(bundle.aliases, vec!["Middle"]);
+
+        assert_eq!(bundle.outgoing_links.len(), 1);
+        assert_eq!(bundle.outgoing_links[0].title, "C");
+        assert_eq!(bundle.outgoing_links[0].path.as_deref(), Some("c.md"));
+        assert_eq!(bundle.backlinks.len(), 1);
+        assert_eq!(bundle.backlinks[0].title, "A");
+        assert_eq!(bundle.backlinks[0].path.as_deref(), Some("a.md"));
+
+        assert!(load_note_bundle(vault_path, &db, "missing.md").is_err());
+        assert!(load_note_bundle(vault_path, &db, "../b.md").is_err());
+    }
+
+    #[test]
+    fn test_merge_note_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::write(
+            vault_path.join("old.md"),
+            "---\ntags: [rust, draft]\naliases: [Legacy]\nauthor: Alice\nstatus: wip\n---\n\n# Old\n\nOld body with [[other]].\n",
+        )
+        .unwrap();
+        fs::write(
+            vault_path.join("new.md"),
+            "---\ntags: [rust]\nstatus: done\n---\n\n# New\n\nNew body, see [[old]].\n",
+        )
+        .unwrap();
+        fs::write(vault_path.join("other.md"), "# Other").unwrap();
+        fs::write(vault_path.join("ref.md"), "Links to [[old]].").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        let old_uuid = uuid_for_path(&db, "old.md").unwrap();
+        let new_uuid = uuid_for_path(&db, "new.md").unwrap();
+        let ref_uuid = uuid_for_path(&db, "ref.md").unwrap();
+        let other_uuid = uuid_for_path(&db, "other.md").unwrap();
+
+        assert!(merge_note_files(vault_path, &mut db, "new.md", "new.md").is_err());
+
+        // 加密笔记不能作为合并的任一方
+        let secret = "-----BEGIN AGE ENCRYPTED FILE-----\nYWJj\n-----END AGE ENCRYPTED FILE-----\n";
+        fs::write(vault_path.join("secret.md"), secret).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_file(&vault_path.join("secret.md"), vault_path, &mut db)
+            .unwrap();
+        assert!(merge_note_files(vault_path, &mut db, "secret.md", "other.md").is_err());
+        assert!(merge_note_files(vault_path, &mut db, "other.md", "secret.md").is_err());
+        assert_eq!(
+            fs::read_to_string(vault_path.join("secret.md")).unwrap(),
+            secret
+        );
+        assert!(vault_path.join("other.md").exists());
+        fs::remove_file(vault_path.join("secret.md")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_file(&vault_path.join("secret.md"), vault_path, &mut db)
+            .unwrap();
+
+        merge_note_files(vault_path, &mut db, "old.md", "new.md").unwrap();
+
+        // 源笔记已删除
+        assert!(!vault_path.join("old.md").exists());
+        assert!(db.get_node_by_path("old.md").unwrap().is_none());
+        assert!(db.get_edges_by_node(&old_uuid).unwrap().is_empty());
+
+        // 目标笔记保留原内容，追加源正文，元数据不重复
+        let merged = fs::read_to_string(vault_path.join("new.md")).unwrap();
+        let (frontmatter, body) = merged.split_at(frontmatter_end(&merged));
+        assert!(body.contains("New body, see [[old]].\n\n# Old\n\nOld body with [[other]].\n"));
+        assert!(frontmatter.contains("tags: [rust, draft]"));
+        assert!(frontmatter.contains("Legacy"));
+        assert!(frontmatter.contains("old"));
+        assert!(frontmatter.contains("author: \"Alice\""));
+        assert!(frontmatter.contains("status: \"done\""));
+        assert_eq!(db.get_tags(&new_uuid).unwrap(), vec!["draft", "rust"]);
+
+        // 入链重定向到目标笔记，源正文的出链由目标笔记继承
+        let edges = db.get_edges_by_node(&new_uuid).unwrap();
+        assert!(edges
+            .iter()
+            .any(|e| e.src_uuid == ref_uuid && e.dst_uuid == new_uuid));
+        assert!(edges
+            .iter()
+            .any(|e| e.src_uuid == new_uuid && e.dst_uuid == other_uuid));
+        assert!(!edges.iter().any(|e| e.src_uuid == e.dst_uuid));
+
+        // 重新索引后，其他笔记中的 `[[old]]` 通过别名仍指向目标笔记
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        let ref_edges = db.get_edges_by_node(&ref_uuid).unwrap();
+        assert!(ref_edges
+            .iter()
+            .any(|e| e.src_uuid == ref_uuid && e.dst_uuid == new_uuid));
+        assert!(!ref_edges.iter().any(|e| e.dst_uuid == "missing:old"));
+        let edges = db.get_edges_by_node(&new_uuid).unwrap();
+        assert!(!edges.iter().any(|e| e.src_uuid == e.dst_uuid));
+    }
+
+    /// 测试设置属性后磁盘与数据库一致
+    #[test]
+    fn test_write_note_property() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::write(
+            vault_path.join("note.md"),
+            "---\nstatus: todo\n---\n\n# Note\n\nBody",
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        let before = db.get_node_by_path("note.md").unwrap().unwrap();
+
+        write_note_property(
+            vault_path,
+            &mut db,
+            "note.md",
+            "status",
+            parse_property_value("\"done\""),
+        )
+        .unwrap();
+
+        // 磁盘 frontmatter
+        let saved = fs::read(vault_path.join("note.md")).unwrap();
+        let registry = AdapterRegistry::default();
+        let adapter = registry
+            .find_adapter_for_path(Path::new("note.md"))
+            .unwrap();
+        let obj = adapter.load(Path::new("note.md"), &saved).unwrap();
+        assert_eq!(
+            obj.get_property("status"),
+            Some(&PropertyValue::string("done"))
+        );
+
+        // 数据库
+        let properties = db.get_properties(&before.uuid).unwrap();
+        assert_eq!(
+            properties.get("status"),
+            Some(&PropertyValue::string("done"))
+        );
+
+        let after = db.get_node_by_path("note.md").unwrap().unwrap();
+        assert_eq!(after.uuid, before.uuid);
+        assert_eq!(after.title, "Note");
+        assert!(after.content.contains("Body"));
+        let saved = String::from_utf8(saved).unwrap();
+        assert_eq!(&saved[frontmatter_end(&saved)..], "\n# Note\n\nBody");
+
+        // 设置属性不改动正文：无标题、二级标题开头、CRLF 且没有 frontmatter
+        for (name, original, body) in [
+            (
+                "plain.md",
+                "---\nstatus: a\n---\nJust some text\n",
+                "Just some text\n",
+            ),
+            (
+                "sub.md",
+                "---\nstatus: a\n---\n## Sub heading\n\nText\n",
+                "## Sub heading\n\nText\n",
+            ),
+            (
+                "crlf.md",
+                "First line\r\nSecond line\r\n",
+                "First line\r\nSecond line\r\n",
+            ),
+        ] {
+            fs::write(vault_path.join(name), original).unwrap();
+            write_note_property(
+                vault_path,
+                &mut db,
+                name,
+                "status",
+                parse_property_value("\"b\""),
+            )
+            .unwrap();
+            let saved = fs::read_to_string(vault_path.join(name)).unwrap();
+            let (fm, _) = parse_frontmatter(&saved);
+            assert_eq!(
+                fm.unwrap().properties["status"],
+                serde_yaml::Value::from("b")
+            );
+            assert!(saved.ends_with(body), "{}: {:?}", name, saved);
+            assert_eq!(
+                saved[frontmatter_end(&saved)..].trim_start_matches('\n'),
+                body
+            );
+            assert!(
+                !saved.contains("# Just")
+                    && !saved.contains("\n# Sub")
+                    && !saved.contains("# First")
+            );
+        }
+
+        // 拒绝知识库以外的路径
+        assert!(write_note_property(
+            vault_path,
+            &mut db,
+            "../note.md",
+            "status",
+            PropertyValue::Null
+        )
+        .is_err());
     }
 
     /// 测试 FileNode 反序列化
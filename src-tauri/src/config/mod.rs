@@ -0,0 +1,233 @@
+//! # Config 模块
+//!
+//! 本模块提供知识库级别的配置，配置文件位于知识库的 `.cognistruct/config.toml`。
+//!
+//! ## 模块依赖
+//!
+//! - `toml` - 配置文件解析与序列化
+//! - `serde` - 序列化/反序列化
+//! - `anyhow` - 错误处理
+//!
+//! ## 导出的主要内容
+//!
+//! ### 结构体
+//! - [`VaultConfig`] - 知识库配置
+//!
+//! ### 函数
+//! - [`parse_config`] - 解析配置文件内容
+//! - [`load_config`] - 从知识库加载配置，文件不存在时使用默认值
+//! - [`save_config`] - 将配置写入知识库
+//!
+//! ## 配置文件示例
+//!
+//! ```toml
+//! debounce_ms = 500
+//! ignore_patterns = ["drafts/", "*.tmp.md"]
+//! respect_gitignore = true
+//! create_folder_nodes = false
+//! follow_links = false
+//! fold_tag_case = false
+//! expand_tag_parents = false
+//! max_graph_nodes = 5000
+//! ```
+//!
+//! 所有字段均可省略，省略的字段使用默认值。
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// 配置文件相对于知识库根目录的路径
+pub const CONFIG_FILE: &str = ".cognistruct/config.toml";
+
+/// 默认的文件监听防抖时间（毫秒）
+pub const DEFAULT_DEBOUNCE_MS: u64 = 200;
+
+/// 默认返回给前端的图节点数上限
+pub const DEFAULT_MAX_GRAPH_NODES: usize = 5000;
+
+/// 知识库配置
+///
+/// # 字段说明
+///
+/// * `debounce_ms` - 文件监听的防抖时间（毫秒）
+/// * `ignore_patterns` - 同步时跳过的路径，使用 gitignore 语法，相对于知识库根目录
+/// * `respect_gitignore` - 是否跳过被 `.gitignore` 忽略的文件
+/// * `create_folder_nodes` - 是否为目录创建文件夹节点
+/// * `follow_links` - 是否跟随符号链接
+/// * `fold_tag_case` - 是否将标签折叠为小写
+/// * `expand_tag_parents` - 是否为嵌套标签（如 `project/rust`）在标签表中写入父级标签行
+/// * `max_graph_nodes` - 图视图最多返回的节点数，超过时只保留连接度最高的节点；`0` 表示不限制
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VaultConfig {
+    /// 防抖时间（毫秒）
+    pub debounce_ms: u64,
+    /// 忽略规则
+    pub ignore_patterns: Vec<String>,
+    /// 是否遵循 `.gitignore`
+    pub respect_gitignore: bool,
+    /// 是否创建文件夹节点
+    pub create_folder_nodes: bool,
+    /// 是否跟随符号链接
+    pub follow_links: bool,
+    /// 是否折叠标签大小写
+    pub fold_tag_case: bool,
+    /// 是否展开嵌套标签的父级
+    pub expand_tag_parents: bool,
+    /// 图节点数上限
+    pub max_graph_nodes: usize,
+}
+
+impl Default for VaultConfig {
+    fn default() -> Self {
+        VaultConfig {
+            debounce_ms: DEFAULT_DEBOUNCE_MS,
+            ignore_patterns: Vec::new(),
+            respect_gitignore: false,
+            create_folder_nodes: false,
+            follow_links: false,
+            fold_tag_case: false,
+            expand_tag_parents: false,
+            max_graph_nodes: DEFAULT_MAX_GRAPH_NODES,
+        }
+    }
+}
+
+impl VaultConfig {
+    /// 获取文件监听的防抖时间
+    pub fn debounce(&self) -> Duration {
+        Duration::from_millis(self.debounce_ms)
+    }
+}
+
+/// 解析配置文件内容
+///
+/// # 参数
+///
+/// * `toml` - `config.toml` 文件内容
+///
+/// # 返回值
+///
+/// * `Ok(VaultConfig)` - 解析出的配置，缺省字段使用默认值
+/// * `Err(anyhow::Error)` - 格式无效
+pub fn parse_config(toml: &str) -> Result<VaultConfig> {
+    toml::from_str(toml).context("配置文件格式无效")
+}
+
+/// 从知识库加载配置
+///
+/// # 参数
+///
+/// * `vault_path` - 知识库根目录
+///
+/// # 返回值
+///
+/// * `Ok(VaultConfig)` - 知识库配置，配置文件不存在时为默认配置
+/// * `Err(anyhow::Error)` - 文件读取失败或格式无效
+pub fn load_config(vault_path: &Path) -> Result<VaultConfig> {
+    let path = vault_path.join(CONFIG_FILE);
+    if !path.exists() {
+        return Ok(VaultConfig::default());
+    }
+
+    let toml = fs::read_to_string(&path).context("读取配置文件失败")?;
+    parse_config(&toml)
+}
+
+/// 将配置写入知识库
+///
+/// 覆盖 `.cognistruct/config.toml`，目录不存在时自动创建。
+///
+/// # 参数
+///
+/// * `vault_path` - 知识库根目录
+/// * `config` - 要保存的配置
+///
+/// # 返回值
+///
+/// * `Ok(())` - 写入成功
+/// * `Err(anyhow::Error)` - 序列化或写入失败
+pub fn save_config(vault_path: &Path, config: &VaultConfig) -> Result<()> {
+    let path = vault_path.join(CONFIG_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("创建配置目录失败")?;
+    }
+
+    let toml = toml::to_string_pretty(config).context("序列化配置失败")?;
+    fs::write(&path, toml).context("写入配置文件失败")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use crate::sync::VaultSyncer;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_config_defaults() {
+        assert_eq!(parse_config("").unwrap(), VaultConfig::default());
+        assert!(parse_config("debounce_ms = \"soon\"").is_err());
+
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(
+            load_config(temp_dir.path()).unwrap(),
+            VaultConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_config_applied_to_syncer() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        fs::create_dir_all(vault_path.join(".cognistruct")).unwrap();
+        fs::write(
+            vault_path.join(CONFIG_FILE),
+            "debounce_ms = 500\nignore_patterns = [\"drafts/\", \"*.tmp.md\"]\n",
+        )
+        .unwrap();
+        fs::create_dir(vault_path.join("drafts")).unwrap();
+        fs::write(vault_path.join("drafts/idea.md"), "# Idea").unwrap();
+        fs::write(vault_path.join("scratch.tmp.md"), "# Scratch").unwrap();
+        fs::write(vault_path.join("note.md"), "# Note").unwrap();
+
+        let config = load_config(vault_path).unwrap();
+        assert_eq!(config.debounce(), Duration::from_millis(500));
+        assert_eq!(config.ignore_patterns, vec!["drafts/", "*.tmp.md"]);
+        assert!(!config.respect_gitignore);
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .with_config(&config)
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let paths: Vec<String> = db
+            .get_all_nodes()
+            .unwrap()
+            .into_iter()
+            .map(|n| n.path)
+            .collect();
+        assert_eq!(paths, vec!["note.md"]);
+    }
+
+    #[test]
+    fn test_save_and_load_config() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let config = VaultConfig {
+            respect_gitignore: true,
+            ignore_patterns: vec!["build/".to_string()],
+            ..Default::default()
+        };
+        save_config(temp_dir.path(), &config).unwrap();
+
+        assert_eq!(load_config(temp_dir.path()).unwrap(), config);
+    }
+}
@@ -4,6 +4,7 @@
 //!
 //! ## 模块依赖
 //!
+//! - [`crate::dcom`] - DCOM 核心数据结构（对象与节点的相互转换）
 //! - `cozo` - CozoDB 嵌入式数据库引擎
 //! - `anyhow` - 错误处理
 //! - `serde` - 序列化/反序列化
@@ -15,6 +16,24 @@
 //! - [`Node`] - 知识节点
 //! - [`Edge`] - 知识节点之间的边（关系）
 //! - [`GraphData`] - 图数据（包含节点和边）
+//! - [`ForceGraph`] - 力导向布局输入（由 [`GraphData::to_force_layout`] 生成）
+//! - [`GraphMetadata`] / [`NodeMeta`] - 不含节点正文的轻量图数据
+//! - [`TagCaseConflict`] - 仅大小写不同的标签冲突
+//! - [`AliasConflict`] - 被多篇笔记声明的别名
+//! - [`ConnectionStep`] - 两个节点之间最短路径上的一跳
+//! - [`NoteLink`] - 解析为标题和路径的出链或反向链接
+//! - [`ExportRecord`] - NDJSON 导出/导入的单行记录
+//! - [`SearchQuery`] - 高级搜索条件
+//! - [`SearchHit`] / [`SearchMatch`] - 搜索结果及首个匹配位置
+//! - [`IntegrityReport`] - 数据库完整性检查报告
+//! - [`SyncPlan`] - 两次索引快照之间的文件变化
+//! - [`LinkStats`] - 链接统计
+//! - [`Capabilities`] - 数据库可选功能（全文索引、图算法）
+//! - [`QueryValidation`] / [`QueryError`] - CozoScript 校验结果及错误位置
+//!
+//! ### 枚举
+//! - [`EdgeSource`] - 边来源（`Edge.source` 的标准取值）
+//! - [`LinkResolution`] - 链接边的目标解析方式
 //!
 //! ## 数据模型
 //!
@@ -39,11 +58,200 @@
 //! let graph_data = db.get_graph_data()?;
 //! ```
 
+use crate::dcom::serialization::BinarySource;
+use crate::dcom::{CognitiveObject, MarkdownSource, PropertyValue, SerializationSource};
 use anyhow::Result;
 use cozo::{DataValue, DbInstance, ScriptMutability};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufRead, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// 哨兵节点前缀
+///
+/// 以这些前缀开头的边目标（如 `tag:rust`、`url:https://...`）不对应 nodes 表中的真实节点，
+/// 完整性检查时不视为悬空。`missing:` 表示无法解析的笔记链接（断链）。
+const SENTINEL_PREFIXES: [&str; 4] = ["tag:", "file:", "url:", "missing:"];
+
+/// 链接统计中保留的被链接最多笔记数量
+const TOP_LINKED_LIMIT: usize = 10;
+
+/// 环检测最多返回的环数量，避免稠密图上枚举爆炸
+const MAX_CYCLES: usize = 1000;
+
+/// 环检测允许的最大环长度，更大的 `max_len` 会被截断
+const MAX_CYCLE_LEN: usize = 10;
+
+/// 环检测最多扩展的搜索步数，稠密图上没有环时同样会提前结束
+const MAX_CYCLE_STEPS: usize = 200_000;
+
+/// 完整性报告中每类问题保留的样例数量上限
+const INTEGRITY_SAMPLE_LIMIT: usize = 10;
+
+/// 校验查询时试运行返回的行数上限
+const VALIDATE_ROW_LIMIT: usize = 1;
+
+/// 流式导出时每批读取的节点数，避免一次性加载所有笔记正文
+const EXPORT_BATCH_SIZE: usize = 500;
+
+/// 保留的索引快照数量，每次打开知识库都会新建快照
+const MAX_SNAPSHOTS: usize = 10;
+
+/// 存储在 nodes 表列中的对象属性
+///
+/// 这些属性由 `title`、`content`、`node_type` 列承载，不重复写入 properties 表。
+const NODE_COLUMN_PROPERTIES: [&str; 3] = ["title", "content", "type"];
+
+/// 数据库 Schema
+///
+/// 每项为 `(表名, 建表脚本)`。打开数据库时只创建尚不存在的表，见 [`Database::init_schema`]。
+const SCHEMA: [(&str, &str); 11] = [
+    // Create nodes table - 认知对象核心表
+    // 保持向后兼容，同时支持 DCOM 扩展字段
+    (
+        "nodes",
+        r#"
+        :create nodes {
+            uuid: String,
+            =>
+            path: String,
+            title: String,
+            content: String,
+            node_type: String,
+            hash: String,
+            created_at: Int,
+            updated_at: Int
+        }
+        "#,
+    ),
+    // Create edges table - 关系边表
+    (
+        "edges",
+        r#"
+        :create edges {
+            src_uuid: String,
+            dst_uuid: String,
+            =>
+            relation: String,
+            weight: Float,
+            source: String
+        }
+        "#,
+    ),
+    // Create properties table - EAV 动态属性表
+    // 实现 Schema-less 的属性存储
+    (
+        "properties",
+        r#"
+        :create properties {
+            object_id: String,
+            name: String,
+            =>
+            value_type: String,
+            value_json: String
+        }
+        "#,
+    ),
+    // Create sources table - 序列化源表
+    // 记录对象的物理表示形式
+    (
+        "sources",
+        r#"
+        :create sources {
+            object_id: String,
+            source_type: String,
+            =>
+            path: String?,
+            content_hash: String?,
+            mime_type: String?,
+            size_bytes: Int?,
+            last_modified: Int
+        }
+        "#,
+    ),
+    // Create tags table - 标签表（多对多关系）
+    (
+        "tags",
+        r#"
+        :create tags {
+            object_id: String,
+            tag: String
+        }
+        "#,
+    ),
+    // Create tag_parents table - 嵌套标签展开出的隐式父级标签
+    // 对应的行同时写入 tags 表；独立成表使已有数据库无需迁移
+    (
+        "tag_parents",
+        r#"
+        :create tag_parents {
+            object_id: String,
+            tag: String
+        }
+        "#,
+    ),
+    // Create aliases table - 别名表
+    (
+        "aliases",
+        r#"
+        :create aliases {
+            object_id: String,
+            alias: String
+        }
+        "#,
+    ),
+    // Create snapshots table - 索引快照表
+    (
+        "snapshots",
+        r#"
+        :create snapshots {
+            snapshot_id: Int,
+            =>
+            created_at: Int
+        }
+        "#,
+    ),
+    // Create node_snapshots table - 快照中的节点哈希
+    // clear_all 不会清除快照，全量同步前后的快照可以比较
+    (
+        "node_snapshots",
+        r#"
+        :create node_snapshots {
+            snapshot_id: Int,
+            path: String,
+            =>
+            hash: String
+        }
+        "#,
+    ),
+    // Create edge_resolutions table - 链接边的解析方式
+    // 独立于 edges 表，已有数据库无需迁移；查询时与 edges 关联，过期记录不会返回
+    (
+        "edge_resolutions",
+        r#"
+        :create edge_resolutions {
+            src_uuid: String,
+            dst_uuid: String,
+            =>
+            method: String
+        }
+        "#,
+    ),
+    // Create layout table - 图谱节点坐标
+    // clear_all 不会清除坐标，重新索引后位置保持不变
+    (
+        "layout",
+        r#"
+        :create layout {
+            uuid: String,
+            =>
+            x: Float,
+            y: Float
+        }
+        "#,
+    ),
+];
 
 /// 数据库操作封装
 ///
@@ -52,9 +260,75 @@ use std::path::PathBuf;
 /// # 字段说明
 ///
 /// * `db` - CozoDB 数据库实例
+/// * `capabilities` - 打开数据库时探测到的可选功能
+/// * `expand_tag_parents` - 保存嵌套标签时是否同时写入父级标签行
 pub struct Database {
     /// CozoDB 数据库实例
     db: DbInstance,
+    /// 可选功能
+    capabilities: Capabilities,
+    /// 是否展开嵌套标签的父级
+    expand_tag_parents: bool,
+}
+
+/// 数据库可选功能
+///
+/// CozoDB 的全文索引和图算法取决于编译时启用的特性。打开数据库时逐项执行一个
+/// 极小的测试查询，记录哪些功能可用，依赖这些功能的查询据此回退到内存实现。
+///
+/// # 字段说明
+///
+/// * `full_text_search` - 是否支持全文索引（`::fts`）
+/// * `graph_algorithms` - 是否支持图算法（如 `ConnectedComponents`、`PageRank`）
+/// * `shortest_path` - 是否支持最短路径算法（`ShortestPathDijkstra`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// 全文索引
+    pub full_text_search: bool,
+    /// 图算法
+    pub graph_algorithms: bool,
+    /// 最短路径
+    pub shortest_path: bool,
+}
+
+impl Capabilities {
+    /// 探测数据库实例支持的可选功能
+    ///
+    /// 探测失败只会将对应标志置为 `false`，不会返回错误。
+    fn probe(db: &DbInstance) -> Self {
+        let succeeds = |script: &str| {
+            db.run_script(script, Default::default(), ScriptMutability::Mutable)
+                .is_ok()
+        };
+
+        let graph_algorithms = succeeds(
+            r#"
+            e[a, b] <- [["a", "b"]]
+            ?[node, rank] <~ PageRank(e[])
+            "#,
+        );
+        let shortest_path = succeeds(
+            r#"
+            e[a, b] <- [["a", "b"]]
+            start[n] <- [["a"]]
+            goal[n] <- [["b"]]
+            ?[s, g, cost, path] <~ ShortestPathDijkstra(e[], start[], goal[])
+            "#,
+        );
+
+        // 全文索引只能建在存储表上，使用临时表探测后删除
+        let _ = succeeds("::remove capability_probe");
+        let full_text_search = succeeds(":create capability_probe {k: String => v: String}")
+            && succeeds("::fts create capability_probe:fts {extractor: v, tokenizer: Simple}");
+        let _ = succeeds("::fts drop capability_probe:fts");
+        let _ = succeeds("::remove capability_probe");
+
+        Capabilities {
+            full_text_search,
+            graph_algorithms,
+            shortest_path,
+        }
+    }
 }
 
 /// 知识节点
@@ -91,6 +365,58 @@ pub struct Node {
     pub updated_at: i64,
 }
 
+impl From<&CognitiveObject> for Node {
+    /// 将认知对象扁平化为节点
+    ///
+    /// 使用对象 ID 作为 UUID，Markdown 源路径作为 `path`；
+    /// 缺少标题时使用文件名，缺少类型时默认为 `note`。时间戳由毫秒转换为秒。
+    fn from(obj: &CognitiveObject) -> Self {
+        let path = obj.path().unwrap_or("").to_string();
+        let title = obj.title().map(|s| s.to_string()).unwrap_or_else(|| {
+            std::path::Path::new(&path)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Untitled")
+                .to_string()
+        });
+        let content = obj.content().unwrap_or("").to_string();
+
+        Node {
+            uuid: obj.id.as_str().to_string(),
+            path,
+            title,
+            hash: crate::sync::calculate_hash(&content),
+            content,
+            node_type: obj.get_type().unwrap_or("note").to_string(),
+            created_at: obj.created_at / 1000,
+            updated_at: obj.updated_at / 1000,
+        }
+    }
+}
+
+impl Node {
+    /// 由节点重建最小的认知对象
+    ///
+    /// [`Node::from`] 的逆操作：以 UUID 作为对象 ID，还原标题、内容和类型，
+    /// 并以 `path` 作为 Markdown 序列化源。不读取磁盘和其他表，
+    /// 可直接对数据库中的内容运行适配器的 `extract_links`；
+    /// 需要属性、标签和别名时使用 [`Database::load_object`]。
+    pub fn to_cognitive_object(&self) -> CognitiveObject {
+        let mut obj = CognitiveObject::with_id(self.uuid.as_str());
+        obj.created_at = self.created_at * 1000;
+        obj.updated_at = self.updated_at * 1000;
+        obj.set_title(&self.title);
+        obj.set_content(&self.content);
+        obj.set_type(&self.node_type);
+        obj.add_source(SerializationSource::Markdown(MarkdownSource::new(
+            &self.path,
+            &self.hash,
+            self.updated_at,
+        )));
+        obj
+    }
+}
+
 /// 知识边（关系）
 ///
 /// 表示知识图谱中两个节点之间的关系。
@@ -124,12 +450,262 @@ pub struct Edge {
 ///
 /// * `nodes` - 所有知识节点的列表
 /// * `edges` - 所有关系边的列表
+/// * `properties` - 节点 UUID 到属性的映射（仅导出子图时填充）
+/// * `tags` - 节点 UUID 到标签的映射（仅导出子图时填充）
+/// * `truncated` - 节点数超过上限、只保留了连接度最高的节点时为 `true`
+/// * `total_nodes` - 裁剪前的节点总数
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphData {
     /// 知识节点列表
     pub nodes: Vec<Node>,
     /// 关系边列表
     pub edges: Vec<Edge>,
+    /// 节点属性
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, HashMap<String, PropertyValue>>,
+    /// 节点标签
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, Vec<String>>,
+    /// 是否被裁剪
+    #[serde(default)]
+    pub truncated: bool,
+    /// 裁剪前的节点总数
+    #[serde(default)]
+    pub total_nodes: usize,
+}
+
+impl GraphData {
+    /// 转换为力导向布局的输入格式
+    ///
+    /// 节点以 UUID 为 `id`、标题为 `label`、节点类型为 `group`；
+    /// 边以权重为 `value`。端点不是节点的边（如指向 `tag:` 等哨兵）会被省略，
+    /// 以免布局库找不到对应节点。
+    ///
+    /// # 返回值
+    ///
+    /// 力导向布局数据
+    pub fn to_force_layout(&self) -> ForceGraph {
+        let ids: std::collections::HashSet<&str> =
+            self.nodes.iter().map(|n| n.uuid.as_str()).collect();
+
+        ForceGraph {
+            nodes: self
+                .nodes
+                .iter()
+                .map(|n| ForceNode {
+                    id: n.uuid.clone(),
+                    label: n.title.clone(),
+                    group: n.node_type.clone(),
+                })
+                .collect(),
+            links: self
+                .edges
+                .iter()
+                .filter(|e| ids.contains(e.src_uuid.as_str()) && ids.contains(e.dst_uuid.as_str()))
+                .map(|e| ForceLink {
+                    source: e.src_uuid.clone(),
+                    target: e.dst_uuid.clone(),
+                    value: e.weight,
+                })
+                .collect(),
+        }
+    }
+
+    /// 转换为 Cytoscape.js 的 elements JSON
+    ///
+    /// 生成 `{ elements: { nodes: [{ data, classes }], edges: [{ data }] } }`：
+    /// 节点的 `data.id` 为 UUID，`classes` 为节点类型；
+    /// 边的 `data.id` 为 `源->目标`，并附带 `relation` 和 `weight`。
+    /// 与 [`Self::to_force_layout`] 相同，端点不是节点的边会被省略。
+    ///
+    /// # 返回值
+    ///
+    /// Cytoscape.js 可直接加载的 JSON
+    pub fn to_cytoscape(&self) -> serde_json::Value {
+        let ids: std::collections::HashSet<&str> =
+            self.nodes.iter().map(|n| n.uuid.as_str()).collect();
+
+        let nodes: Vec<serde_json::Value> = self
+            .nodes
+            .iter()
+            .map(|n| {
+                serde_json::json!({
+                    "data": {
+                        "id": n.uuid,
+                        "label": n.title,
+                        "path": n.path,
+                    },
+                    "classes": n.node_type,
+                })
+            })
+            .collect();
+        let edges: Vec<serde_json::Value> = self
+            .edges
+            .iter()
+            .filter(|e| ids.contains(e.src_uuid.as_str()) && ids.contains(e.dst_uuid.as_str()))
+            .map(|e| {
+                serde_json::json!({
+                    "data": {
+                        "id": format!("{}->{}", e.src_uuid, e.dst_uuid),
+                        "source": e.src_uuid,
+                        "target": e.dst_uuid,
+                        "relation": e.relation,
+                        "weight": e.weight,
+                    },
+                })
+            })
+            .collect();
+
+        serde_json::json!({ "elements": { "nodes": nodes, "edges": edges } })
+    }
+
+    /// 转换为不含节点正文的轻量图数据
+    ///
+    /// 图谱视图只需要标题和类型，去掉 `content` 可显著减小大型知识库的传输量。
+    /// 边、裁剪信息原样保留；属性和标签映射不包含在内。
+    ///
+    /// # 返回值
+    ///
+    /// 轻量图数据
+    pub fn into_metadata(self) -> GraphMetadata {
+        GraphMetadata {
+            nodes: self.nodes.into_iter().map(NodeMeta::from).collect(),
+            edges: self.edges,
+            truncated: self.truncated,
+            total_nodes: self.total_nodes,
+        }
+    }
+}
+
+/// 不含正文的节点元数据
+///
+/// 字段与 [`Node`] 相同，仅省略 `content`。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeMeta {
+    /// 节点的唯一标识符
+    pub uuid: String,
+    /// 文件相对路径
+    pub path: String,
+    /// 节点标题
+    pub title: String,
+    /// 节点类型
+    pub node_type: String,
+    /// 内容哈希值
+    pub hash: String,
+    /// 创建时间戳
+    pub created_at: i64,
+    /// 更新时间戳
+    pub updated_at: i64,
+}
+
+impl From<Node> for NodeMeta {
+    fn from(node: Node) -> Self {
+        NodeMeta {
+            uuid: node.uuid,
+            path: node.path,
+            title: node.title,
+            node_type: node.node_type,
+            hash: node.hash,
+            created_at: node.created_at,
+            updated_at: node.updated_at,
+        }
+    }
+}
+
+/// 轻量图数据
+///
+/// 由 [`GraphData::into_metadata`] 生成，节点不含正文。
+///
+/// # 字段说明
+///
+/// * `nodes` - 节点元数据列表
+/// * `edges` - 所有关系边的列表
+/// * `truncated` - 节点数超过上限、只保留了连接度最高的节点时为 `true`
+/// * `total_nodes` - 裁剪前的节点总数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphMetadata {
+    /// 节点元数据列表
+    pub nodes: Vec<NodeMeta>,
+    /// 关系边列表
+    pub edges: Vec<Edge>,
+    /// 是否被裁剪
+    pub truncated: bool,
+    /// 裁剪前的节点总数
+    pub total_nodes: usize,
+}
+
+/// 力导向布局节点
+///
+/// # 字段说明
+///
+/// * `id` - 节点 UUID
+/// * `label` - 节点标题
+/// * `group` - 节点类型
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForceNode {
+    /// 节点 UUID
+    pub id: String,
+    /// 显示标签
+    pub label: String,
+    /// 分组（节点类型）
+    pub group: String,
+}
+
+/// 力导向布局连线
+///
+/// # 字段说明
+///
+/// * `source` - 源节点 UUID
+/// * `target` - 目标节点 UUID
+/// * `value` - 边权重
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForceLink {
+    /// 源节点 UUID
+    pub source: String,
+    /// 目标节点 UUID
+    pub target: String,
+    /// 连线强度（边权重）
+    pub value: f64,
+}
+
+/// 力导向布局输入
+///
+/// 与前端力导向图组件所需的 `{ nodes, links }` 结构一致。
+///
+/// # 字段说明
+///
+/// * `nodes` - 节点列表
+/// * `links` - 连线列表
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForceGraph {
+    /// 节点列表
+    pub nodes: Vec<ForceNode>,
+    /// 连线列表
+    pub links: Vec<ForceLink>,
+}
+
+/// 两次索引快照之间的文件变化
+///
+/// # 字段说明
+///
+/// * `added` - 新增的文件路径
+/// * `updated` - 内容哈希发生变化的文件路径
+/// * `deleted` - 被删除的文件路径
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncPlan {
+    /// 新增的文件路径（已排序）
+    pub added: Vec<String>,
+    /// 内容变化的文件路径（已排序）
+    pub updated: Vec<String>,
+    /// 删除的文件路径（已排序）
+    pub deleted: Vec<String>,
+}
+
+impl SyncPlan {
+    /// 检查是否没有任何变化
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
 }
 
 /// Vault 统计信息
@@ -151,141 +727,523 @@ pub struct VaultStatistics {
     pub total_tags: usize,
 }
 
-impl Database {
-    /// 创建新的数据库实例
+/// 链接统计
+///
+/// 仅统计笔记之间的 `link` 边，外部链接（`url:`）不计入。
+///
+/// # 字段说明
+///
+/// * `total_links` - 链接总数
+/// * `resolved` - 已解析的链接数（指向已索引笔记或附件）
+/// * `broken` - 断链数（指向 `missing:` 哨兵）
+/// * `top_linked` - 入链最多的笔记 `(路径, 入链数)`，按入链数降序
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkStats {
+    /// 链接总数
+    pub total_links: usize,
+    /// 已解析的链接数
+    pub resolved: usize,
+    /// 断链数
+    pub broken: usize,
+    /// 入链最多的笔记
+    pub top_linked: Vec<(String, usize)>,
+}
+
+/// 高级搜索条件
+///
+/// 各条件之间为"与"关系，未设置的条件不参与过滤。
+///
+/// # 字段说明
+///
+/// * `text` - 标题或内容包含的文本（不区分大小写）
+/// * `node_type` - 节点类型
+/// * `updated_after` - 更新时间下限（含），Unix 时间戳
+/// * `tags` - 必须同时拥有的标签
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchQuery {
+    /// 文本匹配
+    #[serde(default)]
+    pub text: Option<String>,
+    /// 节点类型
+    #[serde(default)]
+    pub node_type: Option<String>,
+    /// 更新时间下限
+    #[serde(default)]
+    pub updated_after: Option<i64>,
+    /// 必须拥有的标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// 搜索匹配位置
+///
+/// 记录第一处匹配所在的行及字符偏移，供前端高亮显示。
+///
+/// # 字段说明
+///
+/// * `line` - 匹配所在行：`0` 表示标题，正文行号从 1 开始（不含 frontmatter）
+/// * `offset` - 匹配在该行中的字符偏移（按 Unicode 字符计，不是字节）
+/// * `snippet` - 匹配所在行的完整文本
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchMatch {
+    /// 所在行
+    pub line: usize,
+    /// 字符偏移
+    pub offset: usize,
+    /// 所在行文本
+    pub snippet: String,
+}
+
+impl SearchMatch {
+    /// 在节点中查找第一处匹配
     ///
-    /// 初始化 CozoDB 数据库并创建必要的表结构。
+    /// 先匹配标题，再逐行匹配正文，不区分大小写。
     ///
     /// # 参数
     ///
-    /// * `db_path` - 数据库文件路径
+    /// * `node` - 被搜索的节点
+    /// * `query` - 搜索文本
     ///
     /// # 返回值
     ///
-    /// * `Ok(Database)` - 成功创建的数据库实例
-    /// * `Err(anyhow::Error)` - 创建失败
-    ///
-    /// # 错误情况
-    ///
-    /// * 数据库文件创建失败
-    /// * Schema 初始化失败
-    pub fn new(db_path: PathBuf) -> Result<Self> {
-        let db = DbInstance::new("sqlite", db_path.to_str().unwrap(), "")
-            .map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+    /// 第一处匹配的位置；没有匹配或 `query` 为空时返回 `None`
+    pub fn find(node: &Node, query: &str) -> Option<Self> {
+        let needle: Vec<char> = query.to_lowercase().chars().collect();
+        if needle.is_empty() {
+            return None;
+        }
 
-        let mut database = Database { db };
-        database.init_schema()?;
+        std::iter::once(node.title.as_str())
+            .chain(node.content.lines())
+            .enumerate()
+            .find_map(|(line, text)| {
+                Self::char_offset(text, &needle).map(|offset| SearchMatch {
+                    line,
+                    offset,
+                    snippet: text.to_string(),
+                })
+            })
+    }
 
-        Ok(database)
+    /// 计算 `needle`（已小写）在 `text` 中首次出现的字符偏移
+    fn char_offset(text: &str, needle: &[char]) -> Option<usize> {
+        text.char_indices()
+            .enumerate()
+            .find_map(|(char_offset, (byte_offset, _))| {
+                let mut rest = text[byte_offset..].chars().flat_map(char::to_lowercase);
+                needle
+                    .iter()
+                    .all(|c| rest.next() == Some(*c))
+                    .then_some(char_offset)
+            })
     }
+}
 
-    /// 初始化数据库 Schema
-    ///
-    /// 创建 nodes、edges、properties 和 sources 表，如果表已存在则忽略错误。
+/// 搜索结果
+///
+/// 序列化时节点字段展开到顶层，并附带 `matched` 字段。
+///
+/// # 字段说明
+///
+/// * `node` - 命中的节点
+/// * `matched` - 第一处文本匹配；没有文本条件时为 `None`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// 命中的节点
+    #[serde(flatten)]
+    pub node: Node,
+    /// 第一处文本匹配
+    pub matched: Option<SearchMatch>,
+}
+
+impl SearchHit {
+    /// 为节点计算匹配位置并构建搜索结果
     ///
-    /// ## Schema 设计（DCOM 架构）
+    /// # 参数
     ///
-    /// - **nodes**: 认知对象核心信息
-    /// - **edges**: 对象之间的关系
-    /// - **properties**: EAV 模式的动态属性存储
-    /// - **sources**: 序列化源信息（物理表示）
-    fn init_schema(&mut self) -> Result<()> {
-        // Create nodes table - 认知对象核心表
-        // 保持向后兼容，同时支持 DCOM 扩展字段
-        let _ = self.db.run_script(
-            r#"
-            :create nodes {
-                uuid: String,
-                =>
-                path: String,
-                title: String,
-                content: String,
-                node_type: String,
-                hash: String,
-                created_at: Int,
-                updated_at: Int
-            }
-            "#,
-            Default::default(),
-            ScriptMutability::Mutable,
-        );
-
-        // Create edges table - 关系边表
-        let _ = self.db.run_script(
-            r#"
-            :create edges {
-                src_uuid: String,
-                dst_uuid: String,
-                =>
-                relation: String,
-                weight: Float,
-                source: String
-            }
-            "#,
-            Default::default(),
-            ScriptMutability::Mutable,
-        );
-
-        // Create properties table - EAV 动态属性表
-        // 实现 Schema-less 的属性存储
-        let _ = self.db.run_script(
-            r#"
-            :create properties {
-                object_id: String,
-                name: String,
-                =>
-                value_type: String,
-                value_json: String
-            }
-            "#,
-            Default::default(),
-            ScriptMutability::Mutable,
-        );
+    /// * `node` - 命中的节点
+    /// * `query` - 搜索文本
+    pub fn new(node: Node, query: &str) -> Self {
+        let matched = SearchMatch::find(&node, query);
+        SearchHit { node, matched }
+    }
+}
 
-        // Create sources table - 序列化源表
-        // 记录对象的物理表示形式
-        let _ = self.db.run_script(
-            r#"
-            :create sources {
-                object_id: String,
-                source_type: String,
-                =>
-                path: String?,
-                content_hash: String?,
-                mime_type: String?,
-                size_bytes: Int?,
-                last_modified: Int
-            }
-            "#,
-            Default::default(),
-            ScriptMutability::Mutable,
-        );
+/// 边来源
+///
+/// `Edge.source` 字段的标准取值，前端据此区分边的样式。
+///
+/// | 变体 | 字符串 | 说明 |
+/// |------|--------|------|
+/// | `BodyWikilink` | `body-wikilink` | 正文中的 `[[link]]`（含块引用） |
+/// | `FrontmatterRef` | `frontmatter-ref` | frontmatter 属性中的 `[[link]]` |
+/// | `Embed` | `embed` | 嵌入 `![[link]]` |
+/// | `Tag` | `tag` | 标签关联，目标为 `tag:` 哨兵 |
+/// | `External` | `external` | 外部链接，目标为 `url:` 哨兵 |
+/// | `Folder` | `folder` | 文件夹节点到其直接子项的包含关系 |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EdgeSource {
+    /// 正文 wikilink
+    BodyWikilink,
+    /// frontmatter 引用
+    FrontmatterRef,
+    /// 嵌入
+    Embed,
+    /// 标签
+    Tag,
+    /// 外部链接
+    External,
+    /// 文件夹包含关系
+    Folder,
+}
 
-        // Create tags table - 标签表（多对多关系）
-        let _ = self.db.run_script(
-            r#"
-            :create tags {
-                object_id: String,
-                tag: String
-            }
-            "#,
-            Default::default(),
-            ScriptMutability::Mutable,
-        );
+impl EdgeSource {
+    /// 获取存储在 `Edge.source` 中的字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EdgeSource::BodyWikilink => "body-wikilink",
+            EdgeSource::FrontmatterRef => "frontmatter-ref",
+            EdgeSource::Embed => "embed",
+            EdgeSource::Tag => "tag",
+            EdgeSource::External => "external",
+            EdgeSource::Folder => "folder",
+        }
+    }
+}
 
-        // Create aliases table - 别名表
-        let _ = self.db.run_script(
-            r#"
-            :create aliases {
-                object_id: String,
-                alias: String
-            }
-            "#,
-            Default::default(),
-            ScriptMutability::Mutable,
-        );
+/// 链接解析方式
+///
+/// 记录链接边的目标是如何解析出来的，前端可据此提示依赖别名的脆弱链接。
+///
+/// | 变体 | 字符串 | 说明 |
+/// |------|--------|------|
+/// | `Filename` | `filename` | 文件名唯一匹配（或无法区分时匹配全部同名文件） |
+/// | `Alias` | `alias` | 通过别名匹配 |
+/// | `Path` | `path` | 路径形式的链接（如 `[[b/note]]`）精确匹配 |
+/// | `Proximity` | `proximity` | 同名文件中按就近原则选出 |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkResolution {
+    /// 文件名匹配
+    Filename,
+    /// 别名匹配
+    Alias,
+    /// 路径精确匹配
+    Path,
+    /// 就近原则
+    Proximity,
+}
 
-        Ok(())
+impl LinkResolution {
+    /// 获取存储在 `edge_resolutions` 表中的字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkResolution::Filename => "filename",
+            LinkResolution::Alias => "alias",
+            LinkResolution::Path => "path",
+            LinkResolution::Proximity => "proximity",
+        }
+    }
+}
+
+/// 数据库完整性检查报告
+///
+/// 记录各类索引损坏问题的数量及部分样例。
+///
+/// # 字段说明
+///
+/// * `dangling_edges` - 引用不存在节点的边数量（哨兵目标除外）
+/// * `dangling_edge_samples` - 悬空边样例
+/// * `orphan_properties` - 所属对象不存在的属性数量
+/// * `orphan_property_samples` - 孤立属性样例 `(object_id, name)`
+/// * `duplicate_paths` - 被多个 UUID 占用的路径数量
+/// * `duplicate_path_samples` - 重复路径样例
+///
+/// # 说明
+///
+/// nodes 表以 `uuid` 为主键，重复 UUID 表现为同一路径对应多个 UUID。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// 悬空边数量
+    pub dangling_edges: usize,
+    /// 悬空边样例
+    pub dangling_edge_samples: Vec<Edge>,
+    /// 孤立属性数量
+    pub orphan_properties: usize,
+    /// 孤立属性样例
+    pub orphan_property_samples: Vec<(String, String)>,
+    /// 重复路径数量
+    pub duplicate_paths: usize,
+    /// 重复路径样例
+    pub duplicate_path_samples: Vec<String>,
+}
+
+impl IntegrityReport {
+    /// 检查是否未发现任何问题
+    pub fn is_healthy(&self) -> bool {
+        self.dangling_edges == 0 && self.orphan_properties == 0 && self.duplicate_paths == 0
+    }
+}
+
+/// 标签大小写冲突
+///
+/// 一组仅大小写不同的标签（如 `#Rust`、`#rust`、`#RUST`）。
+///
+/// # 字段说明
+///
+/// * `normalized` - 折叠为小写后的标签
+/// * `variants` - 数据库中实际存在的各个写法（已排序）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagCaseConflict {
+    /// 折叠为小写后的标签
+    pub normalized: String,
+    /// 实际存在的写法
+    pub variants: Vec<String>,
+}
+
+/// 别名冲突
+///
+/// 同一别名被多篇笔记声明，按别名解析链接时存在歧义。
+///
+/// # 字段说明
+///
+/// * `alias` - 别名
+/// * `owners` - 声明该别名的节点 UUID（已排序）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AliasConflict {
+    /// 别名
+    pub alias: String,
+    /// 声明该别名的节点 UUID
+    pub owners: Vec<String>,
+}
+
+/// 连接路径上的一跳
+///
+/// 由 [`Database::explain_connection`] 生成，供前端渲染为 `A —link→ B —tagged→ #x`。
+///
+/// # 字段说明
+///
+/// * `from_title` - 起点标题；哨兵节点显示为 `#标签`、URL 等可读形式
+/// * `relation` - 所经过边的关系类型
+/// * `to_title` - 终点标题
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionStep {
+    /// 起点标题
+    pub from_title: String,
+    /// 关系类型
+    pub relation: String,
+    /// 终点标题
+    pub to_title: String,
+}
+
+/// 解析后的笔记链接
+///
+/// 由 [`Database::get_outgoing_links`] 和 [`Database::get_backlinks`] 生成，
+/// 链接另一端已解析为标题和路径，前端无需再逐个查询节点。
+///
+/// # 字段说明
+///
+/// * `uuid` - 另一端的节点 UUID，未解析的目标为哨兵（如 `missing:Foo`、`url:https://...`）
+/// * `path` - 另一端的笔记路径，哨兵目标为 `None`
+/// * `title` - 另一端的标题；哨兵目标为去掉前缀后的名称
+/// * `source` - 边来源，标准取值见 [`EdgeSource`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoteLink {
+    /// 节点 UUID
+    pub uuid: String,
+    /// 笔记路径
+    pub path: Option<String>,
+    /// 标题
+    pub title: String,
+    /// 边来源
+    pub source: String,
+}
+
+/// NDJSON 导出记录
+///
+/// [`Database::export_vault_to_writer`] 每行写出一条记录，以 `kind` 字段区分类型，
+/// 如 `{"kind":"tag","object_id":"...","tag":"rust"}`。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ExportRecord {
+    /// 节点
+    Node(Node),
+    /// 边
+    Edge(Edge),
+    /// 属性
+    Property {
+        /// 对象 UUID
+        object_id: String,
+        /// 属性名
+        name: String,
+        /// 属性值
+        value: PropertyValue,
+    },
+    /// 显式标签（嵌套标签展开出的父级行不导出）
+    Tag {
+        /// 对象 UUID
+        object_id: String,
+        /// 标签名
+        tag: String,
+    },
+    /// 别名
+    Alias {
+        /// 对象 UUID
+        object_id: String,
+        /// 别名
+        alias: String,
+    },
+}
+
+/// 查询错误
+///
+/// # 字段说明
+///
+/// * `message` - 错误信息
+/// * `line` - 错误所在行（从 1 开始）；CozoDB 未给出位置时为 `None`
+/// * `column` - 错误所在列（从 1 开始，按 Unicode 字符计）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryError {
+    /// 错误信息
+    pub message: String,
+    /// 所在行
+    pub line: Option<usize>,
+    /// 所在列
+    pub column: Option<usize>,
+}
+
+/// 查询校验结果
+///
+/// # 字段说明
+///
+/// * `valid` - 脚本能否被 CozoDB 接受
+/// * `read_only` - 脚本是否只读；会写入数据（`:put`、`::remove` 等）时为 `false`，无效脚本也为 `false`
+/// * `errors` - 校验错误，脚本有效时为空
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryValidation {
+    /// 是否有效
+    pub valid: bool,
+    /// 是否只读
+    pub read_only: bool,
+    /// 校验错误
+    pub errors: Vec<QueryError>,
+}
+
+impl Database {
+    /// 创建新的数据库实例
+    ///
+    /// 初始化 CozoDB 数据库并创建必要的表结构。
+    ///
+    /// # 参数
+    ///
+    /// * `db_path` - 数据库文件路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Database)` - 成功创建的数据库实例
+    /// * `Err(anyhow::Error)` - 创建失败
+    ///
+    /// # 错误情况
+    ///
+    /// * 数据库文件创建失败
+    /// * Schema 初始化失败
+    pub fn new(db_path: PathBuf) -> Result<Self> {
+        let db = DbInstance::new("sqlite", db_path.to_str().unwrap(), "")
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let mut database = Database {
+            capabilities: Capabilities::probe(&db),
+            db,
+            expand_tag_parents: false,
+        };
+        database.init_schema()?;
+
+        Ok(database)
+    }
+
+    /// 获取打开数据库时探测到的可选功能
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// 设置保存嵌套标签时是否写入父级标签行
+    ///
+    /// 开启后，[`Self::save_tags`] 为 `project/rust/async` 额外写入 `project` 和
+    /// `project/rust` 两行，按标签查询时无需前缀匹配即可包含子标签的笔记。
+    /// 只影响之后保存的标签，已有数据需重新同步。
+    ///
+    /// # 参数
+    ///
+    /// * `expand` - 是否展开父级标签
+    pub fn set_expand_tag_parents(&mut self, expand: bool) {
+        self.expand_tag_parents = expand;
+    }
+
+    /// 初始化数据库 Schema
+    ///
+    /// 先列出已有的表，只创建 [`SCHEMA`] 中缺失的表；已有的表保持不变，
+    /// 因此重复打开同一数据库不会报错，建表失败（如磁盘已满、脚本错误）则作为错误返回。
+    ///
+    /// ## Schema 设计（DCOM 架构）
+    ///
+    /// - **nodes**: 认知对象核心信息
+    /// - **edges**: 对象之间的关系
+    /// - **properties**: EAV 模式的动态属性存储
+    /// - **sources**: 序列化源信息（物理表示）
+    /// - **snapshots** / **node_snapshots**: 索引快照及各节点当时的内容哈希
+    /// - **layout**: 图谱视图中手动摆放的节点坐标
+    /// - **edge_resolutions**: 链接边的目标解析方式
+    fn init_schema(&mut self) -> Result<()> {
+        self.create_missing_relations(&SCHEMA)
+    }
+
+    /// 获取数据库中已有的表名
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(HashSet<String>)` - 已有的表名集合
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    fn existing_relations(&self) -> Result<HashSet<String>> {
+        let result = self
+            .db
+            .run_script(
+                "::relations",
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .filter_map(|row| row[0].get_str().map(str::to_string))
+            .collect())
+    }
+
+    /// 创建缺失的表
+    ///
+    /// # 参数
+    ///
+    /// * `schema` - `(表名, 建表脚本)` 列表
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 所有表均已存在或创建成功
+    /// * `Err(anyhow::Error)` - 查询已有表或创建某个表失败，错误信息包含表名
+    fn create_missing_relations(&self, schema: &[(&str, &str)]) -> Result<()> {
+        let existing = self.existing_relations()?;
+
+        for (name, script) in schema {
+            if existing.contains(*name) {
+                continue;
+            }
+            self.db
+                .run_script(script, Default::default(), ScriptMutability::Mutable)
+                .map_err(|e| anyhow::anyhow!("Failed to create relation {}: {}", name, e))?;
+        }
+
+        Ok(())
     }
 
     /// 将 JSON 转换为 CozoDB 参数映射
@@ -346,7 +1304,7 @@ impl Database {
             "#,
             params,
             ScriptMutability::Mutable,
-        ).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
 
         Ok(())
     }
@@ -379,14 +1337,14 @@ impl Database {
             "#,
             params,
             ScriptMutability::Mutable,
-        ).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
 
         Ok(())
     }
 
     /// 获取所有节点
     ///
-    /// 返回数据库中所有的知识节点。
+    /// 返回数据库中所有的知识节点，按 `uuid`（主键）排序，多次调用顺序一致。
     ///
     /// # 返回值
     ///
@@ -394,10 +1352,13 @@ impl Database {
     /// * `Err(anyhow::Error)` - 数据库查询失败
     pub fn get_all_nodes(&self) -> Result<Vec<Node>> {
         let result = self.db.run_script(
-            "?[uuid, path, title, content, node_type, hash, created_at, updated_at] := *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}",
+            r#"
+            ?[uuid, path, title, content, node_type, hash, created_at, updated_at] := *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}
+            :order uuid
+            "#,
             Default::default(),
             ScriptMutability::Immutable,
-        ).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
 
         let nodes: Vec<Node> = result
             .rows
@@ -419,7 +1380,7 @@ impl Database {
 
     /// 获取所有边
     ///
-    /// 返回数据库中所有的关系边。
+    /// 返回数据库中所有的关系边，按 `(src_uuid, dst_uuid)`（主键）排序，多次调用顺序一致。
     ///
     /// # 返回值
     ///
@@ -427,10 +1388,13 @@ impl Database {
     /// * `Err(anyhow::Error)` - 数据库查询失败
     pub fn get_all_edges(&self) -> Result<Vec<Edge>> {
         let result = self.db.run_script(
-            "?[src_uuid, dst_uuid, relation, weight, source] := *edges{src_uuid, dst_uuid, relation, weight, source}",
+            r#"
+            ?[src_uuid, dst_uuid, relation, weight, source] := *edges{src_uuid, dst_uuid, relation, weight, source}
+            :order src_uuid, dst_uuid
+            "#,
             Default::default(),
             ScriptMutability::Immutable,
-        ).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
 
         let edges: Vec<Edge> = result
             .rows
@@ -474,198 +1438,3109 @@ impl Database {
         Ok(filtered_nodes)
     }
 
-    /// 获取完整的图数据
+    /// 查找文本出现位置
     ///
-    /// 返回包含所有节点和边的图数据结构。
+    /// 在所有节点的已索引内容中逐行匹配，用于全库查找替换前的预览。
+    ///
+    /// # 参数
+    ///
+    /// * `pattern` - 查找内容
+    /// * `is_regex` - `pattern` 是否为正则表达式，为 `false` 时按字面量匹配
     ///
     /// # 返回值
     ///
-    /// * `Ok(GraphData)` - 包含所有节点和边的图数据
-    /// * `Err(anyhow::Error)` - 数据库查询失败
-    pub fn get_graph_data(&self) -> Result<GraphData> {
-        let nodes = self.get_all_nodes()?;
-        let edges = self.get_all_edges()?;
+    /// * `Ok(Vec<(String, usize, String)>)` - `(路径, 行号, 所在行)` 列表，按路径和行号排序；
+    ///   行号从 1 开始，相对于正文（不含 frontmatter）
+    /// * `Err(anyhow::Error)` - 正则表达式无效或数据库查询失败
+    pub fn find_occurrences(
+        &self,
+        pattern: &str,
+        is_regex: bool,
+    ) -> Result<Vec<(String, usize, String)>> {
+        let regex = if is_regex {
+            Regex::new(pattern)
+        } else {
+            Regex::new(&regex::escape(pattern))
+        }
+        .map_err(|e| anyhow::anyhow!("Invalid pattern: {}", e))?;
+
+        let mut nodes = self.get_all_nodes()?;
+        nodes.sort_by(|a, b| a.path.cmp(&b.path));
 
-        Ok(GraphData { nodes, edges })
+        let mut occurrences = Vec::new();
+        for node in &nodes {
+            for (index, line) in node.content.lines().enumerate() {
+                if regex.is_match(line) {
+                    occurrences.push((node.path.clone(), index + 1, line.to_string()));
+                }
+            }
+        }
+
+        Ok(occurrences)
     }
 
-    /// 根据路径获取节点
+    /// 高级搜索
     ///
-    /// 根据文件路径查找对应的节点。
+    /// 将文本、类型、更新时间和标签条件组合为一条 CozoDB 查询执行。
     ///
     /// # 参数
     ///
-    /// * `path` - 文件相对路径
+    /// * `query` - 搜索条件
     ///
     /// # 返回值
     ///
-    /// * `Ok(Some(Node))` - 找到匹配的节点
-    /// * `Ok(None)` - 未找到节点
+    /// * `Ok(Vec<Node>)` - 满足全部条件的节点列表
     /// * `Err(anyhow::Error)` - 数据库查询失败
-    pub fn get_node_by_path(&self, path: &str) -> Result<Option<Node>> {
-        let params = Self::make_params(serde_json::json!({ "path": path }));
+    pub fn advanced_search(&self, query: &SearchQuery) -> Result<Vec<Node>> {
+        let mut conditions = vec![
+            "*nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}"
+                .to_string(),
+        ];
+        let mut params = serde_json::Map::new();
+
+        if let Some(text) = query.text.as_deref().filter(|t| !t.is_empty()) {
+            conditions.push(
+                "(str_includes(lowercase(title), $text) || str_includes(lowercase(content), $text))"
+                    .to_string(),
+            );
+            params.insert("text".into(), text.to_lowercase().into());
+        }
+        if let Some(node_type) = &query.node_type {
+            conditions.push("node_type == $node_type".to_string());
+            params.insert("node_type".into(), node_type.clone().into());
+        }
+        if let Some(updated_after) = query.updated_after {
+            conditions.push("updated_at >= $updated_after".to_string());
+            params.insert("updated_after".into(), updated_after.into());
+        }
+        for (i, tag) in query.tags.iter().enumerate() {
+            conditions.push(format!(
+                "*tags{{object_id: uuid, tag: tag_{i}}}, tag_{i} == $tag_{i}"
+            ));
+            params.insert(format!("tag_{}", i), tag.clone().into());
+        }
 
-        let result = self.db.run_script(
-            "?[uuid, path, title, content, node_type, hash, created_at, updated_at] := *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}, path == $path",
-            params,
-            ScriptMutability::Immutable,
-        ).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+        let script = format!(
+            "?[uuid, path, title, content, node_type, hash, created_at, updated_at] := {}",
+            conditions.join(", ")
+        );
 
-        if result.rows.is_empty() {
-            Ok(None)
-        } else {
-            let row = &result.rows[0];
-            Ok(Some(Node {
-                uuid: row[0].get_str().unwrap_or("").to_string(),
-                path: row[1].get_str().unwrap_or("").to_string(),
-                title: row[2].get_str().unwrap_or("").to_string(),
-                content: row[3].get_str().unwrap_or("").to_string(),
-                node_type: row[4].get_str().unwrap_or("").to_string(),
-                hash: row[5].get_str().unwrap_or("").to_string(),
-                created_at: row[6].get_int().unwrap_or(0),
-                updated_at: row[7].get_int().unwrap_or(0),
-            }))
-        }
+        let result = self
+            .db
+            .run_script(
+                &script,
+                Self::make_params(serde_json::Value::Object(params)),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| Self::row_to_node(row))
+            .collect())
     }
 
-    /// 清空所有数据
+    /// 随机获取一个节点
     ///
-    /// 删除数据库中的所有节点和边。
+    /// 先统计满足条件的节点数，再按随机偏移只取一行，不会加载全部节点。
+    ///
+    /// # 参数
+    ///
+    /// * `node_type` - 节点类型过滤，为 `None` 时从所有节点中选择
     ///
     /// # 返回值
     ///
-    /// * `Ok(())` - 操作成功
-    /// * `Err(anyhow::Error)` - 数据库操作失败
-    pub fn clear_all(&mut self) -> Result<()> {
-        // Delete all nodes
-        let _ = self.db.run_script(
-            "?[uuid, path, title, content, node_type, hash, created_at, updated_at] <- [] :replace nodes {uuid => path, title, content, node_type, hash, created_at, updated_at}",
-            Default::default(),
-            ScriptMutability::Mutable,
-        );
+    /// * `Ok(Some(Node))` - 随机选中的节点
+    /// * `Ok(None)` - 没有满足条件的节点
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_random_node(&self, node_type: Option<&str>) -> Result<Option<Node>> {
+        let (filter, params) = match node_type {
+            Some(node_type) => (
+                ", node_type == $node_type",
+                serde_json::json!({ "node_type": node_type }),
+            ),
+            None => ("", serde_json::json!({})),
+        };
 
-        // Delete all edges
-        let _ = self.db.run_script(
-            "?[src_uuid, dst_uuid, relation, weight, source] <- [] :replace edges {src_uuid, dst_uuid => relation, weight, source}",
-            Default::default(),
-            ScriptMutability::Mutable,
+        let count_result = self
+            .db
+            .run_script(
+                &format!("?[count(uuid)] := *nodes{{uuid, node_type}}{}", filter),
+                Self::make_params(params.clone()),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        let count = count_result
+            .rows
+            .first()
+            .and_then(|row| row[0].get_int())
+            .unwrap_or(0) as usize;
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let script = format!(
+            "?[uuid, path, title, content, node_type, hash, created_at, updated_at] := *nodes{{uuid, path, title, content, node_type, hash, created_at, updated_at}}{}\n:order uuid\n:offset {}\n:limit 1",
+            filter,
+            fastrand::usize(..count)
         );
+        let result = self
+            .db
+            .run_script(
+                &script,
+                Self::make_params(params),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        Ok(())
+        Ok(result.rows.first().map(|row| Self::row_to_node(row)))
     }
 
-    /// 删除节点
+    /// 获取指定时间之后修改过的节点
     ///
-    /// 根据 UUID 删除指定的节点。
+    /// 供同步客户端和插件增量拉取变化。已删除的节点不会出现在结果中。
     ///
     /// # 参数
     ///
-    /// * `uuid` - 要删除的节点 UUID
+    /// * `ts` - 更新时间下限（含），Unix 时间戳
     ///
     /// # 返回值
     ///
-    /// * `Ok(())` - 操作成功
-    /// * `Err(anyhow::Error)` - 数据库操作失败
-    pub fn delete_node(&mut self, uuid: &str) -> Result<()> {
-        let params = Self::make_params(serde_json::json!({ "uuid": uuid }));
-
-        self.db.run_script(
-            r#"
-            ?[uuid, path, title, content, node_type, hash, created_at, updated_at] := *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}, uuid != $uuid
-            :replace nodes {uuid => path, title, content, node_type, hash, created_at, updated_at}
-            "#,
-            params,
-            ScriptMutability::Mutable,
-        ).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+    /// * `Ok(Vec<Node>)` - `updated_at >= ts` 的节点，按更新时间升序排列
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_nodes_modified_since(&self, ts: i64) -> Result<Vec<Node>> {
+        let result = self
+            .db
+            .run_script(
+                "?[uuid, path, title, content, node_type, hash, created_at, updated_at] := *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}, updated_at >= $ts\n:order updated_at, uuid",
+                Self::make_params(serde_json::json!({ "ts": ts })),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        Ok(())
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| Self::row_to_node(row))
+            .collect())
     }
 
-    /// 删除与节点相关的所有边
+    /// 校验 CozoScript 而不修改数据
     ///
-    /// 删除所有源节点或目标节点为指定 UUID 的边。
+    /// CozoDB 没有公开只解析的接口，因此以只读模式试运行脚本：单个查询块追加
+    /// `:limit` 限制返回行数，命令式脚本（`{...}`）和系统操作（`::`）原样运行。
+    /// 需要写入的脚本在执行前就会被只读模式拒绝，据此判断脚本是否只读。
     ///
     /// # 参数
     ///
-    /// * `uuid` - 节点 UUID
+    /// * `script` - 要校验的 CozoScript
     ///
     /// # 返回值
     ///
-    /// * `Ok(())` - 操作成功
-    /// * `Err(anyhow::Error)` - 数据库操作失败
-    pub fn delete_edges_by_node(&mut self, uuid: &str) -> Result<()> {
+    /// 校验结果，语法错误附带所在行列
+    pub fn validate_query(&self, script: &str) -> QueryValidation {
+        let trimmed = script.trim_start();
+        let dry_run = if trimmed.starts_with('{') || trimmed.starts_with("::") {
+            script.to_string()
+        } else {
+            format!("{}\n:limit {}", script, VALIDATE_ROW_LIMIT)
+        };
+
+        let error =
+            match self
+                .db
+                .run_script(&dry_run, Default::default(), ScriptMutability::Immutable)
+            {
+                Ok(_) => {
+                    return QueryValidation {
+                        valid: true,
+                        read_only: true,
+                        errors: Vec::new(),
+                    }
+                }
+                Err(e) => e,
+            };
+
+        let message = error.to_string();
+        if message.to_lowercase().contains("read-only") {
+            return QueryValidation {
+                valid: true,
+                read_only: false,
+                errors: Vec::new(),
+            };
+        }
+
+        // 错误位置来自 miette 标签中的字节偏移，追加的 `:limit` 之后的位置归到脚本末尾
+        let report = cozo::format_error_as_json(error, None);
+        let position = report["labels"]
+            .as_array()
+            .and_then(|labels| labels.first())
+            .and_then(|label| label["span"]["offset"].as_u64())
+            .map(|offset| Self::line_column(script, (offset as usize).min(script.len())));
+
+        QueryValidation {
+            valid: false,
+            read_only: false,
+            errors: vec![QueryError {
+                message,
+                line: position.map(|(line, _)| line),
+                column: position.map(|(_, column)| column),
+            }],
+        }
+    }
+
+    /// 将字节偏移转换为从 1 开始的行号和列号（列按 Unicode 字符计）
+    fn line_column(text: &str, offset: usize) -> (usize, usize) {
+        let mut offset = offset;
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+
+    /// 获取完整的图数据
+    ///
+    /// 返回包含所有节点和边的图数据结构。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(GraphData)` - 包含所有节点和边的图数据
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_graph_data(&self) -> Result<GraphData> {
+        let nodes = self.get_all_nodes()?;
+        let edges = self.get_all_edges()?;
+
+        Ok(GraphData {
+            total_nodes: nodes.len(),
+            nodes,
+            edges,
+            properties: HashMap::new(),
+            tags: HashMap::new(),
+            truncated: false,
+        })
+    }
+
+    /// 获取节点数不超过上限的图数据
+    ///
+    /// 节点数超过 `max_nodes` 时，按连接度（入度与出度之和，含指向哨兵的边）
+    /// 保留最高的 `max_nodes` 个节点，连接度相同时按 UUID 排序；
+    /// 与被裁掉节点相连的边一并移除，并将 `truncated` 置为 `true`。
+    ///
+    /// # 参数
+    ///
+    /// * `max_nodes` - 节点数上限，`0` 表示不限制
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(GraphData)` - 图数据，`total_nodes` 为裁剪前的节点总数
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_graph_data_limited(&self, max_nodes: usize) -> Result<GraphData> {
+        let mut graph = self.get_graph_data()?;
+        if max_nodes == 0 || graph.nodes.len() <= max_nodes {
+            return Ok(graph);
+        }
+
+        let mut degrees: HashMap<&str, usize> = HashMap::new();
+        for edge in &graph.edges {
+            *degrees.entry(edge.src_uuid.as_str()).or_default() += 1;
+            *degrees.entry(edge.dst_uuid.as_str()).or_default() += 1;
+        }
+        let mut ranked: Vec<(usize, &str)> = graph
+            .nodes
+            .iter()
+            .map(|n| {
+                (
+                    degrees.get(n.uuid.as_str()).copied().unwrap_or(0),
+                    n.uuid.as_str(),
+                )
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+        let dropped: std::collections::HashSet<String> = ranked[max_nodes..]
+            .iter()
+            .map(|(_, uuid)| uuid.to_string())
+            .collect();
+
+        graph.nodes.retain(|n| !dropped.contains(&n.uuid));
+        graph
+            .edges
+            .retain(|e| !dropped.contains(&e.src_uuid) && !dropped.contains(&e.dst_uuid));
+        graph.truncated = true;
+        Ok(graph)
+    }
+
+    /// 导出笔记的邻域子图
+    ///
+    /// 从指定节点出发，沿边（不区分方向）广度优先遍历 `depth` 跳，
+    /// 返回范围内的节点、两端都在范围内的边，以及这些节点的属性和标签。
+    /// `tag:`、`missing:` 等哨兵不是节点，不参与遍历。
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 起始节点 UUID
+    /// * `depth` - 遍历深度，0 表示仅包含起始节点
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(GraphData)` - 子图数据
+    /// * `Err(anyhow::Error)` - 起始节点不存在或数据库查询失败
+    pub fn export_subgraph(&self, uuid: &str, depth: usize) -> Result<GraphData> {
+        let start = self
+            .get_node(uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Node not found: {}", uuid))?;
+
+        let mut nodes = vec![start];
+        let mut visited: std::collections::HashSet<String> =
+            std::iter::once(uuid.to_string()).collect();
+        let mut frontier = vec![uuid.to_string()];
+
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for current in &frontier {
+                for edge in self.get_edges_by_node(current)? {
+                    let neighbor = if edge.src_uuid == *current {
+                        edge.dst_uuid
+                    } else {
+                        edge.src_uuid
+                    };
+                    if visited.contains(&neighbor) {
+                        continue;
+                    }
+                    if let Some(node) = self.get_node(&neighbor)? {
+                        visited.insert(neighbor.clone());
+                        nodes.push(node);
+                        next.push(neighbor);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        let mut edges = Vec::new();
+        let mut properties = HashMap::new();
+        let mut tags = HashMap::new();
+        for node in &nodes {
+            edges.extend(
+                self.get_edges_by_node(&node.uuid)?
+                    .into_iter()
+                    .filter(|e| e.src_uuid == node.uuid && visited.contains(&e.dst_uuid)),
+            );
+
+            let node_properties = self.get_properties(&node.uuid)?;
+            if !node_properties.is_empty() {
+                properties.insert(node.uuid.clone(), node_properties);
+            }
+            let node_tags = self.get_tags(&node.uuid)?;
+            if !node_tags.is_empty() {
+                tags.insert(node.uuid.clone(), node_tags);
+            }
+        }
+
+        Ok(GraphData {
+            total_nodes: nodes.len(),
+            nodes,
+            edges,
+            properties,
+            tags,
+            truncated: false,
+        })
+    }
+
+    /// 以 NDJSON 格式流式导出整个索引
+    ///
+    /// 依次写出节点、边、属性、标签和别名，每行一条 [`ExportRecord`]。
+    /// 节点（含正文）按 UUID 分批读取；边、属性、标签和别名各由一次查询全部读出，
+    /// 这部分内存占用随其记录数增长。
+    ///
+    /// # 参数
+    ///
+    /// * `w` - 输出目标
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(usize)` - 写出的记录数
+    /// * `Err(anyhow::Error)` - 数据库查询或写入失败，或某个属性值无法解析
+    pub fn export_vault_to_writer<W: Write>(&self, w: &mut W) -> Result<usize> {
+        let mut count = 0;
+        let mut write = |record: ExportRecord| -> Result<()> {
+            serde_json::to_writer(&mut *w, &record)?;
+            w.write_all(b"\n")?;
+            count += 1;
+            Ok(())
+        };
+
+        let mut offset = 0;
+        loop {
+            let result = self
+                .db
+                .run_script(
+                    &format!(
+                        r#"
+                ?[uuid, path, title, content, node_type, hash, created_at, updated_at] :=
+                    *nodes{{uuid, path, title, content, node_type, hash, created_at, updated_at}}
+                :order uuid
+                :limit {}
+                :offset {}
+                "#,
+                        EXPORT_BATCH_SIZE, offset
+                    ),
+                    Default::default(),
+                    ScriptMutability::Immutable,
+                )
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            for row in &result.rows {
+                write(ExportRecord::Node(Self::row_to_node(row)))?;
+            }
+            if result.rows.len() < EXPORT_BATCH_SIZE {
+                break;
+            }
+            offset += EXPORT_BATCH_SIZE;
+        }
+
+        for edge in self.get_all_edges()? {
+            write(ExportRecord::Edge(edge))?;
+        }
+
+        let result = self
+            .db
+            .run_script(
+                r#"
+            ?[object_id, name, value_json] := *properties{object_id, name, value_json}
+            :order object_id, name
+            "#,
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        for row in &result.rows {
+            let object_id = row[0].get_str().unwrap_or("").to_string();
+            let name = row[1].get_str().unwrap_or("").to_string();
+            let value_json = row[2].get_str().unwrap_or("null");
+            let value = serde_json::from_str::<PropertyValue>(value_json)
+                .map_err(|e| anyhow::anyhow!("属性 {} 的 {} 值无法解析: {}", object_id, name, e))?;
+            write(ExportRecord::Property {
+                object_id,
+                name,
+                value,
+            })?;
+        }
+
+        let result = self
+            .db
+            .run_script(
+                r#"
+            ?[object_id, tag] := *tags{object_id, tag}, not *tag_parents{object_id, tag}
+            :order object_id, tag
+            "#,
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        for row in &result.rows {
+            write(ExportRecord::Tag {
+                object_id: row[0].get_str().unwrap_or("").to_string(),
+                tag: row[1].get_str().unwrap_or("").to_string(),
+            })?;
+        }
+
+        let result = self
+            .db
+            .run_script(
+                r#"
+            ?[object_id, alias] := *aliases{object_id, alias}
+            :order object_id, alias
+            "#,
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        for row in &result.rows {
+            write(ExportRecord::Alias {
+                object_id: row[0].get_str().unwrap_or("").to_string(),
+                alias: row[1].get_str().unwrap_or("").to_string(),
+            })?;
+        }
+
+        Ok(count)
+    }
+
+    /// 从 NDJSON 导入索引
+    ///
+    /// 读取 [`Self::export_vault_to_writer`] 写出的记录并逐条写入：节点、边和属性以插入或更新方式写入，
+    /// 标签和别名按对象汇总后替换该对象原有的标签和别名。已有的其他数据保持不变。
+    /// 空行被忽略。输入读取两遍：第一遍只解析校验，任何一行无效时不写入任何数据；
+    /// 第二遍回到起始位置逐条写入，两遍都不保留已读取的记录。
+    ///
+    /// # 参数
+    ///
+    /// * `r` - 输入来源，需要支持回到起始位置
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(usize)` - 导入的记录数
+    /// * `Err(anyhow::Error)` - 读取失败、某行格式无效或数据库写入失败
+    pub fn import_vault_from_reader<R: BufRead + Seek>(&mut self, mut r: R) -> Result<usize> {
+        let start = r.stream_position()?;
+        Self::read_export_records(&mut r, |_| Ok(()))?;
+        r.seek(SeekFrom::Start(start))?;
+
+        let mut tags: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut aliases: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let count = Self::read_export_records(&mut r, |record| {
+            match record {
+                ExportRecord::Node(node) => self.upsert_node(&node)?,
+                ExportRecord::Edge(edge) => self.upsert_edge(&edge)?,
+                ExportRecord::Property {
+                    object_id,
+                    name,
+                    value,
+                } => self.save_property(&object_id, &name, &value)?,
+                ExportRecord::Tag { object_id, tag } => {
+                    tags.entry(object_id).or_default().push(tag)
+                }
+                ExportRecord::Alias { object_id, alias } => {
+                    aliases.entry(object_id).or_default().push(alias)
+                }
+            }
+            Ok(())
+        })?;
+
+        for (object_id, tags) in tags {
+            self.save_tags(&object_id, &tags)?;
+        }
+        for (object_id, aliases) in aliases {
+            self.save_aliases(&object_id, &aliases)?;
+        }
+
+        Ok(count)
+    }
+
+    /// 逐行解析 NDJSON 记录
+    ///
+    /// 跳过空行，每解析出一条记录即交给 `f` 处理，不保留已处理的记录。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(usize)` - 处理的记录数
+    /// * `Err(anyhow::Error)` - 读取失败、某行格式无效或 `f` 返回错误
+    fn read_export_records<R: BufRead>(
+        r: R,
+        mut f: impl FnMut(ExportRecord) -> Result<()>,
+    ) -> Result<usize> {
+        let mut count = 0;
+        for (i, line) in r.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: ExportRecord = serde_json::from_str(&line)
+                .map_err(|e| anyhow::anyhow!("第 {} 行格式无效: {}", i + 1, e))?;
+            f(record)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// 根据路径获取节点
+    ///
+    /// 根据文件路径查找对应的节点。
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件相对路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Some(Node))` - 找到匹配的节点
+    /// * `Ok(None)` - 未找到节点
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_node_by_path(&self, path: &str) -> Result<Option<Node>> {
+        let params = Self::make_params(serde_json::json!({ "path": path }));
+
+        let result = self.db.run_script(
+            "?[uuid, path, title, content, node_type, hash, created_at, updated_at] := *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}, path == $path",
+            params,
+            ScriptMutability::Immutable,
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        if result.rows.is_empty() {
+            Ok(None)
+        } else {
+            let row = &result.rows[0];
+            Ok(Some(Node {
+                uuid: row[0].get_str().unwrap_or("").to_string(),
+                path: row[1].get_str().unwrap_or("").to_string(),
+                title: row[2].get_str().unwrap_or("").to_string(),
+                content: row[3].get_str().unwrap_or("").to_string(),
+                node_type: row[4].get_str().unwrap_or("").to_string(),
+                hash: row[5].get_str().unwrap_or("").to_string(),
+                created_at: row[6].get_int().unwrap_or(0),
+                updated_at: row[7].get_int().unwrap_or(0),
+            }))
+        }
+    }
+
+    /// 根据 UUID 获取节点
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 节点 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Some(Node))` - 找到匹配的节点
+    /// * `Ok(None)` - 未找到节点
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_node(&self, uuid: &str) -> Result<Option<Node>> {
         let params = Self::make_params(serde_json::json!({ "uuid": uuid }));
 
-        self.db.run_script(
-            r#"
-            ?[src_uuid, dst_uuid, relation, weight, source] := *edges{src_uuid, dst_uuid, relation, weight, source}, 
-                src_uuid != $uuid, dst_uuid != $uuid
-            :replace edges {src_uuid, dst_uuid => relation, weight, source}
-            "#,
-            params,
-            ScriptMutability::Mutable,
-        ).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+        let result = self.db.run_script(
+            "?[uuid, path, title, content, node_type, hash, created_at, updated_at] := *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}, uuid == $uuid",
+            params,
+            ScriptMutability::Immutable,
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result.rows.first().map(|row| Self::row_to_node(row)))
+    }
+
+    /// 按行获取笔记内容片段
+    ///
+    /// 内容从磁盘上的源文件读取（根据节点存储的路径），而非数据库中的完整内容，
+    /// 供编辑器只加载大文件的可见部分。
+    ///
+    /// # 参数
+    ///
+    /// * `vault_root` - 知识库根目录
+    /// * `uuid` - 节点 UUID
+    /// * `start_line` - 起始行号（从 1 开始，包含）
+    /// * `end_line` - 结束行号（包含）
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(String)` - 以 `\n` 连接的行内容；行号超出范围时截断到文件实际行数，
+    ///   范围为空时返回空字符串
+    /// * `Err(anyhow::Error)` - 节点不存在、数据库查询失败或文件读取失败
+    pub fn get_content_range(
+        &self,
+        vault_root: &Path,
+        uuid: &str,
+        start_line: usize,
+        end_line: usize,
+    ) -> Result<String> {
+        let node = self
+            .get_node(uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Node not found: {}", uuid))?;
+
+        let content = std::fs::read_to_string(vault_root.join(&node.path))
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", node.path, e))?;
+
+        let start = start_line.max(1);
+        if end_line < start {
+            return Ok(String::new());
+        }
+
+        Ok(content
+            .lines()
+            .skip(start - 1)
+            .take(end_line - start + 1)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    /// 获取块引用对应的内容
+    ///
+    /// 在磁盘上的笔记中定位带有 `^block_id` 标记的块，用于渲染 `![[Note#^block]]`。
+    /// 标记位于列表项末尾时返回该列表项；单独成行时返回其上方的块；
+    /// 否则返回标记所在的整个段落。返回的文本不含 `^block_id` 标记。
+    ///
+    /// # 参数
+    ///
+    /// * `vault_root` - 知识库根目录
+    /// * `note_path` - 笔记相对路径
+    /// * `block_id` - 块 ID（可带或不带 `^` 前缀）
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Some(String))` - 块的文本内容
+    /// * `Ok(None)` - 笔记未被索引或不存在该块 ID
+    /// * `Err(anyhow::Error)` - 数据库查询或文件读取失败
+    pub fn get_block_content(
+        &self,
+        vault_root: &Path,
+        note_path: &str,
+        block_id: &str,
+    ) -> Result<Option<String>> {
+        if self.get_node_by_path(note_path)?.is_none() {
+            return Ok(None);
+        }
+
+        let raw = std::fs::read_to_string(vault_root.join(note_path))
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", note_path, e))?;
+        let parsed = crate::adapters::obsidian::parse_markdown(&raw);
+
+        let block_id = block_id.trim_start_matches('^');
+        let line_number = match parsed.block_ids.iter().find(|b| b.id == block_id) {
+            Some(block) => block.line_number,
+            None => return Ok(None),
+        };
+
+        let lines: Vec<&str> = parsed.content.lines().collect();
+        let marker = format!("^{}", block_id);
+        let index = line_number - 1;
+        let line = lines[index].trim();
+
+        let is_list_item = |line: &str| {
+            let line = line.trim_start();
+            line.starts_with("- ")
+                || line.starts_with("* ")
+                || line.starts_with("+ ")
+                || line
+                    .split_once(". ")
+                    .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        };
+
+        // 确定块的行范围 [start, end]
+        let (start, end) = if line == marker {
+            // 单独成行的标记指向上方的块
+            let end = match lines[..index].iter().rposition(|l| !l.trim().is_empty()) {
+                Some(end) => end,
+                None => return Ok(None),
+            };
+            let start = lines[..end]
+                .iter()
+                .rposition(|l| l.trim().is_empty())
+                .map_or(0, |i| i + 1);
+            (start, end)
+        } else if is_list_item(lines[index]) {
+            (index, index)
+        } else {
+            let start = lines[..index]
+                .iter()
+                .rposition(|l| l.trim().is_empty() || is_list_item(l))
+                .map_or(0, |i| i + 1);
+            (start, index)
+        };
+
+        let text = lines[start..=end]
+            .iter()
+            .map(|l| l.replace(&marker, "").trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(Some(text.trim().to_string()))
+    }
+
+    /// 清空所有数据
+    ///
+    /// 删除数据库中的所有节点、边、标签和序列化源。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn clear_all(&mut self) -> Result<()> {
+        // Delete all nodes
+        let _ = self.db.run_script(
+            "?[uuid, path, title, content, node_type, hash, created_at, updated_at] <- [] :replace nodes {uuid => path, title, content, node_type, hash, created_at, updated_at}",
+            Default::default(),
+            ScriptMutability::Mutable,
+        );
+
+        // Delete all edges
+        let _ = self.db.run_script(
+            "?[src_uuid, dst_uuid, relation, weight, source] <- [] :replace edges {src_uuid, dst_uuid => relation, weight, source}",
+            Default::default(),
+            ScriptMutability::Mutable,
+        );
+
+        // Delete all tags
+        let _ = self.db.run_script(
+            "?[object_id, tag] <- [] :replace tags {object_id, tag}",
+            Default::default(),
+            ScriptMutability::Mutable,
+        );
+        let _ = self.db.run_script(
+            "?[object_id, tag] <- [] :replace tag_parents {object_id, tag}",
+            Default::default(),
+            ScriptMutability::Mutable,
+        );
+
+        // Delete all edge resolutions
+        let _ = self.db.run_script(
+            "?[src_uuid, dst_uuid, method] <- [] :replace edge_resolutions {src_uuid, dst_uuid => method}",
+            Default::default(),
+            ScriptMutability::Mutable,
+        );
+
+        // Delete all sources
+        let _ = self.db.run_script(
+            "?[object_id, source_type, path, content_hash, mime_type, size_bytes, last_modified] <- [] :replace sources {object_id, source_type => path, content_hash, mime_type, size_bytes, last_modified}",
+            Default::default(),
+            ScriptMutability::Mutable,
+        );
+
+        Ok(())
+    }
+
+    /// 清空所有边
+    ///
+    /// 仅删除 edges 表中的数据，节点、标签和属性保持不变。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn clear_edges(&mut self) -> Result<()> {
+        self.db.run_script(
+            "?[src_uuid, dst_uuid, relation, weight, source] <- [] :replace edges {src_uuid, dst_uuid => relation, weight, source}",
+            Default::default(),
+            ScriptMutability::Mutable,
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        self.db.run_script(
+            "?[src_uuid, dst_uuid, method] <- [] :replace edge_resolutions {src_uuid, dst_uuid => method}",
+            Default::default(),
+            ScriptMutability::Mutable,
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(())
+    }
+
+    /// 删除节点
+    ///
+    /// 根据 UUID 删除指定的节点，只删除 nodes 表中的行。
+    /// 删除对象及其边、属性、标签等关联数据请使用 [`Self::delete_object`]。
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 要删除的节点 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn delete_node(&mut self, uuid: &str) -> Result<()> {
+        let params = Self::make_params(serde_json::json!({ "uuid": uuid }));
+
+        self.db.run_script(
+            r#"
+            ?[uuid, path, title, content, node_type, hash, created_at, updated_at] := *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}, uuid != $uuid
+            :replace nodes {uuid => path, title, content, node_type, hash, created_at, updated_at}
+            "#,
+            params,
+            ScriptMutability::Mutable,
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(())
+    }
+
+    /// 删除与节点相关的所有边
+    ///
+    /// 删除所有源节点或目标节点为指定 UUID 的边。
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 节点 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn delete_edges_by_node(&mut self, uuid: &str) -> Result<()> {
+        let params = Self::make_params(serde_json::json!({ "uuid": uuid }));
+
+        self.db.run_script(
+            r#"
+            ?[src_uuid, dst_uuid, relation, weight, source] := *edges{src_uuid, dst_uuid, relation, weight, source}, 
+                src_uuid != $uuid, dst_uuid != $uuid
+            :replace edges {src_uuid, dst_uuid => relation, weight, source}
+            "#,
+            params,
+            ScriptMutability::Mutable,
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(())
+    }
+
+    /// 删除对象及其所有关联数据
+    ///
+    /// 删除节点、与节点相关的所有边，以及以该 UUID 为键的属性、标签、别名和序列化源，
+    /// 避免残留数据影响标签统计等查询。
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 要删除的对象 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn delete_object(&mut self, uuid: &str) -> Result<()> {
+        self.delete_node(uuid)?;
+        self.delete_edges_by_node(uuid)?;
+        self.save_tags(uuid, &[])?;
+        self.save_aliases(uuid, &[])?;
+        self.delete_properties(uuid)?;
+        self.save_sources(uuid, &[])?;
+        Ok(())
+    }
+
+    /// 清理无引用的哨兵节点
+    ///
+    /// 删除 nodes 表中 UUID 为 `tag:`、`file:` 或 `missing:` 哨兵、且没有任何边指向的行，
+    /// 避免笔记修改后残留的哨兵使图谱膨胀。`url:` 哨兵不会物化为节点，不在清理范围内。
+    ///
+    /// 同步本身只创建指向哨兵的边，不会写入哨兵节点行；这些行只来自外部数据，
+    /// 例如通过 [`Self::import_vault_from_reader`] 导入的、物化了标签节点的图数据。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(usize)` - 删除的哨兵节点数量
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn prune_orphan_sentinels(&mut self) -> Result<usize> {
+        const ORPHANS: &str = r#"
+            referenced[uuid] := *edges{dst_uuid: uuid}
+            ?[uuid] := *nodes{uuid},
+                starts_with(uuid, "tag:") || starts_with(uuid, "file:") || starts_with(uuid, "missing:"),
+                not referenced[uuid]
+        "#;
+
+        let orphans = self
+            .db
+            .run_script(ORPHANS, Default::default(), ScriptMutability::Immutable)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        if orphans.rows.is_empty() {
+            return Ok(0);
+        }
+
+        self.db
+            .run_script(
+                &format!("{}\n:rm nodes {{uuid}}", ORPHANS),
+                Default::default(),
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(orphans.rows.len())
+    }
+
+    /// 删除路径前缀下的所有节点
+    ///
+    /// 删除 `path` 等于 `prefix` 或位于 `prefix` 目录下的节点，以及它们的出边、
+    /// 标签、别名和属性。指向这些节点的入边保留，以便重新索引后（UUID 不变）继续有效。
+    /// 前缀按目录匹配：`a` 匹配 `a/x.md`，不匹配 `ab/x.md`；空前缀匹配所有节点。
+    ///
+    /// # 参数
+    ///
+    /// * `prefix` - 相对于知识库根目录的目录路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<String>)` - 被删除节点的 UUID 列表
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn delete_nodes_under_path(&mut self, prefix: &str) -> Result<Vec<String>> {
+        let prefix = prefix.trim_end_matches(['/', '\\']);
+        let uuids: Vec<String> = self
+            .get_all_nodes()?
+            .into_iter()
+            .filter(|node| {
+                prefix.is_empty()
+                    || node
+                        .path
+                        .strip_prefix(prefix)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '\\']))
+            })
+            .map(|node| node.uuid)
+            .collect();
+
+        for uuid in &uuids {
+            let params = Self::make_params(serde_json::json!({ "uuid": uuid }));
+            self.db
+                .run_script(
+                    r#"
+                ?[src_uuid, dst_uuid] := *edges{src_uuid, dst_uuid}, src_uuid == $uuid
+                :rm edges {src_uuid, dst_uuid}
+                "#,
+                    params,
+                    ScriptMutability::Mutable,
+                )
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+            self.delete_node(uuid)?;
+            self.save_tags(uuid, &[])?;
+            self.save_aliases(uuid, &[])?;
+            self.delete_properties(uuid)?;
+            self.save_sources(uuid, &[])?;
+        }
+
+        Ok(uuids)
+    }
+
+    /// 获取与节点相关的所有边
+    ///
+    /// 返回所有源节点或目标节点为指定 UUID 的边。
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 节点 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Edge>)` - 相关边列表
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_edges_by_node(&self, uuid: &str) -> Result<Vec<Edge>> {
+        let params = Self::make_params(serde_json::json!({ "uuid": uuid }));
+
+        let result = self.db.run_script(
+            r#"
+            ?[src_uuid, dst_uuid, relation, weight, source] := *edges{src_uuid, dst_uuid, relation, weight, source}, src_uuid == $uuid
+            ?[src_uuid, dst_uuid, relation, weight, source] := *edges{src_uuid, dst_uuid, relation, weight, source}, dst_uuid == $uuid
+            "#,
+            params,
+            ScriptMutability::Immutable,
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| Self::row_to_edge(row))
+            .collect())
+    }
+
+    /// 构建反向链接索引
+    ///
+    /// 一次遍历所有 `link` 边，得到每个链接目标被哪些笔记引用，
+    /// 供需要频繁查询反向链接的场景缓存使用。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(HashMap<String, Vec<String>>)` - 目标 UUID 到源 UUID 列表的映射，源 UUID 已排序去重
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn build_backlink_index(&self) -> Result<HashMap<String, Vec<String>>> {
+        let mut index: HashMap<String, Vec<String>> = HashMap::new();
+        for edge in self.get_all_edges()? {
+            if edge.relation == "link" {
+                index.entry(edge.dst_uuid).or_default().push(edge.src_uuid);
+            }
+        }
+
+        for sources in index.values_mut() {
+            sources.sort();
+            sources.dedup();
+        }
+        Ok(index)
+    }
+
+    /// 获取笔记的出链
+    ///
+    /// 只包含 `link` 关系的边；目标为已索引笔记时返回其标题和路径，
+    /// 断链、URL 等哨兵目标的路径为 `None`。
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 笔记 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<NoteLink>)` - 出链列表，按标题排序
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_outgoing_links(&self, uuid: &str) -> Result<Vec<NoteLink>> {
+        self.query_note_links(
+            r#"
+            ?[other, path, title, source] := *edges{src_uuid, dst_uuid: other, relation, source},
+                src_uuid == $uuid, relation == "link",
+                *nodes{uuid: other, path, title}
+            ?[other, path, title, source] := *edges{src_uuid, dst_uuid: other, relation, source},
+                src_uuid == $uuid, relation == "link",
+                not *nodes{uuid: other}, path = null, title = ""
+            "#,
+            uuid,
+        )
+    }
+
+    /// 获取笔记的反向链接
+    ///
+    /// 只包含 `link` 关系的边，来源均为已索引的笔记。
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 笔记 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<NoteLink>)` - 链接到该笔记的笔记列表，按标题排序
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_backlinks(&self, uuid: &str) -> Result<Vec<NoteLink>> {
+        self.query_note_links(
+            r#"
+            ?[other, path, title, source] := *edges{src_uuid: other, dst_uuid, relation, source},
+                dst_uuid == $uuid, relation == "link",
+                *nodes{uuid: other, path, title}
+            "#,
+            uuid,
+        )
+    }
+
+    /// 执行返回 `[uuid, path, title, source]` 的链接查询，补全哨兵标题并排序
+    fn query_note_links(&self, script: &str, uuid: &str) -> Result<Vec<NoteLink>> {
+        let params = Self::make_params(serde_json::json!({ "uuid": uuid }));
+
+        let result = self
+            .db
+            .run_script(script, params, ScriptMutability::Immutable)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let mut links: Vec<NoteLink> = result
+            .rows
+            .iter()
+            .map(|row| {
+                let uuid = row[0].get_str().unwrap_or("").to_string();
+                let path = row[1].get_str().map(str::to_string);
+                let title = match &path {
+                    Some(_) => row[2].get_str().unwrap_or("").to_string(),
+                    None => Self::sentinel_label(&uuid),
+                };
+                NoteLink {
+                    uuid,
+                    path,
+                    title,
+                    source: row[3].get_str().unwrap_or("").to_string(),
+                }
+            })
+            .collect();
+        links.sort_by(|a, b| a.title.cmp(&b.title).then_with(|| a.uuid.cmp(&b.uuid)));
+
+        Ok(links)
+    }
+
+    /// 按来源获取边
+    ///
+    /// # 参数
+    ///
+    /// * `source` - 边来源字符串，标准取值见 [`EdgeSource`]
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Edge>)` - 匹配的边列表
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_edges_by_source(&self, source: &str) -> Result<Vec<Edge>> {
+        let params = Self::make_params(serde_json::json!({ "source": source }));
+
+        let result = self
+            .db
+            .run_script(
+                "?[src_uuid, dst_uuid, relation, weight, source] := *edges{src_uuid, dst_uuid, relation, weight, source}, source == $source",
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| Self::row_to_edge(row))
+            .collect())
+    }
+
+    /// 记录链接边的解析方式
+    ///
+    /// # 参数
+    ///
+    /// * `src_uuid` - 源节点 UUID
+    /// * `dst_uuid` - 目标节点 UUID
+    /// * `method` - 解析方式
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 记录成功，覆盖该边已有的记录
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn set_edge_resolution(
+        &mut self,
+        src_uuid: &str,
+        dst_uuid: &str,
+        method: LinkResolution,
+    ) -> Result<()> {
+        let params = Self::make_params(serde_json::json!({
+            "src_uuid": src_uuid,
+            "dst_uuid": dst_uuid,
+            "method": method.as_str(),
+        }));
+
+        self.db
+            .run_script(
+                r#"
+                ?[src_uuid, dst_uuid, method] <- [[$src_uuid, $dst_uuid, $method]]
+                :put edge_resolutions {src_uuid, dst_uuid => method}
+                "#,
+                params,
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(())
+    }
+
+    /// 按解析方式获取链接边
+    ///
+    /// 例如查询 `alias` 找出所有依赖别名解析的链接。
+    ///
+    /// # 参数
+    ///
+    /// * `method` - 解析方式字符串，标准取值见 [`LinkResolution`]
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Edge>)` - 匹配的边列表
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_edges_resolved_by(&self, method: &str) -> Result<Vec<Edge>> {
+        let params = Self::make_params(serde_json::json!({ "method": method }));
+
+        let result = self
+            .db
+            .run_script(
+                "?[src_uuid, dst_uuid, relation, weight, source] := *edge_resolutions{src_uuid, dst_uuid, method}, method == $method, *edges{src_uuid, dst_uuid, relation, weight, source}",
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| Self::row_to_edge(row))
+            .collect())
+    }
+
+    /// 获取链接统计
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(LinkStats)` - 链接总数、已解析与断链数量，以及入链最多的笔记（最多 10 个）
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_link_statistics(&self) -> Result<LinkStats> {
+        let paths: HashMap<String, String> = self
+            .get_all_nodes()?
+            .into_iter()
+            .map(|node| (node.uuid, node.path))
+            .collect();
+
+        let mut stats = LinkStats::default();
+        let mut inbound: HashMap<&str, usize> = HashMap::new();
+        let edges = self.get_all_edges()?;
+        for edge in edges
+            .iter()
+            .filter(|e| e.relation == "link" && !e.dst_uuid.starts_with("url:"))
+        {
+            stats.total_links += 1;
+            if edge.dst_uuid.starts_with("missing:") {
+                stats.broken += 1;
+            } else {
+                stats.resolved += 1;
+                if let Some(path) = paths.get(&edge.dst_uuid) {
+                    *inbound.entry(path.as_str()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut top_linked: Vec<(String, usize)> = inbound
+            .into_iter()
+            .map(|(path, count)| (path.to_string(), count))
+            .collect();
+        top_linked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_linked.truncate(TOP_LINKED_LIMIT);
+        stats.top_linked = top_linked;
+
+        Ok(stats)
+    }
+
+    /// 获取自链接
+    ///
+    /// 返回源节点与目标节点相同的边（笔记链接到自身），供清理使用。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Edge>)` - 自环边列表
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_self_links(&self) -> Result<Vec<Edge>> {
+        let result = self
+            .db
+            .run_script(
+                "?[src_uuid, dst_uuid, relation, weight, source] := *edges{src_uuid, dst_uuid, relation, weight, source}, src_uuid == dst_uuid",
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| Self::row_to_edge(row))
+            .collect())
+    }
+
+    /// 查找内容完全相同的笔记
+    ///
+    /// 按节点的 `hash` 列（正文内容哈希）分组，返回包含多个路径的分组。
+    /// 文件夹节点不参与比较。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Vec<String>>)` - 重复笔记的路径分组，组内与组间均按路径排序
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn find_exact_duplicates(&self) -> Result<Vec<Vec<String>>> {
+        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+        for node in self.get_all_nodes()? {
+            if node.node_type != crate::sync::FOLDER_NODE_TYPE {
+                groups.entry(node.hash).or_default().push(node.path);
+            }
+        }
+
+        let mut duplicates: Vec<Vec<String>> = groups
+            .into_values()
+            .filter(|paths| paths.len() > 1)
+            .map(|mut paths| {
+                paths.sort();
+                paths
+            })
+            .collect();
+        duplicates.sort();
+
+        Ok(duplicates)
+    }
+
+    /// 获取所有节点的入度和出度
+    ///
+    /// 在数据库中按节点聚合边数，一次查询得到全部节点的连接度，
+    /// 供前端按连接度设置节点大小。没有任何边的节点度数为 `(0, 0)`。
+    ///
+    /// # 参数
+    ///
+    /// * `include_tags` - 是否将标签边（`tagged`）计入出度
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(HashMap<String, (usize, usize)>)` - 节点 UUID 到 `(入度, 出度)` 的映射
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_all_degrees(&self, include_tags: bool) -> Result<HashMap<String, (usize, usize)>> {
+        let filter = if include_tags {
+            ""
+        } else {
+            ", relation != \"tagged\""
+        };
+        let script = format!(
+            r#"
+            in_deg[uuid, count(src)] := *nodes{{uuid}}, *edges{{src_uuid: src, dst_uuid: uuid, relation}}{filter}
+            out_deg[uuid, count(dst)] := *nodes{{uuid}}, *edges{{src_uuid: uuid, dst_uuid: dst, relation}}{filter}
+            ?[uuid, dir, n] := in_deg[uuid, n], dir = "in"
+            ?[uuid, dir, n] := out_deg[uuid, n], dir = "out"
+            ?[uuid, dir, n] := *nodes{{uuid}}, dir = "none", n = 0
+            "#
+        );
+
+        let result = self
+            .db
+            .run_script(&script, Default::default(), ScriptMutability::Immutable)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let mut degrees: HashMap<String, (usize, usize)> = HashMap::new();
+        for row in &result.rows {
+            let uuid = row[0].get_str().unwrap_or("").to_string();
+            let n = row[2].get_int().unwrap_or(0) as usize;
+            let entry = degrees.entry(uuid).or_default();
+            match row[1].get_str() {
+                Some("in") => entry.0 = n,
+                Some("out") => entry.1 = n,
+                _ => {}
+            }
+        }
+        Ok(degrees)
+    }
+
+    /// 获取所有被引用的链接目标
+    ///
+    /// 统计每个 wikilink 目标被多少篇笔记引用，包括未解析的目标（类似 Obsidian 的“未解析链接”）。
+    /// 已解析的链接以目标笔记的文件名（不含扩展名）表示，未解析的链接保留原始目标文本；
+    /// 外部链接和未索引的附件嵌入不计入。前端可结合已有节点找出尚未创建的笔记。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<(String, usize)>)` - `(目标, 引用笔记数)`，按引用数降序、目标升序排列
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_all_link_targets(&self) -> Result<Vec<(String, usize)>> {
+        let paths: HashMap<String, String> = self
+            .get_all_nodes()?
+            .into_iter()
+            .map(|node| (node.uuid, node.path))
+            .collect();
+
+        let mut referrers: HashMap<String, std::collections::HashSet<String>> = HashMap::new();
+        for edge in self.get_all_edges()? {
+            if edge.relation != "link" {
+                continue;
+            }
+
+            let target = if let Some(missing) = edge.dst_uuid.strip_prefix("missing:") {
+                missing.to_string()
+            } else if let Some(path) = paths.get(&edge.dst_uuid) {
+                Path::new(path)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.clone())
+            } else {
+                continue;
+            };
+
+            referrers.entry(target).or_default().insert(edge.src_uuid);
+        }
+
+        let mut targets: Vec<(String, usize)> = referrers
+            .into_iter()
+            .map(|(target, sources)| (target, sources.len()))
+            .collect();
+        targets.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Ok(targets)
+    }
+
+    /// 查找链接环
+    ///
+    /// 在笔记之间的 `link` 边（不含标签和哨兵）上用深度优先搜索枚举长度不超过 `max_len` 的简单环，
+    /// 如 A→B→C→A。每个环只返回一次，从 UUID 最小的节点开始。
+    /// 最多返回 `MAX_CYCLES` 个环；搜索扩展超过 `MAX_CYCLE_STEPS` 步后停止，此时结果可能不完整。
+    ///
+    /// # 参数
+    ///
+    /// * `max_len` - 环中节点数的上限，最大为 `MAX_CYCLE_LEN`
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Vec<String>>)` - 每个环上依次经过的笔记路径（不重复首节点）
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn find_cycles(&self, max_len: usize) -> Result<Vec<Vec<String>>> {
+        let paths: HashMap<String, String> = self
+            .get_all_nodes()?
+            .into_iter()
+            .map(|n| (n.uuid, n.path))
+            .collect();
+
+        let mut adjacency: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for edge in self.get_all_edges()? {
+            if edge.relation == "link" && paths.contains_key(&edge.dst_uuid) {
+                adjacency
+                    .entry(edge.src_uuid)
+                    .or_default()
+                    .push(edge.dst_uuid);
+            }
+        }
+        for targets in adjacency.values_mut() {
+            targets.sort();
+        }
+
+        let max_len = max_len.min(MAX_CYCLE_LEN);
+        let mut cycles = Vec::new();
+        let mut steps = 0;
+        for start in adjacency.keys() {
+            let mut stack = vec![start.clone()];
+            Self::collect_cycles(
+                start,
+                &adjacency,
+                max_len,
+                &mut stack,
+                &mut cycles,
+                &mut steps,
+            );
+            if cycles.len() >= MAX_CYCLES || steps >= MAX_CYCLE_STEPS {
+                break;
+            }
+        }
+
+        Ok(cycles
+            .into_iter()
+            .map(|cycle| {
+                cycle
+                    .into_iter()
+                    .map(|uuid| paths.get(&uuid).cloned().unwrap_or(uuid))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// 从 `stack` 末尾节点继续搜索回到 `start` 的环
+    ///
+    /// 只经过 UUID 大于 `start` 的节点，保证每个环只在其最小节点处被发现一次。
+    /// `steps` 累计所有起点的扩展次数，达到 `MAX_CYCLE_STEPS` 时停止。
+    fn collect_cycles(
+        start: &str,
+        adjacency: &BTreeMap<String, Vec<String>>,
+        max_len: usize,
+        stack: &mut Vec<String>,
+        cycles: &mut Vec<Vec<String>>,
+        steps: &mut usize,
+    ) {
+        let current = stack.last().cloned().unwrap_or_default();
+        let Some(targets) = adjacency.get(&current) else {
+            return;
+        };
+
+        for next in targets {
+            if cycles.len() >= MAX_CYCLES || *steps >= MAX_CYCLE_STEPS {
+                return;
+            }
+            *steps += 1;
+            if next == start {
+                cycles.push(stack.clone());
+            } else if next.as_str() > start && stack.len() < max_len && !stack.contains(next) {
+                stack.push(next.clone());
+                Self::collect_cycles(start, adjacency, max_len, stack, cycles, steps);
+                stack.pop();
+            }
+        }
+    }
+
+    /// 查找两个节点之间的最短路径
+    ///
+    /// 沿边的方向查找经过边数最少的路径，所有关系的边都参与计算，
+    /// 因此路径可以终止于 `tag:` 等哨兵。使用 CozoDB 的 `ShortestPathDijkstra`
+    /// 算法；算法不可用时（见 [`Capabilities`]）在内存中广度优先搜索。
+    ///
+    /// # 参数
+    ///
+    /// * `from` - 起点 UUID
+    /// * `to` - 终点 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Some(Vec<String>))` - 路径上的节点 UUID，包含起点和终点
+    /// * `Ok(None)` - 不可达
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn shortest_path(&self, from: &str, to: &str) -> Result<Option<Vec<String>>> {
+        if from == to {
+            return Ok(Some(vec![from.to_string()]));
+        }
+        if !self.capabilities.shortest_path {
+            return self.shortest_path_in_memory(from, to);
+        }
+
+        let params = Self::make_params(serde_json::json!({ "from": from, "to": to }));
+        let result = self
+            .db
+            .run_script(
+                r#"
+            e[a, b] := *edges{src_uuid: a, dst_uuid: b}
+            start[n] <- [[$from]]
+            goal[n] <- [[$to]]
+            ?[s, g, cost, path] <~ ShortestPathDijkstra(e[], start[], goal[])
+            "#,
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let path: Vec<String> = result
+            .rows
+            .first()
+            .and_then(|row| row[3].get_slice())
+            .map(|path| {
+                path.iter()
+                    .filter_map(|uuid| uuid.get_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok((path.len() > 1).then_some(path))
+    }
+
+    /// 在内存中广度优先搜索最短路径
+    ///
+    /// [`Self::shortest_path`] 在最短路径算法不可用时的回退实现。
+    fn shortest_path_in_memory(&self, from: &str, to: &str) -> Result<Option<Vec<String>>> {
+        let mut adjacency: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for edge in self.get_all_edges()? {
+            adjacency
+                .entry(edge.src_uuid)
+                .or_default()
+                .push(edge.dst_uuid);
+        }
+
+        let mut previous: HashMap<String, String> = HashMap::new();
+        let mut queue = std::collections::VecDeque::from([from.to_string()]);
+        while let Some(current) = queue.pop_front() {
+            for next in adjacency.get(&current).into_iter().flatten() {
+                if next == from || previous.contains_key(next) {
+                    continue;
+                }
+                previous.insert(next.clone(), current.clone());
+                if next == to {
+                    let mut path = vec![to.to_string()];
+                    while let Some(prev) = previous.get(path.last().unwrap()) {
+                        path.push(prev.clone());
+                    }
+                    path.reverse();
+                    return Ok(Some(path));
+                }
+                queue.push_back(next.clone());
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// 将两个节点之间的最短路径描述为带关系的步骤
+    ///
+    /// 基于 [`Self::shortest_path`]，为每一跳标注所经过边的关系类型。
+    ///
+    /// # 参数
+    ///
+    /// * `from` - 起点 UUID
+    /// * `to` - 终点 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Some(Vec<ConnectionStep>))` - 每一跳的描述；起点与终点相同时为空列表
+    /// * `Ok(None)` - 不可达
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn explain_connection(&self, from: &str, to: &str) -> Result<Option<Vec<ConnectionStep>>> {
+        let Some(path) = self.shortest_path(from, to)? else {
+            return Ok(None);
+        };
+
+        let mut steps = Vec::new();
+        for hop in path.windows(2) {
+            let relation = self
+                .get_edges_by_node(&hop[0])?
+                .into_iter()
+                .find(|e| e.src_uuid == hop[0] && e.dst_uuid == hop[1])
+                .map(|e| e.relation)
+                .unwrap_or_default();
+            steps.push(ConnectionStep {
+                from_title: self.node_label(&hop[0])?,
+                relation,
+                to_title: self.node_label(&hop[1])?,
+            });
+        }
+
+        Ok(Some(steps))
+    }
+
+    /// 获取节点的可读名称
+    ///
+    /// 真实节点使用标题；`tag:` 哨兵显示为 `#标签`，其他哨兵去掉前缀。
+    fn node_label(&self, uuid: &str) -> Result<String> {
+        if let Some(node) = self.get_node(uuid)? {
+            return Ok(node.title);
+        }
+        Ok(Self::sentinel_label(uuid))
+    }
+
+    /// 获取哨兵节点的可读名称：`tag:` 显示为 `#标签`，其他哨兵去掉前缀
+    fn sentinel_label(uuid: &str) -> String {
+        if let Some(tag) = uuid.strip_prefix("tag:") {
+            return format!("#{}", tag);
+        }
+        SENTINEL_PREFIXES
+            .iter()
+            .find_map(|prefix| uuid.strip_prefix(prefix))
+            .unwrap_or(uuid)
+            .to_string()
+    }
+
+    /// 检测图社区
+    ///
+    /// 将 `link` 关系的边视为无向边，使用 CozoDB 的 `ConnectedComponents`
+    /// 算法计算连通分量；图算法不可用时（见 [`Capabilities`]）在内存中计算。
+    /// 标签、文件夹包含等其他关系以及指向哨兵的边不参与计算，
+    /// 没有链接的孤立节点各自成为独立社区。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(HashMap<String, usize>)` - 节点 UUID 到社区 ID 的映射，社区 ID 从 0 开始连续编号
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn detect_communities(&self) -> Result<HashMap<String, usize>> {
+        if !self.capabilities.graph_algorithms {
+            return self.detect_communities_in_memory();
+        }
+
+        let result = self
+            .db
+            .run_script(
+                r#"
+            links[src, dst] := *edges{src_uuid: src, dst_uuid: dst, relation},
+                relation == "link", *nodes{uuid: src}, *nodes{uuid: dst}
+            ?[node, component] <~ ConnectedComponents(links[])
+            "#,
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        // 将算法输出的分量编号重新映射为连续编号
+        let mut communities = HashMap::new();
+        let mut component_ids: HashMap<i64, usize> = HashMap::new();
+        for row in &result.rows {
+            let node = row[0].get_str().unwrap_or("").to_string();
+            let component = row[1].get_int().unwrap_or(0);
+            let next_id = component_ids.len();
+            let id = *component_ids.entry(component).or_insert(next_id);
+            communities.insert(node, id);
+        }
+
+        let mut next_id = component_ids.len();
+        for node in self.get_all_nodes()? {
+            if let std::collections::hash_map::Entry::Vacant(entry) = communities.entry(node.uuid) {
+                entry.insert(next_id);
+                next_id += 1;
+            }
+        }
+
+        Ok(communities)
+    }
+
+    /// 在内存中计算连通分量
+    ///
+    /// [`Self::detect_communities`] 在图算法不可用时的回退实现，社区按节点 UUID 顺序编号。
+    fn detect_communities_in_memory(&self) -> Result<HashMap<String, usize>> {
+        let mut uuids: Vec<String> = self
+            .get_all_nodes()?
+            .into_iter()
+            .map(|node| node.uuid)
+            .collect();
+        uuids.sort();
+        let index: HashMap<&str, usize> = uuids
+            .iter()
+            .enumerate()
+            .map(|(i, uuid)| (uuid.as_str(), i))
+            .collect();
+
+        // 并查集
+        let mut parent: Vec<usize> = (0..uuids.len()).collect();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        for edge in self.get_all_edges()? {
+            if edge.relation != "link" {
+                continue;
+            }
+            if let (Some(&a), Some(&b)) = (
+                index.get(edge.src_uuid.as_str()),
+                index.get(edge.dst_uuid.as_str()),
+            ) {
+                let (root_a, root_b) = (find(&mut parent, a), find(&mut parent, b));
+                parent[root_a.max(root_b)] = root_a.min(root_b);
+            }
+        }
+
+        let mut component_ids: HashMap<usize, usize> = HashMap::new();
+        let mut communities = HashMap::new();
+        for (i, uuid) in uuids.iter().enumerate() {
+            let root = find(&mut parent, i);
+            let next_id = component_ids.len();
+            let id = *component_ids.entry(root).or_insert(next_id);
+            communities.insert(uuid.clone(), id);
+        }
+        Ok(communities)
+    }
+
+    /// 获取所有外部链接
+    ///
+    /// 从来源为 [`EdgeSource::External`] 的边中提取去重后的链接地址。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<String>)` - 链接地址列表（已排序，不含 `url:` 前缀）
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_external_links(&self) -> Result<Vec<String>> {
+        let edges = self.get_edges_by_source(EdgeSource::External.as_str())?;
+
+        let urls: std::collections::BTreeSet<String> = edges
+            .into_iter()
+            .filter_map(|edge| edge.dst_uuid.strip_prefix("url:").map(|s| s.to_string()))
+            .collect();
+
+        Ok(urls.into_iter().collect())
+    }
+
+    /// 获取与指定笔记经常被一起链接的笔记
+    ///
+    /// 对 edges 表自连接：链接到该笔记的每个来源笔记所链接的其他笔记都视为共链笔记，
+    /// 按共同来源数量排序。只考虑 `link` 关系，标签等其他关系的边不参与计算。
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 笔记 UUID
+    /// * `limit` - 最多返回的笔记数量
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<(Node, usize)>)` - `(笔记, 共同来源数)`，按来源数降序、路径升序排列，不含该笔记本身
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_co_linked(&self, uuid: &str, limit: usize) -> Result<Vec<(Node, usize)>> {
+        let params = Self::make_params(serde_json::json!({ "uuid": uuid }));
+
+        let result = self
+            .db
+            .run_script(
+                &format!(
+                    r#"
+                shared[other, count(src)] := *edges{{src_uuid: src, dst_uuid: target, relation: r1}},
+                    target == $uuid, r1 == "link",
+                    *edges{{src_uuid: src, dst_uuid: other, relation: r2}},
+                    r2 == "link", other != $uuid
+                ?[uuid, path, title, content, node_type, hash, created_at, updated_at, n] :=
+                    shared[uuid, n],
+                    *nodes{{uuid, path, title, content, node_type, hash, created_at, updated_at}}
+                :order -n, path
+                :limit {}
+                "#,
+                    limit
+                ),
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    Self::row_to_node(row),
+                    row[8].get_int().unwrap_or(0) as usize,
+                )
+            })
+            .collect())
+    }
+
+    /// 获取嵌入指定文件的笔记
+    ///
+    /// 查找来源为 [`EdgeSource::Embed`] 的边，目标可以是已索引的节点，
+    /// 也可以是未索引文件（如图片）对应的 `file:` 哨兵。
+    ///
+    /// # 参数
+    ///
+    /// * `target` - 被嵌入的文件，可为文件名或相对路径，扩展名可省略
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Node>)` - 嵌入了该文件的笔记列表（按路径排序，已去重）
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_embedding_notes(&self, target: &str) -> Result<Vec<Node>> {
+        let params = Self::make_params(serde_json::json!({ "source": EdgeSource::Embed.as_str() }));
+
+        let result = self
+            .db
+            .run_script(
+                r#"
+                embedded[src_uuid, target] := *edges{src_uuid, dst_uuid, source}, source == $source,
+                    *nodes{uuid: dst_uuid, path: target}
+                embedded[src_uuid, target] := *edges{src_uuid, dst_uuid, source}, source == $source,
+                    starts_with(dst_uuid, 'file:'), target = slice_string(dst_uuid, 5, length(dst_uuid))
+                ?[uuid, path, title, content, node_type, hash, created_at, updated_at, target] :=
+                    embedded[uuid, target],
+                    *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}
+                :order path
+                "#,
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let target = Self::normalize_embed_target(target);
+        let mut notes: Vec<Node> = Vec::new();
+        for row in &result.rows {
+            let embedded = Self::normalize_embed_target(row[8].get_str().unwrap_or(""));
+            if !Self::embed_target_matches(&embedded, &target) {
+                continue;
+            }
+            let node = Self::row_to_node(row);
+            if !notes.iter().any(|n| n.uuid == node.uuid) {
+                notes.push(node);
+            }
+        }
+
+        Ok(notes)
+    }
+
+    /// 规范化嵌入目标：去除首尾空白，统一使用 `/` 分隔
+    fn normalize_embed_target(target: &str) -> String {
+        target.trim().replace('\\', "/")
+    }
+
+    /// 判断被嵌入的路径是否匹配查询目标
+    ///
+    /// 允许查询目标省略扩展名（或仅给出文件名），也允许嵌入时省略扩展名，
+    /// 但两侧都带扩展名时扩展名必须一致。
+    fn embed_target_matches(embedded: &str, target: &str) -> bool {
+        fn strip_extension(path: &str) -> &str {
+            let name_start = path.rfind('/').map_or(0, |i| i + 1);
+            match path[name_start..].rfind('.') {
+                Some(dot) if dot > 0 => &path[..name_start + dot],
+                _ => path,
+            }
+        }
+        fn same_or_suffix(a: &str, b: &str) -> bool {
+            a == b
+                || a.strip_suffix(b).is_some_and(|rest| rest.ends_with('/'))
+                || b.strip_suffix(a).is_some_and(|rest| rest.ends_with('/'))
+        }
+
+        same_or_suffix(embedded, target)
+            || same_or_suffix(strip_extension(embedded), target)
+            || same_or_suffix(embedded, strip_extension(target))
+    }
+
+    /// 重命名节点
+    ///
+    /// 用新节点替换旧节点，并将所有指向或来自旧 UUID 的边、旧节点的标签
+    /// 迁移到新 UUID。文件名（不含扩展名）不变时，其他笔记的入链无需全量同步即可
+    /// 指向新节点；文件名改变后 `[[旧名]]` 不再能解析，入链改为指向 `missing:旧名` 哨兵。
+    ///
+    /// # 参数
+    ///
+    /// * `old_uuid` - 旧节点 UUID
+    /// * `new_node` - 新节点（包含新路径与新 UUID）
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(usize)` - 迁移的边数量
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn rename_node(&mut self, old_uuid: &str, new_node: &Node) -> Result<usize> {
+        let edges = self.get_edges_by_node(old_uuid)?;
+        let tags = self.get_tags(old_uuid)?;
+        let stem = |path: &str| {
+            Path::new(path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+        };
+        let old_stem = self.get_node(old_uuid)?.and_then(|node| stem(&node.path));
+        let missing_target = old_stem
+            .filter(|old| stem(&new_node.path).as_ref() != Some(old))
+            .map(|old| format!("missing:{}", old));
+
+        self.delete_node(old_uuid)?;
+        self.delete_edges_by_node(old_uuid)?;
+        self.save_tags(old_uuid, &[])?;
+
+        self.upsert_node(new_node)?;
+        self.save_tags(&new_node.uuid, &tags)?;
+
+        for mut edge in edges.iter().cloned() {
+            if edge.src_uuid == old_uuid {
+                edge.src_uuid = new_node.uuid.clone();
+            }
+            if edge.dst_uuid == old_uuid {
+                edge.dst_uuid = match &missing_target {
+                    Some(missing) if edge.src_uuid != new_node.uuid => missing.clone(),
+                    _ => new_node.uuid.clone(),
+                };
+            }
+            self.upsert_edge(&edge)?;
+        }
+
+        Ok(edges.len())
+    }
+
+    /// 检查数据库完整性
+    ///
+    /// 检测悬空边（引用不存在的节点，`tag:`/`file:` 哨兵除外）、
+    /// 孤立属性（所属对象不存在）以及被多个 UUID 占用的路径。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(IntegrityReport)` - 检查报告
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn check_integrity(&self) -> Result<IntegrityReport> {
+        let dangling = self
+            .db
+            .run_script(
+                &format!(
+                    "{}\n?[src_uuid, dst_uuid, relation, weight, source] := *edges{{src_uuid, dst_uuid, relation, weight, source}}, {}",
+                    Self::dangling_edge_rules(),
+                    "dangling[src_uuid, dst_uuid]"
+                ),
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let orphans = self
+            .db
+            .run_script(
+                r#"
+                node_ids[uuid] := *nodes{uuid}
+                ?[object_id, name] := *properties{object_id, name}, not node_ids[object_id]
+                "#,
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let path_counts = self
+            .db
+            .run_script(
+                "?[path, count(uuid)] := *nodes{uuid, path}",
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let duplicates: Vec<String> = path_counts
+            .rows
+            .iter()
+            .filter(|row| row[1].get_int().unwrap_or(0) > 1)
+            .filter_map(|row| row[0].get_str().map(|s| s.to_string()))
+            .collect();
+
+        Ok(IntegrityReport {
+            dangling_edges: dangling.rows.len(),
+            dangling_edge_samples: dangling
+                .rows
+                .iter()
+                .take(INTEGRITY_SAMPLE_LIMIT)
+                .map(|row| Self::row_to_edge(row))
+                .collect(),
+            orphan_properties: orphans.rows.len(),
+            orphan_property_samples: orphans
+                .rows
+                .iter()
+                .take(INTEGRITY_SAMPLE_LIMIT)
+                .map(|row| {
+                    (
+                        row[0].get_str().unwrap_or("").to_string(),
+                        row[1].get_str().unwrap_or("").to_string(),
+                    )
+                })
+                .collect(),
+            duplicate_paths: duplicates.len(),
+            duplicate_path_samples: duplicates
+                .into_iter()
+                .take(INTEGRITY_SAMPLE_LIMIT)
+                .collect(),
+        })
+    }
+
+    /// 修复数据库完整性问题
+    ///
+    /// 删除悬空边和孤立属性。重复路径需要重新同步才能修复，不在此处理。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(IntegrityReport)` - 修复前的检查报告
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn repair_integrity(&mut self) -> Result<IntegrityReport> {
+        let report = self.check_integrity()?;
+
+        self.db
+            .run_script(
+                &format!(
+                    "{}\n?[src_uuid, dst_uuid] := dangling[src_uuid, dst_uuid]\n:rm edges {{src_uuid, dst_uuid}}",
+                    Self::dangling_edge_rules()
+                ),
+                Default::default(),
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        self.db
+            .run_script(
+                r#"
+                node_ids[uuid] := *nodes{uuid}
+                ?[object_id, name] := *properties{object_id, name}, not node_ids[object_id]
+                :rm properties {object_id, name}
+                "#,
+                Default::default(),
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(report)
+    }
+
+    /// 创建索引快照
+    ///
+    /// 记录当前所有节点的路径和内容哈希，快照 ID 自增。
+    /// 只保留最近 [`MAX_SNAPSHOTS`] 个快照，更早的快照随之删除。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(i64)` - 新快照 ID
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn create_snapshot(&mut self) -> Result<i64> {
+        let snapshot_id = self.list_snapshots()?.last().map_or(1, |id| id + 1);
+        let params = Self::make_params(serde_json::json!({
+            "snapshot_id": snapshot_id,
+            "created_at": chrono::Utc::now().timestamp(),
+        }));
+
+        self.db
+            .run_script(
+                r#"
+                ?[snapshot_id, created_at] <- [[$snapshot_id, $created_at]]
+                :put snapshots {snapshot_id => created_at}
+                "#,
+                params.clone(),
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        self.db
+            .run_script(
+                r#"
+                ?[snapshot_id, path, hash] := *nodes{path, hash}, snapshot_id = $snapshot_id
+                :put node_snapshots {snapshot_id, path => hash}
+                "#,
+                params,
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        self.prune_snapshots(MAX_SNAPSHOTS)?;
+        Ok(snapshot_id)
+    }
+
+    /// 删除较早的快照，只保留最近 `keep` 个
+    ///
+    /// # 参数
+    ///
+    /// * `keep` - 保留的快照数量
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(usize)` - 删除的快照数量
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn prune_snapshots(&mut self, keep: usize) -> Result<usize> {
+        let snapshots = self.list_snapshots()?;
+        let stale = snapshots.len().saturating_sub(keep);
+        if stale == 0 {
+            return Ok(0);
+        }
+        // 删除 ID 小于第一个保留快照的所有快照，`keep` 为 0 时全部删除
+        let cutoff = snapshots.get(stale).copied().unwrap_or(i64::MAX);
+
+        let params = Self::make_params(serde_json::json!({ "cutoff": cutoff }));
+        self.db
+            .run_script(
+                r#"
+                ?[snapshot_id, path] := *node_snapshots{snapshot_id, path}, snapshot_id < $cutoff
+                :rm node_snapshots {snapshot_id, path}
+                "#,
+                params.clone(),
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        self.db
+            .run_script(
+                r#"
+                ?[snapshot_id] := *snapshots{snapshot_id}, snapshot_id < $cutoff
+                :rm snapshots {snapshot_id}
+                "#,
+                params,
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(stale)
+    }
+
+    /// 列出所有快照 ID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<i64>)` - 快照 ID 列表（升序）
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn list_snapshots(&self) -> Result<Vec<i64>> {
+        let result = self
+            .db
+            .run_script(
+                "?[snapshot_id] := *snapshots{snapshot_id} :order snapshot_id",
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .filter_map(|row| row[0].get_int())
+            .collect())
+    }
+
+    /// 比较两个快照
+    ///
+    /// # 参数
+    ///
+    /// * `from_id` - 较早的快照 ID
+    /// * `to_id` - 较新的快照 ID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(SyncPlan)` - 从 `from_id` 到 `to_id` 新增、更新和删除的路径
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn diff_snapshots(&self, from_id: i64, to_id: i64) -> Result<SyncPlan> {
+        let from = self.snapshot_hashes(from_id)?;
+        let to = self.snapshot_hashes(to_id)?;
+
+        let mut plan = SyncPlan::default();
+        for (path, hash) in &to {
+            match from.get(path) {
+                None => plan.added.push(path.clone()),
+                Some(old_hash) if old_hash != hash => plan.updated.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        plan.deleted = from
+            .keys()
+            .filter(|path| !to.contains_key(*path))
+            .cloned()
+            .collect();
+
+        Ok(plan)
+    }
+
+    /// 获取快照中路径到哈希的映射
+    fn snapshot_hashes(&self, snapshot_id: i64) -> Result<BTreeMap<String, String>> {
+        let params = Self::make_params(serde_json::json!({ "snapshot_id": snapshot_id }));
+
+        let result = self
+            .db
+            .run_script(
+                "?[path, hash] := *node_snapshots{snapshot_id, path, hash}, snapshot_id == $snapshot_id",
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row[0].get_str().unwrap_or("").to_string(),
+                    row[1].get_str().unwrap_or("").to_string(),
+                )
+            })
+            .collect())
+    }
+
+    /// 保存节点在图谱视图中的坐标
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 节点 UUID
+    /// * `x` - 横坐标
+    /// * `y` - 纵坐标
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 保存成功，覆盖已有坐标
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn save_node_position(&mut self, uuid: &str, x: f64, y: f64) -> Result<()> {
+        let params = Self::make_params(serde_json::json!({ "uuid": uuid, "x": x, "y": y }));
+
+        self.db
+            .run_script(
+                r#"
+                ?[uuid, x, y] <- [[$uuid, $x, $y]]
+                :put layout {uuid => x, y}
+                "#,
+                params,
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(())
+    }
+
+    /// 获取所有已保存的节点坐标
+    ///
+    /// 只返回仍存在于索引中的节点；没有保存过坐标的节点不会出现在结果中。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(HashMap<String, (f64, f64)>)` - 节点 UUID 到 `(x, y)` 的映射
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_node_positions(&self) -> Result<HashMap<String, (f64, f64)>> {
+        let result = self
+            .db
+            .run_script(
+                "?[uuid, x, y] := *layout{uuid, x, y}, *nodes{uuid}",
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .filter_map(|row| {
+                Some((
+                    row[0].get_str()?.to_string(),
+                    (row[1].get_float()?, row[2].get_float()?),
+                ))
+            })
+            .collect())
+    }
+
+    /// 生成悬空边的 CozoScript 规则
+    ///
+    /// 定义 `dangling[src_uuid, dst_uuid]`：源节点不存在，
+    /// 或目标节点不存在且不是哨兵目标的边。
+    fn dangling_edge_rules() -> String {
+        let sentinel_filters: Vec<String> = SENTINEL_PREFIXES
+            .iter()
+            .map(|prefix| format!("!starts_with(dst_uuid, '{}')", prefix))
+            .collect();
+
+        format!(
+            r#"
+            node_ids[uuid] := *nodes{{uuid}}
+            dangling[src_uuid, dst_uuid] := *edges{{src_uuid, dst_uuid}}, not node_ids[src_uuid]
+            dangling[src_uuid, dst_uuid] := *edges{{src_uuid, dst_uuid}}, not node_ids[dst_uuid], {}
+            "#,
+            sentinel_filters.join(", ")
+        )
+    }
+
+    /// 将查询结果行转换为 Node
+    ///
+    /// 行的列顺序必须为 `uuid, path, title, content, node_type, hash, created_at, updated_at`。
+    fn row_to_node(row: &[DataValue]) -> Node {
+        Node {
+            uuid: row[0].get_str().unwrap_or("").to_string(),
+            path: row[1].get_str().unwrap_or("").to_string(),
+            title: row[2].get_str().unwrap_or("").to_string(),
+            content: row[3].get_str().unwrap_or("").to_string(),
+            node_type: row[4].get_str().unwrap_or("").to_string(),
+            hash: row[5].get_str().unwrap_or("").to_string(),
+            created_at: row[6].get_int().unwrap_or(0),
+            updated_at: row[7].get_int().unwrap_or(0),
+        }
+    }
+
+    /// 将查询结果行转换为 Edge
+    ///
+    /// 行的列顺序必须为 `src_uuid, dst_uuid, relation, weight, source`。
+    fn row_to_edge(row: &[DataValue]) -> Edge {
+        Edge {
+            src_uuid: row[0].get_str().unwrap_or("").to_string(),
+            dst_uuid: row[1].get_str().unwrap_or("").to_string(),
+            relation: row[2].get_str().unwrap_or("").to_string(),
+            weight: row[3].get_float().unwrap_or(1.0),
+            source: row[4].get_str().unwrap_or("").to_string(),
+        }
+    }
+
+    // ==================== DCOM 扩展方法 ====================
+
+    /// 保存对象属性
+    ///
+    /// 将单个属性保存到 properties 表。
+    ///
+    /// # 参数
+    ///
+    /// * `object_id` - 对象 UUID
+    /// * `name` - 属性名
+    /// * `value` - 属性值（PropertyValue）
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn save_property(
+        &mut self,
+        object_id: &str,
+        name: &str,
+        value: &crate::dcom::PropertyValue,
+    ) -> Result<()> {
+        let value_type = match value {
+            crate::dcom::PropertyValue::Null => "null",
+            crate::dcom::PropertyValue::String(_) => "string",
+            crate::dcom::PropertyValue::Integer(_) => "integer",
+            crate::dcom::PropertyValue::Float(_) => "float",
+            crate::dcom::PropertyValue::Boolean(_) => "boolean",
+            crate::dcom::PropertyValue::DateTime(_) => "datetime",
+            crate::dcom::PropertyValue::Duration(_) => "duration",
+            crate::dcom::PropertyValue::Reference(_) => "reference",
+            crate::dcom::PropertyValue::List(_) => "list",
+            crate::dcom::PropertyValue::Json(_) => "json",
+        };
+
+        let value_json = serde_json::to_string(value)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize property: {}", e))?;
+
+        let params = Self::make_params(serde_json::json!({
+            "object_id": object_id,
+            "name": name,
+            "value_type": value_type,
+            "value_json": value_json,
+        }));
+
+        self.db.run_script(
+            r#"
+            ?[object_id, name, value_type, value_json] <- [[$object_id, $name, $value_type, $value_json]]
+            :put properties {object_id, name => value_type, value_json}
+            "#,
+            params,
+            ScriptMutability::Mutable,
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(())
+    }
+
+    /// 删除对象的单个属性
+    ///
+    /// 使用 `:rm` 精确删除 properties 表中的一行，其余属性保持不变。
+    ///
+    /// # 参数
+    ///
+    /// * `object_id` - 对象 UUID
+    /// * `name` - 属性名
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功（属性不存在时同样成功）
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn delete_property(&mut self, object_id: &str, name: &str) -> Result<()> {
+        let params = Self::make_params(serde_json::json!({
+            "object_id": object_id,
+            "name": name,
+        }));
+
+        self.db
+            .run_script(
+                r#"
+            ?[object_id, name] <- [[$object_id, $name]]
+            :rm properties {object_id, name}
+            "#,
+                params,
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(())
+    }
+
+    /// 获取对象的所有属性
+    ///
+    /// # 参数
+    ///
+    /// * `object_id` - 对象 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(HashMap<String, PropertyValue>)` - 属性映射
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_properties(
+        &self,
+        object_id: &str,
+    ) -> Result<std::collections::HashMap<String, crate::dcom::PropertyValue>> {
+        let params = Self::make_params(serde_json::json!({ "object_id": object_id }));
+
+        let result = self.db.run_script(
+            "?[name, value_json] := *properties{object_id, name, value_json}, object_id == $object_id",
+            params,
+            ScriptMutability::Immutable,
+        ).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let mut properties = std::collections::HashMap::new();
+        for row in &result.rows {
+            let name = row[0].get_str().unwrap_or("").to_string();
+            let value_json = row[1].get_str().unwrap_or("null");
+
+            if let Ok(value) = serde_json::from_str::<crate::dcom::PropertyValue>(value_json) {
+                properties.insert(name, value);
+            }
+        }
+
+        Ok(properties)
+    }
+
+    /// 替换对象的所有属性
+    ///
+    /// 先删除对象在 properties 表中的全部记录，再写入新属性。
+    /// `title`、`content`、`type` 存储在节点列中，不写入 properties 表。
+    ///
+    /// # 参数
+    ///
+    /// * `object_id` - 对象 UUID
+    /// * `properties` - 属性映射
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn save_properties(
+        &mut self,
+        object_id: &str,
+        properties: &HashMap<String, PropertyValue>,
+    ) -> Result<()> {
+        self.delete_properties(object_id)?;
+
+        for (name, value) in properties {
+            if !NODE_COLUMN_PROPERTIES.contains(&name.as_str()) {
+                self.save_property(object_id, name, value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 删除对象的所有属性
+    ///
+    /// # 参数
+    ///
+    /// * `object_id` - 对象 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn delete_properties(&mut self, object_id: &str) -> Result<()> {
+        let params = Self::make_params(serde_json::json!({ "object_id": object_id }));
+        self.db
+            .run_script(
+                r#"
+            ?[object_id, name] := *properties{object_id, name}, object_id == $object_id
+            :rm properties {object_id, name}
+            "#,
+                params,
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(())
+    }
+
+    /// 按属性值比较查找节点
+    ///
+    /// 根据 properties 表中记录的 `value_type` 进行类型感知的比较：
+    /// 数值（`integer`、`float`、`duration`）按数值比较，日期（`datetime`、形如
+    /// `2024-01-01` 的字符串或 `+3d` 这样的相对日期）按时间先后比较，
+    /// 两边都是时长写法（如 `2h`、`90m`）的字符串按秒数比较，其余按字符串或布尔值比较。
+    /// 相对日期在查询时以当前时间为基准解析，存储的仍是原始写法。
+    /// 无法比较的值（如类型不兼容）只满足 `ne`。
+    ///
+    /// # 参数
+    ///
+    /// * `name` - 属性名
+    /// * `op` - 比较运算符：`eq`、`ne`、`lt`、`gt`、`le`、`ge`
+    /// * `value` - 比较的目标值
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Node>)` - 满足 `属性值 op value` 的节点，按路径排序
+    /// * `Err(anyhow::Error)` - 运算符无效或数据库查询失败
+    pub fn find_nodes_by_property_cmp(
+        &self,
+        name: &str,
+        op: &str,
+        value: &PropertyValue,
+    ) -> Result<Vec<Node>> {
+        use std::cmp::Ordering;
+
+        let accepts: fn(Option<Ordering>) -> bool = match op {
+            "eq" => |o| o == Some(Ordering::Equal),
+            "ne" => |o| o != Some(Ordering::Equal),
+            "lt" => |o| o == Some(Ordering::Less),
+            "gt" => |o| o == Some(Ordering::Greater),
+            "le" => |o| matches!(o, Some(Ordering::Less | Ordering::Equal)),
+            "ge" => |o| matches!(o, Some(Ordering::Greater | Ordering::Equal)),
+            _ => anyhow::bail!("Unsupported comparison operator: {}", op),
+        };
+
+        let params = Self::make_params(serde_json::json!({ "name": name }));
+        let result = self
+            .db
+            .run_script(
+                r#"
+                ?[uuid, path, title, content, node_type, hash, created_at, updated_at, value_type, value_json] :=
+                    *properties{object_id: uuid, name, value_type, value_json},
+                    name == $name,
+                    *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}
+                :order path
+                "#,
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .filter(|row| {
+                let value_type = row[8].get_str().unwrap_or("");
+                serde_json::from_str::<PropertyValue>(row[9].get_str().unwrap_or("null"))
+                    .is_ok_and(|stored| accepts(Self::compare_property(&stored, value_type, value)))
+            })
+            .map(|row| Self::row_to_node(row))
+            .collect())
+    }
+
+    /// 按存储类型比较属性值与目标值
+    ///
+    /// 类型不兼容时返回 `None`。
+    fn compare_property(
+        stored: &PropertyValue,
+        value_type: &str,
+        target: &PropertyValue,
+    ) -> Option<std::cmp::Ordering> {
+        let as_number = |value: &PropertyValue| match value {
+            PropertyValue::Integer(i) | PropertyValue::Duration(i) => Some(*i as f64),
+            PropertyValue::Float(f) => Some(*f),
+            PropertyValue::String(s) => s.trim().parse::<f64>().ok(),
+            _ => None,
+        };
+        let now = chrono::Utc::now();
+        let as_timestamp = |value: &PropertyValue| match value {
+            PropertyValue::String(s) | PropertyValue::DateTime(s) => {
+                Self::parse_date(s).or_else(|| {
+                    crate::dcom::property::parse_relative_date(s, now)
+                        .and_then(|resolved| Self::parse_date(&resolved))
+                })
+            }
+            _ => None,
+        };
+
+        match value_type {
+            "integer" | "float" | "duration" => as_number(stored)?.partial_cmp(&as_number(target)?),
+            "datetime" => Some(as_timestamp(stored)?.cmp(&as_timestamp(target)?)),
+            _ => match (stored, target) {
+                (PropertyValue::String(_), _) if as_timestamp(stored).is_some() => {
+                    Some(as_timestamp(stored)?.cmp(&as_timestamp(target)?))
+                }
+                (PropertyValue::String(_), _)
+                    if stored.as_duration().is_some() && target.as_duration().is_some() =>
+                {
+                    Some(stored.as_duration()?.cmp(&target.as_duration()?))
+                }
+                (PropertyValue::String(a), PropertyValue::String(b)) => Some(a.cmp(b)),
+                (PropertyValue::Boolean(a), PropertyValue::Boolean(b)) => Some(a.cmp(b)),
+                _ => (stored == target).then_some(std::cmp::Ordering::Equal),
+            },
+        }
+    }
+
+    /// 解析日期字符串为 Unix 时间戳（秒）
+    ///
+    /// 支持 RFC 3339（`2024-01-01T08:00:00Z`）、`2024-01-01T08:00:00`、
+    /// `2024-01-01 08:00` 和 `2024-01-01`，无时区的时间按 UTC 处理。
+    fn parse_date(s: &str) -> Option<i64> {
+        let s = s.trim();
+        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+            return Some(dt.timestamp());
+        }
+        for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+            if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, format) {
+                return Some(dt.and_utc().timestamp());
+            }
+        }
+        chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc().timestamp())
+    }
+
+    /// 按属性值查找节点
+    ///
+    /// 返回 properties 表中 `name` 属性等于 `value` 的所有节点，
+    /// 包括 frontmatter 属性和正文中的内联字段。值按类型精确匹配（`3` 与 `"3"` 不同）。
+    ///
+    /// # 参数
+    ///
+    /// * `name` - 属性名
+    /// * `value` - 属性值
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Node>)` - 匹配的节点列表
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn find_nodes_by_property(&self, name: &str, value: &PropertyValue) -> Result<Vec<Node>> {
+        let value_json = serde_json::to_string(value)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize property: {}", e))?;
+        let params = Self::make_params(serde_json::json!({
+            "name": name,
+            "value_json": value_json,
+        }));
+
+        let result = self
+            .db
+            .run_script(
+                r#"
+                ?[uuid, path, title, content, node_type, hash, created_at, updated_at] :=
+                    *properties{object_id: uuid, name, value_json},
+                    name == $name, value_json == $value_json,
+                    *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}
+                "#,
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| Self::row_to_node(row))
+            .collect())
+    }
+
+    /// 保存对象标签
+    ///
+    /// 替换对象的所有标签。开启 [`Self::set_expand_tag_parents`] 时，
+    /// 嵌套标签的各级父标签（未被显式声明的）同时写入 tags 表，并记录在 tag_parents 表中。
+    ///
+    /// # 参数
+    ///
+    /// * `object_id` - 对象 UUID
+    /// * `tags` - 标签列表
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn save_tags(&mut self, object_id: &str, tags: &[String]) -> Result<()> {
+        // 先删除该对象的旧标签
+        let delete_params = Self::make_params(serde_json::json!({ "object_id": object_id }));
+        self.db
+            .run_script(
+                r#"
+            {
+                ?[object_id, tag] := *tags{object_id, tag}, object_id == $object_id
+                :rm tags {object_id, tag}
+            }
+            {
+                ?[object_id, tag] := *tag_parents{object_id, tag}, object_id == $object_id
+                :rm tag_parents {object_id, tag}
+            }
+            "#,
+                delete_params,
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let parents = if self.expand_tag_parents {
+            Self::implicit_tag_parents(tags)
+        } else {
+            Vec::new()
+        };
+
+        // 添加新标签
+        for tag in tags.iter().chain(&parents) {
+            let params = Self::make_params(serde_json::json!({
+                "object_id": object_id,
+                "tag": tag,
+            }));
+
+            self.db
+                .run_script(
+                    r#"
+                ?[object_id, tag] <- [[$object_id, $tag]]
+                :put tags {object_id, tag}
+                "#,
+                    params,
+                    ScriptMutability::Mutable,
+                )
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+        }
+        for tag in &parents {
+            let params = Self::make_params(serde_json::json!({
+                "object_id": object_id,
+                "tag": tag,
+            }));
+
+            self.db
+                .run_script(
+                    r#"
+                ?[object_id, tag] <- [[$object_id, $tag]]
+                :put tag_parents {object_id, tag}
+                "#,
+                    params,
+                    ScriptMutability::Mutable,
+                )
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// 计算嵌套标签中未被显式声明的各级父标签
+    ///
+    /// `project/rust/async` 产生 `project` 和 `project/rust`，结果去重并保持首次出现的顺序。
+    fn implicit_tag_parents(tags: &[String]) -> Vec<String> {
+        let mut parents: Vec<String> = Vec::new();
+        for tag in tags {
+            for (i, _) in tag.match_indices('/') {
+                let parent = &tag[..i];
+                if !parent.is_empty()
+                    && !tags.iter().any(|t| t == parent)
+                    && !parents.iter().any(|p| p == parent)
+                {
+                    parents.push(parent.to_string());
+                }
+            }
+        }
+        parents
+    }
+
+    /// 移除对象的单个标签
+    ///
+    /// 使用 `:rm` 精确删除 tags 表中的一行，其余标签保持不变。
+    /// 开启父级标签展开时改为重新保存其余标签，使不再需要的父级行一并移除。
+    ///
+    /// # 参数
+    ///
+    /// * `object_id` - 对象 UUID
+    /// * `tag` - 标签名
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 操作成功（标签不存在时同样成功）
+    /// * `Err(anyhow::Error)` - 数据库操作失败
+    pub fn remove_tag(&mut self, object_id: &str, tag: &str) -> Result<()> {
+        if self.expand_tag_parents {
+            let mut tags = self.get_tags(object_id)?;
+            tags.retain(|t| t != tag);
+            return self.save_tags(object_id, &tags);
+        }
+
+        let params = Self::make_params(serde_json::json!({
+            "object_id": object_id,
+            "tag": tag,
+        }));
+
+        self.db
+            .run_script(
+                r#"
+            ?[object_id, tag] <- [[$object_id, $tag]]
+            :rm tags {object_id, tag}
+            "#,
+                params,
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(())
+    }
+
+    /// 获取对象的标签
+    ///
+    /// 只返回显式声明的标签，嵌套标签展开出的父级行不包含在内。
+    ///
+    /// # 参数
+    ///
+    /// * `object_id` - 对象 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<String>)` - 标签列表
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_tags(&self, object_id: &str) -> Result<Vec<String>> {
+        let params = Self::make_params(serde_json::json!({ "object_id": object_id }));
+
+        let result = self
+            .db
+            .run_script(
+                "?[tag] := *tags{object_id, tag}, object_id == $object_id, not *tag_parents{object_id, tag}",
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let tags: Vec<String> = result
+            .rows
+            .iter()
+            .filter_map(|row| row[0].get_str().map(|s| s.to_string()))
+            .collect();
+
+        Ok(tags)
+    }
 
-        Ok(())
+    /// 获取标签的使用情况
+    ///
+    /// 返回携带该标签的笔记，以及通过 wikilink 链接到该标签哨兵 `tag:{tag}` 的笔记（并集）。
+    ///
+    /// # 参数
+    ///
+    /// * `tag` - 标签名（可带前导 `#`）
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Node>)` - 去重后的节点列表
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_tag_usages(&self, tag: &str) -> Result<Vec<Node>> {
+        let tag = tag.trim_start_matches('#');
+        let params = Self::make_params(serde_json::json!({
+            "tag": tag,
+            "sentinel": format!("tag:{}", tag),
+        }));
+
+        let result = self
+            .db
+            .run_script(
+                r#"
+                ?[uuid, path, title, content, node_type, hash, created_at, updated_at] :=
+                    *tags{object_id: uuid, tag}, tag == $tag,
+                    *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}
+                ?[uuid, path, title, content, node_type, hash, created_at, updated_at] :=
+                    *edges{src_uuid: uuid, dst_uuid, relation}, dst_uuid == $sentinel, relation == "link",
+                    *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}
+                "#,
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| Self::row_to_node(row))
+            .collect())
     }
 
-    // ==================== DCOM 扩展方法 ====================
+    /// 获取仅大小写不同的标签冲突
+    ///
+    /// 扫描 tags 表中的所有不同标签，按小写形式分组，
+    /// 返回包含多种写法的分组。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<TagCaseConflict>)` - 冲突列表，按 `normalized` 排序
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_tag_case_conflicts(&self) -> Result<Vec<TagCaseConflict>> {
+        let result = self
+            .db
+            .run_script(
+                "?[tag] := *tags{tag}",
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
 
-    /// 保存对象属性
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for row in &result.rows {
+            if let Some(tag) = row[0].get_str() {
+                groups
+                    .entry(tag.to_lowercase())
+                    .or_default()
+                    .push(tag.to_string());
+            }
+        }
+
+        let conflicts = groups
+            .into_iter()
+            .filter(|(_, variants)| variants.len() > 1)
+            .map(|(normalized, mut variants)| {
+                variants.sort();
+                TagCaseConflict {
+                    normalized,
+                    variants,
+                }
+            })
+            .collect();
+
+        Ok(conflicts)
+    }
+
+    /// 获取标签共现统计
     ///
-    /// 将单个属性保存到 properties 表。
+    /// 对 tags 表自连接，统计同一节点上同时出现的标签对；嵌套标签展开出的父级行不参与统计。
+    /// 每对标签只统计一次，`(a, b)` 中 `a` 按字典序小于 `b`。
+    ///
+    /// # 参数
+    ///
+    /// * `min_count` - 最少共现次数，低于该值的标签对被过滤
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<(String, String, usize)>)` - `(标签 a, 标签 b, 共现次数)`，按次数降序、标签名升序排列
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_tag_cooccurrence(&self, min_count: usize) -> Result<Vec<(String, String, usize)>> {
+        let params = Self::make_params(serde_json::json!({ "min_count": min_count }));
+
+        let result = self
+            .db
+            .run_script(
+                r#"
+                explicit[object_id, tag] := *tags{object_id, tag}, not *tag_parents{object_id, tag}
+                pairs[a, b, count(object_id)] := explicit[object_id, a], explicit[object_id, b], a < b
+                ?[a, b, n] := pairs[a, b, n], n >= $min_count
+                :order -n, a, b
+                "#,
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row[0].get_str().unwrap_or("").to_string(),
+                    row[1].get_str().unwrap_or("").to_string(),
+                    row[2].get_int().unwrap_or(0) as usize,
+                )
+            })
+            .collect())
+    }
+
+    /// 保存对象别名
+    ///
+    /// 替换对象的所有别名。
     ///
     /// # 参数
     ///
     /// * `object_id` - 对象 UUID
-    /// * `name` - 属性名
-    /// * `value` - 属性值（PropertyValue）
+    /// * `aliases` - 别名列表
     ///
     /// # 返回值
     ///
     /// * `Ok(())` - 操作成功
     /// * `Err(anyhow::Error)` - 数据库操作失败
-    pub fn save_property(
-        &mut self,
-        object_id: &str,
-        name: &str,
-        value: &crate::dcom::PropertyValue,
-    ) -> Result<()> {
-        let value_type = match value {
-            crate::dcom::PropertyValue::Null => "null",
-            crate::dcom::PropertyValue::String(_) => "string",
-            crate::dcom::PropertyValue::Integer(_) => "integer",
-            crate::dcom::PropertyValue::Float(_) => "float",
-            crate::dcom::PropertyValue::Boolean(_) => "boolean",
-            crate::dcom::PropertyValue::DateTime(_) => "datetime",
-            crate::dcom::PropertyValue::Reference(_) => "reference",
-            crate::dcom::PropertyValue::List(_) => "list",
-            crate::dcom::PropertyValue::Json(_) => "json",
-        };
-
-        let value_json = serde_json::to_string(value)
-            .map_err(|e| anyhow::anyhow!("Failed to serialize property: {}", e))?;
+    pub fn save_aliases(&mut self, object_id: &str, aliases: &[String]) -> Result<()> {
+        // 先删除该对象的旧别名
+        let delete_params = Self::make_params(serde_json::json!({ "object_id": object_id }));
+        self.db
+            .run_script(
+                r#"
+            ?[object_id, alias] := *aliases{object_id, alias}, object_id == $object_id
+            :rm aliases {object_id, alias}
+            "#,
+                delete_params,
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        let params = Self::make_params(serde_json::json!({
-            "object_id": object_id,
-            "name": name,
-            "value_type": value_type,
-            "value_json": value_json,
-        }));
+        // 添加新别名
+        for alias in aliases {
+            let params = Self::make_params(serde_json::json!({
+                "object_id": object_id,
+                "alias": alias,
+            }));
 
-        self.db.run_script(
-            r#"
-            ?[object_id, name, value_type, value_json] <- [[$object_id, $name, $value_type, $value_json]]
-            :put properties {object_id, name => value_type, value_json}
-            "#,
-            params,
-            ScriptMutability::Mutable,
-        ).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+            self.db
+                .run_script(
+                    r#"
+                ?[object_id, alias] <- [[$object_id, $alias]]
+                :put aliases {object_id, alias}
+                "#,
+                    params,
+                    ScriptMutability::Mutable,
+                )
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+        }
 
         Ok(())
     }
 
-    /// 获取对象的所有属性
+    /// 获取对象的别名
     ///
     /// # 参数
     ///
@@ -673,187 +4548,372 @@ impl Database {
     ///
     /// # 返回值
     ///
-    /// * `Ok(HashMap<String, PropertyValue>)` - 属性映射
+    /// * `Ok(Vec<String>)` - 别名列表
     /// * `Err(anyhow::Error)` - 数据库查询失败
-    pub fn get_properties(
-        &self,
-        object_id: &str,
-    ) -> Result<std::collections::HashMap<String, crate::dcom::PropertyValue>> {
+    pub fn get_aliases(&self, object_id: &str) -> Result<Vec<String>> {
         let params = Self::make_params(serde_json::json!({ "object_id": object_id }));
 
-        let result = self.db.run_script(
-            "?[name, value_json] := *properties{object_id, name, value_json}, object_id == $object_id",
-            params,
-            ScriptMutability::Immutable,
-        ).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+        let result = self
+            .db
+            .run_script(
+                "?[alias] := *aliases{object_id, alias}, object_id == $object_id",
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        let mut properties = std::collections::HashMap::new();
-        for row in &result.rows {
-            let name = row[0].get_str().unwrap_or("").to_string();
-            let value_json = row[1].get_str().unwrap_or("null");
+        let aliases: Vec<String> = result
+            .rows
+            .iter()
+            .filter_map(|row| row[0].get_str().map(|s| s.to_string()))
+            .collect();
 
-            if let Ok(value) = serde_json::from_str::<crate::dcom::PropertyValue>(value_json) {
-                properties.insert(name, value);
-            }
+        Ok(aliases)
+    }
+
+    /// 获取所有别名及其节点路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<(String, String)>)` - `(别名, 节点路径)` 列表，别名对应的节点不存在时不返回
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_alias_paths(&self) -> Result<Vec<(String, String)>> {
+        let result = self
+            .db
+            .run_script(
+                "?[alias, path] := *aliases{object_id: uuid, alias}, *nodes{uuid, path}",
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row[0].get_str().unwrap_or("").to_string(),
+                    row[1].get_str().unwrap_or("").to_string(),
+                )
+            })
+            .collect())
+    }
+
+    /// 获取所有别名及其所属节点
+    ///
+    /// 供别名冲突检测和链接补全使用。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<(String, String)>)` - `(别名, 节点 UUID)` 列表，按别名和 UUID 排序；
+    ///   所属节点不存在的别名不返回
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_all_aliases(&self) -> Result<Vec<(String, String)>> {
+        let result = self
+            .db
+            .run_script(
+                r#"
+                ?[alias, uuid] := *aliases{object_id: uuid, alias}, *nodes{uuid}
+                :order alias, uuid
+                "#,
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        Ok(result
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row[0].get_str().unwrap_or("").to_string(),
+                    row[1].get_str().unwrap_or("").to_string(),
+                )
+            })
+            .collect())
+    }
+
+    /// 获取被多篇笔记声明的别名
+    ///
+    /// 别名按原样比较，与链接解析时的别名匹配规则一致。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<AliasConflict>)` - 冲突列表，按别名排序
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_alias_conflicts(&self) -> Result<Vec<AliasConflict>> {
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (alias, uuid) in self.get_all_aliases()? {
+            groups.entry(alias).or_default().push(uuid);
         }
 
-        Ok(properties)
+        Ok(groups
+            .into_iter()
+            .filter(|(_, owners)| owners.len() > 1)
+            .map(|(alias, owners)| AliasConflict { alias, owners })
+            .collect())
     }
 
-    /// 保存对象标签
+    /// 保存对象的序列化源
     ///
-    /// 替换对象的所有标签。
+    /// 替换对象的所有 Markdown 和二进制源。虚拟源由规则重新计算，不做持久化。
     ///
     /// # 参数
     ///
     /// * `object_id` - 对象 UUID
-    /// * `tags` - 标签列表
+    /// * `sources` - 序列化源列表
     ///
     /// # 返回值
     ///
     /// * `Ok(())` - 操作成功
     /// * `Err(anyhow::Error)` - 数据库操作失败
-    pub fn save_tags(&mut self, object_id: &str, tags: &[String]) -> Result<()> {
-        // 先删除旧标签
+    pub fn save_sources(&mut self, object_id: &str, sources: &[SerializationSource]) -> Result<()> {
         let delete_params = Self::make_params(serde_json::json!({ "object_id": object_id }));
-        let _ = self.db.run_script(
-            r#"
-            ?[object_id, tag] := *tags{object_id, tag}, object_id != $object_id
-            :replace tags {object_id, tag}
+        self.db
+            .run_script(
+                r#"
+            ?[object_id, source_type] := *sources{object_id, source_type}, object_id == $object_id
+            :rm sources {object_id, source_type}
             "#,
-            delete_params,
-            ScriptMutability::Mutable,
-        );
-
-        // 添加新标签
-        for tag in tags {
-            let params = Self::make_params(serde_json::json!({
-                "object_id": object_id,
-                "tag": tag,
-            }));
+                delete_params,
+                ScriptMutability::Mutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        for source in sources {
+            let row = match source {
+                SerializationSource::Markdown(m) => serde_json::json!({
+                    "source_type": "markdown",
+                    "path": m.path,
+                    "content_hash": m.content_hash,
+                    "mime_type": null,
+                    "size_bytes": null,
+                    "last_modified": m.last_modified,
+                }),
+                SerializationSource::Binary(b) => serde_json::json!({
+                    "source_type": "binary",
+                    "path": b.path,
+                    "content_hash": b.content_hash,
+                    "mime_type": b.mime_type,
+                    "size_bytes": b.size_bytes,
+                    "last_modified": b.last_modified,
+                }),
+                SerializationSource::Virtual(_) => continue,
+            };
+
+            let mut params = Self::make_params(row);
+            params.insert("object_id".to_string(), DataValue::Str(object_id.into()));
 
             self.db
                 .run_script(
                     r#"
-                ?[object_id, tag] <- [[$object_id, $tag]]
-                :put tags {object_id, tag}
+                ?[object_id, source_type, path, content_hash, mime_type, size_bytes, last_modified] <- [[$object_id, $source_type, $path, $content_hash, $mime_type, $size_bytes, $last_modified]]
+                :put sources {object_id, source_type => path, content_hash, mime_type, size_bytes, last_modified}
                 "#,
                     params,
                     ScriptMutability::Mutable,
                 )
-                .map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
         }
 
-        Ok(())
+        Ok(())
+    }
+
+    /// 获取对象的序列化源
+    ///
+    /// # 参数
+    ///
+    /// * `object_id` - 对象 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<SerializationSource>)` - 序列化源列表
+    /// * `Err(anyhow::Error)` - 数据库查询失败
+    pub fn get_sources(&self, object_id: &str) -> Result<Vec<SerializationSource>> {
+        let params = Self::make_params(serde_json::json!({ "object_id": object_id }));
+
+        let result = self
+            .db
+            .run_script(
+                "?[source_type, path, content_hash, mime_type, size_bytes, last_modified] := *sources{object_id, source_type, path, content_hash, mime_type, size_bytes, last_modified}, object_id == $object_id",
+                params,
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let sources = result
+            .rows
+            .iter()
+            .filter_map(|row| {
+                let path = row[1].get_str().unwrap_or("");
+                let content_hash = row[2].get_str().unwrap_or("");
+                let last_modified = row[5].get_int().unwrap_or(0);
+                match row[0].get_str()? {
+                    "markdown" => Some(SerializationSource::Markdown(MarkdownSource::new(
+                        path,
+                        content_hash,
+                        last_modified,
+                    ))),
+                    "binary" => Some(SerializationSource::Binary(BinarySource::new(
+                        path,
+                        content_hash,
+                        row[3].get_str().unwrap_or(""),
+                        row[4].get_int().unwrap_or(0) as u64,
+                        last_modified,
+                    ))),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        Ok(sources)
     }
 
-    /// 获取对象的标签
+    /// 按序列化源类型获取节点
+    ///
+    /// 用于按物理表示筛选对象，例如列出所有以二进制文件存储的对象。
     ///
     /// # 参数
     ///
-    /// * `object_id` - 对象 UUID
+    /// * `source_type` - 序列化源类型，`markdown` 或 `binary`（不区分大小写）
     ///
     /// # 返回值
     ///
-    /// * `Ok(Vec<String>)` - 标签列表
+    /// * `Ok(Vec<Node>)` - 具有该类型序列化源的节点列表
     /// * `Err(anyhow::Error)` - 数据库查询失败
-    pub fn get_tags(&self, object_id: &str) -> Result<Vec<String>> {
-        let params = Self::make_params(serde_json::json!({ "object_id": object_id }));
+    pub fn get_nodes_by_source_type(&self, source_type: &str) -> Result<Vec<Node>> {
+        let params =
+            Self::make_params(serde_json::json!({ "source_type": source_type.to_lowercase() }));
 
         let result = self
             .db
             .run_script(
-                "?[tag] := *tags{object_id, tag}, object_id == $object_id",
+                "?[uuid, path, title, content, node_type, hash, created_at, updated_at] := *sources{object_id: uuid, source_type}, source_type == $source_type, *nodes{uuid, path, title, content, node_type, hash, created_at, updated_at}",
                 params,
                 ScriptMutability::Immutable,
             )
-            .map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        let tags: Vec<String> = result
+        Ok(result
             .rows
             .iter()
-            .filter_map(|row| row[0].get_str().map(|s| s.to_string()))
-            .collect();
-
-        Ok(tags)
+            .map(|row| Self::row_to_node(row))
+            .collect())
     }
 
-    /// 保存对象别名
+    /// 保存完整的认知对象
     ///
-    /// 替换对象的所有别名。
+    /// 写入节点（见 [`Node::from`]）、属性、标签、别名和序列化源，
+    /// 替换该对象已有的属性、标签、别名和序列化源。
     ///
     /// # 参数
     ///
-    /// * `object_id` - 对象 UUID
-    /// * `aliases` - 别名列表
+    /// * `obj` - 认知对象，使用其 ID 作为 UUID
     ///
     /// # 返回值
     ///
     /// * `Ok(())` - 操作成功
     /// * `Err(anyhow::Error)` - 数据库操作失败
-    pub fn save_aliases(&mut self, object_id: &str, aliases: &[String]) -> Result<()> {
-        // 先删除旧别名
-        let delete_params = Self::make_params(serde_json::json!({ "object_id": object_id }));
-        let _ = self.db.run_script(
-            r#"
-            ?[object_id, alias] := *aliases{object_id, alias}, object_id != $object_id
-            :replace aliases {object_id, alias}
-            "#,
-            delete_params,
-            ScriptMutability::Mutable,
-        );
+    pub fn save_object(&mut self, obj: &CognitiveObject) -> Result<()> {
+        let uuid = obj.id.as_str();
+        self.upsert_node(&Node::from(obj))?;
+        self.save_properties(uuid, obj.properties())?;
 
-        // 添加新别名
-        for alias in aliases {
-            let params = Self::make_params(serde_json::json!({
-                "object_id": object_id,
-                "alias": alias,
-            }));
+        self.save_tags(uuid, obj.tags())?;
+        self.save_aliases(uuid, obj.aliases())?;
+        self.save_sources(uuid, obj.sources())?;
 
-            self.db
-                .run_script(
-                    r#"
-                ?[object_id, alias] <- [[$object_id, $alias]]
-                :put aliases {object_id, alias}
-                "#,
-                    params,
-                    ScriptMutability::Mutable,
-                )
-                .map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+        Ok(())
+    }
+
+    /// 从数据库重建完整的认知对象
+    ///
+    /// 合并 nodes、properties、tags、aliases 和 sources 表中的数据。
+    /// 标题、内容和类型由节点列还原为对应属性。
+    ///
+    /// # 参数
+    ///
+    /// * `uuid` - 对象 UUID
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(CognitiveObject)` - 重建的对象
+    /// * `Err(anyhow::Error)` - 对象不存在或数据库查询失败
+    pub fn load_object(&self, uuid: &str) -> Result<CognitiveObject> {
+        let node = self
+            .get_node(uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Object not found: {}", uuid))?;
+
+        let mut obj = CognitiveObject::with_id(uuid);
+        obj.created_at = node.created_at * 1000;
+        obj.updated_at = node.updated_at * 1000;
+
+        for (name, value) in self.get_properties(uuid)? {
+            obj.set_property(name, value);
         }
+        obj.set_property("title", PropertyValue::string(node.title));
+        obj.set_property("content", PropertyValue::string(node.content));
+        obj.set_property("type", PropertyValue::string(node.node_type));
 
-        Ok(())
+        for tag in self.get_tags(uuid)? {
+            obj.add_tag(tag);
+        }
+        for alias in self.get_aliases(uuid)? {
+            obj.add_alias(alias);
+        }
+        for source in self.get_sources(uuid)? {
+            obj.add_source(source);
+        }
+
+        Ok(obj)
     }
 
-    /// 获取对象的别名
+    /// 获取活动直方图
+    ///
+    /// 按天、周（周一开始）或月（UTC）统计创建或更新的节点数量，供时间线视图使用。
+    /// 时间戳未知（不大于 0）的节点（如文件夹节点）不参与统计。
     ///
     /// # 参数
     ///
-    /// * `object_id` - 对象 UUID
+    /// * `field` - 时间字段，`created_at` 或 `updated_at`
+    /// * `bucket` - 分桶粒度，`day`、`week` 或 `month`
     ///
     /// # 返回值
     ///
-    /// * `Ok(Vec<String>)` - 别名列表
-    /// * `Err(anyhow::Error)` - 数据库查询失败
-    pub fn get_aliases(&self, object_id: &str) -> Result<Vec<String>> {
-        let params = Self::make_params(serde_json::json!({ "object_id": object_id }));
+    /// * `Ok(Vec<(i64, usize)>)` - `(桶起始时间戳（秒）, 节点数)`，按时间升序排列
+    /// * `Err(anyhow::Error)` - 字段或粒度无效，或数据库查询失败
+    pub fn get_activity_histogram(&self, field: &str, bucket: &str) -> Result<Vec<(i64, usize)>> {
+        if field != "created_at" && field != "updated_at" {
+            return Err(anyhow::anyhow!("Invalid time field: {}", field));
+        }
+
+        // Unix 纪元为周四，偏移 3 天后按 7 天取模即对齐到周一
+        let bucket_expr = match bucket {
+            "day" => "ts - mod(ts, 86400)",
+            "week" => "ts - mod(ts + 259200, 604800)",
+            "month" => "to_int(parse_timestamp(concat(slice_string(format_timestamp(ts, 'UTC'), 0, 7), '-01T00:00:00Z')))",
+            _ => return Err(anyhow::anyhow!("Invalid time bucket: {}", bucket)),
+        };
 
+        let script = format!(
+            "?[bucket, count(uuid)] := *nodes{{uuid, {}: ts}}, ts > 0, bucket = {}\n:order bucket",
+            field, bucket_expr
+        );
         let result = self
             .db
-            .run_script(
-                "?[alias] := *aliases{object_id, alias}, object_id == $object_id",
-                params,
-                ScriptMutability::Immutable,
-            )
-            .map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+            .run_script(&script, Default::default(), ScriptMutability::Immutable)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        let aliases: Vec<String> = result
+        Ok(result
             .rows
             .iter()
-            .filter_map(|row| row[0].get_str().map(|s| s.to_string()))
-            .collect();
-
-        Ok(aliases)
+            .map(|row| {
+                (
+                    row[0].get_int().unwrap_or(0),
+                    row[1].get_int().unwrap_or(0) as usize,
+                )
+            })
+            .collect())
     }
 
     /// 获取 Vault 统计信息
@@ -873,7 +4933,7 @@ impl Database {
                 Default::default(),
                 ScriptMutability::Immutable,
             )
-            .map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
 
         let total_nodes = node_count_result
             .rows
@@ -889,7 +4949,7 @@ impl Database {
                 Default::default(),
                 ScriptMutability::Immutable,
             )
-            .map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
 
         let total_edges = edge_count_result
             .rows
@@ -905,7 +4965,7 @@ impl Database {
                 Default::default(),
                 ScriptMutability::Immutable,
             )
-            .map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
 
         let total_tags = tag_count_result
             .rows
@@ -935,33 +4995,723 @@ mod tests {
     }
 
     #[test]
-    fn test_database_creation() {
-        let (db, _temp_dir) = setup_test_db();
-        let nodes = db.get_all_nodes().unwrap();
-        assert_eq!(nodes.len(), 0);
+    fn test_database_creation() {
+        let (db, _temp_dir) = setup_test_db();
+        let nodes = db.get_all_nodes().unwrap();
+        assert_eq!(nodes.len(), 0);
+    }
+
+    #[test]
+    fn test_upsert_node() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        let node = Node {
+            uuid: "test-uuid-1".to_string(),
+            path: "test.md".to_string(),
+            title: "Test Node".to_string(),
+            content: "Test content".to_string(),
+            node_type: "note".to_string(),
+            hash: "test-hash".to_string(),
+            created_at: 1234567890,
+            updated_at: 1234567890,
+        };
+
+        db.upsert_node(&node).unwrap();
+
+        let nodes = db.get_all_nodes().unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].uuid, "test-uuid-1");
+        assert_eq!(nodes[0].title, "Test Node");
+    }
+
+    #[test]
+    fn test_get_content_range() {
+        let (mut db, temp_dir) = setup_test_db();
+        let vault_root = temp_dir.path();
+
+        std::fs::write(vault_root.join("long.md"), "l1\nl2\nl3\nl4\nl5\nl6\n").unwrap();
+        db.upsert_node(&Node {
+            uuid: "long".to_string(),
+            path: "long.md".to_string(),
+            title: "Long".to_string(),
+            content: String::new(),
+            node_type: "note".to_string(),
+            hash: String::new(),
+            created_at: 0,
+            updated_at: 0,
+        })
+        .unwrap();
+
+        let range = |start, end| {
+            db.get_content_range(vault_root, "long", start, end)
+                .unwrap()
+        };
+
+        assert_eq!(range(2, 4), "l2\nl3\nl4");
+        // 超出范围时截断
+        assert_eq!(range(0, 2), "l1\nl2");
+        assert_eq!(range(5, 100), "l5\nl6");
+        assert_eq!(range(7, 9), "");
+        assert_eq!(range(4, 3), "");
+
+        assert!(db.get_content_range(vault_root, "missing", 1, 2).is_err());
+    }
+
+    #[test]
+    fn test_graph_data_to_force_layout() {
+        let node = |uuid: &str, title: &str, node_type: &str| Node {
+            uuid: uuid.to_string(),
+            path: format!("{}.md", uuid),
+            title: title.to_string(),
+            content: String::new(),
+            node_type: node_type.to_string(),
+            hash: String::new(),
+            created_at: 0,
+            updated_at: 0,
+        };
+        let edge = |src: &str, dst: &str, weight: f64| Edge {
+            src_uuid: src.to_string(),
+            dst_uuid: dst.to_string(),
+            relation: "link".to_string(),
+            weight,
+            source: String::new(),
+        };
+        let graph = GraphData {
+            nodes: vec![node("a", "Alpha", "note"), node("b", "Beta", "task")],
+            edges: vec![edge("a", "b", 0.8), edge("a", "tag:rust", 0.3)],
+            properties: HashMap::new(),
+            tags: HashMap::new(),
+            truncated: false,
+            total_nodes: 2,
+        };
+
+        let force = graph.to_force_layout();
+
+        assert_eq!(
+            force.nodes,
+            vec![
+                ForceNode {
+                    id: "a".to_string(),
+                    label: "Alpha".to_string(),
+                    group: "note".to_string(),
+                },
+                ForceNode {
+                    id: "b".to_string(),
+                    label: "Beta".to_string(),
+                    group: "task".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            force.links,
+            vec![ForceLink {
+                source: "a".to_string(),
+                target: "b".to_string(),
+                value: 0.8,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_graph_data_to_cytoscape() {
+        let node = |uuid: &str, title: &str, node_type: &str| Node {
+            uuid: uuid.to_string(),
+            path: format!("{}.md", uuid),
+            title: title.to_string(),
+            content: String::new(),
+            node_type: node_type.to_string(),
+            hash: String::new(),
+            created_at: 0,
+            updated_at: 0,
+        };
+        let graph = GraphData {
+            nodes: vec![node("a", "Alpha", "note"), node("b", "Beta", "task")],
+            edges: vec![Edge {
+                src_uuid: "a".to_string(),
+                dst_uuid: "b".to_string(),
+                relation: "link".to_string(),
+                weight: 0.5,
+                source: "body-wikilink".to_string(),
+            }],
+            properties: HashMap::new(),
+            tags: HashMap::new(),
+            truncated: false,
+            total_nodes: 2,
+        };
+
+        assert_eq!(
+            graph.to_cytoscape(),
+            serde_json::json!({
+                "elements": {
+                    "nodes": [
+                        {
+                            "data": { "id": "a", "label": "Alpha", "path": "a.md" },
+                            "classes": "note",
+                        },
+                        {
+                            "data": { "id": "b", "label": "Beta", "path": "b.md" },
+                            "classes": "task",
+                        },
+                    ],
+                    "edges": [
+                        {
+                            "data": {
+                                "id": "a->b",
+                                "source": "a",
+                                "target": "b",
+                                "relation": "link",
+                                "weight": 0.5,
+                            },
+                        },
+                    ],
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_graph_data_into_metadata() {
+        let graph = GraphData {
+            nodes: vec![Node {
+                uuid: "a".to_string(),
+                path: "a.md".to_string(),
+                title: "Alpha".to_string(),
+                content: "A very long body".to_string(),
+                node_type: "note".to_string(),
+                hash: "h".to_string(),
+                created_at: 1,
+                updated_at: 2,
+            }],
+            edges: vec![Edge {
+                src_uuid: "a".to_string(),
+                dst_uuid: "tag:rust".to_string(),
+                relation: "tagged".to_string(),
+                weight: 0.5,
+                source: "tag".to_string(),
+            }],
+            properties: HashMap::new(),
+            tags: HashMap::new(),
+            truncated: true,
+            total_nodes: 3,
+        };
+
+        let metadata = graph.into_metadata();
+        assert_eq!(
+            metadata.nodes,
+            vec![NodeMeta {
+                uuid: "a".to_string(),
+                path: "a.md".to_string(),
+                title: "Alpha".to_string(),
+                node_type: "note".to_string(),
+                hash: "h".to_string(),
+                created_at: 1,
+                updated_at: 2,
+            }]
+        );
+        assert_eq!(metadata.edges.len(), 1);
+        assert_eq!(metadata.edges[0].dst_uuid, "tag:rust");
+        assert!(metadata.truncated);
+        assert_eq!(metadata.total_nodes, 3);
+
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert!(json["nodes"][0].get("content").is_none());
+        assert_eq!(json["nodes"][0]["title"], "Alpha");
+    }
+
+    #[test]
+    fn test_export_subgraph() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["a", "b", "c", "d"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        // d -> a -> b -> c
+        for (src, dst, relation) in [
+            ("d", "a", "link"),
+            ("a", "b", "link"),
+            ("b", "c", "link"),
+            ("a", "tag:rust", "tagged"),
+        ] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: relation.to_string(),
+                weight: 1.0,
+                source: String::new(),
+            })
+            .unwrap();
+        }
+        db.save_tags("a", &["rust".to_string()]).unwrap();
+        db.save_property("b", "status", &PropertyValue::string("draft"))
+            .unwrap();
+        db.save_property("c", "status", &PropertyValue::string("done"))
+            .unwrap();
+
+        let graph = db.export_subgraph("a", 1).unwrap();
+
+        let mut uuids: Vec<&str> = graph.nodes.iter().map(|n| n.uuid.as_str()).collect();
+        uuids.sort();
+        assert_eq!(uuids, vec!["a", "b", "d"]);
+
+        let mut edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.src_uuid.as_str(), e.dst_uuid.as_str()))
+            .collect();
+        edges.sort();
+        assert_eq!(edges, vec![("a", "b"), ("d", "a")]);
+
+        assert_eq!(graph.tags.get("a"), Some(&vec!["rust".to_string()]));
+        assert_eq!(
+            graph.properties["b"].get("status"),
+            Some(&PropertyValue::string("draft"))
+        );
+        assert!(!graph.properties.contains_key("c"));
+
+        assert_eq!(db.export_subgraph("a", 0).unwrap().nodes.len(), 1);
+        assert!(db.export_subgraph("nope", 1).is_err());
+    }
+
+    #[test]
+    fn test_find_exact_duplicates() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for (uuid, path, content) in [
+            ("a", "notes/a.md", "Same body"),
+            ("b", "archive/a copy.md", "Same body"),
+            ("c", "c.md", "Different body"),
+        ] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: path.to_string(),
+                title: uuid.to_string(),
+                content: content.to_string(),
+                node_type: "note".to_string(),
+                hash: crate::sync::calculate_hash(content),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        assert_eq!(
+            db.find_exact_duplicates().unwrap(),
+            vec![vec![
+                "archive/a copy.md".to_string(),
+                "notes/a.md".to_string()
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_get_all_link_targets() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["a", "b", "c"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("notes/{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        for (src, dst, relation) in [
+            ("a", "c", "link"),
+            ("b", "c", "link"),
+            ("a", "missing:Someday", "link"),
+            ("b", "missing:Someday", "link"),
+            ("c", "missing:Someday", "link"),
+            ("a", "url:https://example.com", "link"),
+            ("a", "tag:rust", "tagged"),
+        ] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: relation.to_string(),
+                weight: 1.0,
+                source: String::new(),
+            })
+            .unwrap();
+        }
+
+        assert_eq!(
+            db.get_all_link_targets().unwrap(),
+            vec![("Someday".to_string(), 3), ("c".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_build_backlink_index() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for (src, dst, relation) in [
+            ("a", "c", "link"),
+            ("b", "c", "link"),
+            ("d", "c", "link"),
+            ("c", "a", "link"),
+            ("a", "missing:Someday", "link"),
+            ("a", "tag:rust", "tagged"),
+        ] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: relation.to_string(),
+                weight: 1.0,
+                source: String::new(),
+            })
+            .unwrap();
+        }
+
+        let index = db.build_backlink_index().unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(index["c"], vec!["a", "b", "d"]);
+        assert_eq!(index["a"], vec!["c"]);
+        assert_eq!(index["missing:Someday"], vec!["a"]);
+        assert!(!index.contains_key("b"));
+        assert!(!index.contains_key("tag:rust"));
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["a", "b", "c", "d"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        let add_edge = |db: &mut Database, src: &str, dst: &str, relation: &str| {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: relation.to_string(),
+                weight: 1.0,
+                source: String::new(),
+            })
+            .unwrap();
+        };
+
+        // 无环：a -> b -> c，d -> c，以及标签边
+        add_edge(&mut db, "a", "b", "link");
+        add_edge(&mut db, "b", "c", "link");
+        add_edge(&mut db, "d", "c", "link");
+        add_edge(&mut db, "c", "tag:rust", "tagged");
+        assert!(db.find_cycles(5).unwrap().is_empty());
+
+        // c -> a 形成 a -> b -> c -> a
+        add_edge(&mut db, "c", "a", "link");
+        assert_eq!(
+            db.find_cycles(5).unwrap(),
+            vec![vec![
+                "a.md".to_string(),
+                "b.md".to_string(),
+                "c.md".to_string()
+            ]]
+        );
+
+        // 长度上限
+        assert!(db.find_cycles(2).unwrap().is_empty());
+
+        // 超长的 max_len 被截断：长度为 MAX_CYCLE_LEN + 1 的环不会被找到
+        let ring: Vec<String> = (0..=MAX_CYCLE_LEN).map(|i| format!("r{:02}", i)).collect();
+        for (i, uuid) in ring.iter().enumerate() {
+            db.upsert_node(&Node {
+                uuid: uuid.clone(),
+                path: format!("{}.md", uuid),
+                title: uuid.clone(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+            add_edge(&mut db, uuid, &ring[(i + 1) % ring.len()], "link");
+        }
+        assert_eq!(db.find_cycles(usize::MAX).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_find_cycles_bounded_on_dense_graph() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        // 完全有向无环图：路径数随节点数指数增长，但不存在环
+        let uuids: Vec<String> = (0..40).map(|i| format!("n{:02}", i)).collect();
+        for uuid in &uuids {
+            db.upsert_node(&Node {
+                uuid: uuid.clone(),
+                path: format!("{}.md", uuid),
+                title: uuid.clone(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+        for (i, src) in uuids.iter().enumerate() {
+            for dst in &uuids[i + 1..] {
+                db.upsert_edge(&Edge {
+                    src_uuid: src.clone(),
+                    dst_uuid: dst.clone(),
+                    relation: "link".to_string(),
+                    weight: 1.0,
+                    source: String::new(),
+                })
+                .unwrap();
+            }
+        }
+
+        assert!(db.find_cycles(usize::MAX).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_all_degrees() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["a", "b", "c", "d"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        for (src, dst, relation) in [
+            ("a", "b", "link"),
+            ("a", "c", "link"),
+            ("b", "c", "link"),
+            ("c", "a", "link"),
+            ("a", "missing:Someday", "link"),
+            ("a", "tag:rust", "tagged"),
+            ("b", "tag:rust", "tagged"),
+        ] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: relation.to_string(),
+                weight: 1.0,
+                source: String::new(),
+            })
+            .unwrap();
+        }
+
+        let degrees = db.get_all_degrees(false).unwrap();
+        assert_eq!(degrees.len(), 4);
+        assert_eq!(degrees["a"], (1, 3));
+        assert_eq!(degrees["b"], (1, 1));
+        assert_eq!(degrees["c"], (2, 1));
+        assert_eq!(degrees["d"], (0, 0));
+
+        let degrees = db.get_all_degrees(true).unwrap();
+        assert_eq!(degrees["a"], (1, 4));
+        assert_eq!(degrees["b"], (1, 2));
+        assert_eq!(degrees["c"], (2, 1));
+    }
+
+    #[test]
+    fn test_get_link_statistics() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["a", "b", "c"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        for (src, dst, relation) in [
+            ("a", "c", "link"),
+            ("b", "c", "link"),
+            ("c", "a", "link"),
+            ("a", "missing:Nowhere", "link"),
+            ("a", "url:https://example.com", "link"),
+            ("a", "tag:rust", "tagged"),
+        ] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: relation.to_string(),
+                weight: 1.0,
+                source: String::new(),
+            })
+            .unwrap();
+        }
+
+        let stats = db.get_link_statistics().unwrap();
+        assert_eq!(stats.total_links, 4);
+        assert_eq!(stats.resolved, 3);
+        assert_eq!(stats.broken, 1);
+        assert_eq!(
+            stats.top_linked,
+            vec![("c.md".to_string(), 2), ("a.md".to_string(), 1)]
+        );
+
+        // 断链哨兵不视为悬空边
+        assert!(db.check_integrity().unwrap().is_healthy());
+    }
+
+    #[test]
+    fn test_get_activity_histogram() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        // 2024-01-15（周一）两篇、2024-01-16 一篇、2024-02-01 一篇，文件夹节点无时间戳
+        let day = 86400;
+        let jan15 = 1705276800;
+        for (uuid, created_at, updated_at) in [
+            ("a", jan15 + 100, jan15),
+            ("b", jan15 + 3600, jan15),
+            ("c", jan15 + day + 10, jan15),
+            ("d", jan15 + 17 * day, jan15),
+            ("folder", 0, 0),
+        ] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at,
+                updated_at,
+            })
+            .unwrap();
+        }
+
+        assert_eq!(
+            db.get_activity_histogram("created_at", "day").unwrap(),
+            vec![(jan15, 2), (jan15 + day, 1), (jan15 + 17 * day, 1)]
+        );
+        assert_eq!(
+            db.get_activity_histogram("created_at", "week").unwrap(),
+            vec![(jan15, 3), (jan15 + 14 * day, 1)]
+        );
+        // 2024-01-01 与 2024-02-01
+        assert_eq!(
+            db.get_activity_histogram("created_at", "month").unwrap(),
+            vec![(1704067200, 3), (1706745600, 1)]
+        );
+        assert_eq!(
+            db.get_activity_histogram("updated_at", "day").unwrap(),
+            vec![(jan15, 4)]
+        );
+
+        assert!(db.get_activity_histogram("deleted_at", "day").is_err());
+        assert!(db.get_activity_histogram("created_at", "year").is_err());
     }
 
     #[test]
-    fn test_upsert_node() {
+    fn test_find_occurrences() {
         let (mut db, _temp_dir) = setup_test_db();
 
-        let node = Node {
-            uuid: "test-uuid-1".to_string(),
-            path: "test.md".to_string(),
-            title: "Test Node".to_string(),
-            content: "Test content".to_string(),
+        for (uuid, content) in [("b", "foo bar\nnothing\nfoo2"), ("a", "a.b\naxb")] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: content.to_string(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        let found = db.find_occurrences("foo", false).unwrap();
+        assert_eq!(
+            found,
+            vec![
+                ("b.md".to_string(), 1, "foo bar".to_string()),
+                ("b.md".to_string(), 3, "foo2".to_string()),
+            ]
+        );
+
+        // 字面量模式不解释正则元字符
+        assert_eq!(db.find_occurrences("a.b", false).unwrap().len(), 1);
+        assert_eq!(db.find_occurrences("a.b", true).unwrap().len(), 2);
+
+        assert!(db.find_occurrences("(unclosed", true).is_err());
+    }
+
+    #[test]
+    fn test_get_block_content() {
+        let (mut db, temp_dir) = setup_test_db();
+        let vault_root = temp_dir.path();
+
+        std::fs::write(
+            vault_root.join("note.md"),
+            "---\ntags: [a]\n---\n# Note\n\nFirst line of para\nsecond line ^para1\n\n- item one\n- item two ^item2\n\n> quoted\n\n^quote\n",
+        )
+        .unwrap();
+        db.upsert_node(&Node {
+            uuid: "note".to_string(),
+            path: "note.md".to_string(),
+            title: "Note".to_string(),
+            content: String::new(),
             node_type: "note".to_string(),
-            hash: "test-hash".to_string(),
-            created_at: 1234567890,
-            updated_at: 1234567890,
-        };
+            hash: String::new(),
+            created_at: 0,
+            updated_at: 0,
+        })
+        .unwrap();
 
-        db.upsert_node(&node).unwrap();
+        let block = |id: &str| db.get_block_content(vault_root, "note.md", id).unwrap();
 
-        let nodes = db.get_all_nodes().unwrap();
-        assert_eq!(nodes.len(), 1);
-        assert_eq!(nodes[0].uuid, "test-uuid-1");
-        assert_eq!(nodes[0].title, "Test Node");
+        assert_eq!(
+            block("para1").as_deref(),
+            Some("First line of para\nsecond line")
+        );
+        assert_eq!(block("^item2").as_deref(), Some("- item two"));
+        assert_eq!(block("quote").as_deref(), Some("> quoted"));
+        assert_eq!(block("missing"), None);
+
+        // 未索引的笔记
+        assert_eq!(
+            db.get_block_content(vault_root, "other.md", "para1")
+                .unwrap(),
+            None
+        );
     }
 
     #[test]
@@ -1021,6 +5771,66 @@ mod tests {
         assert_eq!(edges[0].dst_uuid, "uuid-2");
     }
 
+    #[test]
+    fn test_get_all_nodes_and_edges_ordered() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["c", "a", "d", "b"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+        for (src, dst) in [("d", "a"), ("a", "c"), ("a", "b"), ("b", "a")] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: "link".to_string(),
+                weight: 1.0,
+                source: "body-wikilink".to_string(),
+            })
+            .unwrap();
+        }
+
+        let first: Vec<String> = db
+            .get_all_nodes()
+            .unwrap()
+            .into_iter()
+            .map(|n| n.uuid)
+            .collect();
+        let second: Vec<String> = db
+            .get_all_nodes()
+            .unwrap()
+            .into_iter()
+            .map(|n| n.uuid)
+            .collect();
+        assert_eq!(first, second);
+        assert_eq!(first, vec!["a", "b", "c", "d"]);
+
+        let edges: Vec<(String, String)> = db
+            .get_all_edges()
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.src_uuid, e.dst_uuid))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("a".to_string(), "b".to_string()),
+                ("a".to_string(), "c".to_string()),
+                ("b".to_string(), "a".to_string()),
+                ("d".to_string(), "a".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_get_graph_data() {
         let (mut db, _temp_dir) = setup_test_db();
@@ -1065,6 +5875,54 @@ mod tests {
         assert_eq!(graph_data.edges.len(), 1);
     }
 
+    #[test]
+    fn test_get_graph_data_limited() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        // hub 连接所有节点，n1 额外连接 n2
+        for uuid in ["hub", "n1", "n2", "n3", "n4", "n5"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+        let link = |src: &str, dst: &str| Edge {
+            src_uuid: src.to_string(),
+            dst_uuid: dst.to_string(),
+            relation: "link".to_string(),
+            weight: 1.0,
+            source: "body-wikilink".to_string(),
+        };
+        for dst in ["n1", "n2", "n3", "n4", "n5"] {
+            db.upsert_edge(&link("hub", dst)).unwrap();
+        }
+        db.upsert_edge(&link("n1", "n2")).unwrap();
+
+        let graph = db.get_graph_data_limited(3).unwrap();
+        assert!(graph.truncated);
+        assert_eq!(graph.total_nodes, 6);
+        let mut kept: Vec<&str> = graph.nodes.iter().map(|n| n.uuid.as_str()).collect();
+        kept.sort();
+        assert_eq!(kept, vec!["hub", "n1", "n2"]);
+        assert_eq!(graph.edges.len(), 3);
+        assert!(graph
+            .edges
+            .iter()
+            .all(|e| kept.contains(&e.src_uuid.as_str()) && kept.contains(&e.dst_uuid.as_str())));
+
+        let full = db.get_graph_data_limited(6).unwrap();
+        assert!(!full.truncated);
+        assert_eq!(full.nodes.len(), 6);
+        assert_eq!(db.get_graph_data_limited(0).unwrap().nodes.len(), 6);
+    }
+
     #[test]
     fn test_search_nodes() {
         let (mut db, _temp_dir) = setup_test_db();
@@ -1080,29 +5938,586 @@ mod tests {
             updated_at: 1234567890,
         };
 
-        let node2 = Node {
-            uuid: "uuid-2".to_string(),
-            path: "python.md".to_string(),
-            title: "Python Programming".to_string(),
-            content: "Learn Python language".to_string(),
-            node_type: "note".to_string(),
-            hash: "hash2".to_string(),
-            created_at: 1234567890,
-            updated_at: 1234567890,
-        };
+        let node2 = Node {
+            uuid: "uuid-2".to_string(),
+            path: "python.md".to_string(),
+            title: "Python Programming".to_string(),
+            content: "Learn Python language".to_string(),
+            node_type: "note".to_string(),
+            hash: "hash2".to_string(),
+            created_at: 1234567890,
+            updated_at: 1234567890,
+        };
+
+        db.upsert_node(&node1).unwrap();
+        db.upsert_node(&node2).unwrap();
+
+        let results = db.search_nodes("Rust").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Rust Programming");
+
+        let results = db.search_nodes("Programming").unwrap();
+        assert_eq!(results.len(), 2);
+
+        let results = db.search_nodes("JavaScript").unwrap();
+        assert_eq!(results.len(), 0);
+    }
+
+    #[test]
+    fn test_search_match_in_body() {
+        let node = Node {
+            uuid: "uuid-1".to_string(),
+            path: "日记.md".to_string(),
+            title: "日记".to_string(),
+            content: "第一行\n今天学习了 Rust 和 Cozo\nRust again".to_string(),
+            node_type: "note".to_string(),
+            hash: String::new(),
+            created_at: 0,
+            updated_at: 0,
+        };
+
+        let hit = SearchHit::new(node.clone(), "rust");
+        assert_eq!(
+            hit.matched,
+            Some(SearchMatch {
+                line: 2,
+                offset: 6,
+                snippet: "今天学习了 Rust 和 Cozo".to_string(),
+            })
+        );
+
+        // 标题优先于正文
+        let title_match = SearchMatch::find(&node, "日记").unwrap();
+        assert_eq!((title_match.line, title_match.offset), (0, 0));
+
+        assert_eq!(SearchMatch::find(&node, "python"), None);
+        assert_eq!(SearchMatch::find(&node, ""), None);
+
+        // 节点字段展开到顶层
+        let json = serde_json::to_value(&hit).unwrap();
+        assert_eq!(json["path"], "日记.md");
+        assert_eq!(json["matched"]["offset"], 6);
+    }
+
+    #[test]
+    fn test_rename_node() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        let make_node = |uuid: &str, path: &str| Node {
+            uuid: uuid.to_string(),
+            path: path.to_string(),
+            title: path.to_string(),
+            content: String::new(),
+            node_type: "note".to_string(),
+            hash: "hash".to_string(),
+            created_at: 1234567890,
+            updated_at: 1234567890,
+        };
+        let link = |src: &str, dst: &str| Edge {
+            src_uuid: src.to_string(),
+            dst_uuid: dst.to_string(),
+            relation: "link".to_string(),
+            weight: 1.0,
+            source: "WikiLink".to_string(),
+        };
+
+        db.upsert_node(&make_node("uuid-a", "a.md")).unwrap();
+        db.upsert_node(&make_node("uuid-b", "b.md")).unwrap();
+        db.upsert_edge(&link("uuid-a", "uuid-b")).unwrap();
+        db.upsert_edge(&link("uuid-b", "uuid-a")).unwrap();
+        db.save_tags("uuid-b", &["rust".to_string()]).unwrap();
+
+        // 文件名不变（仅移动目录）时入链跟随新节点
+        let moved = db
+            .rename_node("uuid-b", &make_node("uuid-c", "moved/b.md"))
+            .unwrap();
+        assert_eq!(moved, 2);
+
+        let nodes = db.get_all_nodes().unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes.iter().all(|n| n.uuid != "uuid-b"));
+
+        let edges = db.get_all_edges().unwrap();
+        assert_eq!(edges.len(), 2);
+        assert!(edges
+            .iter()
+            .any(|e| e.src_uuid == "uuid-a" && e.dst_uuid == "uuid-c"));
+        assert!(edges
+            .iter()
+            .any(|e| e.src_uuid == "uuid-c" && e.dst_uuid == "uuid-a"));
+
+        assert!(db.get_tags("uuid-b").unwrap().is_empty());
+        assert_eq!(db.get_tags("uuid-c").unwrap(), vec!["rust".to_string()]);
+
+        // 文件名改变后 [[b]] 不再能解析，入链变为断链，出链保留
+        db.rename_node("uuid-c", &make_node("uuid-d", "moved/d.md"))
+            .unwrap();
+        let edges = db.get_all_edges().unwrap();
+        assert_eq!(edges.len(), 2);
+        assert!(edges
+            .iter()
+            .any(|e| e.src_uuid == "uuid-a" && e.dst_uuid == "missing:b"));
+        assert!(edges
+            .iter()
+            .any(|e| e.src_uuid == "uuid-d" && e.dst_uuid == "uuid-a"));
+        assert!(edges.iter().all(|e| e.dst_uuid != "uuid-d"));
+    }
+
+    #[test]
+    fn test_advanced_search() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        let make_node = |uuid: &str, title: &str, node_type: &str, updated_at: i64| Node {
+            uuid: uuid.to_string(),
+            path: format!("{}.md", uuid),
+            title: title.to_string(),
+            content: format!("{} body", title),
+            node_type: node_type.to_string(),
+            hash: "hash".to_string(),
+            created_at: 1000,
+            updated_at,
+        };
+
+        db.upsert_node(&make_node("t1", "Deploy service", "task", 2000))
+            .unwrap();
+        db.upsert_node(&make_node("t2", "Deploy docs", "task", 500))
+            .unwrap();
+        db.upsert_node(&make_node("n1", "Deploy notes", "note", 2000))
+            .unwrap();
+        db.upsert_node(&make_node("t3", "Review", "task", 2000))
+            .unwrap();
+        db.save_tags("t1", &["ops".to_string(), "urgent".to_string()])
+            .unwrap();
+        db.save_tags("t2", &["ops".to_string()]).unwrap();
+
+        // 文本 + 类型
+        let query = SearchQuery {
+            text: Some("DEPLOY".to_string()),
+            node_type: Some("task".to_string()),
+            ..Default::default()
+        };
+        let mut uuids: Vec<String> = db
+            .advanced_search(&query)
+            .unwrap()
+            .into_iter()
+            .map(|n| n.uuid)
+            .collect();
+        uuids.sort();
+        assert_eq!(uuids, vec!["t1", "t2"]);
+
+        // 文本 + 类型 + 标签
+        let query = SearchQuery {
+            text: Some("deploy".to_string()),
+            node_type: Some("task".to_string()),
+            tags: vec!["ops".to_string(), "urgent".to_string()],
+            ..Default::default()
+        };
+        let results = db.advanced_search(&query).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].uuid, "t1");
+
+        // 更新时间过滤
+        let query = SearchQuery {
+            text: Some("deploy".to_string()),
+            updated_after: Some(1000),
+            ..Default::default()
+        };
+        let mut uuids: Vec<String> = db
+            .advanced_search(&query)
+            .unwrap()
+            .into_iter()
+            .map(|n| n.uuid)
+            .collect();
+        uuids.sort();
+        assert_eq!(uuids, vec!["n1", "t1"]);
+
+        // 无条件时返回全部节点
+        assert_eq!(
+            db.advanced_search(&SearchQuery::default()).unwrap().len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_get_random_node() {
+        let (mut db, _temp_dir) = setup_test_db();
+        assert!(db.get_random_node(None).unwrap().is_none());
+
+        for (uuid, node_type) in [
+            ("n1", "note"),
+            ("n2", "note"),
+            ("n3", "note"),
+            ("t1", "task"),
+        ] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: node_type.to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..100 {
+            seen.insert(db.get_random_node(None).unwrap().unwrap().uuid);
+        }
+        assert!(seen.len() > 1);
+
+        for _ in 0..20 {
+            let node = db.get_random_node(Some("task")).unwrap().unwrap();
+            assert_eq!(node.uuid, "t1");
+        }
+        assert!(db.get_random_node(Some("canvas")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_get_nodes_modified_since() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for (uuid, updated_at) in [("old", 100), ("edge", 200), ("new", 300)] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at,
+            })
+            .unwrap();
+        }
+
+        let uuids = |ts: i64| -> Vec<String> {
+            db.get_nodes_modified_since(ts)
+                .unwrap()
+                .into_iter()
+                .map(|n| n.uuid)
+                .collect()
+        };
+        assert_eq!(uuids(200), vec!["edge", "new"]);
+        assert_eq!(uuids(0), vec!["old", "edge", "new"]);
+        assert!(uuids(301).is_empty());
+    }
+
+    #[test]
+    fn test_validate_query() {
+        let (db, _temp_dir) = setup_test_db();
+
+        let valid = db.validate_query("?[uuid, title] := *nodes{uuid, title}");
+        assert!(valid.valid);
+        assert!(valid.read_only);
+        assert!(valid.errors.is_empty());
+
+        let broken = db.validate_query("?[uuid] :=\n    *nodes{uuid} == = 1");
+        assert!(!broken.valid);
+        assert_eq!(broken.errors.len(), 1);
+        assert_eq!(broken.errors[0].line, Some(2));
+
+        let write =
+            db.validate_query("?[object_id, tag] <- [['n1', 'rust']]\n:put tags {object_id, tag}");
+        assert!(write.valid);
+        assert!(!write.read_only);
+        assert!(!db.validate_query("::remove tags").read_only);
+
+        // 校验不会写入数据
+        assert!(db.get_tags("n1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_check_and_repair_integrity() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        use crate::dcom::PropertyValue;
+
+        let node = Node {
+            uuid: "uuid-1".to_string(),
+            path: "test.md".to_string(),
+            title: "Test".to_string(),
+            content: "Content".to_string(),
+            node_type: "note".to_string(),
+            hash: "hash1".to_string(),
+            created_at: 1234567890,
+            updated_at: 1234567890,
+        };
+        db.upsert_node(&node).unwrap();
+
+        let edge = |dst: &str, relation: &str| Edge {
+            src_uuid: "uuid-1".to_string(),
+            dst_uuid: dst.to_string(),
+            relation: relation.to_string(),
+            weight: 1.0,
+            source: "test".to_string(),
+        };
+        db.upsert_edge(&edge("ghost", "link")).unwrap();
+        db.upsert_edge(&edge("tag:rust", "tagged")).unwrap();
+        db.save_property("uuid-1", "author", &PropertyValue::string("Alice"))
+            .unwrap();
+        db.save_property("ghost", "author", &PropertyValue::string("Bob"))
+            .unwrap();
+
+        let report = db.check_integrity().unwrap();
+        assert!(!report.is_healthy());
+        assert_eq!(report.dangling_edges, 1);
+        assert_eq!(report.dangling_edge_samples[0].dst_uuid, "ghost");
+        assert_eq!(report.orphan_properties, 1);
+        assert_eq!(
+            report.orphan_property_samples[0],
+            ("ghost".to_string(), "author".to_string())
+        );
+        assert_eq!(report.duplicate_paths, 0);
+
+        let repaired = db.repair_integrity().unwrap();
+        assert_eq!(repaired.dangling_edges, 1);
+
+        assert!(db.check_integrity().unwrap().is_healthy());
+        let edges = db.get_all_edges().unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].dst_uuid, "tag:rust");
+        assert_eq!(db.get_properties("uuid-1").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_node_from_cognitive_object() {
+        let mut obj = CognitiveObject::with_id("obj-1");
+        obj.set_content("Body");
+        obj.add_source(SerializationSource::Markdown(MarkdownSource::new(
+            "notes/idea.md",
+            "h",
+            0,
+        )));
+
+        let node = Node::from(&obj);
+        assert_eq!(node.uuid, "obj-1");
+        assert_eq!(node.path, "notes/idea.md");
+        assert_eq!(node.title, "idea");
+        assert_eq!(node.content, "Body");
+        assert_eq!(node.node_type, "note");
+        assert_eq!(node.hash, crate::sync::calculate_hash("Body"));
+        assert_eq!(node.created_at, obj.created_at / 1000);
+    }
+
+    #[test]
+    fn test_save_and_load_object_round_trip() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        let mut obj = CognitiveObject::with_id("obj-1");
+        obj.set_title("Idea");
+        obj.set_content("Some content");
+        obj.set_type("concept");
+        obj.set_property("author", PropertyValue::string("Alice"));
+        obj.set_property("priority", PropertyValue::integer(3));
+        obj.add_tag("rust");
+        obj.add_alias("Big Idea");
+        obj.add_alias("BI");
+        obj.add_source(SerializationSource::Markdown(MarkdownSource::new(
+            "idea.md", "hash-1", 1700000000,
+        )));
+
+        db.save_object(&obj).unwrap();
+        let loaded = db.load_object("obj-1").unwrap();
+
+        assert_eq!(loaded.id.as_str(), "obj-1");
+        assert_eq!(loaded.title(), Some("Idea"));
+        assert_eq!(loaded.content(), Some("Some content"));
+        assert_eq!(loaded.get_type(), Some("concept"));
+        assert_eq!(loaded.properties(), obj.properties());
+        assert_eq!(loaded.tags(), obj.tags());
+
+        let mut aliases = loaded.aliases().to_vec();
+        aliases.sort();
+        assert_eq!(aliases, vec!["BI", "Big Idea"]);
+
+        assert_eq!(loaded.sources(), obj.sources());
+        assert_eq!(loaded.created_at, obj.created_at / 1000 * 1000);
+
+        // 属性只存储一份，标题等不重复写入 properties 表
+        assert!(!db.get_properties("obj-1").unwrap().contains_key("title"));
+
+        // 再次保存时删除的属性不残留
+        obj.remove_property("author");
+        db.save_object(&obj).unwrap();
+        assert!(!db
+            .load_object("obj-1")
+            .unwrap()
+            .properties()
+            .contains_key("author"));
+
+        assert!(db.load_object("missing").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        let node = |path: &str, hash: &str| Node {
+            uuid: format!("uuid-{}", path),
+            path: path.to_string(),
+            title: path.to_string(),
+            content: String::new(),
+            node_type: "note".to_string(),
+            hash: hash.to_string(),
+            created_at: 0,
+            updated_at: 0,
+        };
+
+        db.upsert_node(&node("kept.md", "h1")).unwrap();
+        db.upsert_node(&node("changed.md", "h2")).unwrap();
+        db.upsert_node(&node("removed.md", "h3")).unwrap();
+        let first = db.create_snapshot().unwrap();
+
+        // 快照在 clear_all 后保留
+        db.clear_all().unwrap();
+        db.upsert_node(&node("kept.md", "h1")).unwrap();
+        db.upsert_node(&node("changed.md", "h2-new")).unwrap();
+        db.upsert_node(&node("new.md", "h4")).unwrap();
+        let second = db.create_snapshot().unwrap();
+
+        assert_eq!(db.list_snapshots().unwrap(), vec![first, second]);
+
+        let plan = db.diff_snapshots(first, second).unwrap();
+        assert_eq!(plan.added, vec!["new.md"]);
+        assert_eq!(plan.updated, vec!["changed.md"]);
+        assert_eq!(plan.deleted, vec!["removed.md"]);
+
+        assert!(db.diff_snapshots(second, second).unwrap().is_empty());
+
+        // 只保留最近的快照，旧快照的节点哈希一并删除
+        for _ in 0..MAX_SNAPSHOTS {
+            db.create_snapshot().unwrap();
+        }
+        let snapshots = db.list_snapshots().unwrap();
+        assert_eq!(snapshots.len(), MAX_SNAPSHOTS);
+        assert_eq!(snapshots.last(), Some(&(second + MAX_SNAPSHOTS as i64)));
+        assert!(db.snapshot_hashes(first).unwrap().is_empty());
+        assert!(db.snapshot_hashes(second).unwrap().is_empty());
+
+        assert_eq!(db.prune_snapshots(2).unwrap(), MAX_SNAPSHOTS - 2);
+        assert_eq!(db.list_snapshots().unwrap(), snapshots[MAX_SNAPSHOTS - 2..]);
+        assert_eq!(db.prune_snapshots(2).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_node_positions() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["a", "b", "c"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        db.save_node_position("a", 10.0, -20.5).unwrap();
+        db.save_node_position("b", 0.0, 0.0).unwrap();
+        db.save_node_position("b", 3.5, 4.0).unwrap();
+
+        let positions = db.get_node_positions().unwrap();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions["a"], (10.0, -20.5));
+        assert_eq!(positions["b"], (3.5, 4.0));
+        assert!(!positions.contains_key("c"));
+    }
 
-        db.upsert_node(&node1).unwrap();
-        db.upsert_node(&node2).unwrap();
+    #[test]
+    fn test_delete_object_removes_metadata() {
+        let (mut db, _temp_dir) = setup_test_db();
 
-        let results = db.search_nodes("Rust").unwrap();
-        assert_eq!(results.len(), 1);
-        assert_eq!(results[0].title, "Rust Programming");
+        let mut obj = CognitiveObject::with_id("obj-1");
+        obj.set_title("Idea");
+        obj.set_property("author", PropertyValue::string("Alice"));
+        obj.add_tag("rust");
+        obj.add_tag("wasm");
+        obj.add_alias("Big Idea");
+        obj.add_source(SerializationSource::Markdown(MarkdownSource::new(
+            "idea.md", "hash-1", 1700000000,
+        )));
+        db.save_object(&obj).unwrap();
+
+        let mut other = CognitiveObject::with_id("obj-2");
+        other.set_title("Other");
+        other.add_tag("rust");
+        db.save_object(&other).unwrap();
+        assert_eq!(db.get_statistics().unwrap().total_tags, 3);
+
+        db.delete_object("obj-1").unwrap();
+
+        assert!(db.get_node("obj-1").unwrap().is_none());
+        assert!(db.get_properties("obj-1").unwrap().is_empty());
+        assert!(db.get_tags("obj-1").unwrap().is_empty());
+        assert!(db.get_aliases("obj-1").unwrap().is_empty());
+        assert!(db.get_sources("obj-1").unwrap().is_empty());
 
-        let results = db.search_nodes("Programming").unwrap();
-        assert_eq!(results.len(), 2);
+        let stats = db.get_statistics().unwrap();
+        assert_eq!(stats.total_nodes, 1);
+        assert_eq!(stats.total_tags, 1);
+        assert_eq!(db.get_tags("obj-2").unwrap(), vec!["rust"]);
+    }
 
-        let results = db.search_nodes("JavaScript").unwrap();
-        assert_eq!(results.len(), 0);
+    #[test]
+    fn test_delete_nodes_under_path() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for (uuid, path) in [
+            ("in", "a/x.md"),
+            ("deep", "a/b/y.md"),
+            ("near", "ab/z.md"),
+            ("root", "r.md"),
+        ] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: path.to_string(),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+        for (src, dst) in [("in", "root"), ("root", "in")] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: "link".to_string(),
+                weight: 1.0,
+                source: "body-wikilink".to_string(),
+            })
+            .unwrap();
+        }
+        db.save_tags("in", &["rust".to_string()]).unwrap();
+
+        let mut removed = db.delete_nodes_under_path("a/").unwrap();
+        removed.sort();
+        assert_eq!(removed, vec!["deep", "in"]);
+
+        let remaining: Vec<String> = db
+            .get_all_nodes()
+            .unwrap()
+            .into_iter()
+            .map(|n| n.uuid)
+            .collect();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.contains(&"near".to_string()));
+        assert!(db.get_tags("in").unwrap().is_empty());
+
+        // 仅保留入边
+        let edges = db.get_all_edges().unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].src_uuid, "root");
     }
 
     #[test]
@@ -1179,6 +6594,676 @@ mod tests {
         assert!(retrieved_tags.contains(&"programming".to_string()));
     }
 
+    #[test]
+    fn test_delete_property() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        use crate::dcom::PropertyValue;
+
+        db.save_property("obj-1", "author", &PropertyValue::string("Alice"))
+            .unwrap();
+        db.save_property("obj-1", "status", &PropertyValue::string("draft"))
+            .unwrap();
+
+        db.delete_property("obj-1", "status").unwrap();
+
+        let props = db.get_properties("obj-1").unwrap();
+        assert_eq!(props.len(), 1);
+        assert!(!props.contains_key("status"));
+        assert_eq!(props.get("author").unwrap().as_string(), Some("Alice"));
+    }
+
+    #[test]
+    fn test_find_nodes_by_property_cmp() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for (uuid, priority, due) in [
+            ("low", PropertyValue::integer(1), "2024-01-05"),
+            ("mid", PropertyValue::integer(3), "2024-02-01"),
+            ("high", PropertyValue::integer(5), "2024-03-01"),
+            ("urgent", PropertyValue::float(10.5), "2024-03-01T12:00:00Z"),
+        ] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+            db.save_property(uuid, "priority", &priority).unwrap();
+            db.save_property(uuid, "due", &PropertyValue::string(due))
+                .unwrap();
+        }
+        db.save_property("low", "status", &PropertyValue::string("done"))
+            .unwrap();
+        for (uuid, estimate) in [("low", "90m"), ("mid", "2h"), ("high", "10m")] {
+            db.save_property(uuid, "estimate", &PropertyValue::string(estimate))
+                .unwrap();
+        }
+        db.save_property("urgent", "remind", &PropertyValue::string("+3d"))
+            .unwrap();
+
+        let uuids = |op: &str, name: &str, value: PropertyValue| -> Vec<String> {
+            db.find_nodes_by_property_cmp(name, op, &value)
+                .unwrap()
+                .into_iter()
+                .map(|n| n.uuid)
+                .collect()
+        };
+
+        // 数值比较（10.5 > 3，而按字符串比较时 "10.5" < "3"）
+        assert_eq!(
+            uuids("gt", "priority", PropertyValue::integer(3)),
+            vec!["high", "urgent"]
+        );
+        assert_eq!(
+            uuids("le", "priority", PropertyValue::integer(3)),
+            vec!["low", "mid"]
+        );
+        assert_eq!(
+            uuids("eq", "priority", PropertyValue::string("3")),
+            vec!["mid"]
+        );
+
+        // 日期比较
+        assert_eq!(
+            uuids("lt", "due", PropertyValue::string("2024-02-01")),
+            vec!["low"]
+        );
+
+        // 时长和相对日期按原样存储，查询时解释
+        assert_eq!(
+            uuids("gt", "estimate", PropertyValue::string("1h")),
+            vec!["low", "mid"]
+        );
+        assert_eq!(
+            db.get_properties("urgent").unwrap()["remind"],
+            PropertyValue::string("+3d")
+        );
+        assert_eq!(
+            uuids("gt", "remind", PropertyValue::string("+1d")),
+            vec!["urgent"]
+        );
+        assert!(uuids("gt", "remind", PropertyValue::string("+1w")).is_empty());
+        assert_eq!(
+            uuids("ge", "due", PropertyValue::string("2024-03-01")),
+            vec!["high", "urgent"]
+        );
+
+        assert_eq!(
+            uuids("ne", "status", PropertyValue::string("open")),
+            vec!["low"]
+        );
+        assert!(db
+            .find_nodes_by_property_cmp("priority", "like", &PropertyValue::integer(3))
+            .is_err());
+    }
+
+    #[test]
+    fn test_find_nodes_by_property() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["uuid-1", "uuid-2"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        let mut props = HashMap::new();
+        props.insert("status".to_string(), PropertyValue::string("active"));
+        props.insert("priority".to_string(), PropertyValue::integer(3));
+        db.save_properties("uuid-1", &props).unwrap();
+        db.save_property("uuid-2", "status", &PropertyValue::string("done"))
+            .unwrap();
+
+        let found = db
+            .find_nodes_by_property("status", &PropertyValue::string("active"))
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].uuid, "uuid-1");
+
+        assert!(db
+            .find_nodes_by_property("priority", &PropertyValue::string("3"))
+            .unwrap()
+            .is_empty());
+
+        // 替换后旧属性被移除
+        props.remove("priority");
+        db.save_properties("uuid-1", &props).unwrap();
+        assert!(db
+            .find_nodes_by_property("priority", &PropertyValue::integer(3))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_remove_tag() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        let tags = vec!["rust".to_string(), "wasm".to_string()];
+        db.save_tags("obj-1", &tags).unwrap();
+        db.save_tags("obj-2", &tags).unwrap();
+
+        db.remove_tag("obj-1", "wasm").unwrap();
+
+        assert_eq!(db.get_tags("obj-1").unwrap(), vec!["rust".to_string()]);
+        assert_eq!(db.get_tags("obj-2").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_get_tag_usages() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for (uuid, path) in [("uuid-1", "a.md"), ("uuid-2", "b.md"), ("uuid-3", "c.md")] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: path.to_string(),
+                title: path.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        db.save_tags("uuid-1", &["project".to_string()]).unwrap();
+        db.save_tags("uuid-3", &["other".to_string()]).unwrap();
+        db.upsert_edge(&Edge {
+            src_uuid: "uuid-2".to_string(),
+            dst_uuid: "tag:project".to_string(),
+            relation: "link".to_string(),
+            weight: 1.0,
+            source: "body-wikilink".to_string(),
+        })
+        .unwrap();
+
+        let mut uuids: Vec<String> = db
+            .get_tag_usages("#project")
+            .unwrap()
+            .into_iter()
+            .map(|n| n.uuid)
+            .collect();
+        uuids.sort();
+        assert_eq!(uuids, vec!["uuid-1", "uuid-2"]);
+
+        assert!(db.get_tag_usages("missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_detect_communities() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["a", "b", "c", "d", "e", "lonely"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+
+        // 两个簇：a-b-c 与 d-e；共同标签不应把它们合并
+        for (src, dst, relation) in [
+            ("a", "b", "link"),
+            ("c", "b", "link"),
+            ("d", "e", "link"),
+            ("a", "tag:shared", "tagged"),
+            ("d", "tag:shared", "tagged"),
+        ] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: relation.to_string(),
+                weight: 1.0,
+                source: "body-wikilink".to_string(),
+            })
+            .unwrap();
+        }
+
+        let communities = db.detect_communities().unwrap();
+        assert_eq!(communities.len(), 6);
+        assert_eq!(communities["a"], communities["b"]);
+        assert_eq!(communities["b"], communities["c"]);
+        assert_eq!(communities["d"], communities["e"]);
+        assert_ne!(communities["a"], communities["d"]);
+        assert_ne!(communities["lonely"], communities["a"]);
+        assert_ne!(communities["lonely"], communities["d"]);
+        assert!(!communities.contains_key("tag:shared"));
+
+        // 内存回退实现得到相同的划分
+        let fallback = db.detect_communities_in_memory().unwrap();
+        assert_eq!(fallback.len(), communities.len());
+        for x in communities.keys() {
+            for y in communities.keys() {
+                assert_eq!(communities[x] == communities[y], fallback[x] == fallback[y]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ndjson_export_import_round_trip() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["a", "b"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("notes/{}.md", uuid),
+                title: uuid.to_uppercase(),
+                content: format!("Line one\nLine \"{}\"", uuid),
+                node_type: "note".to_string(),
+                hash: format!("hash-{}", uuid),
+                created_at: 1,
+                updated_at: 2,
+            })
+            .unwrap();
+        }
+        db.upsert_edge(&Edge {
+            src_uuid: "a".to_string(),
+            dst_uuid: "b".to_string(),
+            relation: "link".to_string(),
+            weight: 0.5,
+            source: "body-wikilink".to_string(),
+        })
+        .unwrap();
+        db.save_property("a", "status", &PropertyValue::string("done"))
+            .unwrap();
+        db.save_property("b", "year", &PropertyValue::integer(2024))
+            .unwrap();
+        db.save_tags("a", &["rust".to_string(), "project/x".to_string()])
+            .unwrap();
+        db.save_aliases("b", &["Bee".to_string()]).unwrap();
+
+        let mut buffer = Vec::new();
+        let written = db.export_vault_to_writer(&mut buffer).unwrap();
+        let text = String::from_utf8(buffer.clone()).unwrap();
+        assert_eq!(written, 8);
+        assert_eq!(text.lines().count(), written);
+        assert!(text
+            .lines()
+            .all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok()));
+
+        let (mut restored, _restored_dir) = setup_test_db();
+        let imported = restored
+            .import_vault_from_reader(std::io::Cursor::new(buffer))
+            .unwrap();
+        assert_eq!(imported, written);
+
+        let graph = |db: &Database| serde_json::to_value(db.get_graph_data().unwrap()).unwrap();
+        assert_eq!(graph(&restored), graph(&db));
+        assert_eq!(
+            restored.get_properties("a").unwrap(),
+            db.get_properties("a").unwrap()
+        );
+        assert_eq!(
+            restored.get_properties("b").unwrap(),
+            db.get_properties("b").unwrap()
+        );
+        assert_eq!(restored.get_tags("a").unwrap(), db.get_tags("a").unwrap());
+        assert_eq!(restored.get_aliases("b").unwrap(), vec!["Bee"]);
+
+        assert!(restored
+            .import_vault_from_reader(std::io::Cursor::new("{\"kind\":\"unknown\"}"))
+            .is_err());
+
+        // 后面的行无效时，前面的行也不写入
+        let (mut partial, _partial_dir) = setup_test_db();
+        let first_node = text.lines().next().unwrap();
+        let input = format!("{}\nnot json\n", first_node);
+        assert!(partial
+            .import_vault_from_reader(std::io::Cursor::new(input))
+            .is_err());
+        assert!(partial.get_all_nodes().unwrap().is_empty());
+
+        // 无法解析的属性值使导出失败而不是被跳过
+        db.db
+            .run_script(
+                r#"?[object_id, name, value_type, value_json] <- [["a", "broken", "string", "{not json"]]
+                :put properties {object_id, name => value_type, value_json}"#,
+                Default::default(),
+                ScriptMutability::Mutable,
+            )
+            .unwrap();
+        let err = db.export_vault_to_writer(&mut Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("broken"));
+    }
+
+    #[test]
+    fn test_get_note_links() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for (uuid, title) in [("a", "Alpha"), ("b", "Beta"), ("c", "Gamma")] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: title.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+        for (src, dst, relation) in [
+            ("a", "b", "link"),
+            ("b", "c", "link"),
+            ("b", "missing:Ghost", "link"),
+            ("b", "tag:rust", "tagged"),
+        ] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: relation.to_string(),
+                weight: 1.0,
+                source: "body-wikilink".to_string(),
+            })
+            .unwrap();
+        }
+
+        let outgoing = db.get_outgoing_links("b").unwrap();
+        assert_eq!(
+            outgoing
+                .iter()
+                .map(|l| (l.title.as_str(), l.path.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![("Gamma", Some("c.md")), ("Ghost", None)]
+        );
+        assert_eq!(outgoing[1].uuid, "missing:Ghost");
+
+        let backlinks = db.get_backlinks("b").unwrap();
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].uuid, "a");
+        assert_eq!(backlinks[0].title, "Alpha");
+        assert_eq!(backlinks[0].path.as_deref(), Some("a.md"));
+        assert_eq!(backlinks[0].source, "body-wikilink");
+
+        assert!(db.get_backlinks("a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_co_linked() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for uuid in ["a", "b", "c", "d", "e"] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: uuid.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+        // B 和 C 都被 A、D 链接；E 只与 B 共享来源 A；标签边不参与
+        for (src, dst, relation) in [
+            ("a", "b", "link"),
+            ("a", "c", "link"),
+            ("a", "e", "link"),
+            ("d", "b", "link"),
+            ("d", "c", "link"),
+            ("a", "tag:x", "tagged"),
+            ("d", "tag:x", "tagged"),
+        ] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: relation.to_string(),
+                weight: 1.0,
+                source: "body-wikilink".to_string(),
+            })
+            .unwrap();
+        }
+
+        let related: Vec<(String, usize)> = db
+            .get_co_linked("b", 10)
+            .unwrap()
+            .into_iter()
+            .map(|(node, n)| (node.uuid, n))
+            .collect();
+        assert_eq!(related, vec![("c".to_string(), 2), ("e".to_string(), 1)]);
+
+        assert_eq!(db.get_co_linked("b", 1).unwrap().len(), 1);
+        assert!(db.get_co_linked("a", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_explain_connection() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        for (uuid, title) in [("a", "Alpha"), ("b", "Beta"), ("c", "Gamma")] {
+            db.upsert_node(&Node {
+                uuid: uuid.to_string(),
+                path: format!("{}.md", uuid),
+                title: title.to_string(),
+                content: String::new(),
+                node_type: "note".to_string(),
+                hash: String::new(),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .unwrap();
+        }
+        for (src, dst, relation) in [
+            ("a", "b", "link"),
+            ("b", "tag:x", "tagged"),
+            ("c", "a", "link"),
+        ] {
+            db.upsert_edge(&Edge {
+                src_uuid: src.to_string(),
+                dst_uuid: dst.to_string(),
+                relation: relation.to_string(),
+                weight: 1.0,
+                source: "body-wikilink".to_string(),
+            })
+            .unwrap();
+        }
+
+        let steps = db.explain_connection("a", "tag:x").unwrap().unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                ConnectionStep {
+                    from_title: "Alpha".to_string(),
+                    relation: "link".to_string(),
+                    to_title: "Beta".to_string(),
+                },
+                ConnectionStep {
+                    from_title: "Beta".to_string(),
+                    relation: "tagged".to_string(),
+                    to_title: "#x".to_string(),
+                },
+            ]
+        );
+
+        // 沿边的方向查找；不可达时为 None
+        assert_eq!(db.explain_connection("a", "c").unwrap(), None);
+        assert_eq!(db.explain_connection("a", "a").unwrap(), Some(Vec::new()));
+
+        // 内存回退实现得到相同的路径
+        assert_eq!(
+            db.shortest_path_in_memory("c", "tag:x").unwrap(),
+            db.shortest_path("c", "tag:x").unwrap()
+        );
+        assert_eq!(db.shortest_path_in_memory("a", "c").unwrap(), None);
+    }
+
+    #[test]
+    fn test_init_schema_creates_missing_relations() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let mut db = Database::new(db_path.clone()).unwrap();
+        let relations = db.existing_relations().unwrap();
+        for (name, _) in SCHEMA {
+            assert!(relations.contains(name), "missing relation {}", name);
+        }
+
+        // 重复初始化和重新打开已有数据库都不报错，数据保持不变
+        db.upsert_node(&Node {
+            uuid: "n1".to_string(),
+            path: "n1.md".to_string(),
+            title: "N1".to_string(),
+            content: String::new(),
+            node_type: "note".to_string(),
+            hash: String::new(),
+            created_at: 0,
+            updated_at: 0,
+        })
+        .unwrap();
+        db.init_schema().unwrap();
+        assert_eq!(db.get_all_nodes().unwrap().len(), 1);
+        drop(db);
+        let db = Database::new(db_path).unwrap();
+
+        // 已存在的表不会重新执行建表脚本；缺失表的建表失败不会被吞掉
+        db.create_missing_relations(&[("nodes", ":create nodes {")])
+            .unwrap();
+        let err = db
+            .create_missing_relations(&[("broken", ":create broken {")])
+            .unwrap_err();
+        assert!(err.to_string().contains("broken"));
+        assert!(!db.existing_relations().unwrap().contains("broken"));
+    }
+
+    #[test]
+    fn test_capabilities_probe() {
+        let (db, _temp_dir) = setup_test_db();
+
+        // 本项目启用了 graph-algo 特性，CozoDB 0.7 内置全文索引
+        let capabilities = db.capabilities();
+        assert!(capabilities.graph_algorithms);
+        assert!(capabilities.shortest_path);
+        assert!(capabilities.full_text_search);
+
+        // 探测用的临时表不应残留
+        let relations = db
+            .db
+            .run_script(
+                "::relations",
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .unwrap();
+        assert!(relations
+            .rows
+            .iter()
+            .all(|row| row[0].get_str() != Some("capability_probe")));
+    }
+
+    #[test]
+    fn test_get_tag_cooccurrence() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        db.save_tags("n1", &["rust".to_string(), "wasm".to_string()])
+            .unwrap();
+        db.save_tags("n2", &["web".to_string(), "rust".to_string()])
+            .unwrap();
+        db.save_tags("n3", &["wasm".to_string(), "rust".to_string()])
+            .unwrap();
+        db.save_tags("n4", &["solo".to_string()]).unwrap();
+
+        let pairs = db.get_tag_cooccurrence(1).unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("rust".to_string(), "wasm".to_string(), 2),
+                ("rust".to_string(), "web".to_string(), 1),
+            ]
+        );
+
+        let frequent = db.get_tag_cooccurrence(2).unwrap();
+        assert_eq!(frequent, vec![("rust".to_string(), "wasm".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_get_tag_case_conflicts() {
+        let (mut db, _temp_dir) = setup_test_db();
+
+        db.save_tags("obj-1", &["Rust".to_string(), "wasm".to_string()])
+            .unwrap();
+        db.save_tags("obj-2", &["rust".to_string()]).unwrap();
+        db.save_tags("obj-3", &["RUST".to_string(), "wasm".to_string()])
+            .unwrap();
+
+        let conflicts = db.get_tag_case_conflicts().unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].normalized, "rust");
+        assert_eq!(conflicts[0].variants, vec!["RUST", "Rust", "rust"]);
+    }
+
+    #[test]
+    fn test_save_tags_expands_parents() {
+        let (mut db, _temp_dir) = setup_test_db();
+        let tag_rows = |db: &Database| {
+            let mut rows: Vec<String> = db
+                .db
+                .run_script(
+                    "?[tag] := *tags{object_id: 'obj-1', tag}",
+                    Default::default(),
+                    ScriptMutability::Immutable,
+                )
+                .unwrap()
+                .rows
+                .iter()
+                .map(|row| row[0].get_str().unwrap().to_string())
+                .collect();
+            rows.sort();
+            rows
+        };
+
+        // 默认不展开
+        db.save_tags("obj-1", &["project/rust/async".to_string()])
+            .unwrap();
+        assert_eq!(tag_rows(&db), vec!["project/rust/async"]);
+
+        db.set_expand_tag_parents(true);
+        db.save_tags("obj-1", &["project/rust/async".to_string()])
+            .unwrap();
+        assert_eq!(
+            tag_rows(&db),
+            vec!["project", "project/rust", "project/rust/async"]
+        );
+        assert_eq!(db.get_tags("obj-1").unwrap(), vec!["project/rust/async"]);
+        assert!(db.get_tag_cooccurrence(1).unwrap().is_empty());
+
+        // 显式声明的父标签仍由 get_tags 返回
+        db.save_tags(
+            "obj-1",
+            &["project".to_string(), "project/rust/async".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            db.get_tags("obj-1").unwrap(),
+            vec!["project", "project/rust/async"]
+        );
+
+        // 移除叶子标签时不再需要的父级行一并删除
+        db.remove_tag("obj-1", "project/rust/async").unwrap();
+        assert_eq!(tag_rows(&db), vec!["project"]);
+    }
+
     #[test]
     fn test_save_and_get_aliases() {
         let (mut db, _temp_dir) = setup_test_db();
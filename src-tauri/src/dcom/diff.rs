@@ -0,0 +1,264 @@
+//! # Diff 模块
+//!
+//! 本模块提供认知对象两个版本之间的差异比较，用于撤销历史和冲突解决。
+//!
+//! ## 模块依赖
+//!
+//! - `serde` - 序列化/反序列化
+//! - [`super::object`] - 认知对象
+//! - [`super::property`] - 属性值
+//!
+//! ## 导出的主要内容
+//!
+//! ### 结构体
+//! - [`ObjectDiff`] - 两个对象版本之间的差异
+//! - [`PropertyChange`] - 单个属性的值变化
+//! - [`ListDiff`] - 列表的新增与移除项
+//!
+//! ## 使用示例
+//!
+//! ```rust,ignore
+//! let diff = old.diff(&new);
+//! for tag in &diff.tags.added {
+//!     println!("+#{}", tag);
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::object::{CognitiveObject, ObjectId};
+use super::property::PropertyValue;
+
+/// 列表差异
+///
+/// 按集合语义比较，只关心元素是否出现，不关心顺序。
+///
+/// # 字段说明
+///
+/// * `added` - 只在新版本中出现的元素，保持新版本中的顺序
+/// * `removed` - 只在旧版本中出现的元素，保持旧版本中的顺序
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListDiff<T> {
+    /// 新增的元素
+    pub added: Vec<T>,
+    /// 移除的元素
+    pub removed: Vec<T>,
+}
+
+impl<T: PartialEq + Clone> ListDiff<T> {
+    /// 比较两个列表
+    ///
+    /// # 参数
+    ///
+    /// * `old` - 旧版本列表
+    /// * `new` - 新版本列表
+    pub fn between(old: &[T], new: &[T]) -> Self {
+        ListDiff {
+            added: new.iter().filter(|x| !old.contains(x)).cloned().collect(),
+            removed: old.iter().filter(|x| !new.contains(x)).cloned().collect(),
+        }
+    }
+
+    /// 检查是否没有任何变化
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<T> Default for ListDiff<T> {
+    fn default() -> Self {
+        ListDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+}
+
+/// 属性值变化
+///
+/// # 字段说明
+///
+/// * `old` - 旧值
+/// * `new` - 新值
+/// * `items` - 新旧值均为列表时的元素级变化；仅顺序不同时为空差异
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyChange {
+    /// 旧值
+    pub old: PropertyValue,
+    /// 新值
+    pub new: PropertyValue,
+    /// 列表元素的变化
+    pub items: Option<ListDiff<PropertyValue>>,
+}
+
+/// 对象差异
+///
+/// 由 [`CognitiveObject::diff`] 生成，描述从旧版本到新版本的变化。
+/// 属性按名称排序，便于稳定展示。
+///
+/// # 字段说明
+///
+/// * `added_properties` - 新增的属性
+/// * `removed_properties` - 移除的属性（值为旧值）
+/// * `changed_properties` - 值发生变化的属性
+/// * `tags` - 标签变化
+/// * `aliases` - 别名变化
+/// * `links` - 出链变化
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObjectDiff {
+    /// 新增的属性
+    pub added_properties: BTreeMap<String, PropertyValue>,
+    /// 移除的属性
+    pub removed_properties: BTreeMap<String, PropertyValue>,
+    /// 变化的属性
+    pub changed_properties: BTreeMap<String, PropertyChange>,
+    /// 标签变化
+    pub tags: ListDiff<String>,
+    /// 别名变化
+    pub aliases: ListDiff<String>,
+    /// 出链变化
+    pub links: ListDiff<ObjectId>,
+}
+
+impl ObjectDiff {
+    /// 检查两个版本是否没有任何差异
+    pub fn is_empty(&self) -> bool {
+        self.added_properties.is_empty()
+            && self.removed_properties.is_empty()
+            && self.changed_properties.is_empty()
+            && self.tags.is_empty()
+            && self.aliases.is_empty()
+            && self.links.is_empty()
+    }
+}
+
+impl CognitiveObject {
+    /// 比较两个版本的对象
+    ///
+    /// 以 `self` 为旧版本、`other` 为新版本，比较属性、标签、别名和出链。
+    /// 对象 ID、时间戳和序列化源不参与比较。
+    ///
+    /// # 参数
+    ///
+    /// * `other` - 新版本对象
+    ///
+    /// # 返回值
+    ///
+    /// 从 `self` 到 `other` 的差异
+    pub fn diff(&self, other: &CognitiveObject) -> ObjectDiff {
+        let mut diff = ObjectDiff {
+            tags: ListDiff::between(&self.tags, &other.tags),
+            aliases: ListDiff::between(&self.aliases, &other.aliases),
+            links: ListDiff::between(&self.links, &other.links),
+            ..Default::default()
+        };
+
+        for (name, old) in &self.properties {
+            match other.properties.get(name) {
+                None => {
+                    diff.removed_properties.insert(name.clone(), old.clone());
+                }
+                Some(new) if new != old => {
+                    let items = match (old, new) {
+                        (PropertyValue::List(old_items), PropertyValue::List(new_items)) => {
+                            Some(ListDiff::between(old_items, new_items))
+                        }
+                        _ => None,
+                    };
+                    diff.changed_properties.insert(
+                        name.clone(),
+                        PropertyChange {
+                            old: old.clone(),
+                            new: new.clone(),
+                            items,
+                        },
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, new) in &other.properties {
+            if !self.properties.contains_key(name) {
+                diff.added_properties.insert(name.clone(), new.clone());
+            }
+        }
+
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> CognitiveObject {
+        let mut obj = CognitiveObject::with_id("note");
+        obj.set_title("Note");
+        obj.set_property("status", PropertyValue::string("draft"));
+        obj.add_tag("rust");
+        obj.add_tag("wip");
+        obj.add_alias("N");
+        obj
+    }
+
+    #[test]
+    fn test_diff_property_and_tag() {
+        let old = sample();
+        let mut new = sample();
+        new.set_property("status", PropertyValue::string("done"));
+        new.tags.retain(|t| t != "wip");
+
+        let diff = old.diff(&new);
+        assert!(diff.added_properties.is_empty());
+        assert!(diff.removed_properties.is_empty());
+        assert_eq!(diff.changed_properties.len(), 1);
+        let change = &diff.changed_properties["status"];
+        assert_eq!(change.old, PropertyValue::string("draft"));
+        assert_eq!(change.new, PropertyValue::string("done"));
+        assert_eq!(change.items, None);
+        assert!(diff.tags.added.is_empty());
+        assert_eq!(diff.tags.removed, vec!["wip"]);
+        assert!(diff.aliases.is_empty());
+        assert!(diff.links.is_empty());
+
+        assert!(old.diff(&sample()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_list_property() {
+        let list = |items: &[&str]| {
+            PropertyValue::List(items.iter().map(|s| PropertyValue::string(*s)).collect())
+        };
+
+        let mut old = sample();
+        old.set_property("authors", list(&["alice", "bob"]));
+        old.set_property("obsolete", PropertyValue::boolean(true));
+        let mut new = sample();
+        new.set_property("authors", list(&["bob", "carol"]));
+        new.set_property("year", PropertyValue::integer(2024));
+
+        let diff = old.diff(&new);
+        let items = diff.changed_properties["authors"].items.as_ref().unwrap();
+        assert_eq!(items.added, vec![PropertyValue::string("carol")]);
+        assert_eq!(items.removed, vec![PropertyValue::string("alice")]);
+        assert_eq!(
+            diff.added_properties.keys().collect::<Vec<_>>(),
+            vec!["year"]
+        );
+        assert_eq!(
+            diff.removed_properties.keys().collect::<Vec<_>>(),
+            vec!["obsolete"]
+        );
+
+        // 仅顺序变化：值不同，但元素级差异为空
+        let mut reordered = old.clone();
+        reordered.set_property("authors", list(&["bob", "alice"]));
+        let diff = old.diff(&reordered);
+        assert!(diff.changed_properties["authors"]
+            .items
+            .as_ref()
+            .unwrap()
+            .is_empty());
+    }
+}
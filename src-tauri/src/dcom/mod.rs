@@ -20,6 +20,7 @@
 //! ## 模块结构
 //!
 //! - [`object`] - 认知对象核心定义
+//! - [`diff`] - 对象版本差异比较
 //! - [`property`] - 动态属性系统
 //! - [`serialization`] - 序列化层（物理表示）
 //!
@@ -30,6 +31,7 @@
 //! - [`ObjectId`] - 对象唯一标识
 //! - [`Property`] - 动态属性
 //! - [`PropertyValue`] - 属性值（多态）
+//! - [`ObjectDiff`] - 对象两个版本之间的差异
 //!
 //! ## 使用示例
 //!
@@ -46,11 +48,13 @@
 //! 本模块定义的数据结构是无状态的值对象（Value Object）。
 //! 对象的持久化由 `db` 模块负责。
 
+pub mod diff;
 pub mod object;
 pub mod property;
 pub mod serialization;
 
 // Re-export main types
+pub use diff::{ListDiff, ObjectDiff, PropertyChange};
 pub use object::{CognitiveObject, ObjectId};
-pub use property::{Property, PropertyValue};
-pub use serialization::{MarkdownSource, SerializationSource};
+pub use property::{is_computed_property, ObjectRef, Property, PropertyValue};
+pub use serialization::{LineEnding, MarkdownSource, SerializationSource, YamlListStyle};
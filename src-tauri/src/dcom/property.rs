@@ -15,6 +15,12 @@
 //!
 //! ### 结构体
 //! - [`Property`] - 属性定义（名称 + 值）
+//! - [`ObjectRef`] - 对象引用（目标、锚点和显示文本）
+//!
+//! ### 函数
+//! - [`is_computed_property`] - 判断属性是否为计算属性
+//! - [`parse_duration`] / [`format_duration`] - 时长写法（如 `1h30m`）与秒数互转
+//! - [`parse_relative_date`] - 解析相对日期（如 `+3d`）
 //!
 //! ## 设计说明
 //!
@@ -22,6 +28,8 @@
 //! - 任何对象可以拥有任意数量的属性
 //! - 属性值支持多种类型（字符串、数字、布尔、日期、引用等）
 //! - 属性可以是对另一个对象的引用（实现关联）
+//! - 同步过程写入的属性为计算属性（见 [`COMPUTED_PROPERTIES`]，如 `_word_count`），
+//!   只存在于索引中，保存文件时不会写回 frontmatter；用户自己写的 `_private` 等属性不受影响
 //!
 //! ## 使用示例
 //!
@@ -34,6 +42,139 @@
 //! ```
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 同步时写入的字数属性名
+pub const WORD_COUNT_PROPERTY: &str = "_word_count";
+
+/// 计算属性
+///
+/// 同步过程写入的全部属性名。新增由系统生成的属性时须在此登记，
+/// 否则保存文件时会被当作用户数据写回 frontmatter。
+pub const COMPUTED_PROPERTIES: [&str; 1] = [WORD_COUNT_PROPERTY];
+
+/// 判断属性是否为计算属性
+///
+/// 计算属性由系统生成（如字数统计），不属于用户数据，保存时不写入文件。
+/// 只按 [`COMPUTED_PROPERTIES`] 判断，用户写的同样以 `_` 开头的属性仍是普通属性。
+///
+/// # 参数
+///
+/// * `name` - 属性名
+///
+/// # 返回值
+///
+/// 属性名属于 [`COMPUTED_PROPERTIES`] 时返回 true
+pub fn is_computed_property(name: &str) -> bool {
+    COMPUTED_PROPERTIES.contains(&name)
+}
+
+/// 时长单位及对应的秒数，按从大到小排列
+const DURATION_UNITS: [(char, i64); 5] = [
+    ('w', 7 * 86400),
+    ('d', 86400),
+    ('h', 3600),
+    ('m', 60),
+    ('s', 1),
+];
+
+/// 解析时长写法
+///
+/// 支持由一个或多个 `<数字><单位>` 组成的写法，单位为 `w`、`d`、`h`、`m`、`s`，
+/// 例如 `2h`、`30m`、`1h30m`。
+///
+/// # 参数
+///
+/// * `s` - 时长字符串
+///
+/// # 返回值
+///
+/// 时长秒数；不是合法时长写法时返回 `None`
+pub fn parse_duration(s: &str) -> Option<i64> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut total: i64 = 0;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let (_, unit_secs) = DURATION_UNITS.iter().find(|(u, _)| *u == c)?;
+        if number.is_empty() {
+            return None;
+        }
+        let n: i64 = number.parse().ok()?;
+        total = total.checked_add(n.checked_mul(*unit_secs)?)?;
+        number.clear();
+    }
+
+    // 末尾不能是没有单位的数字
+    if !number.is_empty() {
+        return None;
+    }
+    Some(total)
+}
+
+/// 将秒数格式化为时长写法
+///
+/// 与 [`parse_duration`] 互逆，例如 `5400` → `1h30m`，`0` → `0s`。
+///
+/// # 参数
+///
+/// * `secs` - 时长秒数
+///
+/// # 返回值
+///
+/// 时长字符串
+pub fn format_duration(secs: i64) -> String {
+    if secs == 0 {
+        return "0s".to_string();
+    }
+
+    let mut remaining = secs.abs();
+    let mut out = String::new();
+    if secs < 0 {
+        out.push('-');
+    }
+    for (unit, unit_secs) in DURATION_UNITS {
+        let n = remaining / unit_secs;
+        if n > 0 {
+            out.push_str(&n.to_string());
+            out.push(unit);
+            remaining %= unit_secs;
+        }
+    }
+    out
+}
+
+/// 解析相对日期
+///
+/// 支持 `+<时长>` 或 `-<时长>` 写法（如 `+3d`、`-1w`、`+2h`），
+/// 以 `now` 为基准计算出绝对时间。
+///
+/// # 参数
+///
+/// * `s` - 相对日期字符串
+/// * `now` - 基准时间
+///
+/// # 返回值
+///
+/// ISO 8601（RFC 3339）格式的时间字符串；不是相对日期写法时返回 `None`
+pub fn parse_relative_date(s: &str, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    let s = s.trim();
+    let (sign, rest) = match s.chars().next()? {
+        '+' => (1, &s[1..]),
+        '-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let secs = parse_duration(rest)?;
+    let target = now.checked_add_signed(chrono::Duration::try_seconds(sign * secs)?)?;
+    Some(target.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
 
 /// 属性值
 ///
@@ -48,13 +189,15 @@ use serde::{Deserialize, Serialize};
 /// * `Float` - 浮点数
 /// * `Boolean` - 布尔值
 /// * `DateTime` - 日期时间（ISO 8601 格式字符串）
-/// * `Reference` - 对另一个对象的引用（存储目标对象 ID）
+/// * `Duration` - 时长（秒）
+/// * `Reference` - 对另一个对象的引用（目标对象及可选的锚点、显示文本）
 /// * `List` - 值列表
 /// * `Json` - 任意 JSON 值（用于复杂嵌套结构）
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", content = "value")]
 pub enum PropertyValue {
     /// 空值
+    #[default]
     Null,
     /// 字符串值
     String(String),
@@ -66,8 +209,10 @@ pub enum PropertyValue {
     Boolean(bool),
     /// 日期时间（ISO 8601 格式）
     DateTime(String),
+    /// 时长（秒）
+    Duration(i64),
     /// 对另一个对象的引用
-    Reference(String),
+    Reference(ObjectRef),
     /// 值列表
     List(Vec<PropertyValue>),
     /// 任意 JSON 值
@@ -95,9 +240,14 @@ impl PropertyValue {
         PropertyValue::Boolean(b)
     }
 
+    /// 创建时长属性值（秒）
+    pub fn duration(secs: i64) -> Self {
+        PropertyValue::Duration(secs)
+    }
+
     /// 创建引用属性值
     pub fn reference(id: impl Into<String>) -> Self {
-        PropertyValue::Reference(id.into())
+        PropertyValue::Reference(ObjectRef::new(id))
     }
 
     /// 创建字符串列表属性值
@@ -129,10 +279,26 @@ impl PropertyValue {
         }
     }
 
-    /// 尝试获取引用 ID
+    /// 尝试获取时长（秒）
+    ///
+    /// 字符串按 [`parse_duration`] 的写法（如 `2h`）解释。
+    pub fn as_duration(&self) -> Option<i64> {
+        match self {
+            PropertyValue::Duration(secs) => Some(*secs),
+            PropertyValue::String(s) => parse_duration(s),
+            _ => None,
+        }
+    }
+
+    /// 尝试获取引用的目标
     pub fn as_reference(&self) -> Option<&str> {
+        self.as_object_ref().map(|r| r.target.as_str())
+    }
+
+    /// 尝试获取完整的引用（含锚点和显示文本）
+    pub fn as_object_ref(&self) -> Option<&ObjectRef> {
         match self {
-            PropertyValue::Reference(id) => Some(id),
+            PropertyValue::Reference(r) => Some(r),
             _ => None,
         }
     }
@@ -143,12 +309,6 @@ impl PropertyValue {
     }
 }
 
-impl Default for PropertyValue {
-    fn default() -> Self {
-        PropertyValue::Null
-    }
-}
-
 impl From<String> for PropertyValue {
     fn from(s: String) -> Self {
         PropertyValue::String(s)
@@ -208,6 +368,109 @@ impl From<serde_json::Value> for PropertyValue {
     }
 }
 
+/// 对象引用
+///
+/// 对应 wikilink `[[target#anchor|display]]` 的各个部分，保存时按原样还原。
+/// 序列化为不含方括号的链接文本（如 `"Home#Section|Go Home"`），
+/// 与只存储目标 ID 的旧数据兼容。
+///
+/// # 字段说明
+///
+/// * `target` - 目标对象（ID、文件名或路径）
+/// * `anchor` - 标题或块锚点（`#` 之后的部分，如 `Section`、`^block`）
+/// * `display` - 显示文本（`|` 之后的部分）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct ObjectRef {
+    /// 目标对象
+    pub target: String,
+    /// 锚点
+    pub anchor: Option<String>,
+    /// 显示文本
+    pub display: Option<String>,
+}
+
+impl ObjectRef {
+    /// 创建只有目标的引用
+    pub fn new(target: impl Into<String>) -> Self {
+        ObjectRef {
+            target: target.into(),
+            anchor: None,
+            display: None,
+        }
+    }
+
+    /// 设置锚点
+    pub fn with_anchor(mut self, anchor: impl Into<String>) -> Self {
+        self.anchor = Some(anchor.into());
+        self
+    }
+
+    /// 设置显示文本
+    pub fn with_display(mut self, display: impl Into<String>) -> Self {
+        self.display = Some(display.into());
+        self
+    }
+
+    /// 解析链接文本
+    ///
+    /// # 参数
+    ///
+    /// * `link` - 链接文本，可带外层 `[[` `]]`，如 `[[Home#Section|Go Home]]`
+    ///
+    /// # 返回值
+    ///
+    /// 解析出的引用；没有 `#`、`|` 时锚点、显示文本为 `None`
+    pub fn parse(link: &str) -> Self {
+        let link = link.trim();
+        let link = link
+            .strip_prefix("[[")
+            .and_then(|l| l.strip_suffix("]]"))
+            .unwrap_or(link);
+
+        let (link, display) = match link.split_once('|') {
+            Some((link, display)) => (link, Some(display.to_string())),
+            None => (link, None),
+        };
+        let (target, anchor) = match link.split_once('#') {
+            Some((target, anchor)) => (target, Some(anchor.to_string())),
+            None => (link, None),
+        };
+
+        ObjectRef {
+            target: target.to_string(),
+            anchor,
+            display,
+        }
+    }
+}
+
+impl fmt::Display for ObjectRef {
+    /// 格式化为不含方括号的链接文本
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.target)?;
+        if let Some(anchor) = &self.anchor {
+            write!(f, "#{}", anchor)?;
+        }
+        if let Some(display) = &self.display {
+            write!(f, "|{}", display)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<String> for ObjectRef {
+    fn from(link: String) -> Self {
+        ObjectRef::parse(&link)
+    }
+}
+
+impl From<ObjectRef> for String {
+    fn from(r: ObjectRef) -> Self {
+        r.to_string()
+    }
+}
+
 /// 属性定义
 ///
 /// 表示一个命名的属性，包含属性名和属性值。
@@ -243,6 +506,41 @@ impl Property {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2h"), Some(7200));
+        assert_eq!(parse_duration("30m"), Some(1800));
+        assert_eq!(parse_duration("1h30m"), Some(5400));
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("30"), None);
+        assert_eq!(parse_duration("rust"), None);
+
+        // 格式化后可以解析回原值
+        for secs in [7200, 1800, 5400, 90061] {
+            assert_eq!(parse_duration(&format_duration(secs)), Some(secs));
+        }
+
+        assert_eq!(PropertyValue::string("2h").as_duration(), Some(7200));
+        assert_eq!(PropertyValue::duration(60).as_duration(), Some(60));
+        assert_eq!(PropertyValue::string("soon").as_duration(), None);
+    }
+
+    #[test]
+    fn test_parse_relative_date() {
+        let base = chrono::DateTime::parse_from_rfc3339("2024-01-10T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            parse_relative_date("+3d", base).as_deref(),
+            Some("2024-01-13T00:00:00Z")
+        );
+        assert_eq!(
+            parse_relative_date("-1w", base).as_deref(),
+            Some("2024-01-03T00:00:00Z")
+        );
+        assert_eq!(parse_relative_date("3d", base), None);
+    }
+
     #[test]
     fn test_property_value_string() {
         let val = PropertyValue::string("hello");
@@ -263,6 +561,35 @@ mod tests {
         assert_eq!(val.as_reference(), Some("uuid-123"));
     }
 
+    #[test]
+    fn test_object_ref_parse_and_serialize() {
+        let r = ObjectRef::parse("[[Home#Section|Go Home]]");
+        assert_eq!(
+            r,
+            ObjectRef::new("Home")
+                .with_anchor("Section")
+                .with_display("Go Home")
+        );
+        assert_eq!(r.to_string(), "Home#Section|Go Home");
+        assert_eq!(ObjectRef::parse("Home"), ObjectRef::new("Home"));
+
+        let val = PropertyValue::Reference(r);
+        assert_eq!(val.as_reference(), Some("Home"));
+        let json = serde_json::to_string(&val).unwrap();
+        assert!(json.contains(r#""Home#Section|Go Home""#));
+        assert_eq!(serde_json::from_str::<PropertyValue>(&json).unwrap(), val);
+    }
+
+    #[test]
+    fn test_property_value_duration_serialization() {
+        let val = PropertyValue::duration(7200);
+        assert_eq!(val.as_duration(), Some(7200));
+
+        let json = serde_json::to_string(&val).unwrap();
+        let parsed: PropertyValue = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, val);
+    }
+
     #[test]
     fn test_property_value_from_string() {
         let val: PropertyValue = "test".into();
@@ -276,6 +603,14 @@ mod tests {
         assert!(matches!(val, PropertyValue::Json(_)));
     }
 
+    #[test]
+    fn test_is_computed_property() {
+        assert!(is_computed_property("_word_count"));
+        assert!(!is_computed_property("author"));
+        assert!(!is_computed_property("word_count"));
+        assert!(!is_computed_property("_private"));
+    }
+
     #[test]
     fn test_property_new() {
         let prop = Property::new("title", "My Note");
@@ -22,6 +22,8 @@
 //!
 //! ### 枚举
 //! - [`SerializationSource`] - 序列化源类型
+//! - [`YamlListStyle`] - frontmatter 列表字段的书写形式
+//! - [`LineEnding`] - 源文件使用的换行符
 //!
 //! ### 结构体
 //! - [`MarkdownSource`] - Markdown 文件源
@@ -36,10 +38,69 @@
 //!     path: "notes/my-note.md".into(),
 //!     content_hash: "abc123".into(),
 //!     last_modified: 1704067200,
+//!     list_styles: Default::default(),
+//!     line_ending: LineEnding::Lf,
+//!     trailing_newline: Some(true),
 //! });
 //! ```
 
+use super::PropertyValue;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// frontmatter 列表字段的书写形式
+///
+/// 用于保存时还原 `tags`、`aliases` 等字段在源文件中的写法。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum YamlListStyle {
+    /// 单个标量：`aliases: Foo`
+    Scalar,
+    /// 行内数组：`aliases: [a, b]`
+    Flow,
+    /// 块列表：每项一行 `- a`
+    Block,
+}
+
+/// 源文件使用的换行符
+///
+/// 保存时按源文件的主要换行符输出，避免整文件的换行符被改写。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    Crlf,
+}
+
+impl LineEnding {
+    /// 检测文本的主要换行符
+    ///
+    /// `\r\n` 多于单独的 `\n` 时为 [`LineEnding::Crlf`]，否则（包括没有换行时）为 [`LineEnding::Lf`]。
+    ///
+    /// # 参数
+    ///
+    /// * `text` - 源文件文本
+    pub fn detect(text: &str) -> Self {
+        let crlf = text.matches("\r\n").count();
+        let lf = text.matches('\n').count() - crlf;
+        if crlf > lf {
+            LineEnding::Crlf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// 换行符字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+        }
+    }
+}
 
 /// Markdown 文件源
 ///
@@ -50,6 +111,11 @@ use serde::{Deserialize, Serialize};
 /// * `path` - 相对于 Vault 根目录的文件路径
 /// * `content_hash` - 文件内容的哈希值，用于检测变化
 /// * `last_modified` - 最后修改时间戳（Unix 时间戳）
+/// * `list_styles` - frontmatter 列表字段（字段名 → 书写形式），保存时用于还原原始写法
+/// * `line_ending` - 源文件的主要换行符
+/// * `trailing_newline` - 源文件是否以换行结尾，未知时为 `None`
+/// * `inline_fields` - 仅由正文 `key:: value` 内联字段提供的属性名，保存时不写入 frontmatter
+/// * `raw_values` - 解析为时长或日期的 frontmatter 字符串（字段名 → 原始写法及解析结果），值未改动时按原始写法保存
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MarkdownSource {
     /// 相对于 Vault 的文件路径
@@ -58,6 +124,21 @@ pub struct MarkdownSource {
     pub content_hash: String,
     /// 最后修改时间戳
     pub last_modified: i64,
+    /// frontmatter 列表字段的书写形式
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub list_styles: HashMap<String, YamlListStyle>,
+    /// 源文件的主要换行符
+    #[serde(default)]
+    pub line_ending: LineEnding,
+    /// 源文件是否以换行结尾
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailing_newline: Option<bool>,
+    /// 来自正文内联字段的属性名
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inline_fields: Vec<String>,
+    /// 解析为时长或日期的 frontmatter 字段的原始写法
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub raw_values: HashMap<String, (String, PropertyValue)>,
 }
 
 impl MarkdownSource {
@@ -77,9 +158,55 @@ impl MarkdownSource {
             path: path.into(),
             content_hash: content_hash.into(),
             last_modified,
+            list_styles: HashMap::new(),
+            line_ending: LineEnding::Lf,
+            trailing_newline: None,
+            inline_fields: Vec::new(),
+            raw_values: HashMap::new(),
         }
     }
 
+    /// 设置 frontmatter 列表字段的书写形式
+    ///
+    /// # 参数
+    ///
+    /// * `styles` - 字段名到书写形式的映射
+    pub fn with_list_styles(mut self, styles: HashMap<String, YamlListStyle>) -> Self {
+        self.list_styles = styles;
+        self
+    }
+
+    /// 设置来自正文内联字段的属性名
+    ///
+    /// # 参数
+    ///
+    /// * `keys` - 只出现在正文 `key:: value` 中的属性名
+    pub fn with_inline_fields(mut self, keys: Vec<String>) -> Self {
+        self.inline_fields = keys;
+        self
+    }
+
+    /// 设置解析为时长或日期的 frontmatter 字段的原始写法
+    ///
+    /// # 参数
+    ///
+    /// * `values` - 字段名到（原始写法，解析结果）的映射
+    pub fn with_raw_values(mut self, values: HashMap<String, (String, PropertyValue)>) -> Self {
+        self.raw_values = values;
+        self
+    }
+
+    /// 按源文件文本记录换行符和结尾换行
+    ///
+    /// # 参数
+    ///
+    /// * `text` - 源文件文本
+    pub fn with_line_format(mut self, text: &str) -> Self {
+        self.line_ending = LineEnding::detect(text);
+        self.trailing_newline = Some(text.ends_with('\n'));
+        self
+    }
+
     /// 检查内容是否发生变化
     ///
     /// # 参数
@@ -260,6 +387,17 @@ mod tests {
         assert!(!source.has_changed("hash1"));
     }
 
+    #[test]
+    fn test_line_ending_detect() {
+        assert_eq!(LineEnding::detect("a\r\nb\r\n"), LineEnding::Crlf);
+        assert_eq!(LineEnding::detect("a\nb\r\nc\n"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("single line"), LineEnding::Lf);
+
+        let source = MarkdownSource::new("a.md", "h", 0).with_line_format("x\r\ny");
+        assert_eq!(source.line_ending, LineEnding::Crlf);
+        assert_eq!(source.trailing_newline, Some(false));
+    }
+
     #[test]
     fn test_binary_source_new() {
         let source = BinarySource::new(
@@ -7,8 +7,10 @@
 //!
 //! - [`adapters`] - 适配器模块，将各种格式转换为 DCOM 认知对象
 //! - [`commands`] - Tauri 命令处理模块，提供前端调用的 API 接口
+//! - [`config`] - 知识库配置模块，读写 `.cognistruct/config.toml`
 //! - [`db`] - 数据库模块，基于 CozoDB 实现图数据存储
 //! - [`dcom`] - DCOM 核心模块，定义认知对象数据结构
+//! - [`linkcheck`] - 外部链接检查模块
 //! - [`sync`] - 同步模块，负责文件监听和变化处理
 //!
 //! ## 架构设计
@@ -36,8 +38,10 @@
 
 pub mod adapters;
 mod commands;
+mod config;
 mod db;
 pub mod dcom;
+mod linkcheck;
 mod sync;
 
 use commands::AppState;
@@ -64,13 +68,72 @@ pub fn run() {
         .manage(AppState::default())
         .invoke_handler(tauri::generate_handler![
             commands::open_vault,
+            commands::reindex_vault,
+            commands::get_config,
+            commands::save_config,
+            commands::rebuild_graph_edges,
+            commands::diff_last_sync,
             commands::get_graph_data,
+            commands::get_full_graph,
+            commands::get_graph_metadata,
+            commands::get_force_graph,
+            commands::get_cytoscape_graph,
+            commands::export_note_subgraph,
+            commands::export_vault_ndjson,
+            commands::import_vault_ndjson,
+            commands::save_node_position,
+            commands::get_node_positions,
+            commands::get_backlink_index,
             commands::get_file_tree,
+            commands::get_file_tree_level,
             commands::get_file_content,
+            commands::open_note,
+            commands::get_file_contents,
+            commands::get_content_range,
+            commands::get_block_content,
+            commands::resolve_wikilink,
+            commands::get_embedders,
+            commands::get_related_notes,
             commands::save_file,
+            commands::append_to_note,
             commands::search_nodes,
+            commands::advanced_search,
+            commands::get_random_note,
+            commands::get_changes_since,
+            commands::validate_query,
             commands::get_vault_statistics,
-            commands::get_dcom_info
+            commands::get_link_statistics,
+            commands::get_all_degrees,
+            commands::find_exact_duplicates,
+            commands::get_referenced_titles,
+            commands::find_link_cycles,
+            commands::get_activity,
+            commands::get_note_stats,
+            commands::get_note_outline,
+            commands::check_integrity,
+            commands::repair_integrity,
+            commands::get_dcom_info,
+            commands::which_adapter,
+            commands::get_nodes_by_source,
+            commands::get_note_properties,
+            commands::query_property_cmp,
+            commands::set_note_property,
+            commands::delete_note_property,
+            commands::remove_note_tag,
+            commands::sync_frontmatter_to_disk,
+            commands::get_tag_conflicts,
+            commands::get_all_aliases,
+            commands::get_alias_conflicts,
+            commands::get_tag_usages,
+            commands::get_tag_cooccurrence,
+            commands::get_communities,
+            commands::explain_connection,
+            commands::check_external_links,
+            commands::rename_file,
+            commands::merge_notes,
+            commands::replace_all,
+            commands::list_templates,
+            commands::get_template
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,280 @@
+//! # Linkcheck 模块
+//!
+//! 本模块提供外部链接有效性检查，用于发现笔记中失效的网页链接。
+//!
+//! ## 模块依赖
+//!
+//! - `reqwest` - 异步 HTTP 客户端
+//! - `futures` - 并发执行请求
+//!
+//! ## 导出的主要内容
+//!
+//! ### 结构体
+//! - [`LinkChecker`] - 外部链接检查器
+//! - [`LinkCheckResult`] - 单个链接的检查结果
+//!
+//! ### 枚举
+//! - [`LinkStatus`] - 链接状态
+//!
+//! ## 检查规则
+//!
+//! - 仅检查 `http://` 和 `https://` 链接，其他协议（如 `mailto:`）被跳过
+//! - 使用 HEAD 请求；服务器不支持 HEAD（405）时回退到 GET
+//! - 2xx/3xx 视为有效，4xx/5xx 和连接失败视为失效
+//! - 同时进行的请求数量受并发上限约束
+//!
+//! ## 使用示例
+//!
+//! ```rust,ignore
+//! use linkcheck::LinkChecker;
+//!
+//! let checker = LinkChecker::new().with_concurrency(4);
+//! let results = checker.check(urls).await;
+//! ```
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 默认并发上限
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// 默认单个请求超时时间
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 链接状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkStatus {
+    /// 链接有效（2xx/3xx）
+    Ok,
+    /// 链接失效（4xx/5xx 或连接失败）
+    Broken,
+    /// 请求超时
+    Timeout,
+}
+
+/// 单个链接的检查结果
+///
+/// # 字段说明
+///
+/// * `url` - 链接地址
+/// * `status` - 链接状态
+/// * `http_status` - HTTP 状态码（未收到响应时为 `None`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkCheckResult {
+    /// 链接地址
+    pub url: String,
+    /// 链接状态
+    pub status: LinkStatus,
+    /// HTTP 状态码
+    pub http_status: Option<u16>,
+}
+
+/// 外部链接检查器
+///
+/// # 使用示例
+///
+/// ```rust,ignore
+/// let checker = LinkChecker::new()
+///     .with_concurrency(4)
+///     .with_timeout(Duration::from_secs(5));
+/// ```
+pub struct LinkChecker {
+    /// 并发上限
+    concurrency: usize,
+    /// 单个请求超时时间
+    timeout: Duration,
+}
+
+impl LinkChecker {
+    /// 使用默认配置创建检查器
+    ///
+    /// 默认并发上限为 8，单个请求超时 10 秒。
+    pub fn new() -> Self {
+        LinkChecker {
+            concurrency: DEFAULT_CONCURRENCY,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// 设置并发上限
+    ///
+    /// # 参数
+    ///
+    /// * `concurrency` - 同时进行的最大请求数，最小为 1
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// 设置单个请求超时时间
+    ///
+    /// # 参数
+    ///
+    /// * `timeout` - 超时时间
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 检查一组链接
+    ///
+    /// # 参数
+    ///
+    /// * `urls` - 待检查的链接，非 HTTP(S) 链接会被跳过
+    ///
+    /// # 返回值
+    ///
+    /// 每个 HTTP(S) 链接的检查结果，顺序与输入一致
+    pub async fn check(&self, urls: Vec<String>) -> Vec<LinkCheckResult> {
+        let client = match reqwest::Client::builder().timeout(self.timeout).build() {
+            Ok(client) => client,
+            Err(_) => return Vec::new(),
+        };
+
+        stream::iter(urls.into_iter().filter(|url| is_http_url(url)))
+            .map(|url| {
+                let client = client.clone();
+                async move { check_url(&client, url).await }
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await
+    }
+}
+
+impl Default for LinkChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 检查链接是否为 HTTP(S) 协议
+fn is_http_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// 检查单个链接
+async fn check_url(client: &reqwest::Client, url: String) -> LinkCheckResult {
+    let mut response = client.head(&url).send().await;
+
+    // 部分服务器不支持 HEAD 请求
+    if let Ok(ref r) = response {
+        if r.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+            response = client.get(&url).send().await;
+        }
+    }
+
+    match response {
+        Ok(r) => {
+            let code = r.status();
+            LinkCheckResult {
+                url,
+                status: if code.is_client_error() || code.is_server_error() {
+                    LinkStatus::Broken
+                } else {
+                    LinkStatus::Ok
+                },
+                http_status: Some(code.as_u16()),
+            }
+        }
+        Err(e) => LinkCheckResult {
+            url,
+            status: if e.is_timeout() {
+                LinkStatus::Timeout
+            } else {
+                LinkStatus::Broken
+            },
+            http_status: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// 启动本地模拟服务器
+    ///
+    /// `/ok` 返回 200，`/slow` 延迟 2 秒后返回 200，其他路径返回 404。
+    fn start_mock_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+                thread::spawn(move || {
+                    let mut buf = [0u8; 1024];
+                    let n = stream.read(&mut buf).unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+                    let status = match path {
+                        "/ok" => "200 OK",
+                        "/slow" => {
+                            thread::sleep(Duration::from_secs(2));
+                            "200 OK"
+                        }
+                        _ => "404 Not Found",
+                    };
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        status
+                    );
+                });
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_check_ok_and_broken() {
+        let base = start_mock_server();
+        let checker = LinkChecker::new().with_concurrency(2);
+
+        let results = checker
+            .check(vec![
+                format!("{}/ok", base),
+                format!("{}/missing", base),
+                "mailto:someone@example.com".to_string(),
+            ])
+            .await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].status, LinkStatus::Ok);
+        assert_eq!(results[0].http_status, Some(200));
+        assert_eq!(results[1].status, LinkStatus::Broken);
+        assert_eq!(results[1].http_status, Some(404));
+    }
+
+    #[tokio::test]
+    async fn test_check_timeout() {
+        let base = start_mock_server();
+        let checker = LinkChecker::new().with_timeout(Duration::from_millis(200));
+
+        let results = checker.check(vec![format!("{}/slow", base)]).await;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, LinkStatus::Timeout);
+        assert_eq!(results[0].http_status, None);
+    }
+
+    #[test]
+    fn test_is_http_url() {
+        assert!(is_http_url("https://example.com"));
+        assert!(is_http_url("HTTP://example.com"));
+        assert!(!is_http_url("mailto:a@b.c"));
+        assert!(!is_http_url("obsidian://open"));
+    }
+}
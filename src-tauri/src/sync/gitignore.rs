@@ -0,0 +1,131 @@
+//! # Gitignore 模块
+//!
+//! 本模块提供基于 `.gitignore` 的路径过滤，用于跳过知识库中代码仓库的构建产物等文件。
+//!
+//! ## 设计说明
+//!
+//! 与 git 一致，每个目录下的 `.gitignore` 只作用于该目录及其子目录。
+//! 判断路径时从最近的目录向上逐级查找，第一个给出结论（忽略或 `!` 取消忽略）的规则文件生效。
+//! 各目录的规则文件只解析一次并缓存。
+//!
+//! ## 模块依赖
+//!
+//! - `ignore` - gitignore 规则解析与匹配
+//!
+//! ## 导出的主要内容
+//!
+//! ### 结构体
+//! - [`GitignoreFilter`] - 支持嵌套 `.gitignore` 的路径过滤器
+
+use ignore::gitignore::Gitignore;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Gitignore 路径过滤器
+///
+/// # 字段说明
+///
+/// * `root` - 知识库根目录，不会查找根目录之外的 `.gitignore`
+/// * `matchers` - 目录到其 `.gitignore` 规则的缓存，目录下没有规则文件时为 `None`
+pub struct GitignoreFilter {
+    /// 知识库根目录
+    root: PathBuf,
+    /// 各目录的规则缓存
+    matchers: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl GitignoreFilter {
+    /// 创建过滤器
+    ///
+    /// # 参数
+    ///
+    /// * `root` - 知识库根目录；传入的路径须与之后判断的路径使用相同的前缀形式
+    pub fn new(root: &Path) -> Self {
+        GitignoreFilter {
+            root: root.to_path_buf(),
+            matchers: HashMap::new(),
+        }
+    }
+
+    /// 判断路径是否被忽略
+    ///
+    /// 路径本身或其任一上级目录被忽略时均视为忽略，因此单独判断被忽略目录中的文件
+    /// （如只同步 `target/out.md`）也能得到正确结果。
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 知识库根目录下的路径
+    /// * `is_dir` - 路径是否为目录（影响 `target/` 这类只匹配目录的规则）
+    ///
+    /// # 返回值
+    ///
+    /// 被 `.gitignore` 忽略时返回 `true`
+    pub fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        let mut dir = path.parent();
+        while let Some(current) = dir {
+            if !current.starts_with(&self.root) {
+                break;
+            }
+
+            if let Some(gitignore) = self.matcher(current) {
+                let matched = gitignore.matched_path_or_any_parents(path, is_dir);
+                if matched.is_ignore() {
+                    return true;
+                }
+                if matched.is_whitelist() {
+                    return false;
+                }
+            }
+
+            if current == self.root {
+                break;
+            }
+            dir = current.parent();
+        }
+        false
+    }
+
+    /// 获取目录下 `.gitignore` 的规则，首次访问时解析并缓存
+    fn matcher(&mut self, dir: &Path) -> Option<&Gitignore> {
+        self.matchers
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let file = dir.join(".gitignore");
+                // 个别无效的规则行被跳过，其余规则仍然生效
+                file.is_file().then(|| Gitignore::new(&file).0)
+            })
+            .as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_nested_gitignore() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        fs::create_dir_all(root.join("code/target")).unwrap();
+        fs::write(root.join("code/.gitignore"), "target/\n!keep.log\n").unwrap();
+
+        let mut filter = GitignoreFilter::new(root);
+        assert!(filter.is_ignored(&root.join("debug.log"), false));
+        assert!(filter.is_ignored(&root.join("code/target"), true));
+        assert!(!filter.is_ignored(&root.join("code/src"), true));
+        assert!(!filter.is_ignored(&root.join("code/keep.log"), false));
+        assert!(filter.is_ignored(&root.join("code/other.log"), false));
+
+        // 嵌套规则不影响上级目录
+        assert!(!filter.is_ignored(&root.join("target"), true));
+
+        // 不经过目录本身，直接判断被忽略目录中的文件
+        let mut filter = GitignoreFilter::new(root);
+        assert!(filter.is_ignored(&root.join("code/target/doc/api.md"), false));
+        assert!(!filter.is_ignored(&root.join("code/src/main.md"), false));
+    }
+}
@@ -15,11 +15,17 @@
 //! - [`crate::db`] - 数据库操作
 //! - [`crate::dcom`] - DCOM 核心数据结构
 //! - `walkdir` - 目录遍历
+//! - `ignore` - `.gitignore` 规则解析
+//! - `rayon` - 并行读取与解析文件
+//! - `blake3` - 内容哈希
 //! - `anyhow` - 错误处理
 //!
 //! ## 子模块
 //!
 //! - [`watcher`] - 文件监听器，监控知识库文件变化
+//! - [`stats`] - 笔记字数与阅读时间统计
+//! - [`rules`] - 基于属性的节点类型推断规则
+//! - [`gitignore`] - 支持嵌套 `.gitignore` 的路径过滤
 //!
 //! ## 导出的主要内容
 //!
@@ -30,9 +36,18 @@
 //! - [`sync_vault`] - 同步整个知识库（兼容旧接口）
 //! - [`calculate_hash`] - 计算内容哈希值
 //! - [`path_to_uuid`] - 根据路径生成 UUID
+//! - [`normalize_uuid_path`] - 规范化用于生成 UUID 的路径
+//! - [`object_uuid`] - 获取对象的节点 UUID（支持 frontmatter `id`）
+//! - [`link_edge_source`] - 获取链接类型对应的边来源
+//! - [`uuid_for_path`] - 查找路径对应的已索引节点 UUID
+//! - [`folder_uuid`] - 根据目录路径生成文件夹节点 UUID
+//! - [`resolve_wikilink`] - 基于已索引数据解析 wikilink 目标路径
 //!
 //! ### 重导出
 //! - [`FileWatcher`] - 从 watcher 模块重导出
+//! - [`NoteStats`]、[`compute_note_stats`] - 从 stats 模块重导出
+//! - [`TypeRule`]、[`load_type_rules`] - 从 rules 模块重导出
+//! - [`GitignoreFilter`] - 从 gitignore 模块重导出
 //!
 //! ## 使用示例
 //!
@@ -53,48 +68,101 @@
 //! - `VaultSyncer` 持有适配器注册表，可重用
 //! - 同步操作会修改数据库状态
 
+pub mod gitignore;
+pub mod rules;
+pub mod stats;
 pub mod watcher;
 
-use crate::adapters::AdapterRegistry;
-use crate::db::{Database, Edge, Node};
-use crate::dcom::CognitiveObject;
+use crate::adapters::{AdapterRegistry, LinkKind};
+use crate::config::VaultConfig;
+use crate::db::{Database, Edge, EdgeSource, LinkResolution, Node};
+use crate::dcom::{CognitiveObject, ObjectId, PropertyValue};
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+pub use gitignore::GitignoreFilter;
+pub use rules::{load_type_rules, TypeRule};
+pub use stats::{compute_note_stats, NoteStats};
 pub use watcher::FileWatcher;
 
+/// 同步时写入的字数属性名（计算属性，不写回文件）
+pub use crate::dcom::property::WORD_COUNT_PROPERTY;
+
+/// frontmatter 中声明稳定节点 UUID 的属性名
+pub const ID_PROPERTY: &str = "id";
+
+/// 默认边权重
+///
+/// 键为关系类型或边来源，标签关联弱于直接引用，嵌入介于两者之间。
+pub const DEFAULT_RELATION_WEIGHTS: [(&str, f64); 3] =
+    [("link", 1.0), ("tagged", 0.3), ("embed", 0.8)];
+
 /// 计算内容哈希值
 ///
-/// 使用标准库的 DefaultHasher 计算字符串内容的哈希值。
+/// 使用 BLAKE3 计算字符串内容的哈希值。
 /// 用于检测文件内容是否发生变化。
 ///
+/// 与标准库的 `DefaultHasher` 不同，BLAKE3 的输出跨 Rust 版本、平台和进程稳定，
+/// 重启或换机后哈希比较仍然有效。
+///
 /// # 参数
 ///
 /// * `content` - 要计算哈希的内容
 ///
 /// # 返回值
 ///
-/// 返回十六进制格式的哈希字符串
+/// 返回 64 位十六进制字符的哈希字符串
 ///
 /// # 副作用
 ///
 /// 无副作用，纯函数
 pub fn calculate_hash(content: &str) -> String {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
+    blake3::hash(content.as_bytes()).to_hex().to_string()
+}
 
-    let mut hasher = DefaultHasher::new();
-    content.hash(&mut hasher);
-    format!("{:x}", hasher.finish())
+/// 当前平台的文件系统是否默认不区分大小写
+///
+/// macOS 和 Windows 上 `Notes/A.md` 与 `notes/a.md` 指向同一文件。
+pub const CASE_INSENSITIVE_PATHS: bool = cfg!(any(target_os = "macos", target_os = "windows"));
+
+/// 规范化用于生成 UUID 的相对路径
+///
+/// - 去除首尾空白、开头的 `./` 和 `/`
+/// - 将 `\` 统一为 `/`（无条件执行，使 Windows 与 Unix 路径一致）
+/// - `fold_case` 为 `true` 时转换为小写
+///
+/// # 参数
+///
+/// * `relative_path` - 相对于知识库根目录的路径
+/// * `fold_case` - 是否折叠大小写
+///
+/// # 返回值
+///
+/// 规范化后的路径
+pub fn normalize_uuid_path(relative_path: &str, fold_case: bool) -> String {
+    let mut path = relative_path.trim().replace('\\', "/");
+    while let Some(rest) = path.strip_prefix("./") {
+        path = rest.to_string();
+    }
+    let path = path.trim_start_matches('/');
+
+    if fold_case {
+        path.to_lowercase()
+    } else {
+        path.to_string()
+    }
 }
 
 /// 根据相对路径生成确定性 UUID
 ///
 /// 使用文件的相对路径生成一个确定性的 UUID 样式的标识符。
-/// 同一路径始终生成相同的 UUID。
+/// 路径先经过 [`normalize_uuid_path`] 规范化（在不区分大小写的平台上折叠大小写），
+/// 因此同一文件的不同写法始终生成相同的 UUID。
 ///
 /// # 参数
 ///
@@ -112,7 +180,7 @@ pub fn path_to_uuid(relative_path: &str) -> String {
     use std::hash::{Hash, Hasher};
 
     let mut hasher = DefaultHasher::new();
-    relative_path.hash(&mut hasher);
+    normalize_uuid_path(relative_path, CASE_INSENSITIVE_PATHS).hash(&mut hasher);
     let hash = hasher.finish();
     format!(
         "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
@@ -124,6 +192,102 @@ pub fn path_to_uuid(relative_path: &str) -> String {
     )
 }
 
+/// 获取链接类型对应的边来源
+///
+/// 块引用视为正文 wikilink。
+pub fn link_edge_source(kind: &LinkKind) -> EdgeSource {
+    match kind {
+        LinkKind::WikiLink | LinkKind::BlockReference => EdgeSource::BodyWikilink,
+        LinkKind::FrontmatterRef => EdgeSource::FrontmatterRef,
+        LinkKind::Embed => EdgeSource::Embed,
+        LinkKind::External => EdgeSource::External,
+    }
+}
+
+/// 获取对象的节点 UUID
+///
+/// frontmatter 中声明了 `id` 时使用该值作为 UUID，移动文件不会改变节点身份，
+/// 只更新 `path` 列；否则回退到基于路径的 [`path_to_uuid`]。
+///
+/// # 参数
+///
+/// * `obj` - 认知对象
+/// * `relative_path` - 相对于知识库根目录的文件路径
+///
+/// # 返回值
+///
+/// 节点 UUID
+pub fn object_uuid(obj: &CognitiveObject, relative_path: &str) -> String {
+    match obj.get_property(ID_PROPERTY) {
+        Some(PropertyValue::String(id)) if !id.trim().is_empty() => id.trim().to_string(),
+        Some(PropertyValue::Integer(id)) => id.to_string(),
+        _ => path_to_uuid(relative_path),
+    }
+}
+
+/// 检查多个文件是否映射到同一个节点 UUID
+///
+/// 两篇笔记在 frontmatter 中声明了相同的 `id` 时会合并为一个节点，后同步的笔记覆盖先同步的。
+/// 为每个冲突的 UUID 生成一条列出所有相关路径的警告。
+///
+/// # 参数
+///
+/// * `uuid_by_path` - 相对路径到节点 UUID 的映射
+///
+/// # 返回值
+///
+/// 按 UUID 排序的警告列表
+fn duplicate_id_warnings(uuid_by_path: &HashMap<&str, String>) -> Vec<String> {
+    let mut paths_by_uuid: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (path, uuid) in uuid_by_path {
+        paths_by_uuid.entry(uuid.as_str()).or_default().push(path);
+    }
+
+    paths_by_uuid
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(uuid, mut paths)| {
+            paths.sort();
+            format!("多个笔记声明了相同的 id {}: {}", uuid, paths.join(", "))
+        })
+        .collect()
+}
+
+/// 查找路径对应的节点 UUID
+///
+/// 优先使用数据库中已索引节点的 UUID（可能来自 frontmatter `id`），
+/// 路径未被索引时回退到 [`path_to_uuid`]。
+///
+/// # 参数
+///
+/// * `db` - 数据库实例
+/// * `relative_path` - 相对于知识库根目录的文件路径
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 节点 UUID
+/// * `Err(anyhow::Error)` - 数据库查询失败
+pub fn uuid_for_path(db: &Database, relative_path: &str) -> Result<String> {
+    Ok(db
+        .get_node_by_path(relative_path)?
+        .map(|node| node.uuid)
+        .unwrap_or_else(|| path_to_uuid(relative_path)))
+}
+
+/// 文件夹节点类型
+pub const FOLDER_NODE_TYPE: &str = "folder";
+
+/// 根据目录路径生成文件夹节点 UUID
+///
+/// 在路径末尾追加 `/` 后哈希，避免与同名文件的 UUID 冲突。
+///
+/// # 参数
+///
+/// * `relative_path` - 相对于知识库根目录的目录路径
+pub fn folder_uuid(relative_path: &str) -> String {
+    path_to_uuid(&format!("{}/", relative_path))
+}
+
 /// 知识库同步器
 ///
 /// 负责将知识库文件同步到 DCOM 系统。
@@ -144,6 +308,24 @@ pub fn path_to_uuid(relative_path: &str) -> String {
 pub struct VaultSyncer {
     /// 适配器注册表
     registry: AdapterRegistry,
+    /// 是否跟随符号链接遍历
+    follow_links: bool,
+    /// 是否将标签折叠为小写
+    fold_tag_case: bool,
+    /// 是否并行读取和解析文件
+    parallel: bool,
+    /// 是否为目录创建文件夹节点
+    create_folder_nodes: bool,
+    /// 各关系类型的边权重
+    relation_weights: HashMap<String, f64>,
+    /// 是否保留笔记指向自身的链接边
+    allow_self_links: bool,
+    /// 节点类型推断规则
+    type_rules: Vec<TypeRule>,
+    /// 是否跳过被 `.gitignore` 忽略的文件
+    respect_gitignore: bool,
+    /// 额外的忽略规则（gitignore 语法，相对于知识库根目录）
+    ignore_patterns: Option<Gitignore>,
 }
 
 impl VaultSyncer {
@@ -153,14 +335,178 @@ impl VaultSyncer {
     ///
     /// * `registry` - 适配器注册表
     pub fn new(registry: AdapterRegistry) -> Self {
-        VaultSyncer { registry }
+        VaultSyncer {
+            registry,
+            follow_links: false,
+            fold_tag_case: false,
+            parallel: true,
+            create_folder_nodes: false,
+            relation_weights: DEFAULT_RELATION_WEIGHTS
+                .iter()
+                .map(|(relation, weight)| (relation.to_string(), *weight))
+                .collect(),
+            allow_self_links: false,
+            type_rules: Vec::new(),
+            respect_gitignore: false,
+            ignore_patterns: None,
+        }
     }
 
     /// 使用默认适配器创建同步器
     pub fn with_defaults() -> Self {
-        VaultSyncer {
-            registry: AdapterRegistry::default(),
+        Self::new(AdapterRegistry::default())
+    }
+
+    /// 设置是否跟随符号链接
+    ///
+    /// 默认不跟随，遍历时遇到的符号链接会被跳过并记录到 `SyncResult.warnings`。
+    /// 开启后依赖 `WalkDir` 的循环检测，形成环路的链接同样会被跳过并记录。
+    ///
+    /// # 参数
+    ///
+    /// * `follow` - 是否跟随符号链接
+    pub fn with_follow_links(mut self, follow: bool) -> Self {
+        self.follow_links = follow;
+        self
+    }
+
+    /// 设置是否并行加载文件
+    ///
+    /// 默认开启，全量同步时使用 rayon 线程池并行读取和解析文件，
+    /// 结果顺序与目录遍历顺序一致，随后的数据库写入仍为顺序执行。
+    ///
+    /// # 参数
+    ///
+    /// * `parallel` - 是否并行
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// 设置是否折叠标签大小写
+    ///
+    /// 开启后 `#Rust`、`#rust`、`#RUST` 会合并为同一个小写标签，默认关闭。
+    ///
+    /// # 参数
+    ///
+    /// * `fold` - 是否折叠为小写
+    pub fn with_tag_case_folding(mut self, fold: bool) -> Self {
+        self.fold_tag_case = fold;
+        self
+    }
+
+    /// 设置是否创建文件夹节点
+    ///
+    /// 开启后全量同步会为每个包含已索引文件的目录创建 `node_type` 为 `folder` 的节点，
+    /// 并从文件夹指向其直接子笔记和子文件夹创建 `contains` 边。默认关闭。
+    ///
+    /// # 参数
+    ///
+    /// * `create` - 是否创建文件夹节点
+    pub fn with_folder_nodes(mut self, create: bool) -> Self {
+        self.create_folder_nodes = create;
+        self
+    }
+
+    /// 设置是否保留自链接
+    ///
+    /// 默认关闭，笔记中指向自身的链接和嵌入（如 `[[This Note]]`）不会创建自环边。
+    /// 可通过 [`Database::get_self_links`] 查找已存在的自环边。
+    ///
+    /// # 参数
+    ///
+    /// * `allow` - 是否保留自链接
+    pub fn with_self_links(mut self, allow: bool) -> Self {
+        self.allow_self_links = allow;
+        self
+    }
+
+    /// 设置关系类型的边权重
+    ///
+    /// 创建边时先按边来源（如 `embed`）查找权重，再按关系类型（如 `link`、`tagged`）查找，
+    /// 都未配置时权重为 1.0。默认值见 [`DEFAULT_RELATION_WEIGHTS`]。
+    ///
+    /// # 参数
+    ///
+    /// * `relation` - 关系类型或边来源
+    /// * `weight` - 边权重
+    pub fn with_relation_weight(mut self, relation: &str, weight: f64) -> Self {
+        self.relation_weights.insert(relation.to_string(), weight);
+        self
+    }
+
+    /// 设置节点类型推断规则
+    ///
+    /// 加载对象时按顺序匹配规则，命中时设置对象的 `inferred_type` 并以其作为节点类型。
+    ///
+    /// # 参数
+    ///
+    /// * `rules` - 规则列表，通常由 [`load_type_rules`] 从知识库加载
+    pub fn with_type_rules(mut self, rules: Vec<TypeRule>) -> Self {
+        self.type_rules = rules;
+        self
+    }
+
+    /// 设置是否遵循 `.gitignore`
+    ///
+    /// 开启后全量同步跳过被 `.gitignore`（包括子目录中的 `.gitignore`）忽略的文件和目录，
+    /// 适用于知识库中存放代码仓库的情况。默认关闭。
+    ///
+    /// # 参数
+    ///
+    /// * `respect` - 是否遵循 `.gitignore`
+    pub fn with_respect_gitignore(mut self, respect: bool) -> Self {
+        self.respect_gitignore = respect;
+        self
+    }
+
+    /// 设置额外的忽略规则
+    ///
+    /// 规则使用 gitignore 语法（如 `drafts/`、`*.tmp.md`），相对于知识库根目录匹配，
+    /// 全量同步时跳过匹配的文件和目录。无效的规则会被忽略。
+    ///
+    /// # 参数
+    ///
+    /// * `patterns` - 忽略规则列表
+    pub fn with_ignore_patterns(mut self, patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new("");
+        for pattern in patterns {
+            let _ = builder.add_line(None, pattern);
         }
+        self.ignore_patterns = builder
+            .build()
+            .ok()
+            .filter(|gitignore| !gitignore.is_empty());
+        self
+    }
+
+    /// 应用知识库配置
+    ///
+    /// 设置忽略规则、`.gitignore` 支持、文件夹节点、符号链接和标签大小写选项。
+    ///
+    /// # 参数
+    ///
+    /// * `config` - 知识库配置，通常由 [`crate::config::load_config`] 加载
+    pub fn with_config(self, config: &VaultConfig) -> Self {
+        self.with_ignore_patterns(&config.ignore_patterns)
+            .with_respect_gitignore(config.respect_gitignore)
+            .with_folder_nodes(config.create_folder_nodes)
+            .with_follow_links(config.follow_links)
+            .with_tag_case_folding(config.fold_tag_case)
+    }
+
+    /// 获取边权重
+    ///
+    /// # 参数
+    ///
+    /// * `relation` - 关系类型
+    /// * `source` - 边来源
+    fn edge_weight(&self, relation: &str, source: EdgeSource) -> f64 {
+        self.relation_weights
+            .get(source.as_str())
+            .or_else(|| self.relation_weights.get(relation))
+            .copied()
+            .unwrap_or(1.0)
     }
 
     /// 全量同步知识库
@@ -182,72 +528,348 @@ impl VaultSyncer {
     /// - 清除数据库中所有现有数据
     /// - 创建新的节点和边
     pub fn sync_full(&self, vault_path: &Path, db: &mut Database) -> Result<SyncResult> {
+        self.sync_full_with_progress(vault_path, db, |_, _| {})
+    }
+
+    /// 全量同步知识库并报告进度
+    ///
+    /// 与 [`Self::sync_full`] 相同，额外在节点和边两个阶段中回调进度。
+    ///
+    /// # 参数
+    ///
+    /// * `vault_path` - 知识库根目录路径
+    /// * `db` - 数据库实例的可变引用
+    /// * `progress` - 进度回调 `(processed, total)`，`total` 为收集到的对象数的两倍
+    ///   （每个对象在节点阶段和边阶段各计一次）。文件收集完成后先以 `processed = 0` 调用一次，
+    ///   最后一次调用满足 `processed == total`
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(SyncResult)` - 同步成功，返回统计信息
+    /// * `Err(anyhow::Error)` - 同步失败
+    pub fn sync_full_with_progress(
+        &self,
+        vault_path: &Path,
+        db: &mut Database,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<SyncResult> {
         // 清除现有数据
         db.clear_all()?;
 
         // 收集所有对象
-        let objects = self.collect_objects(vault_path)?;
+        let CollectedVault {
+            objects,
+            mut warnings,
+            errors,
+            extension_counts,
+            attachments,
+        } = self.collect_objects(vault_path, vault_path)?;
+
+        // 构建文件名到相对路径的映射（用于解析 wikilinks）
+        let filename_index = self.build_filename_index(&objects);
+        let alias_index = Self::index_aliases(objects.iter().flat_map(|(obj, relative_path)| {
+            obj.aliases()
+                .iter()
+                .map(move |alias| (alias.as_str(), relative_path.as_str()))
+        }));
+
+        let attachment_index = Self::index_attachments(attachments.iter().map(|p| p.as_str()));
+        let link_index = LinkIndex {
+            filenames: &filename_index,
+            aliases: &alias_index,
+            attachments: &attachment_index,
+        };
 
-        // 构建文件名到 UUID 的映射（用于解析 wikilinks）
-        let filename_to_uuids = self.build_filename_index(&objects);
+        // 相对路径到节点 UUID 的映射
+        let uuid_by_path: HashMap<&str, String> = objects
+            .iter()
+            .map(|(obj, relative_path)| (relative_path.as_str(), object_uuid(obj, relative_path)))
+            .collect();
+        warnings.extend(duplicate_id_warnings(&uuid_by_path));
+
+        let total = objects.len() * 2;
+        let mut processed = 0;
+        progress(processed, total);
 
         // 第一遍：创建所有节点
         for (obj, relative_path) in &objects {
             let node = self.object_to_node(obj, relative_path);
             db.upsert_node(&node)?;
+            self.save_node_properties(obj, &node, db)?;
+            processed += 1;
+            progress(processed, total);
         }
 
         // 第二遍：创建边
         let mut edge_count = 0;
+        if self.create_folder_nodes {
+            edge_count += self.sync_folder_nodes(&uuid_by_path, db)?;
+        }
         for (obj, relative_path) in &objects {
-            let src_uuid = path_to_uuid(relative_path);
-
-            // 从适配器提取链接
-            if let Some(adapter) = self
-                .registry
-                .find_adapter_for_path(Path::new(relative_path))
-            {
-                let links = adapter.extract_links(obj);
-
-                for link in links {
-                    // 尝试通过文件名解析链接目标
-                    if let Some(dst_uuids) = filename_to_uuids.get(&link.target) {
-                        for dst_uuid in dst_uuids {
-                            let edge = Edge {
-                                src_uuid: src_uuid.clone(),
-                                dst_uuid: dst_uuid.clone(),
-                                relation: "link".to_string(),
-                                weight: 1.0,
-                                source: format!("{:?}", link.kind),
-                            };
-                            db.upsert_edge(&edge)?;
-                            edge_count += 1;
-                        }
-                    }
-                }
-            }
+            edge_count +=
+                self.sync_object_edges(obj, relative_path, &link_index, &uuid_by_path, db)?;
+            processed += 1;
+            progress(processed, total);
+        }
+
+        Ok(SyncResult {
+            nodes_synced: objects.len(),
+            edges_created: edge_count,
+            warnings,
+            errors,
+            extension_counts,
+        })
+    }
+
+    /// 同步子目录
+    ///
+    /// 仅清除并重新索引 `path` 位于 `subdir` 下的节点，其余节点保持不变。
+    /// 子目录内笔记的出链基于整个知识库的文件名索引重新解析，因此指向子目录外的链接会被重建；
+    /// 子目录外笔记指向子目录内的入链因 UUID 不变而保留，指向已删除文件的入链会被移除。
+    ///
+    /// # 参数
+    ///
+    /// * `vault_path` - 知识库根目录路径
+    /// * `subdir` - 相对于知识库根目录的子目录路径，也可以是单个文件
+    /// * `db` - 数据库实例的可变引用
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(SyncResult)` - 同步成功，统计信息仅包含子目录
+    /// * `Err(anyhow::Error)` - 同步失败
+    ///
+    /// # 注意
+    ///
+    /// 子目录外笔记中指向子目录内新增文件的链接不会被补建，需要全量同步。
+    pub fn sync_subtree(
+        &self,
+        vault_path: &Path,
+        subdir: &Path,
+        db: &mut Database,
+    ) -> Result<SyncResult> {
+        self.sync_subtrees(vault_path, &[subdir], db)?
+            .pop()
+            .expect("每个子目录对应一个同步结果")
+    }
+
+    /// 同步多个子目录
+    ///
+    /// 与逐个调用 [`Self::sync_subtree`] 的结果相同，但文件名、别名与附件索引只构建一次，
+    /// 适用于文件监听合并后的一批变化：N 个文件只需读取一次全部节点、遍历一次知识库。
+    ///
+    /// # 参数
+    ///
+    /// * `vault_path` - 知识库根目录路径
+    /// * `subdirs` - 相对于知识库根目录的子目录或文件路径
+    /// * `db` - 数据库实例的可变引用
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<Result<SyncResult>>)` - 与 `subdirs` 一一对应的同步结果，单个路径失败不影响其他路径
+    /// * `Err(anyhow::Error)` - 构建索引或清理哨兵节点失败
+    pub fn sync_subtrees(
+        &self,
+        vault_path: &Path,
+        subdirs: &[&Path],
+        db: &mut Database,
+    ) -> Result<Vec<Result<SyncResult>>> {
+        // 先清除并收集所有子目录，剩余节点即为所有子目录以外的笔记
+        let collected: Vec<Result<(Vec<String>, CollectedVault)>> = subdirs
+            .iter()
+            .map(|subdir| {
+                let removed = db.delete_nodes_under_path(&subdir.to_string_lossy())?;
+                let vault = self.collect_objects(vault_path, &vault_path.join(subdir))?;
+                Ok((removed, vault))
+            })
+            .collect();
+        let objects: Vec<&(CognitiveObject, String)> = collected
+            .iter()
+            .flatten()
+            .flat_map(|(_, vault)| &vault.objects)
+            .collect();
+
+        let outside: Vec<(String, String)> = db
+            .get_all_nodes()?
+            .into_iter()
+            .filter(|node| node.node_type != FOLDER_NODE_TYPE)
+            .map(|node| (node.path, node.uuid))
+            .collect();
+
+        let filename_index = Self::index_filenames(
+            outside
+                .iter()
+                .map(|(path, _)| path.as_str())
+                .chain(objects.iter().map(|(_, path)| path.as_str())),
+        );
+        let outside_aliases = db.get_alias_paths()?;
+        let alias_index = Self::index_aliases(
+            outside_aliases
+                .iter()
+                .map(|(alias, path)| (alias.as_str(), path.as_str()))
+                .chain(objects.iter().flat_map(|(obj, relative_path)| {
+                    obj.aliases()
+                        .iter()
+                        .map(move |alias| (alias.as_str(), relative_path.as_str()))
+                })),
+        );
+        // 嵌入的附件可能位于子目录外，按整个知识库索引
+        let attachments = self.collect_attachments(vault_path);
+        let attachment_index = Self::index_attachments(attachments.iter().map(|p| p.as_str()));
+        let link_index = LinkIndex {
+            filenames: &filename_index,
+            aliases: &alias_index,
+            attachments: &attachment_index,
+        };
+
+        let mut uuid_by_path: HashMap<&str, String> = outside
+            .iter()
+            .map(|(path, uuid)| (path.as_str(), uuid.clone()))
+            .collect();
+        uuid_by_path.extend(
+            objects.iter().map(|(obj, relative_path)| {
+                (relative_path.as_str(), object_uuid(obj, relative_path))
+            }),
+        );
+        let duplicate_warnings = duplicate_id_warnings(&uuid_by_path);
+
+        let mut results = Vec::with_capacity(collected.len());
+        for entry in &collected {
+            results.push(match entry {
+                Ok((removed, vault)) => self.write_subtree(
+                    removed,
+                    vault,
+                    &link_index,
+                    &uuid_by_path,
+                    &duplicate_warnings,
+                    db,
+                ),
+                // 索引仍借用着收集结果，错误按完整信息重新构造
+                Err(e) => Err(anyhow::anyhow!("{:#}", e)),
+            });
+        }
+        db.prune_orphan_sentinels()?;
+
+        Ok(results)
+    }
+
+    /// 写入一个子目录的节点和边
+    ///
+    /// [`Self::sync_subtrees`] 的内部步骤，`removed` 为清除前该子目录下的节点 UUID。
+    fn write_subtree(
+        &self,
+        removed: &[String],
+        vault: &CollectedVault,
+        link_index: &LinkIndex,
+        uuid_by_path: &HashMap<&str, String>,
+        duplicate_warnings: &[String],
+        db: &mut Database,
+    ) -> Result<SyncResult> {
+        let objects = &vault.objects;
+        let mut warnings = vault.warnings.clone();
+        warnings.extend_from_slice(duplicate_warnings);
+
+        let subtree_uuids: HashMap<&str, String> = objects
+            .iter()
+            .map(|(_, relative_path)| {
+                let path = relative_path.as_str();
+                (path, uuid_by_path[path].clone())
+            })
+            .collect();
+
+        for (obj, relative_path) in objects {
+            let node = self.object_to_node(obj, relative_path);
+            db.upsert_node(&node)?;
+            self.save_node_properties(obj, &node, db)?;
+        }
+
+        let mut edge_count = 0;
+        if self.create_folder_nodes {
+            edge_count += self.sync_folder_nodes(&subtree_uuids, db)?;
+        }
+        for (obj, relative_path) in objects {
+            edge_count +=
+                self.sync_object_edges(obj, relative_path, link_index, uuid_by_path, db)?;
+        }
 
-            // 处理标签
-            for tag in obj.tags() {
-                let tag_uuid = format!("tag:{}", tag);
-                let edge = Edge {
-                    src_uuid: src_uuid.clone(),
-                    dst_uuid: tag_uuid,
-                    relation: "tagged".to_string(),
-                    weight: 1.0,
-                    source: "tag".to_string(),
-                };
-                db.upsert_edge(&edge)?;
-                edge_count += 1;
+        // 移除指向已删除文件的入链
+        for uuid in removed {
+            if db.get_node(uuid)?.is_none() {
+                db.delete_edges_by_node(uuid)?;
             }
         }
 
         Ok(SyncResult {
             nodes_synced: objects.len(),
             edges_created: edge_count,
+            warnings,
+            errors: vault.errors.clone(),
+            extension_counts: vault.extension_counts.clone(),
         })
     }
 
+    /// 仅重建边
+    ///
+    /// 不读取磁盘文件，而是从数据库中已存储的节点内容、属性、标签和别名还原对象，
+    /// 重新构建文件名与别名索引后清空 edges 表并重新创建所有边。
+    /// 适用于调整链接解析设置后快速重算链接图，比全量同步快得多。
+    /// 数据库中存在文件夹节点时，同时重建其 `contains` 边。
+    ///
+    /// # 参数
+    ///
+    /// * `db` - 数据库实例的可变引用
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(usize)` - 创建的边数量
+    /// * `Err(anyhow::Error)` - 数据库读写失败
+    pub fn rebuild_edges(&self, db: &mut Database) -> Result<usize> {
+        let nodes = db.get_all_nodes()?;
+        let has_folder_nodes = nodes.iter().any(|n| n.node_type == FOLDER_NODE_TYPE);
+
+        let mut objects = Vec::new();
+        for node in nodes {
+            if node.node_type != FOLDER_NODE_TYPE {
+                objects.push((db.load_object(&node.uuid)?, node.path));
+            }
+        }
+
+        let filename_index = self.build_filename_index(&objects);
+        let alias_index = Self::index_aliases(objects.iter().flat_map(|(obj, relative_path)| {
+            obj.aliases()
+                .iter()
+                .map(move |alias| (alias.as_str(), relative_path.as_str()))
+        }));
+        let uuid_by_path: HashMap<&str, String> = objects
+            .iter()
+            .map(|(obj, relative_path)| (relative_path.as_str(), obj.id.to_string()))
+            .collect();
+
+        // 不读取磁盘，沿用上次同步时确认存在的附件
+        let attachment_index: HashSet<String> = db
+            .get_all_edges()?
+            .into_iter()
+            .filter_map(|e| e.dst_uuid.strip_prefix("file:").map(str::to_string))
+            .collect();
+        let link_index = LinkIndex {
+            filenames: &filename_index,
+            aliases: &alias_index,
+            attachments: &attachment_index,
+        };
+
+        db.clear_edges()?;
+
+        let mut edge_count = 0;
+        if has_folder_nodes {
+            edge_count += self.sync_folder_nodes(&uuid_by_path, db)?;
+        }
+        for (obj, relative_path) in &objects {
+            edge_count +=
+                self.sync_object_edges(obj, relative_path, &link_index, &uuid_by_path, db)?;
+        }
+
+        Ok(edge_count)
+    }
+
     /// 同步单个文件
     ///
     /// 处理单个文件的变化，更新对应的节点。
@@ -277,9 +899,9 @@ impl VaultSyncer {
                 .unwrap_or(file_path)
                 .to_string_lossy()
                 .to_string();
-            let uuid = path_to_uuid(&relative_path);
-            db.delete_node(&uuid)?;
-            db.delete_edges_by_node(&uuid)?;
+            let uuid = uuid_for_path(db, &relative_path)?;
+            db.delete_object(&uuid)?;
+            db.prune_orphan_sentinels()?;
             return Ok(true);
         }
 
@@ -300,26 +922,38 @@ impl VaultSyncer {
             .to_string();
 
         // 使用适配器加载对象
-        let obj = adapter
+        let mut obj = adapter
             .load(Path::new(&relative_path), &content)
             .context("解析文件失败")?;
+        self.prepare_object(&mut obj, &relative_path);
 
         // 转换为节点并保存
         let node = self.object_to_node(&obj, &relative_path);
+
+        // frontmatter `id` 增删或修改后 UUID 会变化，移除该路径下的旧节点
+        if let Some(existing) = db.get_node_by_path(&relative_path)? {
+            if existing.uuid != node.uuid {
+                db.delete_object(&existing.uuid)?;
+            }
+        }
+
         db.upsert_node(&node)?;
+        self.save_node_properties(&obj, &node, db)?;
 
         // 更新边（先删除旧边）
-        let uuid = path_to_uuid(&relative_path);
+        let uuid = node.uuid.clone();
         db.delete_edges_by_node(&uuid)?;
 
         // 重新创建标签边
-        for tag in obj.tags() {
+        let tags = self.object_tags(&obj);
+        db.save_tags(&uuid, &tags)?;
+        for tag in &tags {
             let edge = Edge {
                 src_uuid: uuid.clone(),
                 dst_uuid: format!("tag:{}", tag),
                 relation: "tagged".to_string(),
-                weight: 1.0,
-                source: "tag".to_string(),
+                weight: self.edge_weight("tagged", EdgeSource::Tag),
+                source: EdgeSource::Tag.as_str().to_string(),
             };
             db.upsert_edge(&edge)?;
         }
@@ -327,53 +961,506 @@ impl VaultSyncer {
         // 注意：wikilink 边需要完整的文件名索引才能正确解析
         // 增量同步时可能需要重新扫描或使用缓存
 
+        db.prune_orphan_sentinels()?;
         Ok(true)
     }
 
-    /// 收集知识库中所有对象
+    /// 同步文件重命名
     ///
-    /// 遍历目录，使用适配器将文件转换为 CognitiveObject。
-    fn collect_objects(&self, vault_path: &Path) -> Result<Vec<(CognitiveObject, String)>> {
-        let mut objects = Vec::new();
-
-        for entry in WalkDir::new(vault_path)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-
-            // 查找适配器
-            if let Some(adapter) = self.registry.find_adapter_for_path(path) {
-                if let Ok(content) = fs::read(path) {
-                    let relative_path = path
-                        .strip_prefix(vault_path)
-                        .unwrap_or(path)
-                        .to_string_lossy()
-                        .to_string();
-
-                    if let Ok(obj) = adapter.load(Path::new(&relative_path), &content) {
-                        objects.push((obj, relative_path));
-                    }
-                }
-            }
-        }
-
-        Ok(objects)
-    }
-
-    /// 构建文件名到 UUID 的索引
+    /// 在文件已于磁盘上重命名/移动后调用。以新路径重新加载文件，
+    /// 并将旧节点的所有入链、出链和标签迁移到新节点，无需全量同步。
+    /// 声明了 frontmatter `id` 的笔记 UUID 不变，仅更新路径。
+    /// 文件名改变时重新同步链接到该文件的笔记，入链按新的索引重新解析
+    /// （通过别名仍能解析，`[[旧名]]` 变为断链）。
     ///
-    /// 用于解析 wikilinks（wikilinks 通常引用文件名而非完整路径）。
-    fn build_filename_index(
-        &self,
+    /// # 参数
+    ///
+    /// * `old_path` - 旧文件绝对路径
+    /// * `new_path` - 新文件绝对路径
+    /// * `vault_path` - 知识库根目录
+    /// * `db` - 数据库实例
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(true)` - 重命名已同步
+    /// * `Ok(false)` - 新文件类型不受支持（旧节点仍会被移除）
+    /// * `Err(anyhow::Error)` - 同步失败
+    pub fn rename_file(
+        &self,
+        old_path: &Path,
+        new_path: &Path,
+        vault_path: &Path,
+        db: &mut Database,
+    ) -> Result<bool> {
+        let old_relative = old_path
+            .strip_prefix(vault_path)
+            .unwrap_or(old_path)
+            .to_string_lossy()
+            .to_string();
+        let old_uuid = uuid_for_path(db, &old_relative)?;
+
+        let adapter = match self.registry.find_adapter_for_path(new_path) {
+            Some(a) => a,
+            None => {
+                db.delete_object(&old_uuid)?;
+                return Ok(false);
+            }
+        };
+
+        let content = fs::read(new_path).context("读取文件失败")?;
+        let new_relative = new_path
+            .strip_prefix(vault_path)
+            .unwrap_or(new_path)
+            .to_string_lossy()
+            .to_string();
+        let mut obj = adapter
+            .load(Path::new(&new_relative), &content)
+            .context("解析文件失败")?;
+        self.prepare_object(&mut obj, &new_relative);
+
+        let node = self.object_to_node(&obj, &new_relative);
+        let linking_paths: Vec<String> = if old_path.file_stem() != new_path.file_stem() {
+            let mut paths = Vec::new();
+            for edge in db.get_edges_by_node(&old_uuid)? {
+                if edge.dst_uuid != old_uuid || edge.src_uuid == old_uuid {
+                    continue;
+                }
+                if let Some(src) = db.get_node(&edge.src_uuid)? {
+                    paths.push(src.path);
+                }
+            }
+            paths
+        } else {
+            Vec::new()
+        };
+        db.rename_node(&old_uuid, &node)?;
+        if old_uuid != node.uuid {
+            db.delete_properties(&old_uuid)?;
+            db.save_aliases(&old_uuid, &[])?;
+            db.save_sources(&old_uuid, &[])?;
+        }
+        self.save_node_properties(&obj, &node, db)?;
+
+        let linking_paths: Vec<&Path> = linking_paths.iter().map(Path::new).collect();
+        for result in self.sync_subtrees(vault_path, &linking_paths, db)? {
+            result?;
+        }
+
+        Ok(true)
+    }
+
+    /// 创建对象的出边
+    ///
+    /// 根据适配器提取的链接和对象标签创建边：外部链接指向 `url:` 哨兵，
+    /// 内部链接按 [`Self::resolve_link_target`] 解析，标签指向 `tag:` 哨兵。
+    ///
+    /// # 参数
+    ///
+    /// * `obj` - 认知对象
+    /// * `relative_path` - 对象的相对路径
+    /// * `link_index` - 文件名、别名与附件索引
+    /// * `uuid_by_path` - 相对路径到节点 UUID 的映射，需包含索引中的所有路径
+    /// * `db` - 数据库实例
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(usize)` - 创建的边数量
+    /// * `Err(anyhow::Error)` - 数据库写入失败
+    fn sync_object_edges(
+        &self,
+        obj: &CognitiveObject,
+        relative_path: &str,
+        link_index: &LinkIndex,
+        uuid_by_path: &HashMap<&str, String>,
+        db: &mut Database,
+    ) -> Result<usize> {
+        let src_uuid = uuid_by_path[relative_path].clone();
+        let mut edge_count = 0;
+
+        // 从适配器提取链接
+        if let Some(adapter) = self
+            .registry
+            .find_adapter_for_path(Path::new(relative_path))
+        {
+            let links = adapter.extract_links(obj);
+
+            for link in links {
+                // 外部链接指向 `url:` 哨兵
+                if link.kind == LinkKind::External {
+                    let edge = Edge {
+                        src_uuid: src_uuid.clone(),
+                        dst_uuid: format!("url:{}", link.target),
+                        relation: "link".to_string(),
+                        weight: self.edge_weight("link", EdgeSource::External),
+                        source: EdgeSource::External.as_str().to_string(),
+                    };
+                    db.upsert_edge(&edge)?;
+                    edge_count += 1;
+                    continue;
+                }
+
+                // 按文件名、别名与就近原则解析链接目标
+                let (resolved, resolution) = self.resolve_link(
+                    &link.target,
+                    relative_path,
+                    link_index.filenames,
+                    link_index.aliases,
+                );
+
+                // 未索引但存在于知识库中的嵌入文件（如图片）指向 `file:` 哨兵
+                if resolved.is_empty()
+                    && link.kind == LinkKind::Embed
+                    && Self::attachment_exists(&link.target, link_index.attachments)
+                {
+                    let edge = Edge {
+                        src_uuid: src_uuid.clone(),
+                        dst_uuid: format!("file:{}", link.target),
+                        relation: "link".to_string(),
+                        weight: self.edge_weight("link", EdgeSource::Embed),
+                        source: EdgeSource::Embed.as_str().to_string(),
+                    };
+                    db.upsert_edge(&edge)?;
+                    edge_count += 1;
+                    continue;
+                }
+
+                // 无法解析的链接指向 `missing:` 哨兵，用于统计断链
+                if resolved.is_empty() {
+                    let source = link_edge_source(&link.kind);
+                    let edge = Edge {
+                        src_uuid: src_uuid.clone(),
+                        dst_uuid: format!("missing:{}", link.target),
+                        relation: "link".to_string(),
+                        weight: self.edge_weight("link", source),
+                        source: source.as_str().to_string(),
+                    };
+                    db.upsert_edge(&edge)?;
+                    edge_count += 1;
+                    continue;
+                }
+
+                for dst_path in resolved {
+                    let dst_uuid = uuid_by_path[dst_path.as_str()].clone();
+                    // 笔记链接到自身通常是误操作，默认跳过
+                    if dst_uuid == src_uuid && !self.allow_self_links {
+                        continue;
+                    }
+
+                    let source = link_edge_source(&link.kind);
+                    let edge = Edge {
+                        src_uuid: src_uuid.clone(),
+                        dst_uuid,
+                        relation: "link".to_string(),
+                        weight: self.edge_weight("link", source),
+                        source: source.as_str().to_string(),
+                    };
+                    db.upsert_edge(&edge)?;
+                    db.set_edge_resolution(&edge.src_uuid, &edge.dst_uuid, resolution)?;
+                    edge_count += 1;
+                }
+            }
+        }
+
+        // 处理标签
+        let tags = self.object_tags(obj);
+        db.save_tags(&src_uuid, &tags)?;
+        for tag in &tags {
+            let edge = Edge {
+                src_uuid: src_uuid.clone(),
+                dst_uuid: format!("tag:{}", tag),
+                relation: "tagged".to_string(),
+                weight: self.edge_weight("tagged", EdgeSource::Tag),
+                source: EdgeSource::Tag.as_str().to_string(),
+            };
+            db.upsert_edge(&edge)?;
+            edge_count += 1;
+        }
+
+        Ok(edge_count)
+    }
+
+    /// 创建文件夹节点及其包含边
+    ///
+    /// 根据已索引文件的路径推导出所有祖先目录（知识库根目录除外），
+    /// 为每个目录创建文件夹节点，并创建父目录到子项的 `contains` 边。
+    ///
+    /// # 参数
+    ///
+    /// * `uuid_by_path` - 已索引文件的相对路径到节点 UUID 的映射
+    /// * `db` - 数据库实例
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(usize)` - 创建的边数量
+    /// * `Err(anyhow::Error)` - 数据库写入失败
+    fn sync_folder_nodes(
+        &self,
+        uuid_by_path: &HashMap<&str, String>,
+        db: &mut Database,
+    ) -> Result<usize> {
+        let mut folders: HashMap<String, String> = HashMap::new();
+        let mut edges: Vec<(String, String)> = Vec::new();
+
+        for (relative_path, uuid) in uuid_by_path {
+            let mut child_uuid = uuid.clone();
+            let mut current = Path::new(relative_path).parent();
+
+            while let Some(dir) = current.filter(|d| !d.as_os_str().is_empty()) {
+                let dir_path = dir.to_string_lossy().to_string();
+                let dir_uuid = folder_uuid(&dir_path);
+                edges.push((dir_uuid.clone(), child_uuid));
+
+                // 上层目录已由其他文件处理过
+                if folders.insert(dir_path, dir_uuid.clone()).is_some() {
+                    break;
+                }
+
+                child_uuid = dir_uuid;
+                current = dir.parent();
+            }
+        }
+
+        for (dir_path, uuid) in &folders {
+            let title = Path::new(dir_path)
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or(dir_path)
+                .to_string();
+
+            db.upsert_node(&Node {
+                uuid: uuid.clone(),
+                path: dir_path.clone(),
+                title,
+                content: String::new(),
+                node_type: FOLDER_NODE_TYPE.to_string(),
+                hash: calculate_hash(""),
+                created_at: 0,
+                updated_at: 0,
+            })?;
+        }
+
+        for (src_uuid, dst_uuid) in &edges {
+            db.upsert_edge(&Edge {
+                src_uuid: src_uuid.clone(),
+                dst_uuid: dst_uuid.clone(),
+                relation: "contains".to_string(),
+                weight: self.edge_weight("contains", EdgeSource::Folder),
+                source: EdgeSource::Folder.as_str().to_string(),
+            })?;
+        }
+
+        Ok(edges.len())
+    }
+
+    /// 收集知识库中所有对象
+    ///
+    /// 遍历目录，使用适配器将文件转换为 CognitiveObject。
+    /// 被跳过的符号链接（未开启跟随或形成循环）记录在返回的警告中，
+    /// 读取或解析失败的文件连同错误信息记录在返回的错误中，不影响其他文件。
+    /// 同时按扩展名统计所有文件（包括没有适配器的文件）。
+    ///
+    /// 隐藏文件和目录（如 `.cognistruct/templates`、`.obsidian`）、
+    /// 开启 `respect_gitignore` 时被 `.gitignore` 忽略的文件和目录，
+    /// 以及匹配 `ignore_patterns` 的文件和目录不会被遍历。
+    ///
+    /// 先顺序遍历收集待加载路径，再（按配置并行地）读取和解析，输出顺序与遍历顺序一致。
+    ///
+    /// # 参数
+    ///
+    /// * `vault_path` - 知识库根目录，用于计算相对路径
+    /// * `root` - 遍历起点（知识库根目录或其子目录）
+    fn collect_objects(&self, vault_path: &Path, root: &Path) -> Result<CollectedVault> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        let mut extension_counts: HashMap<String, usize> = HashMap::new();
+        let mut attachments = Vec::new();
+        let (entries, warnings) = self.walk_vault(vault_path, root);
+        for entry in &entries {
+            let path = entry.path();
+            let has_adapter = self.registry.find_adapter_for_path(path).is_some();
+            let relative = path.strip_prefix(vault_path).unwrap_or(path);
+
+            // 统计扩展名
+            if entry.file_type().is_file() {
+                let extension = path
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                *extension_counts.entry(extension).or_insert(0) += 1;
+                if !has_adapter {
+                    attachments.push(relative.to_string_lossy().to_string());
+                }
+            }
+
+            // 仅收集有适配器的文件
+            if has_adapter {
+                paths.push(path.to_path_buf());
+            }
+        }
+
+        let load = |path: &PathBuf| self.load_file(path, vault_path);
+        let loaded: Vec<Result<(CognitiveObject, String), (String, String)>> = if self.parallel {
+            paths.par_iter().map(load).collect()
+        } else {
+            paths.iter().map(load).collect()
+        };
+
+        let mut objects = Vec::with_capacity(loaded.len());
+        let mut errors = Vec::new();
+        for result in loaded {
+            match result {
+                Ok(object) => objects.push(object),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        Ok(CollectedVault {
+            objects,
+            warnings,
+            errors,
+            extension_counts,
+            attachments,
+        })
+    }
+
+    /// 收集知识库中没有适配器处理的文件（附件）的相对路径
+    ///
+    /// 遵循与 [`Self::collect_objects`] 相同的忽略规则，但不读取文件内容。
+    fn collect_attachments(&self, vault_path: &Path) -> Vec<String> {
+        let (entries, _) = self.walk_vault(vault_path, vault_path);
+        entries
+            .iter()
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| self.registry.find_adapter_for_path(entry.path()).is_none())
+            .map(|entry| {
+                entry
+                    .path()
+                    .strip_prefix(vault_path)
+                    .unwrap_or(entry.path())
+            })
+            .map(|relative| relative.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// 遍历目录，返回未被忽略的条目和遍历警告
+    ///
+    /// 跳过隐藏文件和目录，以及被 `.gitignore` 或 `ignore_patterns` 忽略的文件和目录。
+    ///
+    /// # 参数
+    ///
+    /// * `vault_path` - 知识库根目录，用于计算相对路径
+    /// * `root` - 遍历起点（知识库根目录或其子目录）
+    fn walk_vault(&self, vault_path: &Path, root: &Path) -> (Vec<walkdir::DirEntry>, Vec<String>) {
+        let mut entries = Vec::new();
+        let mut warnings = Vec::new();
+        let mut gitignore = self
+            .respect_gitignore
+            .then(|| GitignoreFilter::new(vault_path));
+
+        let walker = WalkDir::new(root)
+            .follow_links(self.follow_links)
+            .into_iter()
+            .filter_entry(|entry| {
+                let is_dir = entry.file_type().is_dir();
+                let relative = entry
+                    .path()
+                    .strip_prefix(vault_path)
+                    .unwrap_or(entry.path());
+                let ignored_by_pattern = self.ignore_patterns.as_ref().is_some_and(|patterns| {
+                    // 只同步单个文件时不会经过其所在目录，需同时检查上级目录
+                    patterns
+                        .matched_path_or_any_parents(relative, is_dir)
+                        .is_ignore()
+                });
+                !is_hidden(relative)
+                    && !ignored_by_pattern
+                    && gitignore
+                        .as_mut()
+                        .is_none_or(|filter| !filter.is_ignored(entry.path(), is_dir))
+            });
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    let path = e
+                        .path()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    if e.loop_ancestor().is_some() {
+                        warnings.push(format!("跳过形成循环的符号链接: {}", path));
+                    } else {
+                        warnings.push(format!("遍历失败: {}: {}", path, e));
+                    }
+                    continue;
+                }
+            };
+
+            if !self.follow_links && entry.path_is_symlink() {
+                warnings.push(format!("跳过符号链接: {}", entry.path().to_string_lossy()));
+                continue;
+            }
+
+            entries.push(entry);
+        }
+
+        (entries, warnings)
+    }
+
+    /// 读取并解析单个文件
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok((CognitiveObject, String))` - 加载的对象及其相对路径
+    /// * `Err((String, String))` - 读取或解析失败的相对路径及错误信息
+    fn load_file(
+        &self,
+        path: &Path,
+        vault_path: &Path,
+    ) -> Result<(CognitiveObject, String), (String, String)> {
+        let relative_path = path
+            .strip_prefix(vault_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+
+        let adapter = match self.registry.find_adapter_for_path(path) {
+            Some(adapter) => adapter,
+            None => return Err((relative_path, "没有可用的适配器".to_string())),
+        };
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(e) => return Err((relative_path, e.to_string())),
+        };
+
+        match adapter.load(Path::new(&relative_path), &content) {
+            Ok(mut obj) => {
+                self.prepare_object(&mut obj, &relative_path);
+                Ok((obj, relative_path))
+            }
+            Err(e) => Err((relative_path, format!("{:#}", e))),
+        }
+    }
+
+    /// 构建文件名到相对路径的索引
+    ///
+    /// 用于解析 wikilinks（wikilinks 通常引用文件名而非完整路径）。
+    fn build_filename_index(
+        &self,
         objects: &[(CognitiveObject, String)],
     ) -> HashMap<String, Vec<String>> {
-        let mut index: HashMap<String, Vec<String>> = HashMap::new();
+        Self::index_filenames(
+            objects
+                .iter()
+                .map(|(_, relative_path)| relative_path.as_str()),
+        )
+    }
 
-        for (_obj, relative_path) in objects {
-            let uuid = path_to_uuid(relative_path);
+    /// 按文件名（不含扩展名）索引相对路径
+    fn index_filenames<'a>(
+        paths: impl IntoIterator<Item = &'a str>,
+    ) -> HashMap<String, Vec<String>> {
+        let mut index: HashMap<String, Vec<String>> = HashMap::new();
 
+        for relative_path in paths {
             // 提取文件名（不含扩展名）
             let filename = Path::new(relative_path)
                 .file_stem()
@@ -381,287 +1468,2184 @@ impl VaultSyncer {
                 .unwrap_or("")
                 .to_string();
 
-            index.entry(filename).or_default().push(uuid);
+            index
+                .entry(filename)
+                .or_default()
+                .push(relative_path.to_string());
+        }
+
+        index
+    }
+
+    /// 按相对路径和文件名索引附件
+    fn index_attachments<'a>(paths: impl IntoIterator<Item = &'a str>) -> HashSet<String> {
+        let mut index = HashSet::new();
+        for relative_path in paths {
+            let normalized = relative_path.replace('\\', "/");
+            if let Some((_, name)) = normalized.rsplit_once('/') {
+                index.insert(name.to_string());
+            }
+            index.insert(normalized);
+        }
+        index
+    }
+
+    /// 检查嵌入目标是否对应知识库中的附件
+    ///
+    /// 目标可以是文件名（任意目录下）、完整相对路径或路径后缀。
+    fn attachment_exists(target: &str, attachments: &HashSet<String>) -> bool {
+        let target = target.trim_start_matches('/');
+        if attachments.contains(target) {
+            return true;
+        }
+        let suffix = format!("/{}", target);
+        target.contains('/') && attachments.iter().any(|path| path.ends_with(&suffix))
+    }
+
+    /// 按别名索引相对路径
+    fn index_aliases<'a>(
+        entries: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> HashMap<String, Vec<String>> {
+        let mut index: HashMap<String, Vec<String>> = HashMap::new();
+
+        for (alias, relative_path) in entries {
+            let paths = index.entry(alias.to_string()).or_default();
+            if !paths.iter().any(|p| p == relative_path) {
+                paths.push(relative_path.to_string());
+            }
         }
 
         index
     }
 
+    /// 解析链接目标（含别名）
+    ///
+    /// 先按文件名解析；没有同名文件时，再按别名解析。两种方式都遵循就近原则。
+    ///
+    /// # 参数
+    ///
+    /// * `target` - 链接目标（不含扩展名）
+    /// * `source_path` - 源笔记的相对路径
+    /// * `filename_index` - 文件名索引
+    /// * `alias_index` - 别名索引
+    ///
+    /// # 返回值
+    ///
+    /// 目标文件的相对路径列表（无法解析时为空）及解析方式；通过别名解析时方式为 `Alias`
+    fn resolve_link(
+        &self,
+        target: &str,
+        source_path: &str,
+        filename_index: &HashMap<String, Vec<String>>,
+        alias_index: &HashMap<String, Vec<String>>,
+    ) -> (Vec<String>, LinkResolution) {
+        let (resolved, method) = self.resolve_link_target(target, source_path, filename_index);
+        if !resolved.is_empty() {
+            return (resolved, method);
+        }
+        let (resolved, _) = self.resolve_link_target(target, source_path, alias_index);
+        (resolved, LinkResolution::Alias)
+    }
+
+    /// 解析链接目标
+    ///
+    /// 解析顺序（与 Obsidian 的就近规则一致）：
+    ///
+    /// 1. 目标包含路径（如 `[[b/note]]`）且与某个文件路径（不含扩展名）完全匹配时，使用该文件
+    /// 2. 同名文件唯一时直接使用
+    /// 3. 同名文件有多个时，优先源笔记所在目录，其次最近的祖先目录
+    /// 4. 无法按就近规则区分时，返回全部同名文件
+    ///
+    /// # 参数
+    ///
+    /// * `target` - 链接目标（不含扩展名）
+    /// * `source_path` - 源笔记的相对路径
+    /// * `filename_index` - `build_filename_index` 构建的索引
+    ///
+    /// # 返回值
+    ///
+    /// 目标文件的相对路径列表（无法解析时为空）及对应的解析方式
+    fn resolve_link_target(
+        &self,
+        target: &str,
+        source_path: &str,
+        filename_index: &HashMap<String, Vec<String>>,
+    ) -> (Vec<String>, LinkResolution) {
+        let target_path = Path::new(target);
+        let filename = target_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or(target);
+
+        let candidates = match filename_index.get(filename) {
+            Some(candidates) => candidates,
+            None => return (Vec::new(), LinkResolution::Filename),
+        };
+
+        // 路径形式的链接：精确匹配路径
+        if target_path.components().count() > 1 {
+            let exact: Vec<String> = candidates
+                .iter()
+                .filter(|path| Path::new(path).with_extension("") == target_path)
+                .cloned()
+                .collect();
+            if !exact.is_empty() {
+                return (exact, LinkResolution::Path);
+            }
+        }
+
+        if candidates.len() <= 1 {
+            return (candidates.clone(), LinkResolution::Filename);
+        }
+
+        // 就近原则：候选所在目录须为源目录或其祖先，目录层级越深越近
+        let source_dir = Path::new(source_path).parent().unwrap_or(Path::new(""));
+        let depth_of = |path: &String| {
+            let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+            source_dir
+                .starts_with(dir)
+                .then(|| dir.components().count())
+        };
+
+        let best_depth = match candidates.iter().filter_map(depth_of).max() {
+            Some(depth) => depth,
+            None => return (candidates.clone(), LinkResolution::Filename),
+        };
+
+        let nearest: Vec<String> = candidates
+            .iter()
+            .filter(|path| depth_of(path) == Some(best_depth))
+            .cloned()
+            .collect();
+
+        if nearest.len() == 1 {
+            (nearest, LinkResolution::Proximity)
+        } else {
+            (candidates.clone(), LinkResolution::Filename)
+        }
+    }
+
+    /// 获取对象的标签
+    ///
+    /// 开启大小写折叠时将标签转为小写并去重，保持首次出现的顺序。
+    fn object_tags(&self, obj: &CognitiveObject) -> Vec<String> {
+        if !self.fold_tag_case {
+            return obj.tags().to_vec();
+        }
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in obj.tags() {
+            let folded = tag.to_lowercase();
+            if !tags.contains(&folded) {
+                tags.push(folded);
+            }
+        }
+        tags
+    }
+
+    /// 保存节点属性
+    ///
+    /// 以对象属性（frontmatter 及内联字段）替换 properties 表中的记录，写入字数属性，
+    /// 并保存别名（用于链接解析）和序列化源（对象的物理表示）。
+    fn save_node_properties(
+        &self,
+        obj: &CognitiveObject,
+        node: &Node,
+        db: &mut Database,
+    ) -> Result<()> {
+        db.save_properties(&node.uuid, obj.properties())?;
+        db.save_aliases(&node.uuid, obj.aliases())?;
+        db.save_sources(&node.uuid, obj.sources())?;
+        self.save_word_count(node, db)
+    }
+
+    /// 保存节点的字数属性
+    ///
+    /// 以 [`WORD_COUNT_PROPERTY`] 属性存储正文词数，便于聚合查询（如全库总字数）。
+    fn save_word_count(&self, node: &Node, db: &mut Database) -> Result<()> {
+        let stats = compute_note_stats(&node.content);
+        db.save_property(
+            &node.uuid,
+            WORD_COUNT_PROPERTY,
+            &PropertyValue::integer(stats.word_count as i64),
+        )
+    }
+
+    /// 完成适配器加载后的对象处理
+    ///
+    /// 应用类型推断规则，并将对象 ID 设为其节点 UUID（见 [`object_uuid`]），
+    /// 使按对象 ID 查询属性、别名等记录时与数据库节点一致。
+    fn prepare_object(&self, obj: &mut CognitiveObject, relative_path: &str) {
+        self.apply_type_rules(obj);
+        obj.id = ObjectId::from_string(object_uuid(obj, relative_path));
+    }
+
+    /// 按类型推断规则设置对象的 `inferred_type`
+    fn apply_type_rules(&self, obj: &mut CognitiveObject) {
+        obj.inferred_type = rules::infer_type(&self.type_rules, obj).map(str::to_string);
+    }
+
     /// 将 CognitiveObject 转换为数据库 Node
+    ///
+    /// 在 [`Node::from`] 的基础上，使用 [`object_uuid`] 确定 UUID 并覆盖路径，
+    /// 缺少标题时使用该路径的文件名，存在推演类型时以其作为节点类型。
     fn object_to_node(&self, obj: &CognitiveObject, relative_path: &str) -> Node {
-        let uuid = path_to_uuid(relative_path);
-        let now = chrono::Utc::now().timestamp();
+        let mut node = Node::from(obj);
+        node.uuid = object_uuid(obj, relative_path);
+        node.path = relative_path.to_string();
+        if let Some(inferred_type) = &obj.inferred_type {
+            node.node_type = inferred_type.clone();
+        }
 
-        // 获取标题，优先使用对象的 title 属性，否则使用文件名
-        let title = obj.title().map(|s| s.to_string()).unwrap_or_else(|| {
-            Path::new(relative_path)
+        if obj.title().is_none() {
+            node.title = Path::new(relative_path)
                 .file_stem()
                 .and_then(|s| s.to_str())
                 .unwrap_or("Untitled")
-                .to_string()
-        });
+                .to_string();
+        }
+
+        node
+    }
+}
+
+impl Default for VaultSyncer {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
 
-        // 获取内容
-        let content = obj.content().unwrap_or("").to_string();
+/// 同步结果
+///
+/// 记录同步操作的统计信息。
+#[derive(Debug, Clone)]
+pub struct SyncResult {
+    /// 同步的节点数量
+    pub nodes_synced: usize,
+    /// 创建的边数量
+    pub edges_created: usize,
+    /// 同步过程中的警告（如被跳过的符号链接）
+    pub warnings: Vec<String>,
+    /// 读取或解析失败而未被索引的文件 `(相对路径, 错误信息)`
+    pub errors: Vec<(String, String)>,
+    /// 按扩展名统计的文件数量（小写、不含点号，无扩展名记为空字符串）
+    ///
+    /// 包括没有适配器处理的文件，不包括隐藏文件。
+    pub extension_counts: HashMap<String, usize>,
+}
+
+/// 目录遍历收集结果
+///
+/// `collect_objects` 的内部返回值。
+struct CollectedVault {
+    /// 加载成功的对象及其相对路径
+    objects: Vec<(CognitiveObject, String)>,
+    /// 遍历过程中的警告
+    warnings: Vec<String>,
+    /// 加载失败的文件及错误信息
+    errors: Vec<(String, String)>,
+    /// 按扩展名统计的文件数量
+    extension_counts: HashMap<String, usize>,
+    /// 没有适配器处理的文件（附件）的相对路径
+    attachments: Vec<String>,
+}
+
+/// 解析链接所需的索引
+struct LinkIndex<'a> {
+    /// 文件名索引
+    filenames: &'a HashMap<String, Vec<String>>,
+    /// 别名索引
+    aliases: &'a HashMap<String, Vec<String>>,
+    /// 附件的相对路径与文件名
+    attachments: &'a HashSet<String>,
+}
+
+/// 检查相对路径是否位于隐藏文件或目录中
+///
+/// 任一路径组件以 `.` 开头即视为隐藏（如 `.cognistruct/db`、`.obsidian/app.json`）。
+fn is_hidden(relative_path: &Path) -> bool {
+    relative_path
+        .components()
+        .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+}
+
+/// 基于已索引数据解析 wikilink 目标
+///
+/// 使用与同步相同的规则（文件名、别名、就近原则）将链接目标解析为相对路径。
+/// 目标中的 `#标题`、`#^块` 和 `|显示文本` 部分会被忽略。
+///
+/// # 参数
+///
+/// * `db` - 数据库实例
+/// * `source_path` - 源笔记的相对路径
+/// * `target` - 链接目标（`[[` 与 `]]` 之间的内容）
+///
+/// # 返回值
+///
+/// * `Ok(Some(String))` - 解析到的相对路径；有多个同等候选时返回排序后的第一个
+/// * `Ok(None)` - 目标不存在（悬空链接）
+/// * `Err(anyhow::Error)` - 数据库查询失败
+pub fn resolve_wikilink(db: &Database, source_path: &str, target: &str) -> Result<Option<String>> {
+    let target = target.split('|').next().unwrap_or(target);
+    let target = target.split('#').next().unwrap_or(target).trim();
+    if target.is_empty() {
+        return Ok(None);
+    }
+
+    let paths: Vec<String> = db
+        .get_all_nodes()?
+        .into_iter()
+        .filter(|node| node.node_type != FOLDER_NODE_TYPE)
+        .map(|node| node.path)
+        .collect();
+    let filename_index = VaultSyncer::index_filenames(paths.iter().map(|p| p.as_str()));
+
+    let alias_paths = db.get_alias_paths()?;
+    let alias_index = VaultSyncer::index_aliases(
+        alias_paths
+            .iter()
+            .map(|(alias, path)| (alias.as_str(), path.as_str())),
+    );
+
+    let (mut resolved, _) = VaultSyncer::with_defaults().resolve_link(
+        target,
+        source_path,
+        &filename_index,
+        &alias_index,
+    );
+    resolved.sort();
+
+    Ok(resolved.into_iter().next())
+}
+
+/// 同步知识库（兼容旧接口）
+///
+/// 使用默认适配器同步整个知识库。
+/// 这是一个便捷函数，保持向后兼容。
+///
+/// # 参数
+///
+/// * `vault_path` - 知识库根目录路径
+/// * `db` - 数据库实例的可变引用
+///
+/// # 返回值
+///
+/// * `Ok(())` - 同步成功
+/// * `Err(anyhow::Error)` - 同步失败
+pub fn sync_vault(vault_path: &Path, db: &mut Database) -> Result<()> {
+    let syncer = VaultSyncer::with_defaults();
+    syncer.sync_full(vault_path, db)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_calculate_hash() {
+        let content1 = "Hello, World!";
+        let content2 = "Hello, World!";
+        let content3 = "Different content";
+
+        let hash1 = calculate_hash(content1);
+        let hash2 = calculate_hash(content2);
+        let hash3 = calculate_hash(content3);
+
+        assert_eq!(hash1, hash2);
+        assert_ne!(hash1, hash3);
+
+        // BLAKE3 输出稳定，可跨重启比较
+        assert_eq!(
+            calculate_hash("hello"),
+            "ea8f163db38682925e4491c5e58d4bb3506ef8c14eb78a86e908c5624a67200f"
+        );
+    }
+
+    #[test]
+    fn test_path_to_uuid() {
+        let uuid1 = path_to_uuid("notes/test.md");
+        let uuid2 = path_to_uuid("notes/test.md");
+        let uuid3 = path_to_uuid("notes/other.md");
+
+        assert_eq!(uuid1, uuid2);
+        assert_ne!(uuid1, uuid3);
+        // UUID 格式验证
+        assert!(uuid1.contains('-'));
+    }
+
+    #[test]
+    fn test_path_to_uuid_normalizes_separators() {
+        assert_eq!(path_to_uuid("a\\b.md"), path_to_uuid("a/b.md"));
+        assert_eq!(path_to_uuid("./a/b.md"), path_to_uuid("a/b.md"));
+        assert_eq!(path_to_uuid(" /a/b.md "), path_to_uuid("a/b.md"));
+
+        assert_eq!(normalize_uuid_path("Notes\\A.md", false), "Notes/A.md");
+        assert_eq!(normalize_uuid_path("Notes\\A.md", true), "notes/a.md");
+    }
+
+    #[test]
+    fn test_vault_syncer_new() {
+        let syncer = VaultSyncer::with_defaults();
+        // 验证能够创建
+        assert!(syncer.registry.find_adapter("md").is_some());
+    }
+
+    #[test]
+    fn test_sync_vault() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        // 创建测试文件
+        fs::write(
+            vault_path.join("note1.md"),
+            "# Note 1\n\nLink to [[note2]].\n\n#tag1",
+        )
+        .unwrap();
+
+        fs::write(
+            vault_path.join("note2.md"),
+            "# Note 2\n\nLink to [[note1]].\n\n#tag2",
+        )
+        .unwrap();
+
+        // 创建子目录
+        fs::create_dir(vault_path.join("subfolder")).unwrap();
+        fs::write(
+            vault_path.join("subfolder/note3.md"),
+            "# Note 3\n\nLink to [[note1]].\n\n#tag3",
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let db_path = db_dir.path().join("test.db");
+        let mut db = Database::new(db_path).unwrap();
+
+        // 使用兼容接口
+        sync_vault(vault_path, &mut db).unwrap();
+
+        let nodes = db.get_all_nodes().unwrap();
+        assert_eq!(nodes.len(), 3);
+
+        let titles: Vec<String> = nodes.iter().map(|n| n.title.clone()).collect();
+        assert!(titles.contains(&"Note 1".to_string()));
+        assert!(titles.contains(&"Note 2".to_string()));
+        assert!(titles.contains(&"Note 3".to_string()));
+    }
+
+    #[test]
+    fn test_sync_full_with_result() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        fs::write(
+            vault_path.join("note1.md"),
+            "# Note 1\n\nLink to [[note2]].\n\n#tag1",
+        )
+        .unwrap();
+
+        fs::write(vault_path.join("note2.md"), "# Note 2\n\nContent").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let db_path = db_dir.path().join("test.db");
+        let mut db = Database::new(db_path).unwrap();
+
+        let syncer = VaultSyncer::with_defaults();
+        let result = syncer.sync_full(vault_path, &mut db).unwrap();
+
+        assert_eq!(result.nodes_synced, 2);
+        assert!(result.edges_created >= 2); // 至少 1 个链接 + 1 个标签
+    }
+
+    #[test]
+    fn test_sync_file_new() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        let db_dir = TempDir::new().unwrap();
+        let db_path = db_dir.path().join("test.db");
+        let mut db = Database::new(db_path).unwrap();
+
+        // 创建文件
+        let file_path = vault_path.join("new_note.md");
+        fs::write(&file_path, "# New Note\n\nContent here.").unwrap();
+
+        let syncer = VaultSyncer::with_defaults();
+        let synced = syncer.sync_file(&file_path, vault_path, &mut db).unwrap();
+
+        assert!(synced);
+
+        let nodes = db.get_all_nodes().unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].title, "New Note");
+    }
+
+    #[test]
+    fn test_sync_file_updates_metadata() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+        fs::write(
+            vault_path.join("a.md"),
+            "---\ntags: [draft, rust]\naliases: [Alpha]\nstatus: draft\n---\n# A",
+        )
+        .unwrap();
+        fs::write(vault_path.join("b.md"), "---\ntags: [rust]\n---\n# B").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        let syncer = VaultSyncer::with_defaults();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+
+        let uuid = path_to_uuid("a.md");
+        let mut tags = db.get_tags(&uuid).unwrap();
+        tags.sort();
+        assert_eq!(tags, vec!["draft", "rust"]);
+
+        // 单文件编辑后无需全量同步即可反映新的标签、别名和属性
+        fs::write(
+            vault_path.join("a.md"),
+            "---\ntags: [rust, published]\naliases: [A1]\n---\n# A",
+        )
+        .unwrap();
+        syncer
+            .sync_file(&vault_path.join("a.md"), vault_path, &mut db)
+            .unwrap();
+
+        let mut tags = db.get_tags(&uuid).unwrap();
+        tags.sort();
+        assert_eq!(tags, vec!["published", "rust"]);
+        assert_eq!(db.get_aliases(&uuid).unwrap(), vec!["A1"]);
+        assert!(!db.get_properties(&uuid).unwrap().contains_key("status"));
+
+        // 其他笔记的元数据不受影响
+        assert_eq!(db.get_tags(&path_to_uuid("b.md")).unwrap(), vec!["rust"]);
+    }
+
+    #[test]
+    fn test_sync_prunes_orphan_tag_sentinel() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+        fs::write(vault_path.join("a.md"), "# A\n\n#rust #wasm").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        let syncer = VaultSyncer::with_defaults();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+
+        // 同步不会写入哨兵节点行，它们只来自导入的物化标签节点
+        assert!(db.get_node("tag:rust").unwrap().is_none());
+        let ndjson: String = ["rust", "wasm"]
+            .iter()
+            .map(|tag| {
+                let record = crate::db::ExportRecord::Node(Node {
+                    uuid: format!("tag:{}", tag),
+                    path: String::new(),
+                    title: tag.to_string(),
+                    content: String::new(),
+                    node_type: "tag".to_string(),
+                    hash: String::new(),
+                    created_at: 0,
+                    updated_at: 0,
+                });
+                serde_json::to_string(&record).unwrap() + "\n"
+            })
+            .collect();
+        db.import_vault_from_reader(std::io::Cursor::new(ndjson))
+            .unwrap();
+        assert!(db.get_node("tag:wasm").unwrap().is_some());
+        assert_eq!(db.prune_orphan_sentinels().unwrap(), 0);
+
+        // 移除 #wasm 后其哨兵不再被引用
+        fs::write(vault_path.join("a.md"), "# A\n\n#rust").unwrap();
+        syncer
+            .sync_file(&vault_path.join("a.md"), vault_path, &mut db)
+            .unwrap();
+
+        assert!(db.get_node("tag:wasm").unwrap().is_none());
+        assert!(db.get_node("tag:rust").unwrap().is_some());
+        assert_eq!(db.prune_orphan_sentinels().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sync_file_unsupported() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        let db_dir = TempDir::new().unwrap();
+        let db_path = db_dir.path().join("test.db");
+        let mut db = Database::new(db_path).unwrap();
+
+        // 创建不支持的文件类型
+        let file_path = vault_path.join("image.png");
+        fs::write(&file_path, "fake image data").unwrap();
+
+        let syncer = VaultSyncer::with_defaults();
+        let synced = syncer.sync_file(&file_path, vault_path, &mut db).unwrap();
+
+        assert!(!synced); // 不支持的文件类型
+    }
+
+    #[test]
+    fn test_sync_vault_empty() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        let db_dir = TempDir::new().unwrap();
+        let db_path = db_dir.path().join("test.db");
+        let mut db = Database::new(db_path).unwrap();
+
+        sync_vault(vault_path, &mut db).unwrap();
+
+        let nodes = db.get_all_nodes().unwrap();
+        let edges = db.get_all_edges().unwrap();
+        assert_eq!(nodes.len(), 0);
+        assert_eq!(edges.len(), 0);
+    }
+
+    #[test]
+    fn test_sync_vault_clear_old_data() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        fs::write(vault_path.join("note1.md"), "# Note 1\n\nContent").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let db_path = db_dir.path().join("test.db");
+        let mut db = Database::new(db_path).unwrap();
+
+        // 第一次同步
+        sync_vault(vault_path, &mut db).unwrap();
+        let nodes1 = db.get_all_nodes().unwrap();
+        assert_eq!(nodes1.len(), 1);
+
+        // 第二次同步 - 应该清除并重建
+        sync_vault(vault_path, &mut db).unwrap();
+        let nodes2 = db.get_all_nodes().unwrap();
+        assert_eq!(nodes2.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sync_full_symlink_loop() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        fs::create_dir(vault_path.join("sub")).unwrap();
+        fs::write(vault_path.join("sub/note.md"), "# Note\n\nContent").unwrap();
+        // sub/loop -> vault 根目录，形成循环
+        std::os::unix::fs::symlink(vault_path, vault_path.join("sub/loop")).unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        // 默认不跟随符号链接
+        let result = VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        assert_eq!(result.nodes_synced, 1);
+        assert!(result.warnings.iter().any(|w| w.contains("loop")));
+
+        // 跟随符号链接时依赖循环检测，不应挂起
+        let result = VaultSyncer::with_defaults()
+            .with_follow_links(true)
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        assert_eq!(result.nodes_synced, 1);
+        assert!(result.warnings.iter().any(|w| w.contains("循环")));
+    }
+
+    #[test]
+    fn test_sync_full_with_progress() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+        for name in ["a", "b", "c"] {
+            fs::write(
+                vault_path.join(format!("{}.md", name)),
+                format!("# {}", name),
+            )
+            .unwrap();
+        }
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        let mut calls = Vec::new();
+        VaultSyncer::with_defaults()
+            .sync_full_with_progress(vault_path, &mut db, |processed, total| {
+                calls.push((processed, total))
+            })
+            .unwrap();
+
+        assert_eq!(calls.first(), Some(&(0, 6)));
+        assert_eq!(calls.last(), Some(&(6, 6)));
+        assert_eq!(calls.len(), 7);
+        assert!(calls.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_sync_subtree() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        fs::create_dir_all(vault_path.join("a")).unwrap();
+        fs::create_dir_all(vault_path.join("b")).unwrap();
+        fs::write(vault_path.join("a").join("one.md"), "# One\n\n[[other]]").unwrap();
+        fs::write(vault_path.join("a").join("gone.md"), "# Gone").unwrap();
+        fs::write(
+            vault_path.join("b").join("other.md"),
+            "# Other\n\n[[one]] [[gone]]",
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        let syncer = VaultSyncer::with_defaults();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+
+        let sep = std::path::MAIN_SEPARATOR;
+        let one = path_to_uuid(&format!("a{}one.md", sep));
+        let gone = path_to_uuid(&format!("a{}gone.md", sep));
+        let other = path_to_uuid(&format!("b{}other.md", sep));
+        let other_before = db.get_node(&other).unwrap().unwrap();
+
+        // 仅修改子目录 a
+        fs::write(vault_path.join("a").join("one.md"), "# One v2\n\n[[other]]").unwrap();
+        fs::remove_file(vault_path.join("a").join("gone.md")).unwrap();
+
+        let result = syncer
+            .sync_subtree(vault_path, Path::new("a"), &mut db)
+            .unwrap();
+        assert_eq!(result.nodes_synced, 1);
+
+        assert_eq!(db.get_node(&one).unwrap().unwrap().title, "One v2");
+        assert!(db.get_node(&gone).unwrap().is_none());
+
+        // 子目录外的节点未被改动
+        let other_after = db.get_node(&other).unwrap().unwrap();
+        assert_eq!(other_after.hash, other_before.hash);
+        assert_eq!(other_after.title, "Other");
+
+        // 跨子目录的链接在两个方向上都保留，指向已删除文件的链接被移除
+        let links: Vec<(String, String)> = db
+            .get_all_edges()
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.relation == "link")
+            .map(|e| (e.src_uuid, e.dst_uuid))
+            .collect();
+        assert_eq!(links.len(), 2);
+        assert!(links.contains(&(one.clone(), other.clone())));
+        assert!(links.contains(&(other.clone(), one.clone())));
+        assert!(db.check_integrity().unwrap().is_healthy());
+    }
+
+    #[test]
+    fn test_sync_subtrees_batch() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+        fs::write(vault_path.join("old.md"), "# Old").unwrap();
+        fs::write(vault_path.join("hub.md"), "# Hub\n\n[[old]]").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        let syncer = VaultSyncer::with_defaults();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+
+        // 同一批中新增两篇互相链接的笔记并删除一篇
+        fs::write(vault_path.join("x.md"), "# X\n\n[[y]]").unwrap();
+        fs::write(vault_path.join("y.md"), "# Y\n\n[[x]]").unwrap();
+        fs::remove_file(vault_path.join("old.md")).unwrap();
+
+        let results = syncer
+            .sync_subtrees(
+                vault_path,
+                &[Path::new("x.md"), Path::new("y.md"), Path::new("old.md")],
+                &mut db,
+            )
+            .unwrap();
+        let synced: Vec<usize> = results
+            .into_iter()
+            .map(|r| r.unwrap().nodes_synced)
+            .collect();
+        assert_eq!(synced, vec![1, 1, 0]);
+
+        // 批内的新笔记彼此可见，链接在两个方向上都能解析
+        let (x, y) = (path_to_uuid("x.md"), path_to_uuid("y.md"));
+        let links: Vec<(String, String)> = db
+            .get_all_edges()
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.relation == "link")
+            .map(|e| (e.src_uuid, e.dst_uuid))
+            .collect();
+        assert!(links.contains(&(x.clone(), y.clone())));
+        assert!(links.contains(&(y, x)));
+        assert!(db.get_node(&path_to_uuid("old.md")).unwrap().is_none());
+        assert!(db.check_integrity().unwrap().is_healthy());
+    }
+
+    #[test]
+    fn test_sync_full_inline_fields() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        fs::write(
+            vault_path.join("task.md"),
+            "# Task\n\nstatus:: active\npriority:: 3\n",
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let uuid = path_to_uuid("task.md");
+        let properties = db.get_properties(&uuid).unwrap();
+        assert_eq!(
+            properties.get("status"),
+            Some(&PropertyValue::string("active"))
+        );
+        assert_eq!(properties.get("priority"), Some(&PropertyValue::integer(3)));
+        assert!(properties.contains_key(WORD_COUNT_PROPERTY));
+
+        let found = db
+            .find_nodes_by_property("status", &PropertyValue::string("active"))
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].uuid, uuid);
+
+        // 增量同步后移除的字段不再保留
+        fs::write(vault_path.join("task.md"), "# Task\n\nstatus:: done\n").unwrap();
+        VaultSyncer::with_defaults()
+            .sync_file(&vault_path.join("task.md"), vault_path, &mut db)
+            .unwrap();
+        let properties = db.get_properties(&uuid).unwrap();
+        assert_eq!(
+            properties.get("status"),
+            Some(&PropertyValue::string("done"))
+        );
+        assert!(!properties.contains_key("priority"));
+    }
+
+    #[test]
+    fn test_sync_full_folder_nodes() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        fs::create_dir_all(vault_path.join("a")).unwrap();
+        fs::write(vault_path.join("a").join("one.md"), "# One").unwrap();
+        fs::write(vault_path.join("a").join("two.md"), "# Two").unwrap();
+        fs::write(vault_path.join("root.md"), "# Root").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        // 默认不创建文件夹节点
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        assert!(db.get_node(&folder_uuid("a")).unwrap().is_none());
+
+        VaultSyncer::with_defaults()
+            .with_folder_nodes(true)
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let folder = db.get_node(&folder_uuid("a")).unwrap().unwrap();
+        assert_eq!(folder.node_type, FOLDER_NODE_TYPE);
+        assert_eq!(folder.title, "a");
+        assert_eq!(folder.path, "a");
+
+        let graph = db.get_graph_data().unwrap();
+        assert_eq!(
+            graph
+                .nodes
+                .iter()
+                .filter(|n| n.node_type == FOLDER_NODE_TYPE)
+                .count(),
+            1
+        );
+
+        let mut contained: Vec<String> = graph
+            .edges
+            .iter()
+            .filter(|e| e.relation == "contains")
+            .inspect(|e| assert_eq!(e.src_uuid, folder.uuid))
+            .map(|e| e.dst_uuid.clone())
+            .collect();
+        contained.sort();
+        let mut expected = vec![
+            path_to_uuid(&format!("a{}one.md", std::path::MAIN_SEPARATOR)),
+            path_to_uuid(&format!("a{}two.md", std::path::MAIN_SEPARATOR)),
+        ];
+        expected.sort();
+        assert_eq!(contained, expected);
+
+        assert!(db.check_integrity().unwrap().is_healthy());
+    }
+
+    #[test]
+    fn test_sync_full_tag_case_folding() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        fs::write(vault_path.join("a.md"), "# A\n\n#Rust and #rust").unwrap();
+        fs::write(vault_path.join("b.md"), "# B\n\n#RUST").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        // 默认保留原始大小写，产生冲突
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        let conflicts = db.get_tag_case_conflicts().unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].variants.len(), 3);
+
+        // 开启折叠后合并为单个标签
+        VaultSyncer::with_defaults()
+            .with_tag_case_folding(true)
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        assert!(db.get_tag_case_conflicts().unwrap().is_empty());
+        assert_eq!(
+            db.get_tags(&path_to_uuid("a.md")).unwrap(),
+            vec!["rust".to_string()]
+        );
+
+        let tag_targets: std::collections::HashSet<String> = db
+            .get_all_edges()
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.relation == "tagged")
+            .map(|e| e.dst_uuid)
+            .collect();
+        assert_eq!(tag_targets.len(), 1);
+        assert!(tag_targets.contains("tag:rust"));
+    }
+
+    #[test]
+    fn test_rename_file_redirects_inbound_edges() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
+
+        fs::write(vault_path.join("a.md"), "# A\n\nLink to [[b]].").unwrap();
+        fs::write(vault_path.join("c.md"), "# C\n\nAlso [[Bee]].").unwrap();
+        fs::write(
+            vault_path.join("b.md"),
+            "---\naliases: [Bee]\n---\n# B\n\nBack to [[a]].",
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        let syncer = VaultSyncer::with_defaults();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+
+        // 仅移动目录，文件名不变
+        fs::create_dir(vault_path.join("moved")).unwrap();
+        fs::rename(vault_path.join("b.md"), vault_path.join("moved/b.md")).unwrap();
+        let renamed = syncer
+            .rename_file(
+                &vault_path.join("b.md"),
+                &vault_path.join("moved/b.md"),
+                vault_path,
+                &mut db,
+            )
+            .unwrap();
+        assert!(renamed);
+
+        let old_uuid = path_to_uuid("b.md");
+        let new_uuid = path_to_uuid("moved/b.md");
+        let edges = db.get_all_edges().unwrap();
+
+        assert!(edges
+            .iter()
+            .all(|e| e.src_uuid != old_uuid && e.dst_uuid != old_uuid));
+        for src in ["a.md", "c.md"] {
+            let src_uuid = path_to_uuid(src);
+            assert!(edges
+                .iter()
+                .any(|e| e.src_uuid == src_uuid && e.dst_uuid == new_uuid));
+        }
+        assert!(edges
+            .iter()
+            .any(|e| e.src_uuid == new_uuid && e.dst_uuid == path_to_uuid("a.md")));
+
+        let node = db.get_node_by_path("moved/b.md").unwrap().unwrap();
+        assert_eq!(node.uuid, new_uuid);
+        assert!(db.get_node_by_path("b.md").unwrap().is_none());
+
+        // 改名后 [[b]] 不再能解析，通过别名的链接仍指向新节点
+        fs::rename(
+            vault_path.join("moved/b.md"),
+            vault_path.join("moved/b2.md"),
+        )
+        .unwrap();
+        syncer
+            .rename_file(
+                &vault_path.join("moved/b.md"),
+                &vault_path.join("moved/b2.md"),
+                vault_path,
+                &mut db,
+            )
+            .unwrap();
+        let renamed_uuid = path_to_uuid("moved/b2.md");
+        let edges = db.get_all_edges().unwrap();
+        assert!(edges
+            .iter()
+            .any(|e| e.src_uuid == path_to_uuid("a.md") && e.dst_uuid == "missing:b"));
+        assert!(edges
+            .iter()
+            .any(|e| e.src_uuid == path_to_uuid("c.md") && e.dst_uuid == renamed_uuid));
+        assert!(edges
+            .iter()
+            .any(|e| e.src_uuid == renamed_uuid && e.dst_uuid == path_to_uuid("a.md")));
+
+        // 与全量同步的结果一致
+        let mut expected: Vec<(String, String)> = edges
+            .iter()
+            .map(|e| (e.src_uuid.clone(), e.dst_uuid.clone()))
+            .collect();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+        let mut actual: Vec<(String, String)> = db
+            .get_all_edges()
+            .unwrap()
+            .iter()
+            .map(|e| (e.src_uuid.clone(), e.dst_uuid.clone()))
+            .collect();
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_sync_full_extension_counts() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        fs::write(vault_path.join("a.md"), "# A").unwrap();
+        fs::create_dir(vault_path.join("sub")).unwrap();
+        fs::write(vault_path.join("sub/b.md"), "# B").unwrap();
+        fs::write(vault_path.join("notes.txt"), "plain").unwrap();
+        fs::write(vault_path.join("image.PNG"), [0u8; 4]).unwrap();
+        fs::write(vault_path.join("README"), "no extension").unwrap();
+        fs::write(vault_path.join(".hidden.md"), "# Hidden").unwrap();
+        fs::create_dir(vault_path.join(".cognistruct")).unwrap();
+        fs::write(vault_path.join(".cognistruct/db.sqlite"), "").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        let syncer = VaultSyncer::default();
+        let result = syncer.sync_full(vault_path, &mut db).unwrap();
+
+        assert_eq!(result.extension_counts.get("md"), Some(&2));
+        assert_eq!(result.extension_counts.get("txt"), Some(&1));
+        assert_eq!(result.extension_counts.get("png"), Some(&1));
+        assert_eq!(result.extension_counts.get(""), Some(&1));
+        assert_eq!(result.extension_counts.get("sqlite"), None);
+        assert_eq!(result.extension_counts.values().sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn test_sync_full_respect_gitignore() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        fs::write(vault_path.join("note.md"), "# Note").unwrap();
+        fs::create_dir_all(vault_path.join("target")).unwrap();
+        fs::write(vault_path.join("target/out.md"), "# Out").unwrap();
+        fs::create_dir_all(vault_path.join("code/target/doc")).unwrap();
+        fs::write(vault_path.join("code/README.md"), "# Code").unwrap();
+        fs::write(vault_path.join("code/target/doc/api.md"), "# API").unwrap();
+        fs::write(vault_path.join(".gitignore"), "target/\n").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        // 默认不遵循 .gitignore
+        VaultSyncer::default()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+        assert_eq!(db.get_all_nodes().unwrap().len(), 4);
+
+        let result = VaultSyncer::default()
+            .with_respect_gitignore(true)
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let mut paths: Vec<String> = db
+            .get_all_nodes()
+            .unwrap()
+            .into_iter()
+            .map(|n| n.path)
+            .collect();
+        paths.sort();
+        assert_eq!(paths, vec!["code/README.md", "note.md"]);
+        assert_eq!(result.extension_counts.get("md"), Some(&2));
+    }
+
+    #[test]
+    fn test_sync_ignore_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        fs::write(vault_path.join("note.md"), "# Note").unwrap();
+        fs::create_dir_all(vault_path.join("drafts")).unwrap();
+        fs::write(vault_path.join("drafts/idea.md"), "# Idea").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        let syncer = VaultSyncer::default().with_ignore_patterns(&["drafts/".to_string()]);
+        syncer.sync_full(vault_path, &mut db).unwrap();
+        assert!(db.get_node_by_path("drafts/idea.md").unwrap().is_none());
+
+        // 监听到被忽略目录中的单个文件变化时同样跳过
+        syncer
+            .sync_subtree(vault_path, Path::new("drafts/idea.md"), &mut db)
+            .unwrap();
+        assert!(db.get_node_by_path("drafts/idea.md").unwrap().is_none());
+        assert!(db.get_node_by_path("note.md").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_sync_skips_hidden_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        fs::write(vault_path.join("note.md"), "# Note\n\n[[meeting]]").unwrap();
+        fs::create_dir_all(vault_path.join(".cognistruct/templates")).unwrap();
+        fs::write(
+            vault_path.join(".cognistruct/templates/meeting.md"),
+            "# {{title}}",
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        let syncer = VaultSyncer::default();
+        let result = syncer.sync_full(vault_path, &mut db).unwrap();
+        assert_eq!(result.nodes_synced, 1);
+        assert!(db
+            .get_node_by_path(".cognistruct/templates/meeting.md")
+            .unwrap()
+            .is_none());
+        // 模板不参与链接解析
+        assert!(db
+            .get_edges_by_node(&path_to_uuid("note.md"))
+            .unwrap()
+            .iter()
+            .any(|e| e.dst_uuid == "missing:meeting"));
+
+        syncer
+            .sync_subtree(vault_path, Path::new(".cognistruct/templates"), &mut db)
+            .unwrap();
+        assert_eq!(db.get_all_nodes().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_sync_records_broken_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        fs::write(vault_path.join("a.md"), "# A\n\nSee [[Nowhere]] and [[b]].").unwrap();
+        fs::write(vault_path.join("b.md"), "# B").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        let syncer = VaultSyncer::default();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+
+        let a_uuid = path_to_uuid("a.md");
+        assert!(db
+            .get_edges_by_node(&a_uuid)
+            .unwrap()
+            .iter()
+            .any(|e| e.src_uuid == a_uuid && e.dst_uuid == "missing:Nowhere"));
+
+        // 断链计入统计和未解析目标，但不视为悬空边
+        let stats = db.get_link_statistics().unwrap();
+        assert_eq!(stats.total_links, 2);
+        assert_eq!(stats.resolved, 1);
+        assert_eq!(stats.broken, 1);
+        assert!(db
+            .get_all_link_targets()
+            .unwrap()
+            .contains(&("Nowhere".to_string(), 1)));
+        assert!(db.check_integrity().unwrap().is_healthy());
+
+        // 创建目标笔记后重新同步，断链被解析
+        fs::write(vault_path.join("Nowhere.md"), "# Nowhere").unwrap();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+        let stats = db.get_link_statistics().unwrap();
+        assert_eq!(stats.resolved, 2);
+        assert_eq!(stats.broken, 0);
+    }
+
+    #[test]
+    fn test_sync_stores_word_count() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::write(vault_path.join("a.md"), "one two three").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        let syncer = VaultSyncer::default();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+
+        let uuid = path_to_uuid("a.md");
+        let props = db.get_properties(&uuid).unwrap();
+        assert_eq!(props[WORD_COUNT_PROPERTY].as_integer(), Some(3));
+
+        fs::remove_file(vault_path.join("a.md")).unwrap();
+        syncer
+            .sync_file(&vault_path.join("a.md"), vault_path, &mut db)
+            .unwrap();
+        assert!(db.get_properties(&uuid).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_loaded_object_id_matches_node_uuid() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::write(vault_path.join("a.md"), "---\nstatus: draft\n---\n# A").unwrap();
+        fs::write(vault_path.join("b.md"), "---\nid: stable-b\n---\n# B").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        let syncer = VaultSyncer::default();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+
+        let (obj, _) = syncer
+            .load_file(&vault_path.join("a.md"), vault_path)
+            .unwrap();
+        let node = db.get_node_by_path("a.md").unwrap().unwrap();
+        assert_eq!(obj.id.as_str(), node.uuid);
+        let props = db.get_properties(obj.id.as_str()).unwrap();
+        assert_eq!(props["status"].as_string(), Some("draft"));
+
+        let (obj, _) = syncer
+            .load_file(&vault_path.join("b.md"), vault_path)
+            .unwrap();
+        assert_eq!(obj.id.as_str(), "stable-b");
+    }
+
+    #[test]
+    fn test_frontmatter_id_survives_move() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        fs::write(
+            vault_path.join("a.md"),
+            "---\nid: stable-note\n---\n# A\n\nBody",
+        )
+        .unwrap();
+        fs::write(vault_path.join("b.md"), "# B\n\nLink to [[a]].").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        let syncer = VaultSyncer::default();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+
+        assert_eq!(
+            db.get_node_by_path("a.md").unwrap().unwrap().uuid,
+            "stable-note"
+        );
+        // 未声明 id 的笔记仍使用路径 UUID
+        assert_eq!(
+            db.get_node_by_path("b.md").unwrap().unwrap().uuid,
+            path_to_uuid("b.md")
+        );
+
+        // 模拟移动
+        fs::create_dir(vault_path.join("moved")).unwrap();
+        fs::rename(vault_path.join("a.md"), vault_path.join("moved/a2.md")).unwrap();
+        syncer
+            .rename_file(
+                &vault_path.join("a.md"),
+                &vault_path.join("moved/a2.md"),
+                vault_path,
+                &mut db,
+            )
+            .unwrap();
+
+        let moved = db.get_node("stable-note").unwrap().unwrap();
+        assert_eq!(Path::new(&moved.path), Path::new("moved/a2.md"));
+        assert!(db.get_node_by_path("a.md").unwrap().is_none());
+        assert_eq!(db.get_all_nodes().unwrap().len(), 2);
+
+        // 文件名改变后 `[[a]]` 无法再解析，b.md 的链接变为断链
+        let b_edges: Vec<String> = db
+            .get_all_edges()
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.src_uuid == path_to_uuid("b.md"))
+            .map(|e| e.dst_uuid)
+            .collect();
+        assert!(b_edges.contains(&"missing:a".to_string()));
+        assert!(!b_edges.contains(&"stable-note".to_string()));
+
+        // 删除后按路径找到稳定 UUID 并移除
+        fs::remove_file(vault_path.join("moved/a2.md")).unwrap();
+        syncer
+            .sync_file(&vault_path.join("moved/a2.md"), vault_path, &mut db)
+            .unwrap();
+        assert!(db.get_node("stable-note").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_sync_warns_on_duplicate_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        fs::write(vault_path.join("a.md"), "---\nid: shared\n---\n# A").unwrap();
+        fs::create_dir(vault_path.join("sub")).unwrap();
+        fs::write(vault_path.join("sub/b.md"), "---\nid: shared\n---\n# B").unwrap();
+        fs::write(vault_path.join("c.md"), "---\nid: unique\n---\n# C").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        let syncer = VaultSyncer::default();
+
+        let result = syncer.sync_full(vault_path, &mut db).unwrap();
+        assert_eq!(
+            result.warnings,
+            vec!["多个笔记声明了相同的 id shared: a.md, sub/b.md".to_string()]
+        );
+
+        // 子目录同步时与子目录外的笔记冲突同样报告
+        let result = syncer
+            .sync_subtree(vault_path, Path::new("sub"), &mut db)
+            .unwrap();
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("a.md"));
+        assert!(result.warnings[0].contains("sub/b.md"));
+    }
+
+    #[test]
+    fn test_sync_persists_object_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::write(
+            vault_path.join("a.md"),
+            "---\naliases: [Alpha]\nstatus: draft\npriority: 2\n---\n# A",
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        let syncer = VaultSyncer::default();
+        syncer.sync_full(vault_path, &mut db).unwrap();
+
+        let uuid = path_to_uuid("a.md");
+        let props = db.get_properties(&uuid).unwrap();
+        assert_eq!(props["status"].as_string(), Some("draft"));
+        assert_eq!(props["priority"].as_integer(), Some(2));
+        assert_eq!(db.get_aliases(&uuid).unwrap(), vec!["Alpha"]);
+
+        let sources = db.get_sources(&uuid).unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].path(), Some("a.md"));
+
+        fs::remove_file(vault_path.join("a.md")).unwrap();
+        syncer
+            .sync_file(&vault_path.join("a.md"), vault_path, &mut db)
+            .unwrap();
+        assert!(db.get_aliases(&uuid).unwrap().is_empty());
+        assert!(db.get_sources(&uuid).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_nodes_by_source_type() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        fs::write(vault_path.join("note.md"), "# Note").unwrap();
+        fs::write(
+            vault_path.join("board.canvas"),
+            r#"{"nodes": [], "edges": []}"#,
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::default()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let paths_of = |source_type: &str| -> Vec<String> {
+            db.get_nodes_by_source_type(source_type)
+                .unwrap()
+                .into_iter()
+                .map(|n| n.path)
+                .collect()
+        };
+        assert_eq!(paths_of("Markdown"), vec!["note.md"]);
+        assert_eq!(paths_of("Binary"), vec!["board.canvas"]);
+        assert!(paths_of("virtual").is_empty());
+    }
+
+    #[test]
+    fn test_extract_links_from_stored_node() {
+        use crate::adapters::obsidian::ObsidianAdapter;
+        use crate::adapters::ObjectAdapter;
+
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+        let content = "---\ntags: [x]\n---\n# A\n\nSee [[b]], [[c#^blk|block]] and ![[img.png]].\n\n[Site](https://example.com)";
+        fs::write(vault_path.join("a.md"), content).unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::default()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let node = db.get_node_by_path("a.md").unwrap().unwrap();
+        let rebuilt = node.to_cognitive_object();
+        assert_eq!(rebuilt.id.as_str(), node.uuid);
+        assert_eq!(rebuilt.path(), Some("a.md"));
+
+        let adapter = ObsidianAdapter::new();
+        let original = adapter.load(Path::new("a.md"), content.as_bytes()).unwrap();
+        let links = adapter.extract_links(&rebuilt);
+        assert_eq!(links.len(), 4);
+        assert_eq!(
+            format!("{:?}", links),
+            format!("{:?}", adapter.extract_links(&original))
+        );
+    }
+
+    #[test]
+    fn test_sync_full_edge_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        fs::write(
+            vault_path.join("a.md"),
+            "---\nrelated: \"[[b]]\"\n---\n# A\n\nBody link to [[c]] and ![[d]].\n\n#topic",
+        )
+        .unwrap();
+        fs::write(vault_path.join("b.md"), "# B").unwrap();
+        fs::write(vault_path.join("c.md"), "# C").unwrap();
+        fs::write(vault_path.join("d.md"), "# D").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::default()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let source_of = |dst: &str| {
+            db.get_all_edges()
+                .unwrap()
+                .into_iter()
+                .find(|e| e.dst_uuid == dst)
+                .map(|e| e.source)
+        };
+        assert_eq!(
+            source_of(&path_to_uuid("b.md")).as_deref(),
+            Some("frontmatter-ref")
+        );
+        assert_eq!(
+            source_of(&path_to_uuid("c.md")).as_deref(),
+            Some("body-wikilink")
+        );
+        assert_eq!(source_of(&path_to_uuid("d.md")).as_deref(), Some("embed"));
+        assert_eq!(source_of("tag:topic").as_deref(), Some("tag"));
+
+        let refs = db
+            .get_edges_by_source(EdgeSource::FrontmatterRef.as_str())
+            .unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].dst_uuid, path_to_uuid("b.md"));
+    }
+
+    #[test]
+    fn test_sync_full_parallel_matches_sequential() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        for i in 0..40 {
+            let dir = vault_path.join(format!("dir{}", i % 4));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join(format!("note{}.md", i)),
+                format!(
+                    "# Note {}\n\nLinks to [[note{}]] and [[note{}]].\n\n#group{}",
+                    i,
+                    (i + 1) % 40,
+                    (i + 7) % 40,
+                    i % 3
+                ),
+            )
+            .unwrap();
+        }
+
+        let snapshot = |syncer: VaultSyncer| {
+            let db_dir = TempDir::new().unwrap();
+            let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+            let result = syncer.sync_full(vault_path, &mut db).unwrap();
+
+            let mut nodes: Vec<_> = db
+                .get_all_nodes()
+                .unwrap()
+                .into_iter()
+                .map(|n| (n.uuid, n.path, n.title, n.hash))
+                .collect();
+            nodes.sort();
+            let mut edges: Vec<_> = db
+                .get_all_edges()
+                .unwrap()
+                .into_iter()
+                .map(|e| (e.src_uuid, e.dst_uuid, e.relation, e.source))
+                .collect();
+            edges.sort();
+            (result.nodes_synced, result.edges_created, nodes, edges)
+        };
+
+        let parallel = snapshot(VaultSyncer::default().with_parallel(true));
+        let sequential = snapshot(VaultSyncer::default().with_parallel(false));
+
+        assert_eq!(parallel.0, 40);
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_sync_full_title_fallbacks() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        fs::write(
+            vault_path.join("frontmatter-only.md"),
+            "---\ntags: [a]\n---\n",
+        )
+        .unwrap();
+        fs::write(vault_path.join("empty.md"), "").unwrap();
+        fs::write(vault_path.join("dashes.md"), "---\nnot: closed\n").unwrap();
+        fs::write(
+            vault_path.join("titled.md"),
+            "---\ntitle: From Frontmatter\n---\n# Heading\n",
+        )
+        .unwrap();
 
-        // 获取类型
-        let node_type = obj.get_type().unwrap_or("note").to_string();
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::default()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let title_of = |path: &str| db.get_node_by_path(path).unwrap().unwrap().title;
+        assert_eq!(title_of("frontmatter-only.md"), "frontmatter-only");
+        assert_eq!(title_of("empty.md"), "empty");
+        assert_eq!(title_of("dashes.md"), "dashes");
+        assert_eq!(title_of("titled.md"), "From Frontmatter");
+    }
 
-        // 计算哈希
-        let hash = calculate_hash(&content);
+    #[test]
+    fn test_sync_full_external_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
 
-        Node {
-            uuid,
-            path: relative_path.to_string(),
-            title,
-            content,
-            node_type,
-            hash,
-            created_at: now,
-            updated_at: now,
-        }
-    }
-}
+        fs::write(
+            vault_path.join("a.md"),
+            "# A\n\nSee [docs](https://example.com/docs) and [blog](http://example.org/blog).",
+        )
+        .unwrap();
+        fs::write(
+            vault_path.join("b.md"),
+            "# B\n\nAlso [docs](https://example.com/docs).",
+        )
+        .unwrap();
 
-impl Default for VaultSyncer {
-    fn default() -> Self {
-        Self::with_defaults()
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::default()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        assert_eq!(
+            db.get_external_links().unwrap(),
+            vec!["http://example.org/blog", "https://example.com/docs"]
+        );
+        // 外部链接哨兵不视为悬空边
+        assert!(db.check_integrity().unwrap().is_healthy());
     }
-}
 
-/// 同步结果
-///
-/// 记录同步操作的统计信息。
-#[derive(Debug, Clone)]
-pub struct SyncResult {
-    /// 同步的节点数量
-    pub nodes_synced: usize,
-    /// 创建的边数量
-    pub edges_created: usize,
-}
+    #[test]
+    fn test_sync_full_alias_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
 
-/// 同步知识库（兼容旧接口）
-///
-/// 使用默认适配器同步整个知识库。
-/// 这是一个便捷函数，保持向后兼容。
-///
-/// # 参数
-///
-/// * `vault_path` - 知识库根目录路径
-/// * `db` - 数据库实例的可变引用
-///
-/// # 返回值
-///
-/// * `Ok(())` - 同步成功
-/// * `Err(anyhow::Error)` - 同步失败
-pub fn sync_vault(vault_path: &Path, db: &mut Database) -> Result<()> {
-    let syncer = VaultSyncer::with_defaults();
-    syncer.sync_full(vault_path, db)?;
-    Ok(())
-}
+        fs::write(
+            vault_path.join("a.md"),
+            "---\naliases: [Shared, OnlyA]\n---\n# A",
+        )
+        .unwrap();
+        fs::write(vault_path.join("b.md"), "---\naliases: [Shared]\n---\n# B").unwrap();
+        fs::write(vault_path.join("c.md"), "---\naliases: [shared]\n---\n# C").unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use tempfile::TempDir;
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::default()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let a = uuid_for_path(&db, "a.md").unwrap();
+        let b = uuid_for_path(&db, "b.md").unwrap();
+        assert_eq!(db.get_all_aliases().unwrap().len(), 4);
+
+        let conflicts = db.get_alias_conflicts().unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].alias, "Shared");
+        let mut expected = vec![a, b];
+        expected.sort();
+        assert_eq!(conflicts[0].owners, expected);
+    }
 
     #[test]
-    fn test_calculate_hash() {
-        let content1 = "Hello, World!";
-        let content2 = "Hello, World!";
-        let content3 = "Different content";
+    fn test_sync_frontmatter_url_properties() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
 
-        let hash1 = calculate_hash(content1);
-        let hash2 = calculate_hash(content2);
-        let hash3 = calculate_hash(content3);
+        fs::write(
+            vault_path.join("paper.md"),
+            "---\nsource: https://example.com\nurl: not a link https://example.org\n---\n# Paper",
+        )
+        .unwrap();
 
-        assert_eq!(hash1, hash2);
-        assert_ne!(hash1, hash3);
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::default()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        assert_eq!(
+            db.get_external_links().unwrap(),
+            vec!["https://example.com"]
+        );
+        let paper = db.get_node_by_path("paper.md").unwrap().unwrap();
+        let edges = db.get_edges_by_node(&paper.uuid).unwrap();
+        assert!(edges.iter().any(|e| e.dst_uuid == "url:https://example.com"
+            && e.source == EdgeSource::External.as_str()));
+
+        // 仅重建边时同样从已存储的属性中提取
+        VaultSyncer::default().rebuild_edges(&mut db).unwrap();
+        assert_eq!(
+            db.get_external_links().unwrap(),
+            vec!["https://example.com"]
+        );
     }
 
     #[test]
-    fn test_path_to_uuid() {
-        let uuid1 = path_to_uuid("notes/test.md");
-        let uuid2 = path_to_uuid("notes/test.md");
-        let uuid3 = path_to_uuid("notes/other.md");
+    fn test_sync_encrypted_note() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
 
-        assert_eq!(uuid1, uuid2);
-        assert_ne!(uuid1, uuid3);
-        // UUID 格式验证
-        assert!(uuid1.contains('-'));
+        fs::write(vault_path.join("target.md"), "# Target").unwrap();
+        fs::write(
+            vault_path.join("secret.md"),
+            "-----BEGIN AGE ENCRYPTED FILE-----\nW1tUYXJnZXRdXQ== [[Target]] #private\n-----END AGE ENCRYPTED FILE-----\n",
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::default()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let secret = db.get_node_by_path("secret.md").unwrap().unwrap();
+        assert_eq!(secret.node_type, "encrypted");
+        assert_eq!(secret.title, "secret");
+        assert!(secret.content.is_empty());
+        assert!(db.get_edges_by_node(&secret.uuid).unwrap().is_empty());
     }
 
     #[test]
-    fn test_vault_syncer_new() {
+    fn test_build_filename_index() {
         let syncer = VaultSyncer::with_defaults();
-        // 验证能够创建
-        assert!(syncer.registry.find_adapter("md").is_some());
+
+        let obj1 = CognitiveObject::new();
+        let obj2 = CognitiveObject::new();
+
+        let objects = vec![
+            (obj1, "notes/test.md".to_string()),
+            (obj2, "other/test.md".to_string()), // 同名文件
+        ];
+
+        let index = syncer.build_filename_index(&objects);
+
+        // 同名文件应该有多个路径
+        assert!(index.contains_key("test"));
+        assert_eq!(index["test"].len(), 2);
     }
 
     #[test]
-    fn test_sync_vault() {
+    fn test_embedding_notes() {
         let vault_dir = TempDir::new().unwrap();
         let vault_path = vault_dir.path();
 
-        // 创建测试文件
-        fs::write(
-            vault_path.join("note1.md"),
-            "# Note 1\n\nLink to [[note2]].\n\n#tag1",
-        )
-        .unwrap();
-
+        fs::create_dir_all(vault_path.join("assets")).unwrap();
+        fs::write(vault_path.join("assets").join("diagram.png"), [0u8; 4]).unwrap();
         fs::write(
-            vault_path.join("note2.md"),
-            "# Note 2\n\nLink to [[note1]].\n\n#tag2",
+            vault_path.join("a.md"),
+            "# A\n\n![[diagram.png]]\n![[ghost.png]]",
         )
         .unwrap();
-
-        // 创建子目录
-        fs::create_dir(vault_path.join("subfolder")).unwrap();
         fs::write(
-            vault_path.join("subfolder/note3.md"),
-            "# Note 3\n\nLink to [[note1]].\n\n#tag3",
+            vault_path.join("b.md"),
+            "# B\n\n![[diagram.png|300]]\n![[c]]",
         )
         .unwrap();
+        fs::write(vault_path.join("c.md"), "# C\n\n[[diagram.png]]").unwrap();
 
         let db_dir = TempDir::new().unwrap();
-        let db_path = db_dir.path().join("test.db");
-        let mut db = Database::new(db_path).unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let embedders = |target: &str| -> Vec<String> {
+            db.get_embedding_notes(target)
+                .unwrap()
+                .into_iter()
+                .map(|n| n.path)
+                .collect()
+        };
 
-        // 使用兼容接口
-        sync_vault(vault_path, &mut db).unwrap();
+        // 普通链接不算嵌入，且扩展名可省略、可带路径
+        assert_eq!(embedders("diagram.png"), vec!["a.md", "b.md"]);
+        assert_eq!(embedders("diagram"), vec!["a.md", "b.md"]);
+        assert_eq!(embedders("assets/diagram.png"), vec!["a.md", "b.md"]);
+        assert!(embedders("diagram.jpg").is_empty());
+
+        // 嵌入已索引的笔记
+        assert_eq!(embedders("c"), vec!["b.md"]);
+        assert_eq!(embedders("c.md"), vec!["b.md"]);
+
+        // 未索引文件的嵌入边指向哨兵，不视为悬空
+        assert!(db.check_integrity().unwrap().is_healthy());
+
+        // 知识库中不存在的嵌入文件视为断链
+        let a_targets = |db: &Database| -> Vec<String> {
+            db.get_edges_by_node(&path_to_uuid("a.md"))
+                .unwrap()
+                .into_iter()
+                .map(|e| e.dst_uuid)
+                .collect()
+        };
+        let targets = a_targets(&db);
+        assert!(targets.contains(&"file:diagram.png".to_string()));
+        assert!(targets.contains(&"missing:ghost.png".to_string()));
+        assert!(!targets.contains(&"file:ghost.png".to_string()));
+
+        // 单文件同步时按整个知识库查找附件
+        VaultSyncer::with_defaults()
+            .sync_subtree(vault_path, Path::new("a.md"), &mut db)
+            .unwrap();
+        let mut resynced = a_targets(&db);
+        let mut expected = targets;
+        resynced.sort();
+        expected.sort();
+        assert_eq!(resynced, expected);
+    }
 
-        let nodes = db.get_all_nodes().unwrap();
-        assert_eq!(nodes.len(), 3);
+    #[test]
+    fn test_sync_full_reports_load_errors() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
 
-        let titles: Vec<String> = nodes.iter().map(|n| n.title.clone()).collect();
-        assert!(titles.contains(&"Note 1".to_string()));
-        assert!(titles.contains(&"Note 2".to_string()));
-        assert!(titles.contains(&"Note 3".to_string()));
+        fs::write(vault_path.join("good.md"), "# Good\n\nLinks to [[other]].").unwrap();
+        fs::write(vault_path.join("other.md"), "# Other").unwrap();
+        // 非 UTF-8 内容会导致适配器加载失败
+        fs::write(vault_path.join("bad.md"), [0xff, 0xfe, 0x00, 0x80]).unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        let result = VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        assert_eq!(result.nodes_synced, 2);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].0, "bad.md");
+        assert!(result.errors[0].1.contains("UTF-8"));
+        assert!(db.get_node_by_path("good.md").unwrap().is_some());
+        assert!(db.get_node_by_path("bad.md").unwrap().is_none());
     }
 
     #[test]
-    fn test_sync_full_with_result() {
+    fn test_self_links() {
         let vault_dir = TempDir::new().unwrap();
         let vault_path = vault_dir.path();
 
         fs::write(
-            vault_path.join("note1.md"),
-            "# Note 1\n\nLink to [[note2]].\n\n#tag1",
+            vault_path.join("This Note.md"),
+            "# This Note\n\nSee [[This Note]] and [[other]].",
         )
         .unwrap();
+        fs::write(vault_path.join("other.md"), "# Other").unwrap();
 
-        fs::write(vault_path.join("note2.md"), "# Note 2\n\nContent").unwrap();
+        let uuid = path_to_uuid("This Note.md");
 
+        // 默认跳过自链接
         let db_dir = TempDir::new().unwrap();
-        let db_path = db_dir.path().join("test.db");
-        let mut db = Database::new(db_path).unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
 
-        let syncer = VaultSyncer::with_defaults();
-        let result = syncer.sync_full(vault_path, &mut db).unwrap();
+        let edges = db.get_edges_by_node(&uuid).unwrap();
+        assert!(edges.iter().all(|e| e.dst_uuid != e.src_uuid));
+        assert!(edges.iter().any(|e| e.dst_uuid == path_to_uuid("other.md")));
+        assert!(db.get_self_links().unwrap().is_empty());
 
-        assert_eq!(result.nodes_synced, 2);
-        assert!(result.edges_created >= 2); // 至少 1 个链接 + 1 个标签
+        // 显式允许时保留
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .with_self_links(true)
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let self_links = db.get_self_links().unwrap();
+        assert_eq!(self_links.len(), 1);
+        assert_eq!(self_links[0].src_uuid, uuid);
+        assert_eq!(self_links[0].dst_uuid, uuid);
     }
 
     #[test]
-    fn test_sync_file_new() {
+    fn test_type_rules_infer_node_type() {
         let vault_dir = TempDir::new().unwrap();
         let vault_path = vault_dir.path();
 
-        let db_dir = TempDir::new().unwrap();
-        let db_path = db_dir.path().join("test.db");
-        let mut db = Database::new(db_path).unwrap();
-
-        // 创建文件
-        let file_path = vault_path.join("new_note.md");
-        fs::write(&file_path, "# New Note\n\nContent here.").unwrap();
+        fs::create_dir(vault_path.join(".cognistruct")).unwrap();
+        fs::write(
+            vault_path.join(rules::RULES_FILE),
+            "type_rules:\n  - property: due\n    type: task\n",
+        )
+        .unwrap();
+        fs::write(
+            vault_path.join("todo.md"),
+            "---\ndue: 2024-06-01\n---\n# Todo",
+        )
+        .unwrap();
+        fs::write(vault_path.join("plain.md"), "# Plain").unwrap();
 
-        let syncer = VaultSyncer::with_defaults();
-        let synced = syncer.sync_file(&file_path, vault_path, &mut db).unwrap();
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        let syncer =
+            VaultSyncer::with_defaults().with_type_rules(load_type_rules(vault_path).unwrap());
+        syncer.sync_full(vault_path, &mut db).unwrap();
 
-        assert!(synced);
+        let node_type =
+            |db: &Database, path: &str| db.get_node_by_path(path).unwrap().unwrap().node_type;
+        assert_eq!(node_type(&db, "todo.md"), "task");
+        assert_eq!(node_type(&db, "plain.md"), "note");
 
-        let nodes = db.get_all_nodes().unwrap();
-        assert_eq!(nodes.len(), 1);
-        assert_eq!(nodes[0].title, "New Note");
+        // 增量同步同样应用规则
+        fs::write(
+            vault_path.join("plain.md"),
+            "---\ndue: 2024-07-01\n---\n# Plain",
+        )
+        .unwrap();
+        syncer
+            .sync_file(&vault_path.join("plain.md"), vault_path, &mut db)
+            .unwrap();
+        assert_eq!(node_type(&db, "plain.md"), "task");
     }
 
     #[test]
-    fn test_sync_file_unsupported() {
+    fn test_rebuild_edges() {
         let vault_dir = TempDir::new().unwrap();
         let vault_path = vault_dir.path();
 
-        let db_dir = TempDir::new().unwrap();
-        let db_path = db_dir.path().join("test.db");
-        let mut db = Database::new(db_path).unwrap();
+        fs::write(
+            vault_path.join("a.md"),
+            "---\ntags: [rust]\nrelated: \"[[c]]\"\n---\n# A\n\nSee [[b]], [[Bee]] and [[nowhere]].",
+        )
+        .unwrap();
+        fs::write(vault_path.join("b.md"), "---\naliases: [Bee]\n---\n# B").unwrap();
+        fs::write(vault_path.join("c.md"), "# C\n\n![[image.png]]").unwrap();
 
-        // 创建不支持的文件类型
-        let file_path = vault_path.join("image.png");
-        fs::write(&file_path, "fake image data").unwrap();
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        let syncer = VaultSyncer::with_defaults().with_folder_nodes(true);
+        let result = syncer.sync_full(vault_path, &mut db).unwrap();
 
-        let syncer = VaultSyncer::with_defaults();
-        let synced = syncer.sync_file(&file_path, vault_path, &mut db).unwrap();
+        let edge_keys = |db: &Database| {
+            let mut keys: Vec<(String, String, String, String)> = db
+                .get_all_edges()
+                .unwrap()
+                .into_iter()
+                .map(|e| (e.src_uuid, e.dst_uuid, e.relation, e.source))
+                .collect();
+            keys.sort();
+            keys
+        };
+        let original = edge_keys(&db);
+
+        // 破坏边表，并删除磁盘文件以确认重建只依赖已存储的内容
+        db.clear_edges().unwrap();
+        db.upsert_edge(&Edge {
+            src_uuid: "bogus".to_string(),
+            dst_uuid: path_to_uuid("a.md"),
+            relation: "link".to_string(),
+            weight: 1.0,
+            source: "body".to_string(),
+        })
+        .unwrap();
+        for name in ["a.md", "b.md", "c.md"] {
+            fs::remove_file(vault_path.join(name)).unwrap();
+        }
 
-        assert!(!synced); // 不支持的文件类型
+        let rebuilt = syncer.rebuild_edges(&mut db).unwrap();
+        assert_eq!(rebuilt, result.edges_created);
+        assert_eq!(edge_keys(&db), original);
+        assert!(original
+            .iter()
+            .any(|(src, dst, _, _)| src == &path_to_uuid("a.md") && dst == &path_to_uuid("c.md")));
     }
 
     #[test]
-    fn test_sync_vault_empty() {
+    fn test_relation_weights() {
         let vault_dir = TempDir::new().unwrap();
         let vault_path = vault_dir.path();
 
+        fs::write(vault_path.join("a.md"), "# A\n\n[[b]] ![[c]] #topic").unwrap();
+        fs::write(vault_path.join("b.md"), "# B").unwrap();
+        fs::write(vault_path.join("c.md"), "# C").unwrap();
+
+        let weight_of = |db: &Database, dst: &str| {
+            db.get_edges_by_node(&path_to_uuid("a.md"))
+                .unwrap()
+                .into_iter()
+                .find(|e| e.dst_uuid == dst)
+                .map(|e| e.weight)
+        };
+
+        // 默认权重
         let db_dir = TempDir::new().unwrap();
-        let db_path = db_dir.path().join("test.db");
-        let mut db = Database::new(db_path).unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
 
-        sync_vault(vault_path, &mut db).unwrap();
+        assert_eq!(weight_of(&db, &path_to_uuid("b.md")), Some(1.0));
+        assert_eq!(weight_of(&db, &path_to_uuid("c.md")), Some(0.8));
+        assert_eq!(weight_of(&db, "tag:topic"), Some(0.3));
 
-        let nodes = db.get_all_nodes().unwrap();
-        let edges = db.get_all_edges().unwrap();
-        assert_eq!(nodes.len(), 0);
-        assert_eq!(edges.len(), 0);
+        // 自定义权重
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .with_relation_weight("tagged", 0.1)
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        assert_eq!(weight_of(&db, &path_to_uuid("b.md")), Some(1.0));
+        assert_eq!(weight_of(&db, "tag:topic"), Some(0.1));
     }
 
     #[test]
-    fn test_sync_vault_clear_old_data() {
+    fn test_resolve_wikilink() {
         let vault_dir = TempDir::new().unwrap();
         let vault_path = vault_dir.path();
 
-        fs::write(vault_path.join("note1.md"), "# Note 1\n\nContent").unwrap();
+        fs::create_dir_all(vault_path.join("a")).unwrap();
+        fs::write(vault_path.join("note.md"), "# Root note").unwrap();
+        fs::write(vault_path.join("a").join("note.md"), "# Nested note").unwrap();
+        fs::write(vault_path.join("a").join("src.md"), "# Src").unwrap();
+        fs::write(
+            vault_path.join("project.md"),
+            "---\naliases: [Big Plan]\n---\n# Project",
+        )
+        .unwrap();
+        fs::write(vault_path.join("user.md"), "# User\n\nSee [[Big Plan]].").unwrap();
 
         let db_dir = TempDir::new().unwrap();
-        let db_path = db_dir.path().join("test.db");
-        let mut db = Database::new(db_path).unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let nested = format!("a{}note.md", std::path::MAIN_SEPARATOR);
+        let src = format!("a{}src.md", std::path::MAIN_SEPARATOR);
+
+        // 按文件名，遵循就近原则
+        assert_eq!(
+            resolve_wikilink(&db, &src, "note#Heading").unwrap(),
+            Some(nested)
+        );
+        assert_eq!(
+            resolve_wikilink(&db, "user.md", "note").unwrap(),
+            Some("note.md".to_string())
+        );
+
+        // 按别名
+        assert_eq!(
+            resolve_wikilink(&db, "user.md", "Big Plan|the plan").unwrap(),
+            Some("project.md".to_string())
+        );
+
+        // 同步时别名链接同样生成边
+        let edges = db.get_edges_by_node(&path_to_uuid("user.md")).unwrap();
+        assert!(edges
+            .iter()
+            .any(|e| e.relation == "link" && e.dst_uuid == path_to_uuid("project.md")));
+
+        // 悬空链接
+        assert_eq!(resolve_wikilink(&db, "user.md", "Missing").unwrap(), None);
+    }
 
-        // 第一次同步
-        sync_vault(vault_path, &mut db).unwrap();
-        let nodes1 = db.get_all_nodes().unwrap();
-        assert_eq!(nodes1.len(), 1);
+    #[test]
+    fn test_sync_full_records_link_resolution() {
+        let vault_dir = TempDir::new().unwrap();
+        let vault_path = vault_dir.path();
 
-        // 第二次同步 - 应该清除并重建
-        sync_vault(vault_path, &mut db).unwrap();
-        let nodes2 = db.get_all_nodes().unwrap();
-        assert_eq!(nodes2.len(), 1);
+        fs::write(
+            vault_path.join("project.md"),
+            "---\naliases: [Big Plan]\n---\n# Project",
+        )
+        .unwrap();
+        fs::write(vault_path.join("notes.md"), "# Notes").unwrap();
+        fs::write(
+            vault_path.join("user.md"),
+            "# User\n\nSee [[Big Plan]] and [[notes]].",
+        )
+        .unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+        VaultSyncer::with_defaults()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let targets = |method: LinkResolution| -> Vec<String> {
+            db.get_edges_resolved_by(method.as_str())
+                .unwrap()
+                .into_iter()
+                .map(|e| e.dst_uuid)
+                .collect()
+        };
+        assert_eq!(
+            targets(LinkResolution::Alias),
+            vec![path_to_uuid("project.md")]
+        );
+        assert_eq!(
+            targets(LinkResolution::Filename),
+            vec![path_to_uuid("notes.md")]
+        );
+
+        // 重建边后过期的解析记录不会返回
+        db.clear_edges().unwrap();
+        assert!(db.get_edges_resolved_by("alias").unwrap().is_empty());
     }
 
     #[test]
-    fn test_build_filename_index() {
+    fn test_resolve_link_target_prefers_nearest() {
         let syncer = VaultSyncer::with_defaults();
 
-        let obj1 = CognitiveObject::new();
-        let obj2 = CognitiveObject::new();
+        let objects: Vec<(CognitiveObject, String)> = [
+            "note.md",
+            "a/note.md",
+            "b/note.md",
+            "a/src.md",
+            "a/deep/src.md",
+            "c/src.md",
+            "unique.md",
+        ]
+        .iter()
+        .map(|p| (CognitiveObject::new(), p.to_string()))
+        .collect();
+        let index = syncer.build_filename_index(&objects);
 
-        let objects = vec![
-            (obj1, "notes/test.md".to_string()),
-            (obj2, "other/test.md".to_string()), // 同名文件
-        ];
+        // 同目录优先
+        assert_eq!(
+            syncer.resolve_link_target("note", "a/src.md", &index),
+            (vec!["a/note.md".to_string()], LinkResolution::Proximity)
+        );
+        // 最近的祖先目录
+        assert_eq!(
+            syncer
+                .resolve_link_target("note", "a/deep/src.md", &index)
+                .0,
+            vec!["a/note.md"]
+        );
+        // 仅根目录为祖先
+        assert_eq!(
+            syncer.resolve_link_target("note", "c/src.md", &index).0,
+            vec!["note.md"]
+        );
+        // 路径形式的链接精确匹配
+        assert_eq!(
+            syncer.resolve_link_target("b/note", "a/src.md", &index),
+            (vec!["b/note.md".to_string()], LinkResolution::Path)
+        );
+        // 唯一文件名和不存在的目标
+        assert_eq!(
+            syncer.resolve_link_target("unique", "a/src.md", &index),
+            (vec!["unique.md".to_string()], LinkResolution::Filename)
+        );
+        assert!(syncer
+            .resolve_link_target("missing", "a/src.md", &index)
+            .0
+            .is_empty());
+    }
+
+    #[test]
+    fn test_resolve_link_target_ambiguous_falls_back_to_all() {
+        let syncer = VaultSyncer::with_defaults();
 
+        let objects: Vec<(CognitiveObject, String)> = ["a/note.md", "b/note.md", "c/src.md"]
+            .iter()
+            .map(|p| (CognitiveObject::new(), p.to_string()))
+            .collect();
         let index = syncer.build_filename_index(&objects);
 
-        // 同名文件应该有多个 UUID
-        assert!(index.contains_key("test"));
-        assert_eq!(index["test"].len(), 2);
+        let (mut resolved, _) = syncer.resolve_link_target("note", "c/src.md", &index);
+        resolved.sort();
+        assert_eq!(resolved, vec!["a/note.md", "b/note.md"]);
+    }
+
+    #[test]
+    fn test_sync_full_duplicate_filenames_link_nearest() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault_path = temp_dir.path();
+
+        fs::create_dir(vault_path.join("a")).unwrap();
+        fs::create_dir(vault_path.join("b")).unwrap();
+        fs::write(vault_path.join("a/note.md"), "# A note").unwrap();
+        fs::write(vault_path.join("b/note.md"), "# B note").unwrap();
+        fs::write(vault_path.join("a/src.md"), "Link to [[note]].").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let mut db = Database::new(db_dir.path().join("test.db")).unwrap();
+
+        VaultSyncer::default()
+            .sync_full(vault_path, &mut db)
+            .unwrap();
+
+        let links: Vec<_> = db
+            .get_all_edges()
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.relation == "link")
+            .collect();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].src_uuid, path_to_uuid("a/src.md"));
+        assert_eq!(links[0].dst_uuid, path_to_uuid("a/note.md"));
     }
 }
@@ -0,0 +1,233 @@
+//! # Rules 模块
+//!
+//! 本模块提供基于 frontmatter 属性的节点类型推断规则。
+//!
+//! ## 设计说明
+//!
+//! 规则的形式为“属性存在 / 属性等于某值 ⇒ 类型”，例如“有 `due` 属性的笔记是 `task`”。
+//! 规则按声明顺序匹配，第一条命中的规则决定对象的 `inferred_type`。
+//! 与 DCOM 一致，推演类型优先于显式的 `type` 属性。
+//!
+//! ## 规则文件
+//!
+//! 规则从知识库的 `.cognistruct/rules.yaml` 加载：
+//!
+//! ```yaml
+//! type_rules:
+//!   - property: due
+//!     type: task
+//!   - property: status
+//!     equals: active
+//!     type: project
+//! ```
+//!
+//! ## 导出的主要内容
+//!
+//! ### 结构体
+//! - [`TypeRule`] - 类型推断规则
+//!
+//! ### 函数
+//! - [`parse_type_rules`] - 解析规则文件内容
+//! - [`load_type_rules`] - 从知识库加载规则文件
+//! - [`infer_type`] - 按规则推断对象类型
+
+use crate::adapters::obsidian::yaml_to_property_value;
+use crate::dcom::{CognitiveObject, PropertyValue};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+/// 规则文件相对于知识库根目录的路径
+pub const RULES_FILE: &str = ".cognistruct/rules.yaml";
+
+/// 类型推断规则
+///
+/// # 字段说明
+///
+/// * `property` - 属性名
+/// * `equals` - 期望的属性值，为 `None` 时只要求属性存在（且不为空）
+/// * `node_type` - 命中时推断出的类型
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeRule {
+    /// 属性名
+    pub property: String,
+    /// 期望的属性值
+    pub equals: Option<PropertyValue>,
+    /// 推断出的类型
+    pub node_type: String,
+}
+
+impl TypeRule {
+    /// 创建“属性存在 ⇒ 类型”规则
+    ///
+    /// # 参数
+    ///
+    /// * `property` - 属性名
+    /// * `node_type` - 推断出的类型
+    pub fn new(property: impl Into<String>, node_type: impl Into<String>) -> Self {
+        TypeRule {
+            property: property.into(),
+            equals: None,
+            node_type: node_type.into(),
+        }
+    }
+
+    /// 要求属性等于指定值
+    ///
+    /// 属性为列表时，包含该值即视为相等。
+    ///
+    /// # 参数
+    ///
+    /// * `value` - 期望的属性值
+    pub fn with_equals(mut self, value: impl Into<PropertyValue>) -> Self {
+        self.equals = Some(value.into());
+        self
+    }
+
+    /// 检查对象是否满足规则
+    pub fn matches(&self, obj: &CognitiveObject) -> bool {
+        let value = match obj.get_property(&self.property) {
+            Some(value) if !value.is_null() => value,
+            _ => return false,
+        };
+
+        match &self.equals {
+            None => true,
+            Some(expected) => match value {
+                PropertyValue::List(items) => items.contains(expected),
+                _ => value == expected,
+            },
+        }
+    }
+}
+
+/// 规则文件结构
+#[derive(Debug, Default, Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    type_rules: Vec<RawTypeRule>,
+}
+
+/// 规则文件中的单条类型规则
+#[derive(Debug, Deserialize)]
+struct RawTypeRule {
+    property: String,
+    #[serde(default)]
+    equals: Option<serde_yaml::Value>,
+    #[serde(rename = "type")]
+    node_type: String,
+}
+
+/// 解析规则文件内容
+///
+/// `equals` 的值与 frontmatter 使用相同的转换规则，因此 `equals: 2h` 可以匹配 `estimate: 2h`。
+///
+/// # 参数
+///
+/// * `yaml` - 规则文件的 YAML 内容
+///
+/// # 返回值
+///
+/// * `Ok(Vec<TypeRule>)` - 按声明顺序排列的规则
+/// * `Err(anyhow::Error)` - YAML 格式无效
+pub fn parse_type_rules(yaml: &str) -> Result<Vec<TypeRule>> {
+    if yaml.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let file: RulesFile = serde_yaml::from_str(yaml).context("规则文件格式无效")?;
+    Ok(file
+        .type_rules
+        .into_iter()
+        .map(|raw| TypeRule {
+            property: raw.property,
+            equals: raw.equals.as_ref().map(yaml_to_property_value),
+            node_type: raw.node_type,
+        })
+        .collect())
+}
+
+/// 从知识库加载类型推断规则
+///
+/// # 参数
+///
+/// * `vault_path` - 知识库根目录
+///
+/// # 返回值
+///
+/// * `Ok(Vec<TypeRule>)` - 规则列表，规则文件不存在时为空
+/// * `Err(anyhow::Error)` - 文件读取失败或格式无效
+pub fn load_type_rules(vault_path: &Path) -> Result<Vec<TypeRule>> {
+    let path = vault_path.join(RULES_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let yaml = std::fs::read_to_string(&path).context("读取规则文件失败")?;
+    parse_type_rules(&yaml)
+}
+
+/// 按规则推断对象类型
+///
+/// # 参数
+///
+/// * `rules` - 规则列表
+/// * `obj` - 认知对象
+///
+/// # 返回值
+///
+/// 第一条命中规则的类型，没有规则命中时返回 `None`
+pub fn infer_type<'a>(rules: &'a [TypeRule], obj: &CognitiveObject) -> Option<&'a str> {
+    rules
+        .iter()
+        .find(|rule| rule.matches(obj))
+        .map(|rule| rule.node_type.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_type_rules() {
+        let rules = parse_type_rules(
+            "type_rules:\n  - property: due\n    type: task\n  - property: status\n    equals: active\n    type: project\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            rules,
+            vec![
+                TypeRule::new("due", "task"),
+                TypeRule::new("status", "project").with_equals("active"),
+            ]
+        );
+        assert!(parse_type_rules("").unwrap().is_empty());
+        assert!(parse_type_rules("type_rules: [[").is_err());
+    }
+
+    #[test]
+    fn test_infer_type() {
+        let rules = vec![
+            TypeRule::new("status", "project").with_equals("active"),
+            TypeRule::new("due", "task"),
+        ];
+
+        let mut task = CognitiveObject::new();
+        task.set_property("due", "2024-01-01");
+        assert_eq!(infer_type(&rules, &task), Some("task"));
+
+        let mut project = CognitiveObject::new();
+        project.set_property("due", "2024-01-01");
+        project.set_property(
+            "status",
+            PropertyValue::string_list(vec!["active".into(), "q1".into()]),
+        );
+        assert_eq!(infer_type(&rules, &project), Some("project"));
+
+        let mut other = CognitiveObject::new();
+        other.set_property("status", "archived");
+        other.set_property("due", PropertyValue::Null);
+        assert_eq!(infer_type(&rules, &other), None);
+    }
+}
@@ -0,0 +1,133 @@
+//! # Stats 模块
+//!
+//! 本模块提供笔记正文的字数、字符数和阅读时间统计。
+//!
+//! ## 导出的主要内容
+//!
+//! ### 结构体
+//! - [`NoteStats`] - 笔记统计信息
+//!
+//! ### 函数
+//! - [`compute_note_stats`] - 计算正文统计信息
+//!
+//! ## 计数规则
+//!
+//! - 西文按连续的字母/数字序列计词，词内的标点（如 `don't`、`well-known`）不拆分
+//! - 中日韩文字没有空格分词，每个字符计为一个词
+//! - 字符数不计空白字符
+//! - 阅读时间按西文每分钟 200 词、中日韩每分钟 300 字估算，向上取整
+
+use serde::{Deserialize, Serialize};
+
+/// 西文阅读速度（词/分钟）
+const WORDS_PER_MINUTE: f64 = 200.0;
+
+/// 中日韩文字阅读速度（字/分钟）
+const CJK_CHARS_PER_MINUTE: f64 = 300.0;
+
+/// 笔记统计信息
+///
+/// # 字段说明
+///
+/// * `word_count` - 词数（中日韩字符每字计一词）
+/// * `char_count` - 非空白字符数
+/// * `reading_time_minutes` - 预计阅读时间（分钟，向上取整）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct NoteStats {
+    /// 词数
+    pub word_count: usize,
+    /// 非空白字符数
+    pub char_count: usize,
+    /// 预计阅读时间（分钟）
+    pub reading_time_minutes: usize,
+}
+
+/// 计算正文统计信息
+///
+/// # 参数
+///
+/// * `content` - 笔记正文
+///
+/// # 返回值
+///
+/// 统计信息，空内容返回全零
+pub fn compute_note_stats(content: &str) -> NoteStats {
+    let mut latin_words = 0;
+    let mut cjk_chars = 0;
+    let mut char_count = 0;
+    let mut in_word = false;
+
+    for c in content.chars() {
+        if c.is_whitespace() {
+            in_word = false;
+            continue;
+        }
+
+        char_count += 1;
+
+        if is_cjk(c) {
+            cjk_chars += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() && !in_word {
+            latin_words += 1;
+            in_word = true;
+        }
+    }
+
+    let minutes = latin_words as f64 / WORDS_PER_MINUTE + cjk_chars as f64 / CJK_CHARS_PER_MINUTE;
+
+    NoteStats {
+        word_count: latin_words + cjk_chars,
+        char_count,
+        reading_time_minutes: minutes.ceil() as usize,
+    }
+}
+
+/// 判断字符是否为中日韩文字
+///
+/// 覆盖 CJK 统一表意文字（含扩展 A）、兼容表意文字、平假名、片假名和谚文音节。
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_note_stats_english() {
+        let stats = compute_note_stats(
+            "The quick brown fox jumps over the lazy dog. It's a well-known sentence!",
+        );
+
+        assert_eq!(stats.word_count, 13);
+        assert_eq!(stats.char_count, 60);
+        assert_eq!(stats.reading_time_minutes, 1);
+    }
+
+    #[test]
+    fn test_compute_note_stats_cjk() {
+        let stats = compute_note_stats("知识管理是一种方法。使用 Rust 编写");
+
+        // 13 个汉字 + 1 个西文词，句号不计词
+        assert_eq!(stats.word_count, 14);
+        assert_eq!(stats.char_count, 18);
+        assert_eq!(stats.reading_time_minutes, 1);
+    }
+
+    #[test]
+    fn test_compute_note_stats_empty_and_long() {
+        assert_eq!(compute_note_stats(""), NoteStats::default());
+        assert_eq!(compute_note_stats("  \n\t ").word_count, 0);
+
+        let long = "word ".repeat(450);
+        assert_eq!(compute_note_stats(&long).reading_time_minutes, 3);
+    }
+}
@@ -12,10 +12,15 @@
 //! ### 结构体
 //! - [`FileWatcher`] - 文件监听器
 //!
+//! ### 函数
+//! - [`coalesce_events`] - 合并一个时间窗口内的文件变化事件
+//!
 //! ## 功能说明
 //!
 //! 本模块使用 notify 库监控知识库目录中的 Markdown 文件变化。
-//! 事件经过防抖处理（200ms），避免短时间内的重复触发。
+//! 事件经过防抖处理（默认 200ms，可通过 [`FileWatcher::with_debounce`] 配置），避免短时间内的重复触发。
+//! 防抖之后，一次保存或批量 `git checkout` 仍可能分多批到达，
+//! 消费方可用 [`FileWatcher::next_batch`] 在一个时间窗口内合并并去重。
 //!
 //! ## 使用示例
 //!
@@ -34,9 +39,10 @@
 
 use anyhow::Result;
 use notify_debouncer_full::{new_debouncer, notify::RecursiveMode, DebounceEventResult};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{channel, Receiver};
-use std::time::Duration;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
 
 /// 文件监听器
 ///
@@ -86,6 +92,21 @@ impl FileWatcher {
     ///
     /// 监听器在后台线程中运行，会持续监控直到程序退出。
     pub fn new(vault_path: &Path) -> Result<Self> {
+        Self::with_debounce(vault_path, Duration::from_millis(200))
+    }
+
+    /// 使用指定防抖时间创建文件监听器
+    ///
+    /// # 参数
+    ///
+    /// * `vault_path` - 要监控的知识库目录路径
+    /// * `debounce` - 防抖时间，该时间内的重复事件合并为一次
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(FileWatcher)` - 创建成功的监听器实例
+    /// * `Err(anyhow::Error)` - 创建失败
+    pub fn with_debounce(vault_path: &Path, debounce: Duration) -> Result<Self> {
         let (tx, rx) = channel();
         let vault_path = vault_path.to_path_buf();
 
@@ -93,7 +114,7 @@ impl FileWatcher {
             let (tx_debounced, rx_debounced) = channel();
 
             let mut debouncer = new_debouncer(
-                Duration::from_millis(200),
+                debounce,
                 None,
                 move |result: DebounceEventResult| match result {
                     Ok(events) => {
@@ -130,4 +151,71 @@ impl FileWatcher {
 
         Ok(FileWatcher { receiver: rx })
     }
+
+    /// 接收一个时间窗口内的文件变化，见 [`coalesce_events`]
+    pub fn next_batch(&self, window: Duration) -> Option<Vec<PathBuf>> {
+        coalesce_events(&self.receiver, window)
+    }
+}
+
+/// 合并一个时间窗口内的文件变化事件
+///
+/// 最多等待 `window` 接收第一批路径，随后继续接收，直到第一批到达后再过 `window`。
+/// 同一路径只保留一次，按首次出现的顺序排列。
+///
+/// # 参数
+///
+/// * `receiver` - 文件变化事件接收器
+/// * `window` - 合并窗口
+///
+/// # 返回值
+///
+/// * `Some(Vec<PathBuf>)` - 去重后的路径，窗口内没有事件时为空
+/// * `None` - 发送端已关闭，且没有收到任何事件
+pub fn coalesce_events(
+    receiver: &Receiver<Vec<PathBuf>>,
+    window: Duration,
+) -> Option<Vec<PathBuf>> {
+    let mut batch = match receiver.recv_timeout(window) {
+        Ok(batch) => batch,
+        Err(RecvTimeoutError::Timeout) => return Some(Vec::new()),
+        Err(RecvTimeoutError::Disconnected) => return None,
+    };
+
+    let deadline = Instant::now() + window;
+    let mut seen = HashSet::new();
+    let mut paths = Vec::new();
+    loop {
+        for path in batch {
+            if seen.insert(path.clone()) {
+                paths.push(path);
+            }
+        }
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(next) => batch = next,
+            Err(_) => break,
+        }
+    }
+    Some(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coalesce_events() {
+        let (tx, rx) = channel();
+        let a = PathBuf::from("/vault/a.md");
+        let b = PathBuf::from("/vault/b.md");
+        tx.send(vec![a.clone(), a.clone()]).unwrap();
+        tx.send(vec![b.clone(), a.clone()]).unwrap();
+
+        let window = Duration::from_millis(50);
+        assert_eq!(coalesce_events(&rx, window), Some(vec![a, b]));
+        assert_eq!(coalesce_events(&rx, window), Some(Vec::new()));
+
+        drop(tx);
+        assert_eq!(coalesce_events(&rx, window), None);
+    }
 }
@@ -125,6 +125,8 @@ function getLinkIcon(kind: string): string {
             return '📎';
         case 'External':
             return '🌐';
+        case 'FrontmatterRef':
+            return '📋';
         default:
             return '→';
     }
@@ -92,6 +92,10 @@ export interface GraphData {
   nodes: Node[];
   /** 所有边列表 */
   edges: Edge[];
+  /** 节点数超过上限、只保留了连接度最高的节点时为 true */
+  truncated: boolean;
+  /** 裁剪前的节点总数 */
+  total_nodes: number;
 }
 
 /**